@@ -13,12 +13,12 @@ use wie_util::{Result, WieError};
 use crate::TestPlatform;
 
 // TODO macro?
-pub fn run_jvm_test<T, F>(protos: Box<[Box<[WieJavaClassProto]>]>, func: T) -> Result<()>
+pub fn run_jvm_test<T, F>(platform: TestPlatform, protos: Box<[Box<[WieJavaClassProto]>]>, func: T) -> Result<()>
 where
     T: FnOnce(Jvm) -> F + Send + 'static,
     F: Future<Output = JvmResult<()>> + Send,
 {
-    let mut system = System::new(Box::new(TestPlatform), "");
+    let mut system = System::new(Box::new(platform), "");
 
     let done = Arc::new(AtomicBool::new(false));
     let done_clone = done.clone();
@@ -1,15 +1,94 @@
-use alloc::boxed::Box;
-use core::sync::atomic::{AtomicU64, Ordering};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use wie_backend::{AudioSink, Instant, Platform};
+use spin::Mutex;
+
+use wie_backend::{
+    canvas::{Color, Image},
+    AccessMode, AudioRecording, AudioSink, BacklightState, Database, DatabaseRepository, HandsetProfile, Indicators, Instant, MemoryStorage,
+    Platform, RecordId, RecordingAudioSink, Screen, Storage, StorageRepository,
+};
+use wie_util::Result;
 
 static TEST_EPOCH: AtomicU64 = AtomicU64::new(0);
 
-pub struct TestPlatform;
+// arguments of calls made on platform, shared with test
+type Calls<T> = Arc<Mutex<Vec<T>>>;
+
+#[derive(Default)]
+pub struct TestPlatform {
+    screen: TestScreen,
+    // taken on first `audio_sink` call
+    audio_sink: Cell<Option<Box<dyn AudioSink>>>,
+    database_repository: TestDatabaseRepository,
+    handset_profile: HandsetProfile,
+    indicators: Arc<Mutex<Option<Indicators>>>,
+    vibrations: Calls<(u32, u8)>,
+    backlights: Calls<(BacklightState, Option<u32>)>,
+}
+
+impl TestPlatform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_handset_profile(handset_profile: HandsetProfile) -> Self {
+        Self {
+            handset_profile,
+            ..Default::default()
+        }
+    }
+
+    /// Creates platform with 8-bit indexed color screen using `palette`.
+    pub fn with_palette(palette: Vec<Color>) -> Self {
+        Self {
+            screen: TestScreen { palette: Some(palette) },
+            ..Default::default()
+        }
+    }
+
+    /// Last indicators reported to platform, `None` until application changes them.
+    pub fn indicators(&self) -> Arc<Mutex<Option<Indicators>>> {
+        self.indicators.clone()
+    }
+
+    /// Arguments of each `vibrate` call on platform.
+    pub fn vibrations(&self) -> Calls<(u32, u8)> {
+        self.vibrations.clone()
+    }
+
+    /// Arguments of each `set_backlight` call on platform.
+    pub fn backlights(&self) -> Calls<(BacklightState, Option<u32>)> {
+        self.backlights.clone()
+    }
+
+    /// Creates platform recording everything played on its audio sink.
+    pub fn with_audio_recording() -> (Self, AudioRecording) {
+        let (sink, recording) =
+            RecordingAudioSink::in_memory(Box::new(TestAudioSink), || Instant::from_epoch_millis(TEST_EPOCH.load(Ordering::SeqCst)));
+
+        let platform = Self {
+            audio_sink: Cell::new(Some(Box::new(sink))),
+            ..Default::default()
+        };
+
+        (platform, recording)
+    }
+}
 
 impl Platform for TestPlatform {
-    fn screen(&mut self) -> &mut dyn wie_backend::Screen {
-        todo!()
+    fn screen(&mut self) -> &mut dyn Screen {
+        &mut self.screen
     }
 
     fn now(&self) -> Instant {
@@ -17,37 +96,215 @@ impl Platform for TestPlatform {
         Instant::from_epoch_millis(epoch) // tODO
     }
 
-    fn database_repository(&self) -> &dyn wie_backend::DatabaseRepository {
-        todo!()
+    fn database_repository(&self) -> &dyn DatabaseRepository {
+        &self.database_repository
+    }
+
+    fn storage_repository(&self) -> &dyn StorageRepository {
+        &TestStorageRepository
     }
 
     fn audio_sink(&self) -> Box<dyn AudioSink> {
-        Box::new(TestAudioSink)
+        self.audio_sink.take().unwrap_or_else(|| Box::new(TestAudioSink))
     }
 
     fn write_stdout(&self, _buf: &[u8]) {}
+
+    fn vibrate(&mut self, duration: u32, level: u8) {
+        self.vibrations.lock().push((duration, level));
+    }
+
+    fn set_backlight(&mut self, state: BacklightState, timeout: Option<u32>) {
+        self.backlights.lock().push((state, timeout));
+    }
+
+    fn handset_profile(&self) -> HandsetProfile {
+        self.handset_profile
+    }
+
+    fn update_indicators(&mut self, indicators: &Indicators) {
+        *self.indicators.lock() = Some(indicators.clone());
+    }
+}
+
+// 240x320 screen discarding everything painted. 8-bit indexed color if palette is given, 16-bit otherwise.
+#[derive(Default)]
+struct TestScreen {
+    palette: Option<Vec<Color>>,
+}
+
+impl Screen for TestScreen {
+    fn request_redraw(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn paint(&mut self, _image: &dyn Image) {}
+
+    fn width(&self) -> u32 {
+        240
+    }
+
+    fn height(&self) -> u32 {
+        320
+    }
+
+    fn depth(&self) -> u32 {
+        if self.palette.is_some() {
+            8
+        } else {
+            16
+        }
+    }
+
+    fn palette(&self) -> Option<Vec<Color>> {
+        self.palette.clone()
+    }
 }
 
+// discards everything, wrapped by RecordingAudioSink to capture audio
 struct TestAudioSink;
 
 impl AudioSink for TestAudioSink {
-    fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {
-        todo!()
+    fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {}
+
+    fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+
+    fn midi_note_off(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+
+    fn midi_program_change(&self, _channel_id: u8, _program: u8) {}
+
+    fn midi_control_change(&self, _channel_id: u8, _control: u8, _value: u8) {}
+}
+
+struct TestStorageRepository;
+
+impl StorageRepository for TestStorageRepository {
+    fn open(&self, _app_id: &str) -> Box<dyn Storage> {
+        Box::new(MemoryStorage::with_clock(|| {
+            Instant::from_epoch_millis(TEST_EPOCH.load(Ordering::SeqCst))
+        }))
+    }
+}
+
+#[derive(Default)]
+struct Records {
+    records: BTreeMap<RecordId, Vec<u8>>,
+    next_id: RecordId,
+    access_mode: AccessMode,
+    version: u32,
+    last_modified: Option<Instant>,
+}
+
+impl Records {
+    fn modified(&mut self) {
+        self.version += 1;
+        self.last_modified = Some(Instant::from_epoch_millis(TEST_EPOCH.load(Ordering::SeqCst)));
+    }
+}
+
+// in-memory databases, discarded with platform
+#[derive(Default)]
+struct TestDatabaseRepository {
+    databases: Arc<Mutex<BTreeMap<String, Records>>>,
+}
+
+impl DatabaseRepository for TestDatabaseRepository {
+    fn open(&self, name: &str, app_id: &str) -> Box<dyn Database> {
+        let key = format!("{}/{}", app_id, name);
+        self.databases.lock().entry(key.clone()).or_default();
+
+        Box::new(TestDatabase {
+            key,
+            databases: self.databases.clone(),
+        })
+    }
+
+    fn list(&self, app_id: &str) -> Vec<String> {
+        let prefix = format!("{}/", app_id);
+
+        self.databases
+            .lock()
+            .keys()
+            .filter_map(|x| x.strip_prefix(&prefix))
+            .map(|x| x.to_string())
+            .collect()
+    }
+
+    fn delete(&self, name: &str, app_id: &str) -> bool {
+        self.databases.lock().remove(&format!("{}/{}", app_id, name)).is_some()
+    }
+}
+
+struct TestDatabase {
+    key: String,
+    databases: Arc<Mutex<BTreeMap<String, Records>>>,
+}
+
+impl TestDatabase {
+    fn with_records<T>(&self, f: impl FnOnce(&mut Records) -> T) -> T {
+        f(self.databases.lock().entry(self.key.clone()).or_default())
+    }
+}
+
+impl Database for TestDatabase {
+    fn add(&mut self, data: &[u8]) -> RecordId {
+        self.with_records(|x| {
+            let id = x.next_id;
+            x.next_id += 1;
+            x.records.insert(id, data.to_vec());
+            x.modified();
+
+            id
+        })
+    }
+
+    fn get(&self, id: RecordId) -> Option<Vec<u8>> {
+        self.with_records(|x| x.records.get(&id).cloned())
     }
 
-    fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {
-        todo!()
+    fn set(&mut self, id: RecordId, data: &[u8]) -> bool {
+        self.with_records(|x| {
+            x.records.insert(id, data.to_vec());
+            x.modified();
+        });
+
+        true
+    }
+
+    fn delete(&mut self, id: RecordId) -> bool {
+        self.with_records(|x| {
+            let result = x.records.remove(&id).is_some();
+            if result {
+                x.modified();
+            }
+
+            result
+        })
+    }
+
+    fn get_record_ids(&self) -> Vec<RecordId> {
+        self.with_records(|x| x.records.keys().copied().collect())
     }
 
-    fn midi_note_off(&self, _channel_id: u8, _note: u8, _velocity: u8) {
-        todo!()
+    fn next_id(&self) -> RecordId {
+        self.with_records(|x| x.next_id)
+    }
+
+    fn access_mode(&self) -> AccessMode {
+        self.with_records(|x| x.access_mode)
+    }
+
+    fn set_access_mode(&mut self, mode: AccessMode) -> bool {
+        self.with_records(|x| x.access_mode = mode);
+
+        true
     }
 
-    fn midi_program_change(&self, _channel_id: u8, _program: u8) {
-        todo!()
+    fn version(&self) -> u32 {
+        self.with_records(|x| x.version)
     }
 
-    fn midi_control_change(&self, _channel_id: u8, _control: u8, _value: u8) {
-        todo!()
+    fn last_modified(&self) -> Option<Instant> {
+        self.with_records(|x| x.last_modified)
     }
 }
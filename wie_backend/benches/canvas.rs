@@ -0,0 +1,77 @@
+//! Full-screen fill on a framebuffer living in emulated memory.
+//!
+//! `copy` reads whole framebuffer into rust, fills and writes it back, which is what we did before `MemoryImageBuffer`.
+//! `proxy` fills directly on the memory using `MemoryImageBuffer`.
+
+use std::{hint::black_box, time::Instant};
+
+use bytemuck::pod_collect_to_vec;
+
+use wie_backend::canvas::{Canvas, Color, CompositeMode, ImageBufferCanvas, ImageMemory, MemoryImageBuffer, Rgb565Pixel, VecImageBuffer};
+
+const WIDTH: u32 = 240;
+const HEIGHT: u32 = 320;
+const ITERATIONS: u32 = 1000;
+
+// stands for emulated address space, which is only accessible by copying bytes in and out
+struct EmulatedMemory {
+    data: Vec<u8>,
+}
+
+impl ImageMemory for EmulatedMemory {
+    fn read(&self, offset: u32, buf: &mut [u8]) {
+        buf.copy_from_slice(&self.data[offset as usize..offset as usize + buf.len()]);
+    }
+
+    fn write(&mut self, offset: u32, data: &[u8]) {
+        self.data[offset as usize..offset as usize + data.len()].copy_from_slice(data);
+    }
+}
+
+fn color(i: u32) -> Color {
+    Color {
+        a: 0xff,
+        r: i as u8,
+        g: (i >> 8) as u8,
+        b: 0x80,
+    }
+}
+
+fn fill_copy(memory: &mut EmulatedMemory, i: u32) {
+    let mut data = vec![0; memory.data.len()];
+    memory.read(0, &mut data);
+
+    let mut canvas = ImageBufferCanvas::new(VecImageBuffer::<Rgb565Pixel>::from_raw(WIDTH, HEIGHT, pod_collect_to_vec(&data)));
+    canvas.fill_rect(0, 0, WIDTH, HEIGHT, color(i), CompositeMode::Copy);
+
+    memory.write(0, &canvas.image().raw());
+}
+
+fn fill_proxy(memory: EmulatedMemory, i: u32) -> EmulatedMemory {
+    let mut canvas = ImageBufferCanvas::new(MemoryImageBuffer::<Rgb565Pixel, _>::new(WIDTH, HEIGHT, WIDTH * 2, memory));
+    canvas.fill_rect(0, 0, WIDTH, HEIGHT, color(i), CompositeMode::Copy);
+
+    canvas.into_inner().into_inner()
+}
+
+fn main() {
+    let mut memory = EmulatedMemory {
+        data: vec![0; (WIDTH * HEIGHT * 2) as usize],
+    };
+
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        fill_copy(black_box(&mut memory), i);
+    }
+    let copy = start.elapsed();
+
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        memory = fill_proxy(black_box(memory), i);
+    }
+    let proxy = start.elapsed();
+
+    println!("full-screen fill {}x{} rgb565, {} iterations", WIDTH, HEIGHT, ITERATIONS);
+    println!("copy:  {:?} ({:?}/iter)", copy, copy / ITERATIONS);
+    println!("proxy: {:?} ({:?}/iter)", proxy, proxy / ITERATIONS);
+}
@@ -1,5 +1,25 @@
+mod midi_log;
+mod recorder;
+mod synth;
+
+pub use self::{
+    midi_log::MidiLogAudioSink,
+    recorder::{AudioRecording, RecordingAudioSink},
+    synth::SynthAudioSink,
+};
+
 pub trait AudioSink: Sync + Send {
     fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]);
+    /// Queues wave data on `voice`, which plays independently of other voices.
+    /// Sinks without voice support play it as a plain wave.
+    fn play_voice(&self, _voice: u32, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        self.play_wave(channel, sampling_rate, wave_data)
+    }
+    fn stop_voice(&self, _voice: u32) {}
+    /// `volume` ranges from 0 to 100.
+    fn set_voice_volume(&self, _voice: u32, _volume: u8) {}
+    /// Looping voices replay their queued wave data until stopped.
+    fn set_voice_looping(&self, _voice: u32, _looping: bool) {}
     fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8);
     fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8);
     fn midi_program_change(&self, channel_id: u8, program: u8);
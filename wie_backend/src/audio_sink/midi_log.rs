@@ -0,0 +1,246 @@
+use alloc::{boxed::Box, vec::Vec};
+use std::{
+    io::{self, Seek, SeekFrom, Write},
+    sync::Mutex,
+};
+
+use crate::time::Instant;
+
+use super::AudioSink;
+
+// with 500 ticks per quarter note at default tempo of 120bpm, each tick is 1ms
+const TICKS_PER_QUARTER: u16 = 500;
+const MICROSECONDS_PER_QUARTER: u32 = 500000;
+
+const HEADER_SIZE: u64 = 22;
+const TRACK_LENGTH_OFFSET: u64 = 18;
+const END_OF_TRACK: [u8; 4] = [0x00, 0xff, 0x2f, 0x00];
+
+type Clock = Box<dyn Fn() -> Instant + Send + Sync>;
+
+trait Output: Write + Seek + Send {}
+
+impl<T> Output for T where T: Write + Seek + Send {}
+
+fn write_variable_length(buf: &mut Vec<u8>, value: u32) {
+    let mut bytes = [0u8; 5];
+    let mut length = 0;
+    let mut value = value;
+    loop {
+        bytes[length] = (value & 0x7f) as u8;
+        length += 1;
+        value >>= 7;
+        if value == 0 {
+            break;
+        }
+    }
+
+    for i in (0..length).rev() {
+        buf.push(if i == 0 { bytes[i] } else { bytes[i] | 0x80 });
+    }
+}
+
+fn header() -> Vec<u8> {
+    let mut result = Vec::new();
+
+    result.extend_from_slice(b"MThd");
+    result.extend_from_slice(&6u32.to_be_bytes());
+    result.extend_from_slice(&0u16.to_be_bytes()); // single track
+    result.extend_from_slice(&1u16.to_be_bytes());
+    result.extend_from_slice(&TICKS_PER_QUARTER.to_be_bytes());
+    result.extend_from_slice(b"MTrk");
+    result.extend_from_slice(&0u32.to_be_bytes()); // track length, updated on each event
+
+    // set tempo explicitly, so that 1 tick stays 1ms regardless of player defaults
+    result.push(0x00);
+    result.extend_from_slice(&[0xff, 0x51, 0x03]);
+    result.extend_from_slice(&MICROSECONDS_PER_QUARTER.to_be_bytes()[1..]);
+
+    result.extend_from_slice(&END_OF_TRACK);
+
+    result
+}
+
+struct MidiLogState {
+    output: Box<dyn Output>,
+    start: u64,
+    last_event: u64,
+    track_length: u32,
+    failed: bool,
+}
+
+impl MidiLogState {
+    fn write(&mut self, time: u64, event: &[u8]) -> io::Result<()> {
+        let delta = time.saturating_sub(self.last_event).min(0x0fff_ffff);
+        self.last_event = time.max(self.last_event);
+
+        let mut data = Vec::new();
+        write_variable_length(&mut data, delta as u32);
+        data.extend_from_slice(event);
+        data.extend_from_slice(&END_OF_TRACK);
+
+        // overwrite previous end of track, and keep track length up to date so that file is valid even if we're not dropped properly
+        self.output.seek(SeekFrom::End(-(END_OF_TRACK.len() as i64)))?;
+        self.output.write_all(&data)?;
+        self.track_length += (data.len() - END_OF_TRACK.len()) as u32;
+
+        self.output.seek(SeekFrom::Start(TRACK_LENGTH_OFFSET))?;
+        self.output.write_all(&self.track_length.to_be_bytes())?;
+        self.output.seek(SeekFrom::End(0))?;
+        self.output.flush()
+    }
+}
+
+/// Logs midi events sent to `inner` into standard midi file, timestamped in milliseconds by `clock`.
+///
+/// Wave data is passed through to `inner` without being logged.
+pub struct MidiLogAudioSink {
+    inner: Box<dyn AudioSink>,
+    clock: Clock,
+    state: Mutex<MidiLogState>,
+}
+
+impl MidiLogAudioSink {
+    pub fn new<W, C>(inner: Box<dyn AudioSink>, mut output: W, clock: C) -> Self
+    where
+        W: Write + Seek + Send + 'static,
+        C: Fn() -> Instant + Send + Sync + 'static,
+    {
+        let header = header();
+        let result = output.write_all(&header).and_then(|_| output.flush());
+        if let Err(x) = &result {
+            tracing::error!("Failed to write midi log: {}", x);
+        }
+
+        let start = clock().raw();
+        Self {
+            inner,
+            clock: Box::new(clock),
+            state: Mutex::new(MidiLogState {
+                output: Box::new(output),
+                start,
+                last_event: 0,
+                track_length: (header.len() as u64 - HEADER_SIZE) as u32,
+                failed: result.is_err(),
+            }),
+        }
+    }
+
+    fn log(&self, event: &[u8]) {
+        let now = (self.clock)().raw();
+        let mut state = self.state.lock().unwrap();
+        if state.failed {
+            return;
+        }
+
+        let time = now.saturating_sub(state.start);
+        if let Err(x) = state.write(time, event) {
+            tracing::error!("Failed to write midi log: {}", x);
+            state.failed = true;
+        }
+    }
+}
+
+impl AudioSink for MidiLogAudioSink {
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        self.inner.play_wave(channel, sampling_rate, wave_data)
+    }
+
+    fn play_voice(&self, voice: u32, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        self.inner.play_voice(voice, channel, sampling_rate, wave_data)
+    }
+
+    fn stop_voice(&self, voice: u32) {
+        self.inner.stop_voice(voice)
+    }
+
+    fn set_voice_volume(&self, voice: u32, volume: u8) {
+        self.inner.set_voice_volume(voice, volume)
+    }
+
+    fn set_voice_looping(&self, voice: u32, looping: bool) {
+        self.inner.set_voice_looping(voice, looping)
+    }
+
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.log(&[0x90 | (channel_id & 0x0f), note & 0x7f, velocity & 0x7f]);
+        self.inner.midi_note_on(channel_id, note, velocity)
+    }
+
+    fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.log(&[0x80 | (channel_id & 0x0f), note & 0x7f, velocity & 0x7f]);
+        self.inner.midi_note_off(channel_id, note, velocity)
+    }
+
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        self.log(&[0xc0 | (channel_id & 0x0f), program & 0x7f]);
+        self.inner.midi_program_change(channel_id, program)
+    }
+
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        self.log(&[0xb0 | (channel_id & 0x0f), control & 0x7f, value & 0x7f]);
+        self.inner.midi_control_change(channel_id, control, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, sync::Arc, vec::Vec};
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::{time::Instant, AudioSink};
+
+    use super::{super::recorder::SharedBuffer, write_variable_length, MidiLogAudioSink};
+
+    struct NullAudioSink;
+
+    impl AudioSink for NullAudioSink {
+        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {}
+        fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+        fn midi_note_off(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+        fn midi_program_change(&self, _channel_id: u8, _program: u8) {}
+        fn midi_control_change(&self, _channel_id: u8, _control: u8, _value: u8) {}
+    }
+
+    #[test]
+    fn test_variable_length() {
+        let mut buf = Vec::new();
+        write_variable_length(&mut buf, 0);
+        write_variable_length(&mut buf, 0x7f);
+        write_variable_length(&mut buf, 0x80);
+        write_variable_length(&mut buf, 0x0fffffff);
+
+        assert_eq!(buf, [0x00, 0x7f, 0x81, 0x00, 0xff, 0xff, 0xff, 0x7f]);
+    }
+
+    #[test]
+    fn test_midi_log() {
+        let time = Arc::new(AtomicU64::new(1000));
+        let time_clone = time.clone();
+        let buffer = SharedBuffer::default();
+        let sink = MidiLogAudioSink::new(Box::new(NullAudioSink), buffer.clone(), move || {
+            Instant::from_epoch_millis(time_clone.load(Ordering::SeqCst))
+        });
+
+        sink.midi_program_change(1, 80);
+        sink.midi_note_on(1, 60, 100);
+        time.store(1200, Ordering::SeqCst);
+        sink.midi_note_off(1, 60, 0);
+
+        let data = buffer.0.lock().unwrap().get_ref().clone();
+        assert_eq!(&data[0..4], b"MThd");
+        assert_eq!(&data[14..18], b"MTrk");
+
+        let track_length = u32::from_be_bytes(data[18..22].try_into().unwrap()) as usize;
+        assert_eq!(data.len(), 22 + track_length);
+
+        #[rustfmt::skip]
+        let expected_events = [
+            0x00, 0xc1, 80,
+            0x00, 0x91, 60, 100,
+            0x81, 0x48, 0x81, 60, 0,
+            0x00, 0xff, 0x2f, 0x00,
+        ];
+        assert_eq!(&data[data.len() - expected_events.len()..], expected_events);
+    }
+}
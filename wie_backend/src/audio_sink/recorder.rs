@@ -0,0 +1,286 @@
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use std::{
+    io::{self, Cursor, Seek, SeekFrom, Write},
+    sync::Mutex,
+};
+
+use crate::time::Instant;
+
+use super::{AudioSink, SynthAudioSink};
+
+const RECORDING_SAMPLE_RATE: u32 = 22050;
+const WAVE_HEADER_SIZE: u32 = 44;
+
+type Clock = Box<dyn Fn() -> Instant + Send + Sync>;
+
+trait Output: Write + Seek + Send {}
+
+impl<T> Output for T where T: Write + Seek + Send {}
+
+fn wave_header(data_size: u32) -> [u8; WAVE_HEADER_SIZE as usize] {
+    let mut result = [0; WAVE_HEADER_SIZE as usize];
+
+    result[0..4].copy_from_slice(b"RIFF");
+    result[4..8].copy_from_slice(&(WAVE_HEADER_SIZE - 8 + data_size).to_le_bytes());
+    result[8..16].copy_from_slice(b"WAVEfmt ");
+    result[16..20].copy_from_slice(&16u32.to_le_bytes());
+    result[20..22].copy_from_slice(&1u16.to_le_bytes()); // pcm
+    result[22..24].copy_from_slice(&1u16.to_le_bytes()); // mono
+    result[24..28].copy_from_slice(&RECORDING_SAMPLE_RATE.to_le_bytes());
+    result[28..32].copy_from_slice(&(RECORDING_SAMPLE_RATE * 2).to_le_bytes());
+    result[32..34].copy_from_slice(&2u16.to_le_bytes()); // block align
+    result[34..36].copy_from_slice(&16u16.to_le_bytes()); // bits per sample
+    result[36..40].copy_from_slice(b"data");
+    result[40..44].copy_from_slice(&data_size.to_le_bytes());
+
+    result
+}
+
+struct RecorderState {
+    output: Box<dyn Output>,
+    start: u64,
+    frames: u64,
+    failed: bool,
+}
+
+impl RecorderState {
+    fn write(&mut self, samples: &[i16]) -> io::Result<()> {
+        let data = samples.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<_>>();
+        self.output.write_all(&data)?;
+        self.frames += samples.len() as u64;
+
+        // header is kept up to date, so that file is valid even if we're not dropped properly
+        let data_size = (self.frames * 2).min((u32::MAX - WAVE_HEADER_SIZE) as u64) as u32;
+        self.output.seek(SeekFrom::Start(0))?;
+        self.output.write_all(&wave_header(data_size))?;
+        self.output.seek(SeekFrom::End(0))?;
+        self.output.flush()
+    }
+}
+
+struct Recorder {
+    clock: Clock,
+    synth: SynthAudioSink,
+    state: Mutex<RecorderState>,
+}
+
+impl Recorder {
+    // renders everything played until now into output
+    fn advance(&self) {
+        let now = (self.clock)().raw();
+        let mut state = self.state.lock().unwrap();
+        if state.failed {
+            return;
+        }
+
+        let target = now.saturating_sub(state.start) * RECORDING_SAMPLE_RATE as u64 / 1000;
+        if target <= state.frames {
+            return;
+        }
+
+        let mut samples = vec![0; (target - state.frames) as usize];
+        self.synth.render(&mut samples);
+
+        if let Err(x) = state.write(&samples) {
+            tracing::error!("Failed to write audio recording: {}", x);
+            state.failed = true;
+        }
+    }
+}
+
+/// Captures audio played on `inner` into mono wave file, rendering midi with [`SynthAudioSink`].
+///
+/// Audio is placed by `clock`, which should be emulated time of the platform.
+pub struct RecordingAudioSink {
+    inner: Box<dyn AudioSink>,
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingAudioSink {
+    pub fn new<W, C>(inner: Box<dyn AudioSink>, mut output: W, clock: C) -> Self
+    where
+        W: Write + Seek + Send + 'static,
+        C: Fn() -> Instant + Send + Sync + 'static,
+    {
+        let result = output.write_all(&wave_header(0));
+        if let Err(x) = &result {
+            tracing::error!("Failed to write audio recording: {}", x);
+        }
+
+        let start = clock().raw();
+        let recorder = Recorder {
+            clock: Box::new(clock),
+            synth: SynthAudioSink::new(RECORDING_SAMPLE_RATE),
+            state: Mutex::new(RecorderState {
+                output: Box::new(output),
+                start,
+                frames: 0,
+                failed: result.is_err(),
+            }),
+        };
+
+        Self {
+            inner,
+            recorder: Arc::new(recorder),
+        }
+    }
+
+    /// Records into memory, which can be read with returned [`AudioRecording`].
+    pub fn in_memory<C>(inner: Box<dyn AudioSink>, clock: C) -> (Self, AudioRecording)
+    where
+        C: Fn() -> Instant + Send + Sync + 'static,
+    {
+        let buffer = SharedBuffer::default();
+        let sink = Self::new(inner, buffer.clone(), clock);
+
+        let recording = AudioRecording {
+            recorder: sink.recorder.clone(),
+            buffer,
+        };
+
+        (sink, recording)
+    }
+}
+
+impl Drop for RecordingAudioSink {
+    fn drop(&mut self) {
+        self.recorder.advance();
+    }
+}
+
+impl AudioSink for RecordingAudioSink {
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        self.recorder.advance();
+        self.recorder.synth.play_wave(channel, sampling_rate, wave_data);
+        self.inner.play_wave(channel, sampling_rate, wave_data)
+    }
+
+    fn play_voice(&self, voice: u32, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        self.recorder.advance();
+        self.recorder.synth.play_voice(voice, channel, sampling_rate, wave_data);
+        self.inner.play_voice(voice, channel, sampling_rate, wave_data)
+    }
+
+    fn stop_voice(&self, voice: u32) {
+        self.recorder.advance();
+        self.recorder.synth.stop_voice(voice);
+        self.inner.stop_voice(voice)
+    }
+
+    fn set_voice_volume(&self, voice: u32, volume: u8) {
+        self.recorder.advance();
+        self.recorder.synth.set_voice_volume(voice, volume);
+        self.inner.set_voice_volume(voice, volume)
+    }
+
+    fn set_voice_looping(&self, voice: u32, looping: bool) {
+        self.recorder.advance();
+        self.recorder.synth.set_voice_looping(voice, looping);
+        self.inner.set_voice_looping(voice, looping)
+    }
+
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.recorder.advance();
+        self.recorder.synth.midi_note_on(channel_id, note, velocity);
+        self.inner.midi_note_on(channel_id, note, velocity)
+    }
+
+    fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.recorder.advance();
+        self.recorder.synth.midi_note_off(channel_id, note, velocity);
+        self.inner.midi_note_off(channel_id, note, velocity)
+    }
+
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        self.recorder.advance();
+        self.recorder.synth.midi_program_change(channel_id, program);
+        self.inner.midi_program_change(channel_id, program)
+    }
+
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        self.recorder.advance();
+        self.recorder.synth.midi_control_change(channel_id, control, value);
+        self.inner.midi_control_change(channel_id, control, value)
+    }
+}
+
+#[derive(Clone, Default)]
+pub(super) struct SharedBuffer(pub(super) Arc<Mutex<Cursor<Vec<u8>>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for SharedBuffer {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.lock().unwrap().seek(pos)
+    }
+}
+
+/// Handle to in-memory recording of [`RecordingAudioSink::in_memory`].
+#[derive(Clone)]
+pub struct AudioRecording {
+    recorder: Arc<Recorder>,
+    buffer: SharedBuffer,
+}
+
+impl AudioRecording {
+    /// Returns wave file recorded until now.
+    pub fn wave(&self) -> Vec<u8> {
+        self.recorder.advance();
+
+        self.buffer.0.lock().unwrap().get_ref().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, sync::Arc, vec};
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::{time::Instant, AudioSink};
+
+    use super::{RecordingAudioSink, RECORDING_SAMPLE_RATE};
+
+    struct NullAudioSink;
+
+    impl AudioSink for NullAudioSink {
+        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {}
+        fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+        fn midi_note_off(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+        fn midi_program_change(&self, _channel_id: u8, _program: u8) {}
+        fn midi_control_change(&self, _channel_id: u8, _control: u8, _value: u8) {}
+    }
+
+    #[test]
+    fn test_recording() {
+        let time = Arc::new(AtomicU64::new(1000));
+        let time_clone = time.clone();
+        let (sink, recording) = RecordingAudioSink::in_memory(Box::new(NullAudioSink), move || {
+            Instant::from_epoch_millis(time_clone.load(Ordering::SeqCst))
+        });
+
+        // 100ms of silence, then 100ms of wave
+        time.store(1100, Ordering::SeqCst);
+        sink.play_wave(1, RECORDING_SAMPLE_RATE, &vec![16384; RECORDING_SAMPLE_RATE as usize / 10]);
+        time.store(1200, Ordering::SeqCst);
+
+        let wave = recording.wave();
+        let data_size = u32::from_le_bytes(wave[40..44].try_into().unwrap()) as usize;
+        assert_eq!(&wave[0..4], b"RIFF");
+        assert_eq!(&wave[8..12], b"WAVE");
+        assert_eq!(data_size, RECORDING_SAMPLE_RATE as usize / 5 * 2);
+        assert_eq!(wave.len(), 44 + data_size);
+
+        let samples = wave[44..].chunks(2).map(|x| i16::from_le_bytes([x[0], x[1]])).collect::<Vec<_>>();
+        let half = samples.len() / 2;
+        assert!(samples[..half].iter().all(|&x| x == 0));
+        assert!(samples[half..].iter().all(|&x| x != 0));
+    }
+}
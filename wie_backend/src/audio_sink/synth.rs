@@ -0,0 +1,515 @@
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::f32::consts::TAU;
+use std::sync::Mutex;
+
+use super::AudioSink;
+
+const MAX_VOICES: usize = 32;
+const DRUM_CHANNEL: u8 = 9;
+// headroom so that several voices can play together without clipping
+const VOICE_GAIN: f32 = 0.2;
+
+const CONTROL_VOLUME: u8 = 7;
+const CONTROL_EXPRESSION: u8 = 11;
+const CONTROL_SUSTAIN: u8 = 64;
+const CONTROL_ALL_SOUND_OFF: u8 = 120;
+const CONTROL_RESET_ALL_CONTROLLERS: u8 = 121;
+const CONTROL_ALL_NOTES_OFF: u8 = 123;
+
+/// Two-operator fm patch. Times are in seconds.
+#[derive(Clone, Copy)]
+struct Patch {
+    ratio: f32,
+    index: f32,
+    attack: f32,
+    decay: f32,
+    sustain: f32,
+    release: f32,
+}
+
+impl Patch {
+    const fn new(ratio: f32, index: f32, attack: f32, decay: f32, sustain: f32, release: f32) -> Self {
+        Self {
+            ratio,
+            index,
+            attack,
+            decay,
+            sustain,
+            release,
+        }
+    }
+
+    // one patch per general midi instrument family
+    fn from_program(program: u8) -> Self {
+        match program / 8 {
+            0 => Self::new(1.0, 1.5, 0.002, 1.2, 0.0, 0.3),    // piano
+            1 => Self::new(3.5, 2.0, 0.001, 0.6, 0.0, 0.3),    // chromatic percussion
+            2 => Self::new(2.0, 0.8, 0.01, 0.1, 0.9, 0.05),    // organ
+            3 => Self::new(1.0, 2.5, 0.002, 0.8, 0.0, 0.2),    // guitar
+            4 => Self::new(0.5, 1.5, 0.005, 0.5, 0.4, 0.1),    // bass
+            5 | 6 => Self::new(1.0, 1.2, 0.08, 0.3, 0.8, 0.3), // strings, ensemble
+            7 => Self::new(1.0, 3.0, 0.03, 0.2, 0.7, 0.1),     // brass
+            8 => Self::new(3.0, 1.5, 0.02, 0.2, 0.7, 0.1),     // reed
+            9 => Self::new(1.0, 0.3, 0.03, 0.1, 0.8, 0.1),     // pipe
+            10 => Self::new(1.0, 4.0, 0.005, 0.1, 0.8, 0.1),   // synth lead
+            11 => Self::new(0.5, 1.0, 0.2, 0.5, 0.7, 0.5),     // synth pad
+            _ => Self::new(1.4, 2.0, 0.005, 0.4, 0.3, 0.2),
+        }
+    }
+}
+
+enum VoiceKind {
+    Fm { patch: Patch, phase: f32, mod_phase: f32 },
+    // drums are rendered as a pitch-swept sine for kicks and toms, decaying noise for the rest
+    Kick { phase: f32 },
+    Noise { decay: f32 },
+}
+
+struct Voice {
+    channel: u8,
+    note: u8,
+    velocity: f32,
+    frequency: f32,
+    kind: VoiceKind,
+    time: f32,
+    released: Option<(f32, f32)>,
+    held: bool,
+}
+
+impl Voice {
+    fn envelope(&self) -> f32 {
+        let level_at = |time: f32| match &self.kind {
+            VoiceKind::Fm { patch, .. } => {
+                if time < patch.attack {
+                    time / patch.attack
+                } else if time < patch.attack + patch.decay {
+                    1.0 - (1.0 - patch.sustain) * (time - patch.attack) / patch.decay
+                } else {
+                    patch.sustain
+                }
+            }
+            VoiceKind::Kick { .. } => (-time * 12.0).exp(),
+            VoiceKind::Noise { decay } => (-time / decay).exp(),
+        };
+
+        match self.released {
+            Some((released_at, level)) => {
+                let release = match &self.kind {
+                    VoiceKind::Fm { patch, .. } => patch.release,
+                    _ => 0.05,
+                };
+
+                (level * (1.0 - (self.time - released_at) / release)).max(0.0)
+            }
+            None => level_at(self.time),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        match (&self.kind, self.released) {
+            (_, Some(_)) => self.envelope() <= 0.0,
+            (VoiceKind::Fm { patch, .. }, None) => patch.sustain == 0.0 && self.time > patch.attack + patch.decay,
+            (_, None) => self.envelope() < 0.001,
+        }
+    }
+
+    fn release(&mut self) {
+        if self.released.is_none() {
+            self.released = Some((self.time, self.envelope()));
+        }
+    }
+
+    fn sample(&mut self, sample_rate: f32, noise: &mut u32) -> f32 {
+        let envelope = self.envelope();
+        let value = match &mut self.kind {
+            VoiceKind::Fm { patch, phase, mod_phase } => {
+                let value = (*phase * TAU + patch.index * (*mod_phase * TAU).sin()).sin();
+
+                *phase = (*phase + self.frequency / sample_rate).fract();
+                *mod_phase = (*mod_phase + self.frequency * patch.ratio / sample_rate).fract();
+
+                value
+            }
+            VoiceKind::Kick { phase } => {
+                let value = (*phase * TAU).sin();
+                let frequency = self.frequency * (1.0 + 2.0 * (-self.time * 30.0).exp());
+
+                *phase = (*phase + frequency / sample_rate).fract();
+
+                value
+            }
+            VoiceKind::Noise { .. } => {
+                *noise = noise.wrapping_mul(1_103_515_245).wrapping_add(12345);
+
+                (*noise >> 16) as i16 as f32 / 32768.0
+            }
+        };
+
+        self.time += 1.0 / sample_rate;
+
+        value * envelope * self.velocity
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Channel {
+    program: u8,
+    volume: u8,
+    expression: u8,
+    sustain: bool,
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Self {
+            program: 0,
+            volume: 100,
+            expression: 127,
+            sustain: false,
+        }
+    }
+}
+
+impl Channel {
+    fn gain(&self) -> f32 {
+        (self.volume as f32 / 127.0) * (self.expression as f32 / 127.0)
+    }
+}
+
+struct Wave {
+    voice: Option<u32>,
+    // mono, resampled to output rate
+    data: Vec<f32>,
+    position: usize,
+}
+
+#[derive(Clone, Copy)]
+struct WaveVoice {
+    volume: f32,
+    looping: bool,
+}
+
+impl Default for WaveVoice {
+    fn default() -> Self {
+        Self { volume: 1.0, looping: false }
+    }
+}
+
+struct SynthState {
+    sample_rate: u32,
+    channels: [Channel; 16],
+    voices: Vec<Voice>,
+    waves: Vec<Wave>,
+    wave_voices: BTreeMap<u32, WaveVoice>,
+    noise: u32,
+}
+
+impl SynthState {
+    fn note_on(&mut self, channel_id: u8, note: u8, velocity: u8) {
+        if velocity == 0 {
+            self.note_off(channel_id, note);
+            return;
+        }
+
+        // retrigger the same note instead of stacking voices
+        self.voices.retain(|x| !(x.channel == channel_id && x.note == note));
+        if self.voices.len() >= MAX_VOICES {
+            // steal the oldest voice, preferring released ones
+            let index = self.voices.iter().position(|x| x.released.is_some()).unwrap_or(0);
+            self.voices.remove(index);
+        }
+
+        let channel = &self.channels[channel_id as usize];
+        let (frequency, kind) = if channel_id == DRUM_CHANNEL {
+            match note {
+                35 | 36 => (50.0, VoiceKind::Kick { phase: 0.0 }),
+                41 | 43 | 45 | 47 | 48 | 50 => (60.0 + (note - 41) as f32 * 12.0, VoiceKind::Kick { phase: 0.0 }),
+                42 | 44 => (0.0, VoiceKind::Noise { decay: 0.03 }),
+                46 | 49 | 51 | 52 | 55 | 57 | 59 => (0.0, VoiceKind::Noise { decay: 0.3 }),
+                _ => (0.0, VoiceKind::Noise { decay: 0.08 }),
+            }
+        } else {
+            (
+                440.0 * ((note as f32 - 69.0) / 12.0).exp2(),
+                VoiceKind::Fm {
+                    patch: Patch::from_program(channel.program),
+                    phase: 0.0,
+                    mod_phase: 0.0,
+                },
+            )
+        };
+
+        self.voices.push(Voice {
+            channel: channel_id,
+            note,
+            velocity: velocity as f32 / 127.0,
+            frequency,
+            kind,
+            time: 0.0,
+            released: None,
+            held: false,
+        });
+    }
+
+    fn note_off(&mut self, channel_id: u8, note: u8) {
+        let sustain = self.channels[channel_id as usize].sustain;
+
+        for voice in self.voices.iter_mut().filter(|x| x.channel == channel_id && x.note == note) {
+            if sustain {
+                voice.held = true;
+            } else {
+                voice.release();
+            }
+        }
+    }
+
+    fn control_change(&mut self, channel_id: u8, control: u8, value: u8) {
+        let channel = &mut self.channels[channel_id as usize];
+
+        match control {
+            CONTROL_VOLUME => channel.volume = value,
+            CONTROL_EXPRESSION => channel.expression = value,
+            CONTROL_SUSTAIN => {
+                channel.sustain = value >= 64;
+                if !channel.sustain {
+                    for voice in self.voices.iter_mut().filter(|x| x.channel == channel_id && x.held) {
+                        voice.release();
+                    }
+                }
+            }
+            CONTROL_ALL_SOUND_OFF => self.voices.retain(|x| x.channel != channel_id),
+            CONTROL_RESET_ALL_CONTROLLERS => {
+                *channel = Channel {
+                    program: channel.program,
+                    ..Default::default()
+                }
+            }
+            CONTROL_ALL_NOTES_OFF => {
+                for voice in self.voices.iter_mut().filter(|x| x.channel == channel_id) {
+                    voice.release();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_wave(&mut self, voice: Option<u32>, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        let channel = channel.max(1) as usize;
+        let frames = wave_data.len() / channel;
+        if frames == 0 || sampling_rate == 0 {
+            return;
+        }
+
+        // downmix to mono and resample with linear interpolation
+        let frame = |i: usize| wave_data[i * channel..(i + 1) * channel].iter().map(|&x| x as f32).sum::<f32>() / (channel as f32 * 32768.0);
+
+        let step = sampling_rate as f32 / self.sample_rate as f32;
+        let length = (frames as f32 / step) as usize;
+        let data = (0..length)
+            .map(|i| {
+                let position = i as f32 * step;
+                let index = position as usize;
+                let fraction = position.fract();
+
+                let current = frame(index);
+                let next = if index + 1 < frames { frame(index + 1) } else { current };
+
+                current + (next - current) * fraction
+            })
+            .collect::<Vec<_>>();
+
+        // waves on the same voice are played one after another
+        match self.waves.iter_mut().find(|x| voice.is_some() && x.voice == voice) {
+            Some(wave) => wave.data.extend(data),
+            None => self.waves.push(Wave { voice, data, position: 0 }),
+        }
+    }
+
+    fn render(&mut self, out: &mut [i16]) {
+        let sample_rate = self.sample_rate as f32;
+
+        for sample in out.iter_mut() {
+            let mut value = 0.0;
+
+            for voice in self.voices.iter_mut() {
+                let gain = self.channels[voice.channel as usize].gain();
+
+                value += voice.sample(sample_rate, &mut self.noise) * gain * VOICE_GAIN;
+            }
+
+            for wave in self.waves.iter_mut() {
+                let settings = wave.voice.and_then(|x| self.wave_voices.get(&x).copied()).unwrap_or_default();
+
+                if settings.looping && wave.position == wave.data.len() {
+                    wave.position = 0;
+                }
+
+                if let Some(x) = wave.data.get(wave.position) {
+                    value += x * settings.volume;
+                    wave.position += 1;
+                }
+            }
+
+            *sample = (value.clamp(-1.0, 1.0) * 32767.0) as i16;
+        }
+
+        self.voices.retain(|x| !x.is_finished());
+        let wave_voices = &self.wave_voices;
+        self.waves
+            .retain(|x| x.position < x.data.len() || x.voice.and_then(|x| wave_voices.get(&x)).is_some_and(|x| x.looping));
+    }
+}
+
+/// Software synthesizer for environments without midi output.
+///
+/// Midi events are rendered with simple fm voices and mixed together with `play_wave` data into a single mono stream,
+/// which frontends pull with [`SynthAudioSink::render`]. Cloned sinks share the same state.
+#[derive(Clone)]
+pub struct SynthAudioSink {
+    state: Arc<Mutex<SynthState>>,
+}
+
+impl SynthAudioSink {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SynthState {
+                sample_rate,
+                channels: [Channel::default(); 16],
+                voices: Vec::new(),
+                waves: Vec::new(),
+                wave_voices: BTreeMap::new(),
+                noise: 1,
+            })),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.state.lock().unwrap().sample_rate
+    }
+
+    /// Renders next mono samples into `out`.
+    pub fn render(&self, out: &mut [i16]) {
+        self.state.lock().unwrap().render(out)
+    }
+}
+
+impl AudioSink for SynthAudioSink {
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        self.state.lock().unwrap().add_wave(None, channel, sampling_rate, wave_data)
+    }
+
+    fn play_voice(&self, voice: u32, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        self.state.lock().unwrap().add_wave(Some(voice), channel, sampling_rate, wave_data)
+    }
+
+    fn stop_voice(&self, voice: u32) {
+        self.state.lock().unwrap().waves.retain(|x| x.voice != Some(voice))
+    }
+
+    fn set_voice_volume(&self, voice: u32, volume: u8) {
+        self.state.lock().unwrap().wave_voices.entry(voice).or_default().volume = volume.min(100) as f32 / 100.0;
+    }
+
+    fn set_voice_looping(&self, voice: u32, looping: bool) {
+        self.state.lock().unwrap().wave_voices.entry(voice).or_default().looping = looping;
+    }
+
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.state.lock().unwrap().note_on(channel_id & 0x0f, note, velocity)
+    }
+
+    fn midi_note_off(&self, channel_id: u8, note: u8, _velocity: u8) {
+        self.state.lock().unwrap().note_off(channel_id & 0x0f, note)
+    }
+
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        self.state.lock().unwrap().channels[(channel_id & 0x0f) as usize].program = program & 0x7f;
+    }
+
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        self.state.lock().unwrap().control_change(channel_id & 0x0f, control, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::AudioSink;
+
+    use super::SynthAudioSink;
+
+    fn peak(samples: &[i16]) -> i16 {
+        samples.iter().map(|x| x.saturating_abs()).max().unwrap()
+    }
+
+    #[test]
+    fn test_synth_note() {
+        let synth = SynthAudioSink::new(8000);
+        let mut buf = vec![0; 800];
+
+        synth.render(&mut buf);
+        assert_eq!(peak(&buf), 0);
+
+        synth.midi_program_change(0, 16); // organ, which sustains
+        synth.midi_note_on(0, 69, 127);
+        synth.render(&mut buf);
+        assert!(peak(&buf) > 1000);
+
+        synth.midi_note_off(0, 69, 0);
+        synth.render(&mut buf);
+        synth.render(&mut buf);
+        assert_eq!(peak(&buf), 0);
+    }
+
+    #[test]
+    fn test_synth_sustain() {
+        let synth = SynthAudioSink::new(8000);
+        let mut buf = vec![0; 800];
+
+        synth.midi_program_change(0, 16);
+        synth.midi_control_change(0, 64, 127);
+        synth.midi_note_on(0, 60, 127);
+        synth.midi_note_off(0, 60, 0);
+        synth.render(&mut buf);
+        synth.render(&mut buf);
+        assert!(peak(&buf) > 1000);
+
+        synth.midi_control_change(0, 64, 0);
+        synth.render(&mut buf);
+        synth.render(&mut buf);
+        assert_eq!(peak(&buf), 0);
+    }
+
+    #[test]
+    fn test_synth_wave() {
+        let synth = SynthAudioSink::new(8000);
+        let mut buf = vec![0; 8];
+
+        // stereo 16khz input is downmixed and resampled to half length
+        synth.play_wave(2, 16000, &[16384, 16384, 16384, 16384, -16384, -16384, -16384, -16384]);
+        synth.render(&mut buf);
+
+        assert_eq!(buf[0], 16383);
+        assert_eq!(buf[1], -16383);
+        assert_eq!(&buf[2..], &[0; 6]);
+    }
+
+    #[test]
+    fn test_synth_wave_voice() {
+        let synth = SynthAudioSink::new(8000);
+        let mut buf = vec![0; 6];
+
+        synth.set_voice_volume(1, 50);
+        synth.set_voice_looping(1, true);
+        synth.play_voice(1, 1, 8000, &[16384, -16384]);
+        synth.play_wave(1, 8000, &[16384]);
+        synth.render(&mut buf);
+
+        assert_eq!(buf, [24575, -8191, 8191, -8191, 8191, -8191]);
+
+        synth.stop_voice(1);
+        synth.render(&mut buf);
+        assert_eq!(buf, [0; 6]);
+    }
+}
@@ -1,12 +1,21 @@
-use core::mem::size_of;
+mod decoder;
+mod raster;
 
-use ab_glyph::{Font, FontRef, ScaleFont};
-use bytemuck::{cast_slice, pod_collect_to_vec, Pod};
+use alloc::borrow::Cow;
+use core::{marker::PhantomData, mem::size_of};
+
+use ab_glyph::{point, Font, FontRef, ScaleFont};
+use bytemuck::{cast_slice, cast_slice_mut, pod_collect_to_vec, Pod};
 use image::ImageReader;
 use num_traits::{Num, Zero};
 
 use wie_util::{Result, WieError};
 
+pub use self::{
+    decoder::ImageFormat,
+    raster::{arc_points, arc_spans, line_points, round_rect_points, round_rect_spans, triangle_spans, Span},
+};
+
 lazy_static::lazy_static! {
     static ref FONT: FontRef<'static> = FontRef::try_from_slice(include_bytes!("../../fonts/neodgm.ttf")).unwrap();
 }
@@ -17,7 +26,7 @@ pub enum TextAlignment {
     Right,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct Color {
     pub a: u8,
     pub r: u8,
@@ -25,13 +34,36 @@ pub struct Color {
     pub b: u8,
 }
 
+/// How source pixels are combined with destination pixels.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompositeMode {
+    /// Blend source over destination using source alpha.
+    SourceOver,
+    /// Replace destination with source, ignoring alpha.
+    Copy,
+    Xor,
+    Or,
+    And,
+    /// Blend source over destination with given constant alpha, combined with source alpha.
+    Alpha(u8),
+}
+
 pub trait Image: Send {
     fn width(&self) -> u32;
     fn height(&self) -> u32;
     fn bytes_per_pixel(&self) -> u32;
+    /// Color depth, which can be smaller than `bytes_per_pixel` * 8 as 12-bit pixels are stored in 2 bytes.
+    fn bits_per_pixel(&self) -> u32;
     fn get_pixel(&self, x: u32, y: u32) -> Color;
-    fn raw(&self) -> &[u8];
+    fn raw(&self) -> Cow<'_, [u8]>;
     fn colors(&self) -> Vec<Color>;
+
+    /// Reads `colors.len()` pixels of row `y` starting from `x`.
+    fn get_row(&self, x: u32, y: u32, colors: &mut [Color]) {
+        for (i, color) in colors.iter_mut().enumerate() {
+            *color = self.get_pixel(x + i as u32, y);
+        }
+    }
 }
 
 pub trait ImageBuffer: Send {
@@ -42,16 +74,17 @@ pub trait ImageBuffer: Send {
 pub trait Canvas: Send {
     fn image(&self) -> &dyn Image;
     #[allow(clippy::too_many_arguments)]
-    fn draw(&mut self, dx: u32, dy: u32, w: u32, h: u32, src: &dyn Image, sx: u32, sy: u32);
+    fn draw(&mut self, dx: u32, dy: u32, w: u32, h: u32, src: &dyn Image, sx: u32, sy: u32, mode: CompositeMode, color_key: Option<Color>);
     fn draw_line(&mut self, x1: u32, y1: u32, x2: u32, y2: u32, color: Color);
     fn draw_text(&mut self, string: &str, x: u32, y: u32, text_alignment: TextAlignment);
     fn draw_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color);
-    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color);
+    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color, mode: CompositeMode);
     fn put_pixel(&mut self, x: u32, y: u32, color: Color);
 }
 
 pub trait PixelType: Send {
     type DataType: Copy + Pod + Num + Send;
+    const BITS_PER_PIXEL: u32;
     fn from_color(color: Color) -> Self::DataType;
     fn to_color(raw: Self::DataType) -> Color;
 }
@@ -60,6 +93,7 @@ pub struct Rgb565Pixel;
 
 impl PixelType for Rgb565Pixel {
     type DataType = u16;
+    const BITS_PER_PIXEL: u32 = 16;
 
     fn from_color(color: Color) -> Self::DataType {
         let r = (color.r as u16) >> 3;
@@ -86,6 +120,7 @@ pub struct Rgb8Pixel;
 
 impl PixelType for Rgb8Pixel {
     type DataType = u32;
+    const BITS_PER_PIXEL: u32 = 24;
 
     fn from_color(color: Color) -> Self::DataType {
         (color.r as u32) << 16 | (color.g as u32) << 8 | color.b as u32
@@ -104,6 +139,7 @@ pub struct ArgbPixel;
 
 impl PixelType for ArgbPixel {
     type DataType = u32;
+    const BITS_PER_PIXEL: u32 = 32;
 
     fn from_color(color: Color) -> Self::DataType {
         (color.a as u32) << 24 | (color.r as u32) << 16 | (color.g as u32) << 8 | color.b as u32
@@ -119,10 +155,65 @@ impl PixelType for ArgbPixel {
     }
 }
 
+pub struct Rgb444Pixel;
+
+impl PixelType for Rgb444Pixel {
+    type DataType = u16;
+    const BITS_PER_PIXEL: u32 = 12;
+
+    fn from_color(color: Color) -> Self::DataType {
+        let r = (color.r as u16) >> 4;
+        let g = (color.g as u16) >> 4;
+        let b = (color.b as u16) >> 4;
+
+        (r << 8) | (g << 4) | b
+    }
+
+    fn to_color(raw: Self::DataType) -> Color {
+        let r = ((raw >> 8) & 0xf) as u8;
+        let g = ((raw >> 4) & 0xf) as u8;
+        let b = (raw & 0xf) as u8;
+
+        Color {
+            a: 0xff,
+            r: r * 17,
+            g: g * 17,
+            b: b * 17,
+        }
+    }
+}
+
+pub struct Rgb332Pixel;
+
+impl PixelType for Rgb332Pixel {
+    type DataType = u8;
+    const BITS_PER_PIXEL: u32 = 8;
+
+    fn from_color(color: Color) -> Self::DataType {
+        let r = color.r >> 5;
+        let g = color.g >> 5;
+        let b = color.b >> 6;
+
+        (r << 5) | (g << 2) | b
+    }
+
+    fn to_color(raw: Self::DataType) -> Color {
+        let r = (raw >> 5) & 0x7;
+        let g = (raw >> 2) & 0x7;
+        let b = raw & 0x3;
+
+        let r = ((r as u32 * 255 + 3) / 7) as u8;
+        let g = ((g as u32 * 255 + 3) / 7) as u8;
+
+        Color { a: 0xff, r, g, b: b * 85 }
+    }
+}
+
 pub struct AbgrPixel;
 
 impl PixelType for AbgrPixel {
     type DataType = u32;
+    const BITS_PER_PIXEL: u32 = 32;
 
     fn from_color(color: Color) -> Self::DataType {
         (color.a as u32) << 24 | (color.b as u32) << 16 | (color.g as u32) << 8 | color.r as u32
@@ -180,14 +271,18 @@ where
         size_of::<T::DataType>() as u32
     }
 
+    fn bits_per_pixel(&self) -> u32 {
+        T::BITS_PER_PIXEL
+    }
+
     fn get_pixel(&self, x: u32, y: u32) -> Color {
         let raw = self.data[(y * self.width + x) as usize];
 
         T::to_color(raw)
     }
 
-    fn raw(&self) -> &[u8] {
-        cast_slice(&self.data)
+    fn raw(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(cast_slice(&self.data))
     }
 
     fn colors(&self) -> Vec<Color> {
@@ -225,6 +320,399 @@ where
     }
 }
 
+/// Byte storage of pixel data which lives outside of rust, like emulated memory.
+pub trait ImageMemory: Send {
+    fn read(&self, offset: u32, buf: &mut [u8]);
+    fn write(&mut self, offset: u32, data: &[u8]);
+}
+
+/// Image buffer reading and writing pixels directly on `ImageMemory`, without copying whole image.
+pub struct MemoryImageBuffer<T, M>
+where
+    T: PixelType,
+    M: ImageMemory,
+{
+    width: u32,
+    height: u32,
+    bytes_per_line: u32,
+    memory: M,
+    _pixel_type: PhantomData<T>,
+}
+
+impl<T, M> MemoryImageBuffer<T, M>
+where
+    T: PixelType,
+    M: ImageMemory,
+{
+    pub fn new(width: u32, height: u32, bytes_per_line: u32, memory: M) -> Self {
+        Self {
+            width,
+            height,
+            bytes_per_line,
+            memory,
+            _pixel_type: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.memory
+    }
+
+    fn offset(&self, x: u32, y: u32) -> u32 {
+        y * self.bytes_per_line + x * size_of::<T::DataType>() as u32
+    }
+
+    fn read_row(&self, x: u32, y: u32, raw: &mut [T::DataType]) {
+        self.memory.read(self.offset(x, y), cast_slice_mut(raw));
+    }
+
+    fn write_row(&mut self, x: u32, y: u32, raw: &[T::DataType]) {
+        self.memory.write(self.offset(x, y), cast_slice(raw));
+    }
+}
+
+impl<T, M> Image for MemoryImageBuffer<T, M>
+where
+    T: PixelType + 'static,
+    M: ImageMemory,
+{
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn bytes_per_pixel(&self) -> u32 {
+        size_of::<T::DataType>() as u32
+    }
+
+    fn bits_per_pixel(&self) -> u32 {
+        T::BITS_PER_PIXEL
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> Color {
+        let mut raw = [T::DataType::zero()];
+        self.read_row(x, y, &mut raw);
+
+        T::to_color(raw[0])
+    }
+
+    fn raw(&self) -> Cow<'_, [u8]> {
+        let mut data = vec![T::DataType::zero(); (self.width * self.height) as usize];
+        for (y, row) in data.chunks_mut(self.width as usize).enumerate() {
+            self.read_row(0, y as _, row);
+        }
+
+        Cow::Owned(cast_slice(&data).to_vec())
+    }
+
+    fn colors(&self) -> Vec<Color> {
+        let mut colors = vec![Color::default(); (self.width * self.height) as usize];
+        for (y, row) in colors.chunks_mut(self.width as usize).enumerate() {
+            self.get_row(0, y as _, row);
+        }
+
+        colors
+    }
+
+    fn get_row(&self, x: u32, y: u32, colors: &mut [Color]) {
+        let mut raw = vec![T::DataType::zero(); colors.len()];
+        self.read_row(x, y, &mut raw);
+
+        for (color, raw) in colors.iter_mut().zip(raw) {
+            *color = T::to_color(raw);
+        }
+    }
+}
+
+impl<T, M> ImageBuffer for MemoryImageBuffer<T, M>
+where
+    T: PixelType + 'static,
+    M: ImageMemory,
+{
+    fn put_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        self.write_row(x, y, &[T::from_color(color)]);
+    }
+
+    fn put_pixels(&mut self, x: u32, y: u32, width: u32, colors: &[Color]) {
+        for (row, colors) in colors.chunks(width as usize).enumerate() {
+            let y = y + row as u32;
+            if x >= self.width || y >= self.height {
+                continue;
+            }
+
+            let count = colors.len().min((self.width - x) as usize);
+            let raw = colors[..count].iter().map(|&x| T::from_color(x)).collect::<Vec<_>>();
+
+            self.write_row(x, y, &raw);
+        }
+    }
+}
+
+impl ImageMemory for Vec<u8> {
+    fn read(&self, offset: u32, buf: &mut [u8]) {
+        buf.copy_from_slice(&self[offset as usize..offset as usize + buf.len()]);
+    }
+
+    fn write(&mut self, offset: u32, data: &[u8]) {
+        self[offset as usize..offset as usize + data.len()].copy_from_slice(data);
+    }
+}
+
+/// 8-bit image indexing into a palette.
+pub struct IndexedImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    width: u32,
+    height: u32,
+    bytes_per_line: u32,
+    palette: Vec<Color>,
+    memory: M,
+}
+
+impl<M> IndexedImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    pub fn new(width: u32, height: u32, bytes_per_line: u32, palette: Vec<Color>, memory: M) -> Self {
+        Self {
+            width,
+            height,
+            bytes_per_line,
+            palette,
+            memory,
+        }
+    }
+
+    pub fn palette(&self) -> &[Color] {
+        &self.palette
+    }
+
+    pub fn into_inner(self) -> M {
+        self.memory
+    }
+
+    // index of nearest palette color, as only colors in palette can be stored
+    fn find_index(&self, color: Color) -> u8 {
+        let distance = |x: &Color| {
+            let r = x.r as i32 - color.r as i32;
+            let g = x.g as i32 - color.g as i32;
+            let b = x.b as i32 - color.b as i32;
+
+            r * r + g * g + b * b
+        };
+
+        self.palette
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, x)| distance(x))
+            .map(|(i, _)| i as u8)
+            .unwrap_or(0)
+    }
+
+    fn lookup(&self, index: u8) -> Color {
+        self.palette.get(index as usize).copied().unwrap_or_default()
+    }
+}
+
+impl<M> Image for IndexedImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn bytes_per_pixel(&self) -> u32 {
+        1
+    }
+
+    fn bits_per_pixel(&self) -> u32 {
+        8
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> Color {
+        let mut index = [0];
+        self.memory.read(y * self.bytes_per_line + x, &mut index);
+
+        self.lookup(index[0])
+    }
+
+    fn raw(&self) -> Cow<'_, [u8]> {
+        let mut data = vec![0; (self.width * self.height) as usize];
+        for (y, row) in data.chunks_mut(self.width as usize).enumerate() {
+            self.memory.read(y as u32 * self.bytes_per_line, row);
+        }
+
+        Cow::Owned(data)
+    }
+
+    fn colors(&self) -> Vec<Color> {
+        self.raw().iter().map(|&x| self.lookup(x)).collect()
+    }
+
+    fn get_row(&self, x: u32, y: u32, colors: &mut [Color]) {
+        let mut indices = vec![0; colors.len()];
+        self.memory.read(y * self.bytes_per_line + x, &mut indices);
+
+        for (color, index) in colors.iter_mut().zip(indices) {
+            *color = self.lookup(index);
+        }
+    }
+}
+
+impl<M> ImageBuffer for IndexedImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    fn put_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let index = self.find_index(color);
+        self.memory.write(y * self.bytes_per_line + x, &[index]);
+    }
+
+    fn put_pixels(&mut self, x: u32, y: u32, width: u32, colors: &[Color]) {
+        for (row, colors) in colors.chunks(width as usize).enumerate() {
+            let y = y + row as u32;
+            if x >= self.width || y >= self.height {
+                continue;
+            }
+
+            let count = colors.len().min((self.width - x) as usize);
+            let indices = colors[..count].iter().map(|&x| self.find_index(x)).collect::<Vec<_>>();
+
+            self.memory.write(y * self.bytes_per_line + x, &indices);
+        }
+    }
+}
+
+/// 1-bit image, mostly used as transparency mask. Set bits are opaque black, and cleared bits are transparent.
+/// Pixels are packed from msb.
+pub struct BitmaskImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    width: u32,
+    height: u32,
+    bytes_per_line: u32,
+    memory: M,
+}
+
+impl<M> BitmaskImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    pub fn new(width: u32, height: u32, bytes_per_line: u32, memory: M) -> Self {
+        Self {
+            width,
+            height,
+            bytes_per_line,
+            memory,
+        }
+    }
+
+    pub fn into_inner(self) -> M {
+        self.memory
+    }
+
+    pub fn is_set(&self, x: u32, y: u32) -> bool {
+        let mut byte = [0];
+        self.memory.read(y * self.bytes_per_line + x / 8, &mut byte);
+
+        byte[0] & (0x80 >> (x % 8)) != 0
+    }
+
+    fn set(&mut self, x: u32, y: u32, value: bool) {
+        let offset = y * self.bytes_per_line + x / 8;
+
+        let mut byte = [0];
+        self.memory.read(offset, &mut byte);
+
+        if value {
+            byte[0] |= 0x80 >> (x % 8);
+        } else {
+            byte[0] &= !(0x80 >> (x % 8));
+        }
+
+        self.memory.write(offset, &byte);
+    }
+}
+
+impl<M> Image for BitmaskImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    // we can't express less than a byte, callers should use `raw` with `bytes_per_line` instead
+    fn bytes_per_pixel(&self) -> u32 {
+        0
+    }
+
+    fn bits_per_pixel(&self) -> u32 {
+        1
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> Color {
+        let a = if self.is_set(x, y) { 0xff } else { 0 };
+
+        Color { a, r: 0, g: 0, b: 0 }
+    }
+
+    fn raw(&self) -> Cow<'_, [u8]> {
+        let mut data = vec![0; (self.bytes_per_line * self.height) as usize];
+        self.memory.read(0, &mut data);
+
+        Cow::Owned(data)
+    }
+
+    fn colors(&self) -> Vec<Color> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| self.get_pixel(x, y))
+            .collect()
+    }
+}
+
+impl<M> ImageBuffer for BitmaskImageBuffer<M>
+where
+    M: ImageMemory,
+{
+    fn put_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        self.set(x, y, color.a >= 0x80);
+    }
+
+    fn put_pixels(&mut self, x: u32, y: u32, width: u32, colors: &[Color]) {
+        for (i, color) in colors.iter().enumerate() {
+            self.put_pixel(x + (i as u32 % width), y + (i as u32 / width), *color);
+        }
+    }
+}
+
 pub struct ImageBufferCanvas<T>
 where
     T: ImageBuffer + Image,
@@ -245,17 +733,73 @@ where
     }
 
     fn blend_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if x >= self.image_buffer.width() || y >= self.image_buffer.height() {
+            return;
+        }
+
         let bg = self.image_buffer.get_pixel(x, y);
-        let factor = color.a as f32 / 255.0;
 
-        let computed_color = Color {
+        self.put_pixel(x, y, composite(color, bg, CompositeMode::SourceOver));
+    }
+
+    // composites `colors` onto the row `y` starting from `x`. `None` leaves destination pixel as is.
+    fn composite_row(&mut self, x: u32, y: u32, colors: &[Option<Color>], mode: CompositeMode) {
+        if mode == CompositeMode::Copy && colors.iter().all(Option::is_some) {
+            let colors = colors.iter().map(|x| x.unwrap()).collect::<Vec<_>>();
+            self.image_buffer.put_pixels(x, y, colors.len() as _, &colors);
+
+            return;
+        }
+
+        let mut row = vec![Color::default(); colors.len()];
+        self.image_buffer.get_row(x, y, &mut row);
+
+        for (dst, src) in row.iter_mut().zip(colors) {
+            if let Some(src) = src {
+                *dst = composite(*src, *dst, mode);
+            }
+        }
+
+        self.image_buffer.put_pixels(x, y, row.len() as _, &row);
+    }
+}
+
+fn composite(src: Color, dst: Color, mode: CompositeMode) -> Color {
+    let blend = |src: Color, alpha: u8| {
+        let factor = alpha as f32 / 255.0;
+
+        Color {
             a: 0xff,
-            r: (color.r as f32 * factor + bg.r as f32 * (1.0 - factor)) as u8,
-            g: (color.g as f32 * factor + bg.g as f32 * (1.0 - factor)) as u8,
-            b: (color.b as f32 * factor + bg.b as f32 * (1.0 - factor)) as u8,
-        };
+            r: (src.r as f32 * factor + dst.r as f32 * (1.0 - factor)) as u8,
+            g: (src.g as f32 * factor + dst.g as f32 * (1.0 - factor)) as u8,
+            b: (src.b as f32 * factor + dst.b as f32 * (1.0 - factor)) as u8,
+        }
+    };
+
+    let op = |f: fn(u8, u8) -> u8| Color {
+        a: 0xff,
+        r: f(src.r, dst.r),
+        g: f(src.g, dst.g),
+        b: f(src.b, dst.b),
+    };
+
+    match mode {
+        CompositeMode::SourceOver => blend(src, src.a),
+        CompositeMode::Copy => src,
+        CompositeMode::Xor => op(|a, b| a ^ b),
+        CompositeMode::Or => op(|a, b| a | b),
+        CompositeMode::And => op(|a, b| a & b),
+        CompositeMode::Alpha(alpha) => blend(src, (src.a as u32 * alpha as u32 / 255) as u8),
+    }
+}
 
-        self.put_pixel(x, y, computed_color);
+/// Compares `color` against `color_key` in `src`'s precision, as keys are usually given as 24-bit value.
+pub fn is_color_key(src: &dyn Image, color: Color, color_key: Color) -> bool {
+    match src.bits_per_pixel() {
+        8 => Rgb332Pixel::from_color(color) == Rgb332Pixel::from_color(color_key),
+        12 => Rgb444Pixel::from_color(color) == Rgb444Pixel::from_color(color_key),
+        16 => Rgb565Pixel::from_color(color) == Rgb565Pixel::from_color(color_key),
+        _ => color.r == color_key.r && color.g == color_key.g && color.b == color_key.b,
     }
 }
 
@@ -268,18 +812,27 @@ where
     }
 
     #[allow(clippy::too_many_arguments)]
-    fn draw(&mut self, dx: u32, dy: u32, w: u32, h: u32, src: &dyn Image, sx: u32, sy: u32) {
+    fn draw(&mut self, dx: u32, dy: u32, w: u32, h: u32, src: &dyn Image, sx: u32, sy: u32, mode: CompositeMode, color_key: Option<Color>) {
+        if dx >= self.image_buffer.width() || dy >= self.image_buffer.height() || sx >= src.width() || sy >= src.height() {
+            return;
+        }
+
+        let w = w.min(src.width() - sx).min(self.image_buffer.width() - dx);
+        let h = h.min(src.height() - sy).min(self.image_buffer.height() - dy);
+
+        let mut src_row = vec![Color::default(); w as usize];
         for y in 0..h {
-            for x in 0..w {
-                if sx + x >= src.width() || sy + y >= src.height() {
-                    continue;
-                }
-                if dx + x >= self.image_buffer.width() || dy + y >= self.image_buffer.height() {
-                    continue;
-                }
+            src.get_row(sx, sy + y, &mut src_row);
 
-                self.blend_pixel(dx + x, dy + y, src.get_pixel(sx + x, sy + y));
-            }
+            let colors = src_row
+                .iter()
+                .map(|&color| match color_key {
+                    Some(color_key) if is_color_key(src, color, color_key) => None,
+                    _ => Some(color),
+                })
+                .collect::<Vec<_>>();
+
+            self.composite_row(dx, dy + y, &colors, mode);
         }
     }
 
@@ -367,14 +920,17 @@ where
         }
     }
 
-    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color) {
+    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: Color, mode: CompositeMode) {
+        if x >= self.image_buffer.width() || y >= self.image_buffer.height() {
+            return;
+        }
+
+        let w = w.min(self.image_buffer.width() - x);
+        let h = h.min(self.image_buffer.height() - y);
+
+        let colors = vec![Some(color); w as usize];
         for y in y..y + h {
-            for x in x..x + w {
-                if x >= self.image_buffer.width() || y >= self.image_buffer.height() {
-                    continue;
-                }
-                self.put_pixel(x, y, color);
-            }
+            self.composite_row(x, y, &colors, mode);
         }
     }
 
@@ -384,22 +940,36 @@ where
 }
 
 pub fn decode_image(data: &[u8]) -> Result<Box<dyn Image>> {
+    let format = ImageFormat::detect(data);
+
+    let image = match format {
+        ImageFormat::Wbmp => decoder::decode_wbmp(data).map_err(|x| x.to_string()),
+        ImageFormat::Lbm => decoder::decode_lbm(data).map_err(|x| x.to_string()),
+        ImageFormat::Unknown => Err("unrecognized image format".into()),
+        _ => decode_image_crate(data),
+    }
+    .map_err(|x| WieError::FatalError(format!("Failed to decode {} image: {}", format, x)))?;
+
+    Ok(Box::new(image) as Box<_>)
+}
+
+fn decode_image_crate(data: &[u8]) -> core::result::Result<VecImageBuffer<ArgbPixel>, String> {
     use std::io::Cursor;
 
     let image = ImageReader::new(Cursor::new(&data))
         .with_guessed_format()
-        .map_err(|x| WieError::FatalError(x.to_string()))?
+        .map_err(|x| x.to_string())?
         .decode()
-        .map_err(|x| WieError::FatalError(x.to_string()))?;
+        .map_err(|x| x.to_string())?;
     let rgba = image.into_rgba8();
 
     let data = rgba.pixels().flat_map(|x| [x.0[2], x.0[1], x.0[0], x.0[3]]).collect::<Vec<_>>();
 
-    Ok(Box::new(VecImageBuffer::<ArgbPixel>::from_raw(
+    Ok(VecImageBuffer::<ArgbPixel>::from_raw(
         rgba.width(),
         rgba.height(),
         pod_collect_to_vec(&data),
-    )) as Box<_>)
+    ))
 }
 
 pub fn string_width(string: &str, pt_size: f32) -> f32 {
@@ -408,20 +978,72 @@ pub fn string_width(string: &str, pt_size: f32) -> f32 {
     string.chars().map(|c| font.h_advance(font.scaled_glyph(c).id)).sum::<f32>()
 }
 
+/// Distance from the top of the line to the baseline, in pixels.
+pub fn font_ascent(pt_size: f32) -> u32 {
+    FONT.as_scaled(FONT.pt_to_px_scale(pt_size).unwrap()).ascent().round() as _
+}
+
+/// Height of the line, in pixels.
+pub fn font_height(pt_size: f32) -> u32 {
+    FONT.as_scaled(FONT.pt_to_px_scale(pt_size).unwrap()).height().ceil() as _
+}
+
+/// Renders `string` in `color` onto transparent image as tall as the line, with glyph coverage in alpha.
+pub fn render_text(string: &str, pt_size: f32, color: Color) -> VecImageBuffer<ArgbPixel> {
+    let font = FONT.as_scaled(FONT.pt_to_px_scale(pt_size).unwrap());
+
+    let width = string_width(string, pt_size).ceil() as u32;
+    let height = font_height(pt_size);
+    let mut image = VecImageBuffer::<ArgbPixel>::new(width, height);
+
+    let mut position = 0.0;
+    for c in string.chars() {
+        let mut glyph = font.scaled_glyph(c);
+        let h_advance = font.h_advance(glyph.id);
+
+        if c.is_control() {
+            position += h_advance;
+            continue;
+        }
+
+        glyph.position = point(position, font_ascent(pt_size) as f32);
+        if let Some(outlined_glyph) = font.outline_glyph(glyph) {
+            let bounds = outlined_glyph.px_bounds();
+
+            outlined_glyph.draw(|glyph_x, glyph_y, coverage| {
+                let x = bounds.min.x as i32 + glyph_x as i32;
+                let y = bounds.min.y as i32 + glyph_y as i32;
+
+                if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+                    let a = (coverage.min(1.0) * color.a as f32) as u8;
+                    image.put_pixel(x as _, y as _, Color { a, ..color });
+                }
+            });
+        }
+
+        position += h_advance;
+    }
+
+    image
+}
+
 #[cfg(test)]
 mod tests {
     use wie_util::Result;
 
     use crate::canvas::{Image, ImageBufferCanvas};
 
-    use super::{ArgbPixel, Canvas, Color, VecImageBuffer};
+    use super::{
+        is_color_key, ArgbPixel, BitmaskImageBuffer, Canvas, Color, CompositeMode, IndexedImageBuffer, MemoryImageBuffer, PixelType, Rgb332Pixel,
+        Rgb444Pixel, Rgb565Pixel, VecImageBuffer,
+    };
 
     #[test]
     fn test_canvas() -> Result<()> {
         let image_buffer = VecImageBuffer::<ArgbPixel>::new(10, 10);
         let mut canvas = ImageBufferCanvas::new(image_buffer);
 
-        canvas.fill_rect(0, 0, 10, 10, Color { r: 0, g: 0, b: 0, a: 255 });
+        canvas.fill_rect(0, 0, 10, 10, Color { r: 0, g: 0, b: 0, a: 255 }, CompositeMode::Copy);
 
         let image_buffer = canvas.into_inner();
         let raw = image_buffer.raw();
@@ -436,4 +1058,229 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_composite() -> Result<()> {
+        let background = Color {
+            r: 0xf0,
+            g: 0x0f,
+            b: 0xff,
+            a: 255,
+        };
+        let yellow = Color {
+            r: 0xff,
+            g: 0xff,
+            b: 0,
+            a: 255,
+        };
+        let black = Color { r: 0, g: 0, b: 0, a: 255 };
+
+        let mut canvas = ImageBufferCanvas::new(VecImageBuffer::<ArgbPixel>::new(4, 1));
+        canvas.fill_rect(0, 0, 4, 1, background, CompositeMode::Copy);
+        canvas.fill_rect(0, 0, 1, 1, yellow, CompositeMode::Xor);
+        canvas.fill_rect(1, 0, 1, 1, black, CompositeMode::Alpha(0));
+        canvas.fill_rect(2, 0, 1, 1, yellow, CompositeMode::Or);
+        canvas.fill_rect(3, 0, 1, 1, yellow, CompositeMode::And);
+
+        let image = canvas.into_inner();
+        assert_eq!(
+            image.get_pixel(0, 0),
+            Color {
+                r: 0x0f,
+                g: 0xf0,
+                b: 0xff,
+                a: 255
+            }
+        );
+        assert_eq!(image.get_pixel(1, 0), background);
+        assert_eq!(
+            image.get_pixel(2, 0),
+            Color {
+                r: 0xff,
+                g: 0xff,
+                b: 0xff,
+                a: 255
+            }
+        );
+        assert_eq!(
+            image.get_pixel(3, 0),
+            Color {
+                r: 0xf0,
+                g: 0x0f,
+                b: 0,
+                a: 255
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_color_key() -> Result<()> {
+        let magenta = Color {
+            r: 0xff,
+            g: 0,
+            b: 0xff,
+            a: 255,
+        };
+        let white = Color {
+            r: 0xff,
+            g: 0xff,
+            b: 0xff,
+            a: 255,
+        };
+
+        let mut src = ImageBufferCanvas::new(VecImageBuffer::<Rgb565Pixel>::new(2, 1));
+        src.fill_rect(0, 0, 1, 1, magenta, CompositeMode::Copy);
+        src.fill_rect(1, 0, 1, 1, white, CompositeMode::Copy);
+        let src = src.into_inner();
+
+        let mut canvas = ImageBufferCanvas::new(VecImageBuffer::<ArgbPixel>::new(2, 1));
+        // key with low bits set should still match rgb565 pixel
+        let key = Color {
+            r: 0xfc,
+            g: 0x01,
+            b: 0xfa,
+            a: 255,
+        };
+        canvas.draw(0, 0, 2, 1, &src, 0, 0, CompositeMode::Copy, Some(key));
+
+        let image = canvas.into_inner();
+        assert_eq!(image.get_pixel(0, 0), Color { r: 0, g: 0, b: 0, a: 0 });
+        assert_eq!(image.get_pixel(1, 0), white);
+
+        // rgb444 keeps only 4 bits, so key differing in low bits matches while rgb565 comparison wouldn't
+        let mut src = ImageBufferCanvas::new(VecImageBuffer::<Rgb444Pixel>::new(1, 1));
+        src.fill_rect(0, 0, 1, 1, magenta, CompositeMode::Copy);
+        let src = src.into_inner();
+
+        let key = Color {
+            r: 0xf0,
+            g: 0x0f,
+            b: 0xf0,
+            a: 255,
+        };
+        assert!(is_color_key(&src, src.get_pixel(0, 0), key));
+        assert!(!is_color_key(&VecImageBuffer::<Rgb565Pixel>::new(1, 1), magenta, key));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_image_buffer() -> Result<()> {
+        // 3x2 image with 8 bytes per line, padding should be left untouched
+        let memory = vec![0xaa; 16];
+        let mut canvas = ImageBufferCanvas::new(MemoryImageBuffer::<Rgb565Pixel, _>::new(3, 2, 8, memory));
+
+        canvas.fill_rect(1, 0, 5, 5, Color { r: 0xff, g: 0, b: 0, a: 255 }, CompositeMode::Copy);
+
+        let image_buffer = canvas.into_inner();
+        assert_eq!(image_buffer.get_pixel(0, 1), Rgb565Pixel::to_color(0xaaaa));
+        assert_eq!(image_buffer.get_pixel(2, 1), Color { r: 0xff, g: 0, b: 0, a: 255 });
+        assert_eq!(image_buffer.raw().len(), 3 * 2 * 2);
+
+        let memory = image_buffer.into_inner();
+        assert_eq!(
+            memory,
+            [0xaa, 0xaa, 0x00, 0xf8, 0x00, 0xf8, 0xaa, 0xaa, 0xaa, 0xaa, 0x00, 0xf8, 0x00, 0xf8, 0xaa, 0xaa]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pixel_types() -> Result<()> {
+        let white = Color {
+            a: 0xff,
+            r: 0xff,
+            g: 0xff,
+            b: 0xff,
+        };
+
+        assert_eq!(Rgb444Pixel::from_color(white), 0xfff);
+        assert_eq!(Rgb444Pixel::to_color(0xfff), white);
+        assert_eq!(
+            Rgb444Pixel::to_color(0x800),
+            Color {
+                a: 0xff,
+                r: 0x88,
+                g: 0,
+                b: 0
+            }
+        );
+
+        assert_eq!(VecImageBuffer::<Rgb444Pixel>::new(1, 1).bits_per_pixel(), 12);
+        assert_eq!(VecImageBuffer::<Rgb444Pixel>::new(1, 1).bytes_per_pixel(), 2);
+
+        assert_eq!(Rgb332Pixel::from_color(white), 0xff);
+        assert_eq!(Rgb332Pixel::to_color(0xff), white);
+        assert_eq!(
+            Rgb332Pixel::to_color(0x03),
+            Color {
+                a: 0xff,
+                r: 0,
+                g: 0,
+                b: 0xff
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_indexed_image_buffer() -> Result<()> {
+        let palette = vec![
+            Color { a: 0xff, r: 0, g: 0, b: 0 },
+            Color {
+                a: 0xff,
+                r: 0xff,
+                g: 0,
+                b: 0,
+            },
+            Color {
+                a: 0xff,
+                r: 0,
+                g: 0,
+                b: 0xff,
+            },
+        ];
+        let mut canvas = ImageBufferCanvas::new(IndexedImageBuffer::new(2, 2, 2, palette, vec![0; 4]));
+
+        // nearest palette entry should be used
+        canvas.fill_rect(
+            1,
+            0,
+            1,
+            2,
+            Color {
+                a: 0xff,
+                r: 0x10,
+                g: 0,
+                b: 0xe0,
+            },
+            CompositeMode::Copy,
+        );
+
+        let image_buffer = canvas.into_inner();
+        assert_eq!(image_buffer.get_pixel(1, 1), image_buffer.palette()[2]);
+        assert_eq!(image_buffer.bits_per_pixel(), 8);
+        assert_eq!(image_buffer.into_inner(), [0, 2, 0, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitmask_image_buffer() -> Result<()> {
+        let mut canvas = ImageBufferCanvas::new(BitmaskImageBuffer::new(10, 2, 2, vec![0; 4]));
+
+        canvas.fill_rect(0, 1, 9, 1, Color { a: 0xff, r: 0, g: 0, b: 0 }, CompositeMode::Copy);
+
+        let image_buffer = canvas.into_inner();
+        assert_eq!(image_buffer.get_pixel(0, 0).a, 0);
+        assert_eq!(image_buffer.get_pixel(8, 1).a, 0xff);
+        assert_eq!(image_buffer.bits_per_pixel(), 1);
+        assert_eq!(image_buffer.into_inner(), [0, 0, 0xff, 0x80]);
+
+        Ok(())
+    }
 }
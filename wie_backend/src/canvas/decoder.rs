@@ -0,0 +1,433 @@
+use core::fmt::{self, Display, Formatter};
+
+use image::ImageFormat as ImageCrateFormat;
+
+use super::{ArgbPixel, Color, ImageBuffer, VecImageBuffer};
+
+type DecodeResult<T> = core::result::Result<T, &'static str>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Bmp,
+    Gif,
+    Jpeg,
+    /// Wireless bitmap, type 0
+    Wbmp,
+    /// IFF ILBM, or its chunky variant PBM
+    Lbm,
+    Unknown,
+}
+
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Png => "PNG",
+            Self::Bmp => "BMP",
+            Self::Gif => "GIF",
+            Self::Jpeg => "JPEG",
+            Self::Wbmp => "WBMP",
+            Self::Lbm => "LBM",
+            Self::Unknown => "unknown",
+        };
+
+        f.write_str(name)
+    }
+}
+
+impl ImageFormat {
+    pub fn detect(data: &[u8]) -> Self {
+        match image::guess_format(data) {
+            Ok(ImageCrateFormat::Png) => return Self::Png,
+            Ok(ImageCrateFormat::Bmp) => return Self::Bmp,
+            Ok(ImageCrateFormat::Gif) => return Self::Gif,
+            Ok(ImageCrateFormat::Jpeg) => return Self::Jpeg,
+            _ => {}
+        }
+
+        if data.len() >= 12 && &data[0..4] == b"FORM" && (&data[8..12] == b"ILBM" || &data[8..12] == b"PBM ") {
+            return Self::Lbm;
+        }
+
+        // wbmp doesn't have magic, so we check if header is valid and size matches
+        if let Ok((width, height, offset)) = wbmp_header(data) {
+            if width != 0 && height != 0 && data.len() == offset + width.div_ceil(8) as usize * height as usize {
+                return Self::Wbmp;
+            }
+        }
+
+        Self::Unknown
+    }
+}
+
+fn read_uintvar(data: &[u8], offset: &mut usize) -> DecodeResult<u32> {
+    let mut result = 0u32;
+
+    loop {
+        let byte = *data.get(*offset).ok_or("unexpected end of data")?;
+        *offset += 1;
+
+        result = result.checked_shl(7).ok_or("invalid multi-byte integer")? | (byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+}
+
+fn wbmp_header(data: &[u8]) -> DecodeResult<(u32, u32, usize)> {
+    let mut offset = 0;
+
+    let r#type = read_uintvar(data, &mut offset)?;
+    if r#type != 0 {
+        return Err("unsupported wbmp type");
+    }
+
+    // fix header field, extension headers are not used in type 0
+    let fix_header = *data.get(offset).ok_or("unexpected end of data")?;
+    offset += 1;
+    if fix_header & 0x80 != 0 {
+        return Err("unsupported extension header");
+    }
+
+    let width = read_uintvar(data, &mut offset)?;
+    let height = read_uintvar(data, &mut offset)?;
+
+    Ok((width, height, offset))
+}
+
+pub fn decode_wbmp(data: &[u8]) -> DecodeResult<VecImageBuffer<ArgbPixel>> {
+    let (width, height, offset) = wbmp_header(data)?;
+
+    let stride = width.div_ceil(8) as usize;
+    let body = data.get(offset..offset + stride * height as usize).ok_or("unexpected end of data")?;
+
+    let mut result = VecImageBuffer::<ArgbPixel>::new(width, height);
+    for (y, row) in body.chunks(stride).enumerate() {
+        for x in 0..width {
+            let set = row[x as usize / 8] & (0x80 >> (x % 8)) != 0;
+            let value = if set { 0xff } else { 0 };
+
+            result.put_pixel(
+                x,
+                y as _,
+                Color {
+                    a: 0xff,
+                    r: value,
+                    g: value,
+                    b: value,
+                },
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+struct LbmHeader {
+    width: u16,
+    height: u16,
+    planes: u8,
+    masking: u8,
+    compression: u8,
+    transparent_color: u16,
+}
+
+const LBM_MASK_HAS_MASK: u8 = 1;
+const LBM_MASK_TRANSPARENT_COLOR: u8 = 2;
+
+// ByteRun1 compression used by ILBM body
+fn unpack_byte_run1(data: &[u8], offset: &mut usize, size: usize) -> DecodeResult<Vec<u8>> {
+    let mut result = Vec::with_capacity(size);
+
+    while result.len() < size {
+        let n = *data.get(*offset).ok_or("unexpected end of data")? as i8;
+        *offset += 1;
+
+        match n {
+            0..=127 => {
+                let count = n as usize + 1;
+                let literal = data.get(*offset..*offset + count).ok_or("unexpected end of data")?;
+                result.extend_from_slice(literal);
+                *offset += count;
+            }
+            -127..=-1 => {
+                let byte = *data.get(*offset).ok_or("unexpected end of data")?;
+                result.resize(result.len() + (1 - n as isize) as usize, byte);
+                *offset += 1;
+            }
+            -128 => {}
+        }
+    }
+
+    if result.len() != size {
+        return Err("invalid compressed row");
+    }
+
+    Ok(result)
+}
+
+fn read_row(data: &[u8], offset: &mut usize, size: usize, compression: u8) -> DecodeResult<Vec<u8>> {
+    match compression {
+        0 => {
+            let row = data.get(*offset..*offset + size).ok_or("unexpected end of data")?;
+            *offset += size;
+
+            Ok(row.to_vec())
+        }
+        1 => unpack_byte_run1(data, offset, size),
+        _ => Err("unsupported compression"),
+    }
+}
+
+pub fn decode_lbm(data: &[u8]) -> DecodeResult<VecImageBuffer<ArgbPixel>> {
+    if data.len() < 12 || &data[0..4] != b"FORM" {
+        return Err("not an iff file");
+    }
+    let chunky = &data[8..12] == b"PBM ";
+
+    let mut header = None;
+    let mut palette = Vec::new();
+    let mut body = None;
+
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let id = &data[offset..offset + 4];
+        let size = u32::from_be_bytes(data[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let chunk = data.get(offset + 8..offset + 8 + size).ok_or("truncated chunk")?;
+
+        match id {
+            b"BMHD" => {
+                if chunk.len() < 20 {
+                    return Err("invalid BMHD chunk");
+                }
+
+                header = Some(LbmHeader {
+                    width: u16::from_be_bytes([chunk[0], chunk[1]]),
+                    height: u16::from_be_bytes([chunk[2], chunk[3]]),
+                    planes: chunk[8],
+                    masking: chunk[9],
+                    compression: chunk[10],
+                    transparent_color: u16::from_be_bytes([chunk[12], chunk[13]]),
+                })
+            }
+            b"CMAP" => {
+                palette = chunk
+                    .chunks_exact(3)
+                    .map(|x| Color {
+                        a: 0xff,
+                        r: x[0],
+                        g: x[1],
+                        b: x[2],
+                    })
+                    .collect()
+            }
+            b"BODY" => body = Some(chunk),
+            _ => {}
+        }
+
+        // chunks are padded to even size
+        offset += 8 + size + (size & 1);
+    }
+
+    let header = header.ok_or("missing BMHD chunk")?;
+    let body = body.ok_or("missing BODY chunk")?;
+
+    let (width, height) = (header.width as usize, header.height as usize);
+    let mut result = VecImageBuffer::<ArgbPixel>::new(width as _, height as _);
+
+    let mut offset = 0;
+    for y in 0..height {
+        let indices = if chunky {
+            if header.planes != 8 {
+                return Err("unsupported PBM depth");
+            }
+
+            let row = read_row(body, &mut offset, width + (width & 1), header.compression)?;
+            row[..width].iter().map(|&x| x as u32).collect::<Vec<_>>()
+        } else {
+            let plane_row_size = width.div_ceil(16) * 2;
+            let planes = header.planes as usize + if header.masking == LBM_MASK_HAS_MASK { 1 } else { 0 };
+
+            let mut indices = vec![0u32; width];
+            for plane in 0..planes {
+                let row = read_row(body, &mut offset, plane_row_size, header.compression)?;
+                if plane >= header.planes as usize {
+                    // mask plane, we use transparent color or palette instead
+                    continue;
+                }
+
+                for (x, index) in indices.iter_mut().enumerate() {
+                    if row[x / 8] & (0x80 >> (x % 8)) != 0 {
+                        *index |= 1 << plane;
+                    }
+                }
+            }
+
+            indices
+        };
+
+        for (x, &index) in indices.iter().enumerate() {
+            let color = if header.planes == 24 {
+                Color {
+                    a: 0xff,
+                    r: index as u8,
+                    g: (index >> 8) as u8,
+                    b: (index >> 16) as u8,
+                }
+            } else {
+                let color = *palette.get(index as usize).ok_or("color index out of palette")?;
+                if header.masking == LBM_MASK_TRANSPARENT_COLOR && index == header.transparent_color as u32 {
+                    Color { a: 0, ..color }
+                } else {
+                    color
+                }
+            };
+
+            result.put_pixel(x as _, y as _, color);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::canvas::{decode_image, Color};
+
+    use super::ImageFormat;
+
+    const BLACK: Color = Color { a: 0xff, r: 0, g: 0, b: 0 };
+    const WHITE: Color = Color {
+        a: 0xff,
+        r: 0xff,
+        g: 0xff,
+        b: 0xff,
+    };
+
+    #[test]
+    fn test_wbmp() {
+        let data = include_bytes!("../../test_data/checker.wbmp");
+        assert_eq!(ImageFormat::detect(data), ImageFormat::Wbmp);
+
+        let image = decode_image(data).unwrap();
+
+        assert_eq!(image.width(), 10);
+        assert_eq!(image.height(), 2);
+        assert_eq!(image.get_pixel(0, 0), WHITE);
+        assert_eq!(image.get_pixel(1, 0), BLACK);
+        assert_eq!(image.get_pixel(0, 1), BLACK);
+        assert_eq!(image.get_pixel(9, 1), WHITE);
+    }
+
+    #[test]
+    fn test_ilbm() {
+        let data = include_bytes!("../../test_data/palette.lbm");
+        assert_eq!(ImageFormat::detect(data), ImageFormat::Lbm);
+
+        let image = decode_image(data).unwrap();
+
+        assert_eq!(image.width(), 4);
+        assert_eq!(image.height(), 2);
+        assert_eq!(image.get_pixel(0, 0), Color { a: 0, r: 0, g: 0, b: 0 });
+        assert_eq!(
+            image.get_pixel(1, 0),
+            Color {
+                a: 0xff,
+                r: 0xff,
+                g: 0,
+                b: 0
+            }
+        );
+        assert_eq!(
+            image.get_pixel(2, 0),
+            Color {
+                a: 0xff,
+                r: 0,
+                g: 0xff,
+                b: 0
+            }
+        );
+        assert_eq!(
+            image.get_pixel(3, 0),
+            Color {
+                a: 0xff,
+                r: 0,
+                g: 0,
+                b: 0xff
+            }
+        );
+        assert_eq!(
+            image.get_pixel(0, 1),
+            Color {
+                a: 0xff,
+                r: 0,
+                g: 0,
+                b: 0xff
+            }
+        );
+        assert_eq!(
+            image.get_pixel(3, 1),
+            Color {
+                a: 0xff,
+                r: 0xff,
+                g: 0,
+                b: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_pbm() {
+        let data = include_bytes!("../../test_data/chunky.lbm");
+        assert_eq!(ImageFormat::detect(data), ImageFormat::Lbm);
+
+        let image = decode_image(data).unwrap();
+
+        assert_eq!(image.width(), 3);
+        assert_eq!(image.height(), 1);
+        assert_eq!(image.get_pixel(0, 0), WHITE);
+        assert_eq!(image.get_pixel(1, 0), BLACK);
+        assert_eq!(image.get_pixel(2, 0), WHITE);
+    }
+
+    #[test]
+    fn test_gif() {
+        let data = include_bytes!("../../test_data/pixels.gif");
+        assert_eq!(ImageFormat::detect(data), ImageFormat::Gif);
+
+        let image = decode_image(data).unwrap();
+
+        assert_eq!(image.width(), 2);
+        assert_eq!(image.height(), 1);
+        assert_eq!(image.get_pixel(0, 0), WHITE);
+        assert_eq!(image.get_pixel(1, 0), BLACK);
+    }
+
+    #[test]
+    fn test_jpeg() {
+        let data = include_bytes!("../../test_data/colors.jpg");
+        assert_eq!(ImageFormat::detect(data), ImageFormat::Jpeg);
+
+        let image = decode_image(data).unwrap();
+
+        assert_eq!(image.width(), 16);
+        assert_eq!(image.height(), 8);
+
+        // lossy, so we only check dominant channel
+        let left = image.get_pixel(2, 4);
+        assert!(left.r > 0xc0 && left.b < 0x40);
+        let right = image.get_pixel(13, 4);
+        assert!(right.b > 0xc0 && right.r < 0x40);
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let data = include_bytes!("../../test_data/palette.lbm");
+
+        let error = decode_image(&data[..data.len() - 4]).err().unwrap();
+        assert!(error.to_string().contains("LBM"));
+
+        let error = decode_image(b"not an image").err().unwrap();
+        assert!(error.to_string().contains("unknown"));
+    }
+}
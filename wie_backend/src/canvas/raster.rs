@@ -0,0 +1,284 @@
+// shapes are rasterized in signed coordinates, so callers can translate and clip them before drawing.
+
+/// Horizontal run of pixels on row `y`, from `x_start` inclusive to `x_end` exclusive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Span {
+    pub y: i32,
+    pub x_start: i32,
+    pub x_end: i32,
+}
+
+/// Pixels of the line from (x1, y1) to (x2, y2), including both ends.
+pub fn line_points(x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<(i32, i32)> {
+    let dx = (x2 - x1).abs();
+    let dy = -(y2 - y1).abs();
+    let step_x = if x1 < x2 { 1 } else { -1 };
+    let step_y = if y1 < y2 { 1 } else { -1 };
+
+    let mut result = Vec::with_capacity((dx.max(-dy) + 1) as _);
+
+    let (mut x, mut y) = (x1, y1);
+    let mut error = dx + dy;
+    loop {
+        result.push((x, y));
+        if x == x2 && y == y2 {
+            break;
+        }
+
+        let error2 = error * 2;
+        if error2 >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if error2 <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+
+    result
+}
+
+/// Spans covering the triangle, including its edges.
+pub fn triangle_spans(points: [(i32, i32); 3]) -> Vec<Span> {
+    let min_y = points.iter().map(|x| x.1).min().unwrap();
+    let max_y = points.iter().map(|x| x.1).max().unwrap();
+
+    let edges = [(points[0], points[1]), (points[1], points[2]), (points[2], points[0])];
+
+    (min_y..=max_y)
+        .map(|y| {
+            let mut min_x = f32::MAX;
+            let mut max_x = f32::MIN;
+
+            for &((x1, y1), (x2, y2)) in &edges {
+                if y < y1.min(y2) || y > y1.max(y2) {
+                    continue;
+                }
+
+                let xs = if y1 == y2 {
+                    [x1 as f32, x2 as f32]
+                } else {
+                    let x = x1 as f32 + (y - y1) as f32 * (x2 - x1) as f32 / (y2 - y1) as f32;
+                    [x, x]
+                };
+
+                for x in xs {
+                    min_x = min_x.min(x);
+                    max_x = max_x.max(x);
+                }
+            }
+
+            Span {
+                y,
+                x_start: min_x.round() as i32,
+                x_end: max_x.round() as i32 + 1,
+            }
+        })
+        .collect()
+}
+
+/// Spans covering the pie of the ellipse inscribed in the rectangle, in MIDP angle convention:
+/// degrees counterclockwise from 3 o'clock, negative `arc_angle` going clockwise.
+pub fn arc_spans(x: i32, y: i32, width: i32, height: i32, start_angle: i32, arc_angle: i32) -> Vec<Span> {
+    if width <= 0 || height <= 0 || arc_angle == 0 {
+        return Vec::new();
+    }
+
+    let a = width as f32 / 2.0;
+    let b = height as f32 / 2.0;
+    let center_x = x as f32 + a;
+    let center_y = y as f32 + b;
+
+    let mut result = Vec::new();
+    for py in y..y + height {
+        let mut span_start = None;
+        for px in x..=x + width {
+            let dx = (px as f32 + 0.5 - center_x) / a;
+            let dy = (py as f32 + 0.5 - center_y) / b;
+
+            let inside = px < x + width && dx * dx + dy * dy <= 1.0 && is_in_arc(dx, dy, start_angle, arc_angle);
+            match (inside, span_start) {
+                (true, None) => span_start = Some(px),
+                (false, Some(x_start)) => {
+                    result.push(Span { y: py, x_start, x_end: px });
+                    span_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    result
+}
+
+/// Pixels of the elliptical arc inscribed in the rectangle, which covers `width + 1` by `height + 1` pixels.
+pub fn arc_points(x: i32, y: i32, width: i32, height: i32, start_angle: i32, arc_angle: i32) -> Vec<(i32, i32)> {
+    if width < 0 || height < 0 || arc_angle == 0 {
+        return Vec::new();
+    }
+    if width == 0 || height == 0 {
+        return line_points(x, y, x + width, y + height);
+    }
+
+    let a = width as f32 / 2.0;
+    let b = height as f32 / 2.0;
+    let center_x = x as f32 + a;
+    let center_y = y as f32 + b;
+
+    // sweeping both columns and rows leaves no gaps on steep parts of the curve
+    let mut result = Vec::new();
+    for px in x..=x + width {
+        let t = 1.0 - ((px as f32 - center_x) / a).powi(2);
+        if t >= 0.0 {
+            let dy = b * t.sqrt();
+            result.push((px, (center_y - dy).round() as i32));
+            result.push((px, (center_y + dy).round() as i32));
+        }
+    }
+    for py in y..=y + height {
+        let t = 1.0 - ((py as f32 - center_y) / b).powi(2);
+        if t >= 0.0 {
+            let dx = a * t.sqrt();
+            result.push(((center_x - dx).round() as i32, py));
+            result.push(((center_x + dx).round() as i32, py));
+        }
+    }
+
+    result.sort_unstable();
+    result.dedup();
+    result.retain(|&(px, py)| is_in_arc((px as f32 - center_x) / a, (py as f32 - center_y) / b, start_angle, arc_angle));
+
+    result
+}
+
+/// Spans covering the rectangle with corners rounded by ellipses of `arc_width` by `arc_height`.
+pub fn round_rect_spans(x: i32, y: i32, width: i32, height: i32, arc_width: i32, arc_height: i32) -> Vec<Span> {
+    if width <= 0 || height <= 0 {
+        return Vec::new();
+    }
+
+    let radius_x = arc_width.clamp(0, width) as f32 / 2.0;
+    let radius_y = arc_height.clamp(0, height) as f32 / 2.0;
+
+    (y..y + height)
+        .map(|py| {
+            let center = py as f32 + 0.5;
+            let dy = if center < (y as f32 + radius_y) {
+                y as f32 + radius_y - center
+            } else if center > ((y + height) as f32 - radius_y) {
+                center - ((y + height) as f32 - radius_y)
+            } else {
+                0.0
+            };
+
+            let inset = if dy > 0.0 {
+                (radius_x - radius_x * (1.0 - (dy / radius_y).powi(2)).max(0.0).sqrt()).round() as i32
+            } else {
+                0
+            };
+
+            Span {
+                y: py,
+                x_start: x + inset,
+                x_end: x + width - inset,
+            }
+        })
+        .collect()
+}
+
+/// Pixels of the outline of the rounded rectangle, which covers `width + 1` by `height + 1` pixels.
+pub fn round_rect_points(x: i32, y: i32, width: i32, height: i32, arc_width: i32, arc_height: i32) -> Vec<(i32, i32)> {
+    if width < 0 || height < 0 {
+        return Vec::new();
+    }
+
+    let arc_width = arc_width.clamp(0, width);
+    let arc_height = arc_height.clamp(0, height);
+    let right = x + width - arc_width;
+    let bottom = y + height - arc_height;
+
+    let mut result = Vec::new();
+    result.extend(arc_points(x, y, arc_width, arc_height, 90, 90));
+    result.extend(arc_points(right, y, arc_width, arc_height, 0, 90));
+    result.extend(arc_points(x, bottom, arc_width, arc_height, 180, 90));
+    result.extend(arc_points(right, bottom, arc_width, arc_height, 270, 90));
+
+    result.extend(line_points(x + arc_width / 2, y, x + width - arc_width / 2, y));
+    result.extend(line_points(x + arc_width / 2, y + height, x + width - arc_width / 2, y + height));
+    result.extend(line_points(x, y + arc_height / 2, x, y + height - arc_height / 2));
+    result.extend(line_points(x + width, y + arc_height / 2, x + width, y + height - arc_height / 2));
+
+    result.sort_unstable();
+    result.dedup();
+
+    result
+}
+
+// (dx, dy) is relative to the center of the ellipse, normalized by its radii, with y growing downwards
+fn is_in_arc(dx: f32, dy: f32, start_angle: i32, arc_angle: i32) -> bool {
+    if arc_angle.abs() >= 360 || (dx == 0.0 && dy == 0.0) {
+        return true;
+    }
+
+    let (start_angle, arc_angle) = if arc_angle < 0 {
+        (start_angle + arc_angle, -arc_angle)
+    } else {
+        (start_angle, arc_angle)
+    };
+
+    let angle = (-dy).atan2(dx).to_degrees();
+
+    (angle - start_angle as f32).rem_euclid(360.0) <= arc_angle as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{arc_points, arc_spans, line_points, round_rect_points, triangle_spans, Span};
+
+    #[test]
+    fn test_line() {
+        assert_eq!(line_points(0, 0, 3, 0), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(line_points(2, 2, 0, 0), [(2, 2), (1, 1), (0, 0)]);
+        assert_eq!(line_points(1, 1, 1, 1), [(1, 1)]);
+    }
+
+    #[test]
+    fn test_triangle() {
+        let spans = triangle_spans([(0, 0), (4, 0), (0, 4)]);
+
+        assert_eq!(spans.len(), 5);
+        assert_eq!(spans[0], Span { y: 0, x_start: 0, x_end: 5 });
+        assert_eq!(spans[2], Span { y: 2, x_start: 0, x_end: 3 });
+        assert_eq!(spans[4], Span { y: 4, x_start: 0, x_end: 1 });
+    }
+
+    #[test]
+    fn test_arc() {
+        // full circle covers every row of its bounds
+        let spans = arc_spans(0, 0, 10, 10, 0, 360);
+        assert_eq!(spans.len(), 10);
+        assert!(spans.iter().all(|x| x.x_start >= 0 && x.x_end <= 10));
+        assert_eq!(spans[5], Span { y: 5, x_start: 0, x_end: 10 });
+
+        // upper right quarter only
+        let spans = arc_spans(0, 0, 10, 10, 0, 90);
+        assert!(spans.iter().all(|x| x.y < 5 && x.x_start >= 5));
+
+        // negative arc goes clockwise, to lower right quarter
+        let spans = arc_spans(0, 0, 10, 10, 0, -90);
+        assert!(spans.iter().all(|x| x.y >= 5 && x.x_start >= 5));
+
+        let points = arc_points(0, 0, 10, 10, 0, 360);
+        assert!(points.contains(&(0, 5)) && points.contains(&(10, 5)) && points.contains(&(5, 0)) && points.contains(&(5, 10)));
+        assert!(!points.contains(&(5, 5)));
+    }
+
+    #[test]
+    fn test_round_rect() {
+        let points = round_rect_points(0, 0, 10, 10, 4, 4);
+
+        assert!(points.contains(&(5, 0)) && points.contains(&(0, 5)) && points.contains(&(10, 5)) && points.contains(&(5, 10)));
+        assert!(!points.contains(&(0, 0)) && !points.contains(&(10, 10)));
+    }
+}
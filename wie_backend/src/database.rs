@@ -1,14 +1,52 @@
+use crate::time::Instant;
+
 pub type RecordId = u32;
 
+/// Access mode of database requested by application.
+///
+/// Databases aren't shared between applications, so it's only stored along with database.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AccessMode {
+    pub shared: bool,
+    pub writable: bool,
+}
+
 pub trait Database: Send {
+    /// Adds record with id of [`Database::next_id`]. Ids are never reused, even after deleting last record.
     fn add(&mut self, data: &[u8]) -> RecordId;
     fn get(&self, id: RecordId) -> Option<Vec<u8>>;
     fn set(&mut self, id: RecordId, data: &[u8]) -> bool;
     fn delete(&mut self, id: RecordId) -> bool;
 
     fn get_record_ids(&self) -> Vec<RecordId>;
+
+    /// Id the next added record will get.
+    fn next_id(&self) -> RecordId;
+
+    fn access_mode(&self) -> AccessMode;
+    fn set_access_mode(&mut self, mode: AccessMode) -> bool;
+
+    fn get_record_size(&self, id: RecordId) -> Option<usize> {
+        self.get(id).map(|x| x.len())
+    }
+
+    fn count(&self) -> usize {
+        self.get_record_ids().len()
+    }
+
+    /// Modification counter, incremented on every add, set or delete.
+    fn version(&self) -> u32;
+
+    /// Time of the last add, set or delete, `None` if never modified.
+    fn last_modified(&self) -> Option<Instant>;
 }
 
 pub trait DatabaseRepository {
     fn open(&self, name: &str, app_id: &str) -> Box<dyn Database>;
+    fn list(&self, app_id: &str) -> Vec<String>;
+    fn delete(&self, name: &str, app_id: &str) -> bool;
+
+    fn exists(&self, name: &str, app_id: &str) -> bool {
+        self.list(app_id).iter().any(|x| x == name)
+    }
 }
@@ -6,17 +6,22 @@ mod database;
 mod executor;
 mod platform;
 mod screen;
+mod storage;
 mod system;
 mod task;
 mod time;
 
 pub use self::{
-    audio_sink::AudioSink,
-    database::{Database, DatabaseRepository, RecordId},
+    audio_sink::{AudioRecording, AudioSink, MidiLogAudioSink, RecordingAudioSink, SynthAudioSink},
+    database::{AccessMode, Database, DatabaseRepository, RecordId},
     executor::{AsyncCallable, AsyncCallableResult},
-    platform::Platform,
-    screen::Screen,
-    system::{Event, KeyCode, System},
+    platform::{BacklightState, Platform},
+    screen::{Rect, Screen},
+    storage::{MemoryStorage, Storage, StorageRepository},
+    system::{
+        AudioFormat, AudioHandle, Event, FileHandle, FileMetadata, FilesystemError, HandsetProfile, Indicators, KeyCode, PlaybackEvent,
+        PlaybackState, System, Tone, Waveform,
+    },
     time::Instant,
 };
 
@@ -1,9 +1,36 @@
-use crate::{audio_sink::AudioSink, database::DatabaseRepository, screen::Screen, time::Instant};
+use crate::{
+    audio_sink::AudioSink,
+    database::DatabaseRepository,
+    screen::Screen,
+    storage::StorageRepository,
+    system::{HandsetProfile, Indicators},
+    time::Instant,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BacklightState {
+    /// Device default, which is usually on while in use
+    Default,
+    On,
+    Off,
+}
 
 pub trait Platform: Send {
     fn screen(&mut self) -> &mut dyn Screen;
     fn now(&self) -> Instant;
     fn database_repository(&self) -> &dyn DatabaseRepository;
+    fn storage_repository(&self) -> &dyn StorageRepository;
     fn audio_sink(&self) -> Box<dyn AudioSink>;
     fn write_stdout(&self, buf: &[u8]);
+    /// Vibrates for `duration` milliseconds with `level` from 0 to 100. Zero `duration` stops vibration.
+    fn vibrate(&mut self, _duration: u32, _level: u8) {}
+    /// Sets backlight to `state`, returning to default after `timeout` milliseconds if given.
+    fn set_backlight(&mut self, _state: BacklightState, _timeout: Option<u32>) {}
+    /// Indicator hardware of emulated handset, queried once on startup.
+    fn handset_profile(&self) -> HandsetProfile {
+        HandsetProfile::default()
+    }
+    /// Called when annunciator or led state changes.
+    /// Application draws below the area reserved for annunciator, so platforms should offset screen content by its height.
+    fn update_indicators(&mut self, _indicators: &Indicators) {}
 }
@@ -1,10 +1,89 @@
-use crate::canvas::Image;
+use crate::canvas::{Color, Image};
 
 use wie_util::Result;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Creates rect from signed coordinates, clipped to `width` x `height` bounds.
+    /// Returns `None` if resulting rect is empty.
+    pub fn clipped(x: i32, y: i32, width: i32, height: i32, bound_width: u32, bound_height: u32) -> Option<Self> {
+        let left = x.max(0) as i64;
+        let top = y.max(0) as i64;
+        let right = (x as i64 + width as i64).min(bound_width as i64);
+        let bottom = (y as i64 + height as i64).min(bound_height as i64);
+
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some(Self::new(left as _, top as _, (right - left) as _, (bottom - top) as _))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn union(&self, other: &Rect) -> Rect {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+
+        Rect::new(left, top, right - left, bottom - top)
+    }
+}
+
 pub trait Screen: Send {
     fn request_redraw(&self) -> Result<()>;
     fn paint(&mut self, image: &dyn Image);
+    /// Paints only `region` of `image`, which has the same size as the screen.
+    /// Frontends may override this to avoid uploading unchanged pixels.
+    fn paint_region(&mut self, image: &dyn Image, _region: Rect) {
+        self.paint(image)
+    }
     fn width(&self) -> u32;
     fn height(&self) -> u32;
+    /// Bits per pixel of the display, which framebuffers given to applications use.
+    fn depth(&self) -> u32 {
+        16
+    }
+    /// Palette of 8-bit indexed color display. `None` for direct color display.
+    fn palette(&self) -> Option<Vec<Color>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rect;
+
+    #[test]
+    fn test_rect() {
+        assert_eq!(Rect::clipped(-10, -10, 20, 20, 240, 320), Some(Rect::new(0, 0, 10, 10)));
+        assert_eq!(Rect::clipped(230, 310, 20, 20, 240, 320), Some(Rect::new(230, 310, 10, 10)));
+        assert_eq!(Rect::clipped(250, 0, 20, 20, 240, 320), None);
+        assert_eq!(Rect::clipped(0, 0, 0, 20, 240, 320), None);
+
+        let rect = Rect::new(10, 10, 10, 10).union(&Rect::new(0, 30, 5, 5));
+        assert_eq!(rect, Rect::new(0, 10, 20, 25));
+        assert_eq!(Rect::new(0, 0, 0, 0).union(&rect), rect);
+    }
 }
@@ -0,0 +1,129 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::time::Instant;
+
+type Clock = Box<dyn Fn() -> Instant + Send>;
+
+/// Writable file storage of an application, which persists files created at runtime.
+///
+/// Paths are relative and normalized, using `/` as separator.
+pub trait Storage: Send {
+    fn read(&self, path: &str) -> Option<Vec<u8>>;
+    /// Returns size of file without reading it, `None` if it doesn't exist or is a directory.
+    fn size(&self, path: &str) -> Option<u64>;
+    /// Returns creation time of file or directory, `None` if it doesn't exist or storage doesn't keep it.
+    fn created(&self, path: &str) -> Option<Instant>;
+    fn write(&mut self, path: &str, data: &[u8]) -> bool;
+    /// Writes `data` at `offset` of file, creating it if missing. Gap after end of file is filled with zeros.
+    fn write_at(&mut self, path: &str, offset: u64, data: &[u8]) -> bool;
+    /// Removes file or empty directory.
+    fn remove(&mut self, path: &str) -> bool;
+    fn create_dir(&mut self, path: &str) -> bool;
+    fn is_dir(&self, path: &str) -> bool;
+    /// Returns names of entries in directory, `""` being the root.
+    fn list(&self, path: &str) -> Vec<String>;
+}
+
+pub trait StorageRepository {
+    fn open(&self, app_id: &str) -> Box<dyn Storage>;
+}
+
+/// Storage kept in memory, discarded when dropped.
+#[derive(Default)]
+pub struct MemoryStorage {
+    files: BTreeMap<String, Vec<u8>>,
+    directories: BTreeSet<String>,
+    // creation times are kept only if clock is given
+    clock: Option<Clock>,
+    created: BTreeMap<String, Instant>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates storage keeping creation time of entries, taken from `clock`.
+    pub fn with_clock<C>(clock: C) -> Self
+    where
+        C: Fn() -> Instant + Send + 'static,
+    {
+        Self {
+            clock: Some(Box::new(clock)),
+            ..Default::default()
+        }
+    }
+
+    fn mark_created(&mut self, path: &str) {
+        if let Some(clock) = &self.clock {
+            self.created.entry(path.to_string()).or_insert_with(clock);
+        }
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, path: &str) -> Option<Vec<u8>> {
+        self.files.get(path).cloned()
+    }
+
+    fn size(&self, path: &str) -> Option<u64> {
+        self.files.get(path).map(|x| x.len() as _)
+    }
+
+    fn created(&self, path: &str) -> Option<Instant> {
+        self.created.get(path).copied()
+    }
+
+    fn write(&mut self, path: &str, data: &[u8]) -> bool {
+        self.mark_created(path);
+        self.files.insert(path.to_string(), data.to_vec());
+
+        true
+    }
+
+    fn write_at(&mut self, path: &str, offset: u64, data: &[u8]) -> bool {
+        self.mark_created(path);
+        let file = self.files.entry(path.to_string()).or_default();
+
+        let end = offset as usize + data.len();
+        if file.len() < end {
+            file.resize(end, 0);
+        }
+        file[offset as usize..end].copy_from_slice(data);
+
+        true
+    }
+
+    fn remove(&mut self, path: &str) -> bool {
+        self.created.remove(path);
+
+        self.files.remove(path).is_some() || self.directories.remove(path)
+    }
+
+    fn create_dir(&mut self, path: &str) -> bool {
+        self.mark_created(path);
+
+        self.directories.insert(path.to_string())
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        self.directories.contains(path)
+    }
+
+    fn list(&self, path: &str) -> Vec<String> {
+        let prefix = if path.is_empty() { String::new() } else { path.to_string() + "/" };
+
+        self.files
+            .keys()
+            .chain(self.directories.iter())
+            .filter_map(|x| x.strip_prefix(&prefix))
+            .filter(|x| !x.contains('/'))
+            .map(|x| x.to_string())
+            .collect()
+    }
+}
@@ -1,6 +1,7 @@
 mod audio;
 mod event_queue;
 mod file_system;
+mod indicators;
 
 use alloc::sync::Arc;
 use std::sync::{Mutex, MutexGuard, RwLock, RwLockWriteGuard};
@@ -8,6 +9,7 @@ use std::sync::{Mutex, MutexGuard, RwLock, RwLockWriteGuard};
 use wie_util::Result;
 
 use crate::{
+    canvas::Color,
     executor::{AsyncCallableResult, Executor},
     platform::Platform,
     task::{SleepFuture, YieldFuture},
@@ -16,7 +18,12 @@ use crate::{
 
 use self::{audio::Audio, event_queue::EventQueue, file_system::Filesystem};
 
-pub use self::event_queue::{Event, KeyCode};
+pub use self::{
+    audio::{AudioFormat, AudioHandle, PlaybackEvent, PlaybackState, Tone, Waveform},
+    event_queue::{Event, KeyCode},
+    file_system::{FileHandle, FileMetadata, FilesystemError},
+    indicators::{HandsetProfile, Indicators},
+};
 
 #[derive(Clone)]
 pub struct System {
@@ -25,12 +32,15 @@ pub struct System {
     platform: Arc<Mutex<Box<dyn Platform>>>,
     filesystem: Arc<Mutex<Filesystem>>,
     event_queue: Arc<RwLock<EventQueue>>,
+    indicators: Arc<Mutex<Indicators>>,
     audio: Option<Arc<RwLock<Audio>>>,
 }
 
 impl System {
     pub fn new(platform: Box<dyn Platform>, app_id: &str) -> Self {
         let audio_sink = platform.audio_sink();
+        let storage = platform.storage_repository().open(app_id);
+        let indicators = Indicators::new(platform.handset_profile());
 
         let platform = Arc::new(Mutex::new(platform));
 
@@ -38,8 +48,9 @@ impl System {
             app_id: app_id.to_owned(),
             executor: Executor::new(),
             platform: platform.clone(),
-            filesystem: Arc::new(Mutex::new(Filesystem::new())),
+            filesystem: Arc::new(Mutex::new(Filesystem::new(storage))),
             event_queue: Arc::new(RwLock::new(EventQueue::new())),
+            indicators: Arc::new(Mutex::new(indicators)),
             audio: None,
         };
 
@@ -97,4 +108,43 @@ impl System {
     pub fn event_queue(&self) -> RwLockWriteGuard<'_, EventQueue> {
         self.event_queue.write().unwrap()
     }
+
+    pub fn indicators(&self) -> Indicators {
+        self.indicators.lock().unwrap().clone()
+    }
+
+    /// Updates indicators with `f` and notifies platform of the new state.
+    pub fn update_indicators<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Indicators),
+    {
+        let indicators = {
+            let mut indicators = self.indicators.lock().unwrap();
+            f(&mut indicators);
+
+            indicators.clone()
+        };
+
+        self.platform().update_indicators(&indicators);
+    }
+
+    /// Size of screen area available to application, excluding area reserved for annunciator.
+    pub fn display_size(&self) -> (u32, u32) {
+        let annunciator_height = self.indicators.lock().unwrap().annunciator_height;
+
+        let mut platform = self.platform();
+        let screen = platform.screen();
+
+        (screen.width(), screen.height().saturating_sub(annunciator_height))
+    }
+
+    /// Bits per pixel of the display.
+    pub fn display_depth(&self) -> u32 {
+        self.platform().screen().depth()
+    }
+
+    /// Palette of the display, if it uses indexed colors.
+    pub fn display_palette(&self) -> Option<Vec<Color>> {
+        self.platform().screen().palette()
+    }
 }
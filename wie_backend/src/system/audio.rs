@@ -1,26 +1,138 @@
-use alloc::{collections::BTreeMap, sync::Arc};
-use core::time::Duration;
+mod smf;
+mod tone;
+mod tone_sequence;
+mod wave;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+use core::{
+    future::{poll_fn, Future},
+    mem,
+    pin::pin,
+    task::Poll,
+    time::Duration,
+};
+use std::sync::Mutex;
 
 use smaf_player::{AudioBackend, SmafPlayer};
 
 use crate::{audio_sink::AudioSink, System};
 
+use self::{
+    smf::SmfPlayer,
+    tone::{synthesize, TONE_SAMPLING_RATE},
+    tone_sequence::ToneSequencePlayer,
+    wave::WavePlayer,
+};
+
+pub use self::tone::{Tone, Waveform};
+
+// pause and stop requests are checked at least this often while sleeping
+const POLL_INTERVAL: u64 = 16;
+const MAX_VOLUME: u8 = 100;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlaybackState {
+    Stopped,
+    Playing,
+    Paused,
+}
+
+/// How playback has ended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlaybackEvent {
+    /// Reached the end of data after all repeats.
+    Finished,
+    /// Stopped by `Audio::stop` or restarted by `Audio::play`.
+    Stopped,
+}
+
+struct PlaybackStatus {
+    state: PlaybackState,
+    // incremented on every play and seek, so that tasks of previous playback can notice they're outdated
+    generation: u64,
+    // incremented on every play, kept across seek so that completion waits for the end of the same playback
+    playback: u64,
+    volume: u8,
+    started_at: u64,
+    paused_at: Option<u64>,
+    paused_duration: u64,
+    // position in data where current repetition started, set by seek
+    offset: u64,
+    // repetitions left including current one, `None` repeats until stopped
+    remaining_loops: Option<u32>,
+    last_event: Option<PlaybackEvent>,
+    // (channel, note) of midi notes currently sounding, released on pause or stop
+    notes: BTreeSet<(u8, u8)>,
+}
+
+impl PlaybackStatus {
+    fn position(&self, now: u64) -> u64 {
+        match self.state {
+            PlaybackState::Stopped => self.offset,
+            PlaybackState::Playing => self.offset + now - self.started_at - self.paused_duration,
+            PlaybackState::Paused => self.offset + self.paused_at.unwrap() - self.started_at - self.paused_duration,
+        }
+    }
+}
+
+type SharedStatus = Arc<Mutex<PlaybackStatus>>;
+
 struct AudioBackendImpl {
     system: System,
-    sink: Box<dyn AudioSink>,
+    sink: Arc<dyn AudioSink>,
+    voice: AudioHandle,
+    status: SharedStatus,
+    generation: u64,
+}
+
+impl AudioBackendImpl {
+    fn state(&self) -> PlaybackState {
+        let status = self.status.lock().unwrap();
+        if status.generation != self.generation {
+            return PlaybackState::Stopped;
+        }
+
+        status.state
+    }
+
+    fn is_playing(&self) -> bool {
+        self.state() == PlaybackState::Playing
+    }
 }
 
 #[async_trait::async_trait]
 impl AudioBackend for AudioBackendImpl {
     fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
-        self.sink.play_wave(channel, sampling_rate, wave_data);
+        if self.is_playing() {
+            self.sink.play_voice(self.voice, channel, sampling_rate, wave_data);
+        }
     }
 
     fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
-        self.sink.midi_note_on(channel_id, note, velocity);
+        if self.is_playing() {
+            let volume = {
+                let mut status = self.status.lock().unwrap();
+                // note on with zero velocity is note off
+                if velocity == 0 {
+                    status.notes.remove(&(channel_id, note));
+                } else {
+                    status.notes.insert((channel_id, note));
+                }
+
+                status.volume
+            };
+            let velocity = (velocity as u32 * volume as u32 / MAX_VOLUME as u32) as u8;
+
+            self.sink.midi_note_on(channel_id, note, velocity);
+        }
     }
 
     fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.status.lock().unwrap().notes.remove(&(channel_id, note));
+
         self.sink.midi_note_off(channel_id, note, velocity);
     }
 
@@ -33,14 +145,26 @@ impl AudioBackend for AudioBackendImpl {
     }
 
     async fn sleep(&self, duration: Duration) {
-        let now = self.system.platform().now();
-        let end = now + duration.as_millis() as _;
+        let mut remaining = duration.as_millis() as u64;
+
+        // paused time doesn't count
+        while remaining > 0 {
+            let step = remaining.min(POLL_INTERVAL);
+            let now = self.system.platform().now();
+            self.system.clone().sleep(now + step).await;
 
-        self.system.clone().sleep(end).await
+            match self.state() {
+                PlaybackState::Playing => remaining -= step,
+                PlaybackState::Paused => {}
+                PlaybackState::Stopped => break,
+            }
+        }
     }
 
     fn now_millis(&self) -> u64 {
-        self.system.platform().now().raw()
+        let paused_duration = self.status.lock().unwrap().paused_duration;
+
+        self.system.platform().now().raw() - paused_duration
     }
 }
 
@@ -51,47 +175,718 @@ pub enum AudioError {
     InvalidAudio,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AudioFormat {
+    Smaf,
+    /// Standard midi file
+    Smf,
+    /// RIFF wave
+    Wave,
+    /// MIDP tone sequence
+    ToneSequence,
+}
+
+impl AudioFormat {
+    /// Detects format from content, falling back to `hint` which may be mime type, file extension or WIPI media type name.
+    pub fn detect(data: &[u8], hint: Option<&str>) -> Option<Self> {
+        if data.starts_with(b"MMMD") {
+            return Some(Self::Smaf);
+        }
+        if data.starts_with(b"MThd") {
+            return Some(Self::Smf);
+        }
+        if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
+            return Some(Self::Wave);
+        }
+        // tone sequence starts with VERSION(-2) 1
+        if data.starts_with(&[0xfe, 0x01]) {
+            return Some(Self::ToneSequence);
+        }
+
+        let hint = hint?.to_ascii_lowercase();
+        if hint.contains("tone") {
+            Some(Self::ToneSequence)
+        } else if hint.contains("mid") {
+            Some(Self::Smf)
+        } else if hint.contains("wav") {
+            Some(Self::Wave)
+        } else if ["mmf", "smaf", "ma2", "ma3", "ma5"].iter().any(|x| hint.contains(x)) {
+            Some(Self::Smaf)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone)]
 enum AudioFile {
     Smaf(SmafPlayer),
+    Smf(SmfPlayer),
+    Wave(WavePlayer),
+    ToneSequence(ToneSequencePlayer),
+}
+
+impl AudioFile {
+    fn new(data: &[u8], format: AudioFormat) -> Result<Self, AudioError> {
+        Ok(match format {
+            AudioFormat::Smaf => Self::Smaf(SmafPlayer::new(data.to_vec())),
+            AudioFormat::Smf => Self::Smf(SmfPlayer::new(data)?),
+            AudioFormat::Wave => Self::Wave(WavePlayer::new(data)?),
+            AudioFormat::ToneSequence => Self::ToneSequence(ToneSequencePlayer::new(data)?),
+        })
+    }
+
+    // smaf player can't tell its length nor start from the middle
+    fn duration(&self) -> Option<u64> {
+        match self {
+            Self::Smaf(_) => None,
+            Self::Smf(x) => Some(x.duration()),
+            Self::Wave(x) => Some(x.duration()),
+            Self::ToneSequence(x) => Some(x.duration()),
+        }
+    }
+
+    async fn play(&self, backend: &AudioBackendImpl, from: u64) {
+        match self {
+            Self::Smaf(x) => x.play(backend).await,
+            Self::Smf(x) => x.play(backend, from).await,
+            Self::Wave(x) => x.play(backend, from).await,
+            Self::ToneSequence(x) => x.play(backend, from).await,
+        }
+    }
+}
+
+struct AudioClip {
+    file: AudioFile,
+    status: SharedStatus,
+}
+
+/// Waits for the end of a playback, obtained from [`Audio::completion`].
+pub struct PlaybackCompletion {
+    status: SharedStatus,
+    playback: u64,
+}
+
+impl PlaybackCompletion {
+    pub async fn wait(self, system: &mut System) -> PlaybackEvent {
+        loop {
+            {
+                let status = self.status.lock().unwrap();
+                if status.playback != self.playback {
+                    return PlaybackEvent::Stopped;
+                }
+                if status.state == PlaybackState::Stopped {
+                    return status.last_event.unwrap_or(PlaybackEvent::Stopped);
+                }
+            }
+
+            let now = system.platform().now();
+            system.sleep(now + POLL_INTERVAL).await;
+        }
+    }
 }
 
 pub struct Audio {
     system: System,
-    backend: Arc<AudioBackendImpl>,
-    files: BTreeMap<AudioHandle, AudioFile>,
+    sink: Arc<dyn AudioSink>,
+    clips: BTreeMap<AudioHandle, AudioClip>,
     last_audio_handle: AudioHandle,
+    // shared clip for `play_tone`
+    tone_handle: Option<AudioHandle>,
 }
 
 impl Audio {
     pub fn new(sink: Box<dyn AudioSink>, system: System) -> Self {
         Self {
-            system: system.clone(),
-            backend: Arc::new(AudioBackendImpl { sink, system }),
-            files: BTreeMap::new(),
-            last_audio_handle: 0,
+            system,
+            sink: Arc::from(sink),
+            clips: BTreeMap::new(),
+            last_audio_handle: 1, // 0 is reserved for invalid handle
+            tone_handle: None,
         }
     }
 
-    pub fn load_smaf(&mut self, data: &[u8]) -> Result<AudioHandle, AudioError> {
-        let audio_handle = self.last_audio_handle;
+    /// Loads audio data, detecting its format from content or `hint`. See [`AudioFormat::detect`].
+    pub fn load(&mut self, data: &[u8], hint: Option<&str>) -> Result<AudioHandle, AudioError> {
+        let format = AudioFormat::detect(data, hint).ok_or(AudioError::InvalidAudio)?;
 
+        Ok(self.insert(AudioFile::new(data, format)?))
+    }
+
+    /// Loads tone sequence, rendered with `waveform`.
+    pub fn load_tones(&mut self, tones: &[Tone], waveform: Waveform) -> Result<AudioHandle, AudioError> {
+        Ok(self.insert(Self::tone_file(tones, waveform)))
+    }
+
+    /// Plays tone sequence once without allocating a clip. Previous tones played by this method are stopped.
+    pub fn play_tone(&mut self, tones: &[Tone], waveform: Waveform) -> Result<(), AudioError> {
+        let file = Self::tone_file(tones, waveform);
+
+        let handle = match self.tone_handle {
+            Some(x) => {
+                self.stop(x)?;
+                self.clips.get_mut(&x).unwrap().file = file;
+
+                x
+            }
+            None => {
+                let handle = self.insert(file);
+                self.tone_handle = Some(handle);
+
+                handle
+            }
+        };
+
+        self.play(handle, Some(1))
+    }
+
+    fn tone_file(tones: &[Tone], waveform: Waveform) -> AudioFile {
+        AudioFile::Wave(WavePlayer::from_samples(1, TONE_SAMPLING_RATE, synthesize(tones, waveform)))
+    }
+
+    fn insert(&mut self, file: AudioFile) -> AudioHandle {
+        let audio_handle = self.last_audio_handle;
         self.last_audio_handle += 1;
-        self.files.insert(audio_handle, AudioFile::Smaf(SmafPlayer::new(data.to_vec())));
 
-        Ok(audio_handle)
+        let status = PlaybackStatus {
+            state: PlaybackState::Stopped,
+            generation: 0,
+            playback: 0,
+            volume: MAX_VOLUME,
+            started_at: 0,
+            paused_at: None,
+            paused_duration: 0,
+            offset: 0,
+            remaining_loops: None,
+            last_event: None,
+            notes: BTreeSet::new(),
+        };
+        self.clips.insert(
+            audio_handle,
+            AudioClip {
+                file,
+                status: Arc::new(Mutex::new(status)),
+            },
+        );
+
+        audio_handle
+    }
+
+    /// Stops and releases clip, invalidating `audio_handle`.
+    pub fn unload(&mut self, audio_handle: AudioHandle) -> Result<(), AudioError> {
+        self.stop(audio_handle)?;
+        self.clips.remove(&audio_handle);
+
+        if self.tone_handle == Some(audio_handle) {
+            self.tone_handle = None;
+        }
+
+        Ok(())
+    }
+
+    /// Starts playback from the beginning, or from the position set by [`Audio::seek`] while stopped, stopping previous one.
+    /// Data is played `loop_count` times, or until stopped if `loop_count` is `None`.
+    pub fn play(&mut self, audio_handle: AudioHandle, loop_count: Option<u32>) -> Result<(), AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let from = {
+            let mut status = clip.status.lock().unwrap();
+            status.playback += 1;
+
+            if status.state == PlaybackState::Stopped {
+                status.offset
+            } else {
+                0
+            }
+        };
+
+        self.stop(audio_handle)?;
+        self.start(audio_handle, loop_count, from, false)
+    }
+
+    /// Moves position of current repetition to `position` milliseconds, returning actual position.
+    /// Position is clamped to the length of data, and only rewinding to the beginning is possible for smaf.
+    pub fn seek(&mut self, audio_handle: AudioHandle, position: u64) -> Result<u64, AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let position = clip.file.duration().map(|x| position.min(x)).unwrap_or(0);
+
+        let (state, remaining_loops) = {
+            let mut status = clip.status.lock().unwrap();
+            if status.state == PlaybackState::Stopped {
+                status.offset = position;
+
+                return Ok(position);
+            }
+
+            (status.state, status.remaining_loops)
+        };
+
+        self.silence(audio_handle);
+        self.start(audio_handle, remaining_loops, position, state == PlaybackState::Paused)?;
+
+        Ok(position)
     }
 
-    pub fn play(&self, audio_handle: AudioHandle) -> Result<(), AudioError> {
-        match self.files.get(&audio_handle) {
-            Some(AudioFile::Smaf(player)) => {
-                let player_clone = player.clone();
-                let backend = self.backend.clone();
+    fn start(&mut self, audio_handle: AudioHandle, loop_count: Option<u32>, from: u64, paused: bool) -> Result<(), AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let now = self.system.platform().now().raw();
+
+        let generation = {
+            let mut status = clip.status.lock().unwrap();
+            status.generation += 1;
+            status.state = if paused { PlaybackState::Paused } else { PlaybackState::Playing };
+            status.started_at = now;
+            status.paused_at = paused.then_some(now);
+            status.paused_duration = 0;
+            status.offset = from;
+            status.remaining_loops = loop_count;
+            status.last_event = None;
+
+            status.generation
+        };
+
+        let backend = AudioBackendImpl {
+            system: self.system.clone(),
+            sink: self.sink.clone(),
+            voice: audio_handle,
+            status: clip.status.clone(),
+            generation,
+        };
+
+        let file = clip.file.clone();
+        self.system.clone().spawn(move || async move {
+            // players send data as soon as they start, which would be dropped while paused
+            while backend.state() == PlaybackState::Paused {
+                let now = backend.system.platform().now();
+                backend.system.clone().sleep(now + POLL_INTERVAL).await;
+            }
+
+            let mut from = from;
+            while backend.status.lock().unwrap().remaining_loops != Some(0) {
+                Self::run_until_stopped(&backend, file.play(&backend, from)).await;
+
+                if backend.state() == PlaybackState::Stopped {
+                    return;
+                }
+
+                from = 0;
+                let now = backend.system.platform().now().raw();
+                let mut status = backend.status.lock().unwrap();
+                status.started_at = now;
+                status.paused_duration = 0;
+                status.offset = 0;
+                status.remaining_loops = status.remaining_loops.map(|x| x - 1);
+            }
 
-                self.system.clone().spawn(move || async move { player_clone.play(&*backend).await });
+            let mut status = backend.status.lock().unwrap();
+            if status.generation == generation {
+                status.state = PlaybackState::Stopped;
+                status.offset = 0;
+                status.last_event = Some(PlaybackEvent::Finished);
             }
-            None => return Err(AudioError::InvalidHandle),
+        });
+
+        Ok(())
+    }
+
+    // drops `future` as soon as playback is stopped
+    async fn run_until_stopped<F>(backend: &AudioBackendImpl, future: F) -> Option<F::Output>
+    where
+        F: Future,
+    {
+        let mut future = pin!(future);
+
+        poll_fn(|cx| {
+            if backend.state() == PlaybackState::Stopped {
+                return Poll::Ready(None);
+            }
+
+            future.as_mut().poll(cx).map(Some)
+        })
+        .await
+    }
+
+    pub fn stop(&mut self, audio_handle: AudioHandle) -> Result<(), AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+
+        {
+            let mut status = clip.status.lock().unwrap();
+            if status.state == PlaybackState::Stopped {
+                return Ok(());
+            }
+
+            status.state = PlaybackState::Stopped;
+            status.offset = 0;
+            status.last_event = Some(PlaybackEvent::Stopped);
         }
 
+        self.silence(audio_handle);
+
         Ok(())
     }
+
+    pub fn pause(&mut self, audio_handle: AudioHandle) -> Result<(), AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let now = self.system.platform().now().raw();
+
+        {
+            let mut status = clip.status.lock().unwrap();
+            if status.state != PlaybackState::Playing {
+                return Ok(());
+            }
+
+            status.state = PlaybackState::Paused;
+            status.paused_at = Some(now);
+        }
+
+        self.silence(audio_handle);
+
+        Ok(())
+    }
+
+    pub fn resume(&mut self, audio_handle: AudioHandle) -> Result<(), AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let now = self.system.platform().now().raw();
+
+        let mut status = clip.status.lock().unwrap();
+        if status.state == PlaybackState::Paused {
+            status.paused_duration += now - status.paused_at.take().unwrap();
+            status.state = PlaybackState::Playing;
+        }
+
+        Ok(())
+    }
+
+    /// `volume` ranges from 0 to 100.
+    pub fn set_volume(&mut self, audio_handle: AudioHandle, volume: u8) -> Result<(), AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let volume = volume.min(MAX_VOLUME);
+
+        clip.status.lock().unwrap().volume = volume;
+        self.sink.set_voice_volume(audio_handle, volume);
+
+        Ok(())
+    }
+
+    pub fn volume(&self, audio_handle: AudioHandle) -> Result<u8, AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+
+        Ok(clip.status.lock().unwrap().volume)
+    }
+
+    pub fn state(&self, audio_handle: AudioHandle) -> Result<PlaybackState, AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+
+        Ok(clip.status.lock().unwrap().state)
+    }
+
+    /// Length of data in milliseconds, or `None` if unknown.
+    pub fn duration(&self, audio_handle: AudioHandle) -> Result<Option<u64>, AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+
+        Ok(clip.file.duration())
+    }
+
+    /// Position in current repetition in milliseconds, excluding paused time.
+    pub fn position(&self, audio_handle: AudioHandle) -> Result<u64, AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let now = self.system.platform().now().raw();
+
+        Ok(clip.status.lock().unwrap().position(now))
+    }
+
+    /// Returns waiter for the end of current playback.
+    /// Waiting on stopped clip returns immediately with its last event.
+    pub fn completion(&self, audio_handle: AudioHandle) -> Result<PlaybackCompletion, AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let playback = clip.status.lock().unwrap().playback;
+
+        Ok(PlaybackCompletion {
+            status: clip.status.clone(),
+            playback,
+        })
+    }
+
+    // releases wave voice and midi notes of the clip, leaving other clips sounding
+    fn silence(&self, audio_handle: AudioHandle) {
+        self.sink.stop_voice(audio_handle);
+
+        let notes = match self.clips.get(&audio_handle) {
+            Some(clip) => mem::take(&mut clip.status.lock().unwrap().notes),
+            None => return,
+        };
+        for (channel, note) in notes {
+            self.sink.midi_note_off(channel, note, 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, sync::Arc, vec::Vec};
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+
+    use crate::{AudioSink, DatabaseRepository, Instant, MemoryStorage, Platform, Screen, Storage, StorageRepository, System};
+
+    use super::{AudioHandle, PlaybackEvent, PlaybackState, POLL_INTERVAL};
+
+    // tone sequence playing `note` for 2 seconds
+    fn tones(note: u8) -> [u8; 4] {
+        [0xfe, 0x01, note, 64]
+    }
+
+    #[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+    enum SinkEvent {
+        NoteOn(u8, u8),
+        NoteOff(u8, u8),
+        StopVoice(u32),
+        VoiceVolume(u32, u8),
+    }
+
+    #[derive(Clone, Default)]
+    struct TestAudioSink(Arc<Mutex<Vec<SinkEvent>>>);
+
+    impl TestAudioSink {
+        fn take(&self) -> Vec<SinkEvent> {
+            core::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    impl AudioSink for TestAudioSink {
+        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {}
+
+        fn stop_voice(&self, voice: u32) {
+            self.0.lock().unwrap().push(SinkEvent::StopVoice(voice));
+        }
+
+        fn set_voice_volume(&self, voice: u32, volume: u8) {
+            self.0.lock().unwrap().push(SinkEvent::VoiceVolume(voice, volume));
+        }
+
+        fn midi_note_on(&self, channel_id: u8, note: u8, _velocity: u8) {
+            self.0.lock().unwrap().push(SinkEvent::NoteOn(channel_id, note));
+        }
+
+        fn midi_note_off(&self, channel_id: u8, note: u8, _velocity: u8) {
+            self.0.lock().unwrap().push(SinkEvent::NoteOff(channel_id, note));
+        }
+
+        fn midi_program_change(&self, _channel_id: u8, _program: u8) {}
+
+        fn midi_control_change(&self, _channel_id: u8, _control: u8, _value: u8) {}
+    }
+
+    struct TestPlatform {
+        time: Arc<AtomicU64>,
+        sink: TestAudioSink,
+    }
+
+    impl StorageRepository for TestPlatform {
+        fn open(&self, _app_id: &str) -> Box<dyn Storage> {
+            Box::new(MemoryStorage::new())
+        }
+    }
+
+    impl Platform for TestPlatform {
+        fn screen(&mut self) -> &mut dyn Screen {
+            unimplemented!()
+        }
+
+        fn now(&self) -> Instant {
+            Instant::from_epoch_millis(self.time.load(Ordering::SeqCst))
+        }
+
+        fn database_repository(&self) -> &dyn DatabaseRepository {
+            unimplemented!()
+        }
+
+        fn storage_repository(&self) -> &dyn StorageRepository {
+            self
+        }
+
+        fn audio_sink(&self) -> Box<dyn AudioSink> {
+            Box::new(self.sink.clone())
+        }
+
+        fn write_stdout(&self, _buf: &[u8]) {}
+    }
+
+    struct TestSystem {
+        system: System,
+        time: Arc<AtomicU64>,
+        sink: TestAudioSink,
+    }
+
+    impl TestSystem {
+        fn new() -> Self {
+            let time = Arc::new(AtomicU64::new(1000));
+            let sink = TestAudioSink::default();
+            let mut system = System::new(
+                Box::new(TestPlatform {
+                    time: time.clone(),
+                    sink: sink.clone(),
+                }),
+                "test",
+            );
+
+            // executor doesn't return from tick without sleeping task while clock is stopped
+            let mut system_clone = system.clone();
+            system.spawn(move || async move { system_clone.sleep(Instant::from_epoch_millis(u64::MAX)).await });
+
+            Self { system, time, sink }
+        }
+
+        fn load(&mut self, note: u8) -> AudioHandle {
+            self.system.audio().load(&tones(note), None).unwrap()
+        }
+
+        // advances clock by `duration` milliseconds, running tasks on every poll interval
+        fn run_for(&mut self, duration: u64) {
+            for _ in 0..duration / POLL_INTERVAL {
+                self.time.fetch_add(POLL_INTERVAL, Ordering::SeqCst);
+                self.system.tick().unwrap();
+            }
+        }
+
+        fn completion(&mut self, handle: AudioHandle) -> Arc<Mutex<Option<PlaybackEvent>>> {
+            let result = Arc::new(Mutex::new(None));
+
+            let completion = self.system.audio().completion(handle).unwrap();
+            let (mut system, result_clone) = (self.system.clone(), result.clone());
+            self.system
+                .spawn(move || async move { *result_clone.lock().unwrap() = Some(completion.wait(&mut system).await) });
+
+            result
+        }
+    }
+
+    #[test]
+    fn test_pause_resume() {
+        let mut test = TestSystem::new();
+        let handle = test.load(60);
+
+        test.system.audio().play(handle, Some(1)).unwrap();
+        test.run_for(496);
+        assert_eq!(test.sink.take(), [SinkEvent::NoteOn(0, 60)]);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 496);
+
+        test.system.audio().pause(handle).unwrap();
+        assert_eq!(test.system.audio().state(handle).unwrap(), PlaybackState::Paused);
+        assert_eq!(test.sink.take(), [SinkEvent::StopVoice(handle), SinkEvent::NoteOff(0, 60)]);
+
+        // paused time doesn't count
+        test.run_for(992);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 496);
+        assert_eq!(test.system.audio().state(handle).unwrap(), PlaybackState::Paused);
+
+        test.system.audio().resume(handle).unwrap();
+        test.run_for(208);
+        assert_eq!(test.system.audio().state(handle).unwrap(), PlaybackState::Playing);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 704);
+
+        // completion is polled, so give it another interval to notice the end
+        let completion = test.completion(handle);
+        test.run_for(1312 + POLL_INTERVAL);
+        assert_eq!(test.system.audio().state(handle).unwrap(), PlaybackState::Stopped);
+        assert_eq!(*completion.lock().unwrap(), Some(PlaybackEvent::Finished));
+    }
+
+    #[test]
+    fn test_stop() {
+        let mut test = TestSystem::new();
+        let first = test.load(60);
+        let second = test.load(64);
+
+        test.system.audio().play(first, None).unwrap();
+        test.system.audio().play(second, None).unwrap();
+        test.run_for(96);
+
+        let mut events = test.sink.take();
+        events.sort();
+        assert_eq!(events, [SinkEvent::NoteOn(0, 60), SinkEvent::NoteOn(0, 64)]);
+
+        let completion = test.completion(first);
+
+        // notes of other clips keep sounding
+        test.system.audio().stop(first).unwrap();
+        assert_eq!(test.sink.take(), [SinkEvent::StopVoice(first), SinkEvent::NoteOff(0, 60)]);
+        assert_eq!(test.system.audio().state(first).unwrap(), PlaybackState::Stopped);
+        assert_eq!(test.system.audio().position(first).unwrap(), 0);
+
+        test.run_for(96);
+        assert_eq!(*completion.lock().unwrap(), Some(PlaybackEvent::Stopped));
+        assert_eq!(test.system.audio().state(second).unwrap(), PlaybackState::Playing);
+        assert!(test.sink.take().is_empty());
+
+        // repeats until stopped
+        test.run_for(4000);
+        assert_eq!(test.system.audio().state(second).unwrap(), PlaybackState::Playing);
+        assert!(test.system.audio().position(second).unwrap() <= 192);
+
+        test.system.audio().stop(second).unwrap();
+        assert_eq!(test.system.audio().state(second).unwrap(), PlaybackState::Stopped);
+
+        test.system.audio().unload(second).unwrap();
+        assert!(test.system.audio().state(second).is_err());
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut test = TestSystem::new();
+        let handle = test.load(60);
+
+        assert_eq!(test.system.audio().duration(handle).unwrap(), Some(2000));
+
+        // seeking stopped clip sets starting position of next play
+        assert_eq!(test.system.audio().seek(handle, 500).unwrap(), 500);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 500);
+
+        test.system.audio().play(handle, Some(1)).unwrap();
+        test.run_for(496);
+        assert_eq!(test.sink.take(), [SinkEvent::NoteOn(0, 60)]);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 996);
+
+        // paused clip stays paused at new position
+        test.system.audio().pause(handle).unwrap();
+        test.sink.take();
+        assert_eq!(test.system.audio().seek(handle, 1000).unwrap(), 1000);
+        assert_eq!(test.system.audio().state(handle).unwrap(), PlaybackState::Paused);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 1000);
+
+        test.run_for(96);
+        assert_eq!(test.sink.take(), [SinkEvent::StopVoice(handle)]);
+
+        test.system.audio().resume(handle).unwrap();
+        test.run_for(496);
+        assert_eq!(test.sink.take(), [SinkEvent::NoteOn(0, 60)]);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 1496);
+
+        // completion waited before seek is kept, and seeking past the end finishes playback
+        let completion = test.completion(handle);
+        assert_eq!(test.system.audio().seek(handle, 5000).unwrap(), 2000);
+        test.run_for(POLL_INTERVAL * 2);
+        assert_eq!(test.system.audio().state(handle).unwrap(), PlaybackState::Stopped);
+        assert_eq!(test.system.audio().position(handle).unwrap(), 0);
+        assert_eq!(*completion.lock().unwrap(), Some(PlaybackEvent::Finished));
+    }
+
+    #[test]
+    fn test_volume() {
+        let mut test = TestSystem::new();
+        let handle = test.load(60);
+
+        assert_eq!(test.system.audio().volume(handle).unwrap(), 100);
+
+        test.system.audio().set_volume(handle, 50).unwrap();
+        assert_eq!(test.system.audio().volume(handle).unwrap(), 50);
+
+        test.system.audio().set_volume(handle, 200).unwrap();
+        assert_eq!(test.system.audio().volume(handle).unwrap(), 100);
+
+        assert_eq!(
+            test.sink.take(),
+            [SinkEvent::VoiceVolume(handle, 50), SinkEvent::VoiceVolume(handle, 100)]
+        );
+        assert!(test.system.audio().set_volume(handle + 1, 50).is_err());
+    }
 }
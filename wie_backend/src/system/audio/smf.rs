@@ -0,0 +1,328 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::time::Duration;
+
+use smaf_player::AudioBackend;
+
+use super::AudioError;
+
+const DEFAULT_TEMPO: u64 = 500_000; // microseconds per quarter note, 120 bpm
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum MidiEvent {
+    NoteOn { channel: u8, note: u8, velocity: u8 },
+    NoteOff { channel: u8, note: u8, velocity: u8 },
+    ProgramChange { channel: u8, program: u8 },
+    ControlChange { channel: u8, control: u8, value: u8 },
+}
+
+enum TrackEvent {
+    Midi(MidiEvent),
+    Tempo(u64),
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn read_u8(&mut self) -> Result<u8, AudioError> {
+        let result = *self.data.get(self.offset).ok_or(AudioError::InvalidAudio)?;
+        self.offset += 1;
+
+        Ok(result)
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], AudioError> {
+        let result = self.data.get(self.offset..self.offset + length).ok_or(AudioError::InvalidAudio)?;
+        self.offset += length;
+
+        Ok(result)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, AudioError> {
+        Ok(u32::from_be_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn read_variable_length(&mut self) -> Result<u32, AudioError> {
+        let mut result = 0;
+        for _ in 0..4 {
+            let byte = self.read_u8()?;
+            result = (result << 7) | (byte & 0x7f) as u32;
+
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+
+        Err(AudioError::InvalidAudio)
+    }
+}
+
+fn parse_track(data: &[u8]) -> Result<Vec<(u64, TrackEvent)>, AudioError> {
+    let mut reader = Reader::new(data);
+    let mut events = Vec::new();
+    let mut tick = 0u64;
+    let mut running_status = None;
+
+    while !reader.is_empty() {
+        tick += reader.read_variable_length()? as u64;
+
+        let mut status = reader.read_u8()?;
+        let first = if status < 0x80 {
+            // running status, the byte we've read is first data byte
+            let data = status;
+            status = running_status.ok_or(AudioError::InvalidAudio)?;
+
+            Some(data)
+        } else {
+            None
+        };
+
+        match status {
+            0x80..=0xef => {
+                running_status = Some(status);
+
+                let channel = status & 0x0f;
+                let data1 = match first {
+                    Some(x) => x,
+                    None => reader.read_u8()?,
+                };
+                let data2 = if matches!(status & 0xf0, 0xc0 | 0xd0) { 0 } else { reader.read_u8()? };
+
+                let event = match status & 0xf0 {
+                    0x80 => Some(MidiEvent::NoteOff {
+                        channel,
+                        note: data1,
+                        velocity: data2,
+                    }),
+                    0x90 if data2 == 0 => Some(MidiEvent::NoteOff {
+                        channel,
+                        note: data1,
+                        velocity: 0,
+                    }),
+                    0x90 => Some(MidiEvent::NoteOn {
+                        channel,
+                        note: data1,
+                        velocity: data2,
+                    }),
+                    0xb0 => Some(MidiEvent::ControlChange {
+                        channel,
+                        control: data1,
+                        value: data2,
+                    }),
+                    0xc0 => Some(MidiEvent::ProgramChange { channel, program: data1 }),
+                    _ => None, // aftertouch and pitch bend are not supported by audio sinks
+                };
+
+                if let Some(x) = event {
+                    events.push((tick, TrackEvent::Midi(x)));
+                }
+            }
+            0xf0 | 0xf7 => {
+                let length = reader.read_variable_length()?;
+                reader.read_bytes(length as _)?;
+            }
+            0xff => {
+                let r#type = reader.read_u8()?;
+                let length = reader.read_variable_length()?;
+                let data = reader.read_bytes(length as _)?;
+
+                match r#type {
+                    0x2f => break,
+                    0x51 if data.len() == 3 => {
+                        let tempo = ((data[0] as u64) << 16) | ((data[1] as u64) << 8) | data[2] as u64;
+                        events.push((tick, TrackEvent::Tempo(tempo)));
+                    }
+                    _ => {}
+                }
+            }
+            _ => return Err(AudioError::InvalidAudio),
+        }
+    }
+
+    Ok(events)
+}
+
+/// Player for standard midi files, format 0 and 1.
+#[derive(Clone)]
+pub struct SmfPlayer {
+    // (time in milliseconds, event)
+    events: Arc<Vec<(u64, MidiEvent)>>,
+}
+
+impl SmfPlayer {
+    pub fn new(data: &[u8]) -> Result<Self, AudioError> {
+        let mut reader = Reader::new(data);
+
+        if reader.read_bytes(4)? != b"MThd" {
+            return Err(AudioError::InvalidAudio);
+        }
+        let header_length = reader.read_u32()?;
+        let header = reader.read_bytes(header_length as _)?;
+        if header.len() < 6 {
+            return Err(AudioError::InvalidAudio);
+        }
+        let format = u16::from_be_bytes([header[0], header[1]]);
+        let division = u16::from_be_bytes([header[4], header[5]]);
+        if format > 1 || division == 0 {
+            return Err(AudioError::InvalidAudio);
+        }
+
+        // merge all tracks, keeping order of events within the same tick
+        let mut events = Vec::new();
+        while !reader.is_empty() {
+            let id = reader.read_bytes(4)?;
+            let length = reader.read_u32()?;
+            let chunk = reader.read_bytes(length as _)?;
+
+            if id == b"MTrk" {
+                events.extend(parse_track(chunk)?);
+            }
+        }
+        events.sort_by_key(|x| x.0);
+
+        let events = Self::to_milliseconds(events, division);
+
+        Ok(Self { events: Arc::new(events) })
+    }
+
+    fn to_milliseconds(events: Vec<(u64, TrackEvent)>, division: u16) -> Vec<(u64, MidiEvent)> {
+        // smpte division has negative frames per second in upper byte
+        let smpte_tick_length = if division & 0x8000 != 0 {
+            let fps = ((division >> 8) as i8).unsigned_abs() as u64;
+            let ticks_per_frame = (division & 0xff) as u64;
+
+            Some(1_000_000 / (fps * ticks_per_frame).max(1))
+        } else {
+            None
+        };
+
+        let mut tempo = DEFAULT_TEMPO;
+        let mut last_tick = 0;
+        let mut time = 0; // in microseconds
+
+        events
+            .into_iter()
+            .filter_map(|(tick, event)| {
+                time += match smpte_tick_length {
+                    Some(x) => (tick - last_tick) * x,
+                    None => (tick - last_tick) * tempo / division as u64,
+                };
+                last_tick = tick;
+
+                match event {
+                    TrackEvent::Midi(x) => Some((time / 1000, x)),
+                    TrackEvent::Tempo(x) => {
+                        tempo = x;
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Time of the last event in milliseconds.
+    pub fn duration(&self) -> u64 {
+        self.events.last().map(|x| x.0).unwrap_or(0)
+    }
+
+    /// Plays events from `from` milliseconds.
+    pub async fn play(&self, backend: &dyn AudioBackend, from: u64) {
+        let start = backend.now_millis();
+
+        for (time, event) in self.events.iter() {
+            if *time < from {
+                // skipped notes are not sounded, but channel setup is still needed for the rest
+                match *event {
+                    MidiEvent::ProgramChange { channel, program } => backend.midi_program_change(channel, program),
+                    MidiEvent::ControlChange { channel, control, value } => backend.midi_control_change(channel, control, value),
+                    _ => {}
+                }
+                continue;
+            }
+
+            let now = backend.now_millis();
+            if start + time - from > now {
+                backend.sleep(Duration::from_millis(start + time - from - now)).await;
+            }
+
+            match *event {
+                MidiEvent::NoteOn { channel, note, velocity } => backend.midi_note_on(channel, note, velocity),
+                MidiEvent::NoteOff { channel, note, velocity } => backend.midi_note_off(channel, note, velocity),
+                MidiEvent::ProgramChange { channel, program } => backend.midi_program_change(channel, program),
+                MidiEvent::ControlChange { channel, control, value } => backend.midi_control_change(channel, control, value),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn events(&self) -> &[(u64, MidiEvent)] {
+        &self.events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MidiEvent, SmfPlayer};
+
+    #[test]
+    fn test_smf() {
+        #[rustfmt::skip]
+        let data = [
+            b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 1, 0, 2, 0, 96,
+            // tempo track, 60 bpm
+            b'M', b'T', b'r', b'k', 0, 0, 0, 11,
+            0x00, 0xff, 0x51, 0x03, 0x0f, 0x42, 0x40,
+            0x00, 0xff, 0x2f, 0x00,
+            // note track with running status
+            b'M', b'T', b'r', b'k', 0, 0, 0, 15,
+            0x00, 0xc0, 0x10,
+            0x00, 0x90, 0x3c, 0x64,
+            0x60, 0x3c, 0x00,
+            0x81, 0x40, 0x80, 0x3e, 0x40,
+        ];
+
+        let player = SmfPlayer::new(&data).unwrap();
+
+        assert_eq!(
+            player.events(),
+            [
+                (0, MidiEvent::ProgramChange { channel: 0, program: 0x10 }),
+                (
+                    0,
+                    MidiEvent::NoteOn {
+                        channel: 0,
+                        note: 0x3c,
+                        velocity: 0x64
+                    }
+                ),
+                (
+                    1000,
+                    MidiEvent::NoteOff {
+                        channel: 0,
+                        note: 0x3c,
+                        velocity: 0
+                    }
+                ),
+                (
+                    3000,
+                    MidiEvent::NoteOff {
+                        channel: 0,
+                        note: 0x3e,
+                        velocity: 0x40
+                    }
+                ),
+            ]
+        );
+        assert_eq!(player.duration(), 3000);
+    }
+}
@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+use core::f32::consts::PI;
+
+pub const TONE_SAMPLING_RATE: u32 = 16000;
+
+const MAX_AMPLITUDE: f32 = 0.5;
+// length of fade in and out of each tone, to avoid clicks between tones
+const RAMP_SAMPLES: usize = 32;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Waveform {
+    Square,
+    Sine,
+}
+
+/// Single step of tone sequence, playing up to two frequencies at once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tone {
+    /// In Hz, 0 is silent
+    pub high_frequency: f32,
+    /// In Hz, 0 is silent
+    pub low_frequency: f32,
+    /// In milliseconds
+    pub duration: u32,
+    /// 0 to 100
+    pub volume: u8,
+}
+
+impl Tone {
+    /// Single tone of midi `note`, where 69 is A4.
+    pub fn note(note: u8, duration: u32, volume: u8) -> Self {
+        Self {
+            high_frequency: 440.0 * 2f32.powf((note as f32 - 69.0) / 12.0),
+            low_frequency: 0.0,
+            duration,
+            volume,
+        }
+    }
+
+    pub fn silence(duration: u32) -> Self {
+        Self {
+            high_frequency: 0.0,
+            low_frequency: 0.0,
+            duration,
+            volume: 0,
+        }
+    }
+}
+
+struct Oscillator {
+    phase: f32, // 0..1
+}
+
+impl Oscillator {
+    fn next(&mut self, frequency: f32, waveform: Waveform) -> f32 {
+        let value = match waveform {
+            Waveform::Square => {
+                if self.phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Sine => (self.phase * 2.0 * PI).sin(),
+        };
+
+        self.phase = (self.phase + frequency / TONE_SAMPLING_RATE as f32).fract();
+
+        value
+    }
+}
+
+/// Renders `tones` into mono pcm at [`TONE_SAMPLING_RATE`].
+pub fn synthesize(tones: &[Tone], waveform: Waveform) -> Vec<i16> {
+    // phase is kept across tones so that repeated frequencies continue smoothly
+    let mut oscillators = [Oscillator { phase: 0.0 }, Oscillator { phase: 0.0 }];
+    let mut result = Vec::new();
+
+    for tone in tones {
+        let length = (tone.duration as u64 * TONE_SAMPLING_RATE as u64 / 1000) as usize;
+        let frequencies = [tone.high_frequency, tone.low_frequency];
+
+        let voices = frequencies.iter().filter(|&&x| x > 0.0).count();
+        if voices == 0 || tone.volume == 0 {
+            result.resize(result.len() + length, 0);
+            continue;
+        }
+
+        let amplitude = MAX_AMPLITUDE * tone.volume.min(100) as f32 / 100.0 / voices as f32;
+        let ramp = RAMP_SAMPLES.min(length / 2);
+
+        for i in 0..length {
+            let value = oscillators
+                .iter_mut()
+                .zip(frequencies)
+                .filter(|(_, frequency)| *frequency > 0.0)
+                .map(|(oscillator, frequency)| oscillator.next(frequency, waveform))
+                .sum::<f32>();
+
+            let envelope = if i < ramp {
+                i as f32 / ramp as f32
+            } else if i >= length - ramp {
+                (length - i) as f32 / ramp as f32
+            } else {
+                1.0
+            };
+
+            result.push((value * amplitude * envelope * i16::MAX as f32) as i16);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{synthesize, Tone, Waveform, TONE_SAMPLING_RATE};
+
+    #[test]
+    fn test_note() {
+        let tone = Tone::note(69, 10, 100);
+        assert_eq!(tone.high_frequency, 440.0);
+
+        let tone = Tone::note(81, 10, 100);
+        assert_eq!(tone.high_frequency, 880.0);
+    }
+
+    #[test]
+    fn test_square() {
+        // 4 samples per period
+        let tone = Tone {
+            high_frequency: TONE_SAMPLING_RATE as f32 / 4.0,
+            low_frequency: 0.0,
+            duration: 100,
+            volume: 100,
+        };
+        let result = synthesize(&[tone, Tone::silence(10)], Waveform::Square);
+
+        assert_eq!(result.len(), 1760);
+        assert_eq!(result[0], 0); // ramp
+        assert_eq!(&result[800..804], [16383, 16383, -16383, -16383]);
+        assert!(result[1600..].iter().all(|&x| x == 0));
+    }
+
+    #[test]
+    fn test_dual_frequency() {
+        let tone = Tone {
+            high_frequency: TONE_SAMPLING_RATE as f32 / 4.0,
+            low_frequency: TONE_SAMPLING_RATE as f32 / 8.0,
+            duration: 100,
+            volume: 50,
+        };
+        let result = synthesize(&[tone], Waveform::Sine);
+
+        assert_eq!(result.len(), 1600);
+        assert!(result.iter().all(|&x| x.unsigned_abs() <= i16::MAX as u16 / 4));
+        assert!(result.iter().any(|&x| x.unsigned_abs() > i16::MAX as u16 / 8));
+    }
+}
@@ -0,0 +1,235 @@
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::time::Duration;
+
+use smaf_player::AudioBackend;
+
+use super::AudioError;
+
+// constants from javax.microedition.media.control.ToneControl
+const VERSION: i8 = -2;
+const TEMPO: i8 = -3;
+const RESOLUTION: i8 = -4;
+const BLOCK_START: i8 = -5;
+const BLOCK_END: i8 = -6;
+const PLAY_BLOCK: i8 = -7;
+const SET_VOLUME: i8 = -8;
+const REPEAT: i8 = -9;
+const SILENCE: i8 = -1;
+
+const DEFAULT_TEMPO: u32 = 120; // beats per minute
+const DEFAULT_RESOLUTION: u32 = 64; // 1/64 note
+const MAX_BLOCK_DEPTH: usize = 16;
+
+const TONE_CHANNEL: u8 = 0;
+const TONE_PROGRAM: u8 = 80; // square lead
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Tone {
+    // `None` for silence
+    note: Option<u8>,
+    duration: u64, // in milliseconds
+    volume: u8,    // 0..=100
+}
+
+struct Parser<'a> {
+    data: &'a [i8],
+    blocks: BTreeMap<i8, &'a [i8]>,
+    tone_length: u64, // milliseconds per resolution unit, multiplied by 1000 to keep precision
+    volume: u8,
+    tones: Vec<Tone>,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(data: &'a [i8]) -> Result<Vec<Tone>, AudioError> {
+        let mut parser = Self {
+            data,
+            blocks: BTreeMap::new(),
+            tone_length: 0,
+            volume: 100,
+            tones: Vec::new(),
+        };
+
+        if data.len() < 2 || data[0] != VERSION || data[1] != 1 {
+            return Err(AudioError::InvalidAudio);
+        }
+
+        let mut offset = 2;
+        let mut tempo = DEFAULT_TEMPO;
+        let mut resolution = DEFAULT_RESOLUTION;
+        if offset < data.len() && parser.get(offset)? == TEMPO {
+            tempo = parser.get(offset + 1)?.max(0) as u32 * 4;
+            offset += 2;
+        }
+        if offset < data.len() && parser.get(offset)? == RESOLUTION {
+            resolution = parser.get(offset + 1)?.max(0) as u32;
+            offset += 2;
+        }
+        if tempo == 0 || resolution == 0 {
+            return Err(AudioError::InvalidAudio);
+        }
+
+        // duration of whole note is 4 beats
+        parser.tone_length = 240_000_000 / (tempo as u64 * resolution as u64);
+
+        while offset < data.len() && parser.get(offset)? == BLOCK_START {
+            let id = parser.get(offset + 1)?;
+            let start = offset + 2;
+            let end = (start..data.len() - 1)
+                .step_by(2)
+                .find(|&x| data[x] == BLOCK_END && data[x + 1] == id)
+                .ok_or(AudioError::InvalidAudio)?;
+
+            parser.blocks.insert(id, &data[start..end]);
+            offset = end + 2;
+        }
+
+        parser.parse_sequence(&data[offset..], 0)?;
+
+        Ok(parser.tones)
+    }
+
+    fn get(&self, offset: usize) -> Result<i8, AudioError> {
+        self.data.get(offset).copied().ok_or(AudioError::InvalidAudio)
+    }
+
+    fn parse_sequence(&mut self, sequence: &[i8], depth: usize) -> Result<(), AudioError> {
+        if depth > MAX_BLOCK_DEPTH {
+            return Err(AudioError::InvalidAudio);
+        }
+
+        let mut repeat = 1;
+        for event in sequence.chunks(2) {
+            let &[r#type, value] = event else {
+                return Err(AudioError::InvalidAudio);
+            };
+
+            match r#type {
+                REPEAT => {
+                    repeat = value.max(0) as usize;
+                    continue;
+                }
+                PLAY_BLOCK => {
+                    let block = *self.blocks.get(&value).ok_or(AudioError::InvalidAudio)?;
+                    for _ in 0..repeat {
+                        self.parse_sequence(block, depth + 1)?;
+                    }
+                }
+                SET_VOLUME => self.volume = value.clamp(0, 100) as u8,
+                SILENCE | 0.. => {
+                    let tone = Tone {
+                        note: if r#type == SILENCE { None } else { Some(r#type as u8) },
+                        duration: value.max(0) as u64 * self.tone_length / 1000,
+                        volume: self.volume,
+                    };
+                    self.tones.resize(self.tones.len() + repeat, tone);
+                }
+                _ => return Err(AudioError::InvalidAudio),
+            }
+
+            repeat = 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Player for MIDP tone sequences, `audio/x-tone-seq`.
+#[derive(Clone)]
+pub struct ToneSequencePlayer {
+    tones: Arc<Vec<Tone>>,
+}
+
+impl ToneSequencePlayer {
+    pub fn new(data: &[u8]) -> Result<Self, AudioError> {
+        let data = data.iter().map(|&x| x as i8).collect::<Vec<_>>();
+
+        Ok(Self {
+            tones: Arc::new(Parser::parse(&data)?),
+        })
+    }
+
+    /// Total length of tones in milliseconds.
+    pub fn duration(&self) -> u64 {
+        self.tones.iter().map(|x| x.duration).sum()
+    }
+
+    /// Plays tones from `from` milliseconds. Tone sounding at `from` is played for its remaining duration.
+    pub async fn play(&self, backend: &dyn AudioBackend, from: u64) {
+        backend.midi_program_change(TONE_CHANNEL, TONE_PROGRAM);
+
+        let start = backend.now_millis();
+        let mut time = 0;
+        for tone in self.tones.iter() {
+            time += tone.duration;
+            if time <= from {
+                continue;
+            }
+
+            if let Some(note) = tone.note {
+                backend.midi_note_on(TONE_CHANNEL, note, (tone.volume as u32 * 127 / 100) as u8);
+            }
+
+            let now = backend.now_millis();
+            if start + time - from > now {
+                backend.sleep(Duration::from_millis(start + time - from - now)).await;
+            }
+
+            if let Some(note) = tone.note {
+                backend.midi_note_off(TONE_CHANNEL, note, 0);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn tones(&self) -> &[Tone] {
+        &self.tones
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tone, ToneSequencePlayer};
+
+    #[test]
+    fn test_tone_sequence() {
+        #[rustfmt::skip]
+        let data = [
+            0xfe, 1, // version
+            0xfd, 15, // tempo 60
+            0xfb, 0, // block 0
+            60, 16, // quarter note C4
+            0xff, 8, // eighth rest
+            0xfa, 0,
+            0xf8, 50, // volume
+            0xf7, 3, // repeat
+            0xf9, 0, // play block 0
+            62, 64,
+        ];
+
+        let player = ToneSequencePlayer::new(&data).unwrap();
+
+        let c4 = Tone {
+            note: Some(60),
+            duration: 1000,
+            volume: 50,
+        };
+        let rest = Tone {
+            note: None,
+            duration: 500,
+            volume: 50,
+        };
+        let d4 = Tone {
+            note: Some(62),
+            duration: 4000,
+            volume: 50,
+        };
+        assert_eq!(player.tones(), [c4, rest, c4, rest, c4, rest, d4]);
+        assert_eq!(player.duration(), 8500);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(ToneSequencePlayer::new(&[0xfe, 2]).is_err());
+        assert!(ToneSequencePlayer::new(&[0xfe, 1, 0xf9, 0]).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::time::Duration;
+
+use smaf_player::AudioBackend;
+
+use super::AudioError;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IMA_ADPCM: u16 = 0x11;
+
+// wave data is sent in chunks so that pause and stop take effect without waiting for the whole file
+const CHUNK_MILLIS: u64 = 200;
+// next chunk is queued this early to avoid gaps between chunks
+const CHUNK_LEAD_MILLIS: u64 = 50;
+
+#[rustfmt::skip]
+const IMA_INDEX_TABLE: [i8; 16] = [
+    -1, -1, -1, -1, 2, 4, 6, 8,
+    -1, -1, -1, -1, 2, 4, 6, 8,
+];
+
+#[rustfmt::skip]
+const IMA_STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17,
+    19, 21, 23, 25, 28, 31, 34, 37, 41, 45,
+    50, 55, 60, 66, 73, 80, 88, 97, 107, 118,
+    130, 143, 157, 173, 190, 209, 230, 253, 279, 307,
+    337, 371, 408, 449, 494, 544, 598, 658, 724, 796,
+    876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066,
+    2272, 2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358,
+    5894, 6484, 7132, 7845, 8630, 9493, 10442, 11487, 12635, 13899,
+    15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+struct WaveFormat {
+    format: u16,
+    channels: u16,
+    sampling_rate: u32,
+    block_align: u16,
+    bits_per_sample: u16,
+}
+
+#[derive(Clone, Copy)]
+struct ImaState {
+    predictor: i32,
+    index: i32,
+}
+
+impl ImaState {
+    fn decode(&mut self, nibble: u8) -> i16 {
+        let step = IMA_STEP_TABLE[self.index as usize];
+
+        let mut diff = step >> 3;
+        if nibble & 1 != 0 {
+            diff += step >> 2;
+        }
+        if nibble & 2 != 0 {
+            diff += step >> 1;
+        }
+        if nibble & 4 != 0 {
+            diff += step;
+        }
+        if nibble & 8 != 0 {
+            diff = -diff;
+        }
+
+        self.predictor = (self.predictor + diff).clamp(i16::MIN as i32, i16::MAX as i32);
+        self.index = (self.index + IMA_INDEX_TABLE[nibble as usize] as i32).clamp(0, IMA_STEP_TABLE.len() as i32 - 1);
+
+        self.predictor as i16
+    }
+}
+
+fn decode_ima_adpcm(data: &[u8], channels: usize, block_align: usize) -> Result<Vec<i16>, AudioError> {
+    if channels == 0 || block_align <= 4 * channels {
+        return Err(AudioError::InvalidAudio);
+    }
+
+    let mut result = Vec::new();
+    for block in data.chunks(block_align) {
+        if block.len() <= 4 * channels {
+            break;
+        }
+
+        // each channel has 4 byte header with initial sample and step index
+        let mut states = block[..4 * channels]
+            .chunks_exact(4)
+            .map(|x| ImaState {
+                predictor: i16::from_le_bytes([x[0], x[1]]) as i32,
+                index: (x[2] as i32).min(IMA_STEP_TABLE.len() as i32 - 1),
+            })
+            .collect::<Vec<_>>();
+        result.extend(states.iter().map(|x| x.predictor as i16));
+
+        // rest is interleaved in 4 byte words per channel, each containing 8 samples
+        let mut samples = vec![Vec::new(); channels];
+        for (i, word) in block[4 * channels..].chunks_exact(4).enumerate() {
+            let channel = i % channels;
+            for byte in word {
+                samples[channel].push(states[channel].decode(byte & 0x0f));
+                samples[channel].push(states[channel].decode(byte >> 4));
+            }
+        }
+
+        let frames = samples.iter().map(|x| x.len()).min().unwrap_or(0);
+        for frame in 0..frames {
+            result.extend(samples.iter().map(|x| x[frame]));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Player for RIFF wave files, PCM or IMA ADPCM.
+#[derive(Clone)]
+pub struct WavePlayer {
+    channels: u8,
+    sampling_rate: u32,
+    samples: Arc<Vec<i16>>,
+}
+
+impl WavePlayer {
+    pub fn new(data: &[u8]) -> Result<Self, AudioError> {
+        if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+            return Err(AudioError::InvalidAudio);
+        }
+
+        let mut format = None;
+        let mut body = None;
+
+        let mut offset = 12;
+        while offset + 8 <= data.len() {
+            let id = &data[offset..offset + 4];
+            let size = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().unwrap()) as usize;
+            // some encoders write wrong data size, so we clamp it to the file
+            let chunk = &data[offset + 8..(offset + 8 + size).min(data.len())];
+
+            match id {
+                b"fmt " if chunk.len() >= 16 => {
+                    format = Some(WaveFormat {
+                        format: u16::from_le_bytes([chunk[0], chunk[1]]),
+                        channels: u16::from_le_bytes([chunk[2], chunk[3]]),
+                        sampling_rate: u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+                        block_align: u16::from_le_bytes([chunk[12], chunk[13]]),
+                        bits_per_sample: u16::from_le_bytes([chunk[14], chunk[15]]),
+                    })
+                }
+                b"data" => body = Some(chunk),
+                _ => {}
+            }
+
+            // chunks are padded to even size
+            offset += 8 + size + (size & 1);
+        }
+
+        let format = format.ok_or(AudioError::InvalidAudio)?;
+        let body = body.ok_or(AudioError::InvalidAudio)?;
+        if format.channels == 0 || format.channels > 2 || format.sampling_rate == 0 {
+            return Err(AudioError::InvalidAudio);
+        }
+
+        let samples = match (format.format, format.bits_per_sample) {
+            (FORMAT_PCM, 8) => body.iter().map(|&x| ((x as i16) - 128) << 8).collect(),
+            (FORMAT_PCM, 16) => body.chunks_exact(2).map(|x| i16::from_le_bytes([x[0], x[1]])).collect(),
+            (FORMAT_IMA_ADPCM, 4) => decode_ima_adpcm(body, format.channels as _, format.block_align as _)?,
+            _ => return Err(AudioError::InvalidAudio),
+        };
+
+        Ok(Self::from_samples(format.channels as _, format.sampling_rate, samples))
+    }
+
+    pub fn from_samples(channels: u8, sampling_rate: u32, samples: Vec<i16>) -> Self {
+        Self {
+            channels,
+            sampling_rate,
+            samples: Arc::new(samples),
+        }
+    }
+
+    /// Length of samples in milliseconds.
+    pub fn duration(&self) -> u64 {
+        self.frames() as u64 * 1000 / self.sampling_rate as u64
+    }
+
+    fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    /// Plays samples from `from` milliseconds.
+    pub async fn play(&self, backend: &dyn AudioBackend, from: u64) {
+        let chunk_frames = (self.sampling_rate as u64 * CHUNK_MILLIS / 1000).max(1) as usize;
+        let start = backend.now_millis();
+
+        let skipped_frames = ((from * self.sampling_rate as u64 / 1000) as usize).min(self.frames());
+        let samples = &self.samples[skipped_frames * self.channels as usize..];
+
+        let mut frames_played = 0u64;
+        for chunk in samples.chunks(chunk_frames * self.channels as usize) {
+            backend.play_wave(self.channels, self.sampling_rate, chunk);
+            frames_played += (chunk.len() / self.channels as usize) as u64;
+
+            let end = start + frames_played * 1000 / self.sampling_rate as u64;
+            let now = backend.now_millis();
+            if end > now + CHUNK_LEAD_MILLIS {
+                backend.sleep(Duration::from_millis(end - now - CHUNK_LEAD_MILLIS)).await;
+            }
+        }
+
+        // wait until last chunk is played
+        let end = start + frames_played * 1000 / self.sampling_rate as u64;
+        let now = backend.now_millis();
+        if end > now {
+            backend.sleep(Duration::from_millis(end - now)).await;
+        }
+    }
+
+    #[cfg(test)]
+    fn samples(&self) -> &[i16] {
+        &self.samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WavePlayer;
+
+    fn wave_file(format: u16, channels: u16, block_align: u16, bits_per_sample: u16, data: &[u8]) -> Vec<u8> {
+        let mut result = Vec::new();
+        result.extend_from_slice(b"RIFF");
+        result.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        result.extend_from_slice(b"WAVEfmt ");
+        result.extend_from_slice(&16u32.to_le_bytes());
+        result.extend_from_slice(&format.to_le_bytes());
+        result.extend_from_slice(&channels.to_le_bytes());
+        result.extend_from_slice(&8000u32.to_le_bytes());
+        result.extend_from_slice(&(8000 * block_align as u32).to_le_bytes());
+        result.extend_from_slice(&block_align.to_le_bytes());
+        result.extend_from_slice(&bits_per_sample.to_le_bytes());
+        result.extend_from_slice(b"data");
+        result.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        result.extend_from_slice(data);
+
+        result
+    }
+
+    #[test]
+    fn test_pcm() {
+        let data = wave_file(1, 1, 1, 8, &[0x80, 0xff, 0x00]);
+        let player = WavePlayer::new(&data).unwrap();
+
+        assert_eq!(player.samples(), [0, 127 << 8, -128 << 8]);
+
+        let data = wave_file(1, 2, 4, 16, &[0x01, 0x00, 0xff, 0xff]);
+        let player = WavePlayer::new(&data).unwrap();
+
+        assert_eq!(player.samples(), [1, -1]);
+    }
+
+    #[test]
+    fn test_duration() {
+        let player = WavePlayer::from_samples(2, 8000, vec![0; 8000]);
+
+        assert_eq!(player.duration(), 500);
+    }
+
+    #[test]
+    fn test_ima_adpcm() {
+        // header: predictor 100, step index 0, followed by 8 samples
+        let data = wave_file(0x11, 1, 8, 4, &[100, 0, 0, 0, 0x70, 0x08, 0x00, 0x00]);
+        let player = WavePlayer::new(&data).unwrap();
+
+        assert_eq!(player.samples(), [100, 100, 111, 109, 110, 111, 112, 113, 114]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(WavePlayer::new(b"RIFF\0\0\0\0WAVE").is_err());
+        assert!(WavePlayer::new(&wave_file(2, 1, 1, 4, &[0])).is_err());
+    }
+}
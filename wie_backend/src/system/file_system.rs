@@ -1,33 +1,446 @@
-use alloc::string::String;
-use std::collections::HashMap;
+use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec::Vec};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
+use crate::{storage::Storage, time::Instant};
+
+/// Size of writable storage available to each application.
+pub const STORAGE_CAPACITY: u64 = 0x1000000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FilesystemError {
+    NotFound,
+    AlreadyExists,
+    /// Archive files can't be removed
+    ReadOnly,
+    IsDirectory,
+    NotDirectory,
+    DirectoryNotEmpty,
+    /// Write would exceed [`STORAGE_CAPACITY`]
+    NoSpace,
+    StorageFailure,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FileMetadata {
+    pub size: u64,
+    pub is_directory: bool,
+    /// `None` for archive files, or if storage doesn't keep it
+    pub created: Option<Instant>,
+}
+
+// resolves `.` and `..`, never going above root
+fn normalize(path: &str) -> String {
+    let mut components = Vec::new();
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            x => components.push(x),
+        }
+    }
+
+    components.join("/")
+}
+
+fn parent(path: &str) -> &str {
+    path.rfind('/').map(|x| &path[..x]).unwrap_or("")
+}
+
+fn storage_size(storage: &dyn Storage, path: &str) -> u64 {
+    storage
+        .list(path)
+        .into_iter()
+        .map(|name| {
+            let path = if path.is_empty() { name } else { format!("{}/{}", path, name) };
+
+            if storage.is_dir(&path) {
+                storage_size(storage, &path)
+            } else {
+                storage.size(&path).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// File opened by application, referred by id returned from [`Filesystem::open_handle`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileHandle {
+    pub path: String,
+    pub position: u64,
+    pub readable: bool,
+    pub writable: bool,
+}
+
+/// Read-only files from application archive, overlaid with writable storage of the application.
 #[derive(Default)]
 pub struct Filesystem {
     virtual_files: HashMap<String, Vec<u8>>,
+    storage: Option<Box<dyn Storage>>,
+    // calculated on first use, as it requires reading whole storage
+    used_space: Option<u64>,
+    handles: BTreeMap<u32, FileHandle>,
+    last_handle_id: u32,
 }
 
 impl Filesystem {
-    pub fn new() -> Self {
+    pub fn new(storage: Box<dyn Storage>) -> Self {
         Self {
             virtual_files: HashMap::new(),
+            storage: Some(storage),
+            ..Default::default()
         }
     }
 
     pub fn add(&mut self, path: &str, data: Vec<u8>) {
-        self.virtual_files.insert(path.to_string(), data);
+        self.virtual_files.insert(normalize(path), data);
     }
 
-    pub fn read(&self, path: &str) -> Option<&[u8]> {
-        let normalized_path = path.trim_start_matches('/');
+    pub fn read(&self, path: &str) -> Option<Vec<u8>> {
+        let path = normalize(path);
 
-        if let Some(data) = self.virtual_files.get(normalized_path) {
+        if let Some(data) = self.storage.as_ref().and_then(|x| x.read(&path)) {
             return Some(data);
         }
 
-        None
+        self.virtual_files.get(&path).cloned()
+    }
+
+    pub fn exists(&self, path: &str) -> bool {
+        self.metadata(path).is_some()
+    }
+
+    pub fn metadata(&self, path: &str) -> Option<FileMetadata> {
+        let path = normalize(path);
+
+        let created = self.storage.as_ref().and_then(|x| x.created(&path));
+        if self.is_dir(&path) {
+            return Some(FileMetadata {
+                size: 0,
+                is_directory: true,
+                created,
+            });
+        }
+
+        let size = self.storage.as_ref().and_then(|x| x.size(&path));
+        let size = size.or_else(|| self.virtual_files.get(&path).map(|x| x.len() as _))?;
+
+        Some(FileMetadata {
+            size,
+            is_directory: false,
+            created,
+        })
+    }
+
+    /// Creates or replaces file. Archive files are shadowed by the written file.
+    pub fn write(&mut self, path: &str, data: &[u8]) -> Result<(), FilesystemError> {
+        let path = normalize(path);
+        if path.is_empty() || self.is_dir(&path) {
+            return Err(FilesystemError::IsDirectory);
+        }
+        if !self.is_dir(parent(&path)) {
+            return Err(FilesystemError::NotFound);
+        }
+
+        let old_size = self.storage_file_size(&path);
+        let used_space = self.used_space() - old_size + data.len() as u64;
+        if used_space > STORAGE_CAPACITY {
+            return Err(FilesystemError::NoSpace);
+        }
+
+        let storage = self.storage.as_mut().ok_or(FilesystemError::StorageFailure)?;
+        if !storage.write(&path, data) {
+            return Err(FilesystemError::StorageFailure);
+        }
+        self.used_space = Some(used_space);
+
+        Ok(())
+    }
+
+    /// Writes `data` at `offset` of file, creating it if missing.
+    /// Archive file is copied into storage on first write, and only written part is stored afterwards.
+    pub fn write_at(&mut self, path: &str, offset: u64, data: &[u8]) -> Result<(), FilesystemError> {
+        let path = normalize(path);
+        if self.storage.as_ref().and_then(|x| x.size(&path)).is_none() {
+            let initial = self.virtual_files.get(&path).cloned().unwrap_or_default();
+            self.write(&path, &initial)?;
+        }
+
+        let old_size = self.storage_file_size(&path);
+        let end = offset.checked_add(data.len() as u64).ok_or(FilesystemError::NoSpace)?;
+        let used_space = self.used_space() - old_size + old_size.max(end);
+        if used_space > STORAGE_CAPACITY {
+            return Err(FilesystemError::NoSpace);
+        }
+
+        let storage = self.storage.as_mut().ok_or(FilesystemError::StorageFailure)?;
+        if !storage.write_at(&path, offset, data) {
+            return Err(FilesystemError::StorageFailure);
+        }
+        self.used_space = Some(used_space);
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, path: &str) -> Result<(), FilesystemError> {
+        let path = normalize(path);
+        if self.virtual_files.contains_key(&path) || self.is_archive_dir(&path) {
+            return Err(FilesystemError::ReadOnly);
+        }
+        if !self.exists(&path) {
+            return Err(FilesystemError::NotFound);
+        }
+        if self.is_dir(&path) && !self.list(&path)?.is_empty() {
+            return Err(FilesystemError::DirectoryNotEmpty);
+        }
+
+        let size = self.storage_file_size(&path);
+        let used_space = self.used_space() - size;
+
+        let storage = self.storage.as_mut().ok_or(FilesystemError::StorageFailure)?;
+        if !storage.remove(&path) {
+            return Err(FilesystemError::StorageFailure);
+        }
+        self.used_space = Some(used_space);
+
+        Ok(())
+    }
+
+    /// Moves file to `to`, which must not exist. Directories can't be renamed.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), FilesystemError> {
+        let from = normalize(from);
+        if self.virtual_files.contains_key(&from) {
+            return Err(FilesystemError::ReadOnly);
+        }
+        if self.is_dir(&from) {
+            return Err(FilesystemError::IsDirectory);
+        }
+        let data = self.read(&from).ok_or(FilesystemError::NotFound)?;
+        if self.exists(to) {
+            return Err(FilesystemError::AlreadyExists);
+        }
+
+        self.write(to, &data)?;
+        self.remove(&from)
+    }
+
+    pub fn create_dir(&mut self, path: &str) -> Result<(), FilesystemError> {
+        let path = normalize(path);
+        if self.exists(&path) {
+            return Err(FilesystemError::AlreadyExists);
+        }
+        if !self.is_dir(parent(&path)) {
+            return Err(FilesystemError::NotFound);
+        }
+
+        let storage = self.storage.as_mut().ok_or(FilesystemError::StorageFailure)?;
+        if !storage.create_dir(&path) {
+            return Err(FilesystemError::StorageFailure);
+        }
+
+        Ok(())
+    }
+
+    /// Returns sorted names of entries in directory.
+    pub fn list(&self, path: &str) -> Result<Vec<String>, FilesystemError> {
+        let path = normalize(path);
+        if !self.is_dir(&path) {
+            return Err(if self.exists(&path) {
+                FilesystemError::NotDirectory
+            } else {
+                FilesystemError::NotFound
+            });
+        }
+
+        let mut result = BTreeSet::new();
+        if let Some(storage) = &self.storage {
+            result.extend(storage.list(&path));
+        }
+
+        let prefix = if path.is_empty() { String::new() } else { path.clone() + "/" };
+        for name in self.virtual_files.keys() {
+            if let Some(rest) = name.strip_prefix(&prefix) {
+                result.insert(rest.split('/').next().unwrap().to_owned());
+            }
+        }
+
+        Ok(result.into_iter().collect())
+    }
+
+    /// Returns free space of writable storage, in bytes.
+    pub fn available_space(&mut self) -> u64 {
+        STORAGE_CAPACITY.saturating_sub(self.used_space())
+    }
+
+    /// Registers `handle`, returning its id. Ids are never zero or reused.
+    pub fn open_handle(&mut self, handle: FileHandle) -> u32 {
+        self.last_handle_id += 1;
+        self.handles.insert(self.last_handle_id, handle);
+
+        self.last_handle_id
+    }
+
+    pub fn handle(&mut self, id: u32) -> Option<&mut FileHandle> {
+        self.handles.get_mut(&id)
+    }
+
+    pub fn close_handle(&mut self, id: u32) -> Option<FileHandle> {
+        self.handles.remove(&id)
     }
 
     pub fn files(&self) -> impl Iterator<Item = (&str, &[u8])> {
         self.virtual_files.iter().map(|(k, v)| (k.as_str(), v.as_slice()))
     }
+
+    fn used_space(&mut self) -> u64 {
+        if let Some(x) = self.used_space {
+            return x;
+        }
+
+        let used_space = self.storage.as_ref().map(|x| storage_size(x.as_ref(), "")).unwrap_or(0);
+        self.used_space = Some(used_space);
+
+        used_space
+    }
+
+    fn storage_file_size(&self, path: &str) -> u64 {
+        self.storage.as_ref().and_then(|x| x.size(path)).unwrap_or(0)
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        path.is_empty() || self.is_archive_dir(path) || self.storage.as_ref().is_some_and(|x| x.is_dir(path))
+    }
+
+    // archive has no directory entries, so directories are implied by file paths
+    fn is_archive_dir(&self, path: &str) -> bool {
+        let prefix = path.to_owned() + "/";
+
+        self.virtual_files.keys().any(|x| x.starts_with(&prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, vec};
+
+    use crate::{storage::MemoryStorage, time::Instant};
+
+    use super::{FileHandle, Filesystem, FilesystemError, STORAGE_CAPACITY};
+
+    fn filesystem() -> Filesystem {
+        let mut filesystem = Filesystem::new(Box::new(MemoryStorage::new()));
+        filesystem.add("data/a.bin", b"archive".to_vec());
+        filesystem.add("b.bin", b"b".to_vec());
+
+        filesystem
+    }
+
+    #[test]
+    fn test_overlay() {
+        let mut filesystem = filesystem();
+
+        assert_eq!(filesystem.read("/data/a.bin").unwrap(), b"archive");
+        filesystem.write("data/./a.bin", b"written").unwrap();
+        assert_eq!(filesystem.read("data/a.bin").unwrap(), b"written");
+
+        // archive files are read only
+        assert_eq!(filesystem.remove("b.bin"), Err(FilesystemError::ReadOnly));
+        assert_eq!(filesystem.read("../b.bin").unwrap(), b"b");
+    }
+
+    #[test]
+    fn test_directory() {
+        let mut filesystem = filesystem();
+
+        assert_eq!(filesystem.write("save/1.dat", b"1"), Err(FilesystemError::NotFound));
+        filesystem.create_dir("save").unwrap();
+        assert_eq!(filesystem.create_dir("save"), Err(FilesystemError::AlreadyExists));
+        filesystem.write("save/1.dat", b"1").unwrap();
+
+        assert_eq!(filesystem.list("/").unwrap(), ["b.bin", "data", "save"]);
+        assert_eq!(filesystem.list("save").unwrap(), ["1.dat"]);
+        assert_eq!(filesystem.list("b.bin"), Err(FilesystemError::NotDirectory));
+
+        let metadata = filesystem.metadata("save/1.dat").unwrap();
+        assert_eq!((metadata.size, metadata.is_directory), (1, false));
+        assert!(filesystem.metadata("data").unwrap().is_directory);
+
+        assert_eq!(filesystem.remove("save"), Err(FilesystemError::DirectoryNotEmpty));
+        assert_eq!(filesystem.rename("b.bin", "save/2.dat"), Err(FilesystemError::ReadOnly));
+        filesystem.rename("save/1.dat", "save/2.dat").unwrap();
+        assert_eq!(filesystem.list("save").unwrap(), ["2.dat"]);
+        filesystem.remove("save/2.dat").unwrap();
+        filesystem.remove("save").unwrap();
+        assert!(!filesystem.exists("save"));
+    }
+
+    #[test]
+    fn test_handle() {
+        let mut filesystem = filesystem();
+
+        let handle = FileHandle {
+            path: "b.bin".into(),
+            position: 0,
+            readable: true,
+            writable: false,
+        };
+        let id = filesystem.open_handle(handle.clone());
+        assert_ne!(id, 0);
+
+        filesystem.handle(id).unwrap().position = 1;
+        assert_eq!(filesystem.handle(id).unwrap().position, 1);
+
+        assert_eq!(filesystem.close_handle(id).unwrap().path, "b.bin");
+        assert!(filesystem.handle(id).is_none());
+        assert_ne!(filesystem.open_handle(handle), id);
+    }
+
+    #[test]
+    fn test_available_space() {
+        let mut filesystem = filesystem();
+
+        assert_eq!(filesystem.available_space(), STORAGE_CAPACITY);
+        filesystem.write("a.dat", &[0; 100]).unwrap();
+        filesystem.write("a.dat", &[0; 200]).unwrap();
+        assert_eq!(filesystem.available_space(), STORAGE_CAPACITY - 200);
+
+        let data = vec![0; STORAGE_CAPACITY as usize];
+        assert_eq!(filesystem.write("b.dat", &data), Err(FilesystemError::NoSpace));
+
+        filesystem.remove("a.dat").unwrap();
+        assert_eq!(filesystem.available_space(), STORAGE_CAPACITY);
+    }
+
+    #[test]
+    fn test_write_at() {
+        let mut filesystem = filesystem();
+
+        // archive file is copied on first write
+        filesystem.write_at("data/a.bin", 2, b"CH").unwrap();
+        assert_eq!(filesystem.read("data/a.bin").unwrap(), b"arCHive");
+
+        filesystem.write_at("new.bin", 2, b"ab").unwrap();
+        filesystem.write_at("new.bin", 0, b"x").unwrap();
+        assert_eq!(filesystem.read("new.bin").unwrap(), b"x\0ab");
+        assert_eq!(filesystem.metadata("new.bin").unwrap().size, 4);
+        assert_eq!(filesystem.available_space(), STORAGE_CAPACITY - 11);
+
+        assert_eq!(filesystem.write_at("data", 0, b"x"), Err(FilesystemError::IsDirectory));
+        assert_eq!(filesystem.write_at("new.bin", STORAGE_CAPACITY, b"x"), Err(FilesystemError::NoSpace));
+    }
+
+    #[test]
+    fn test_created() {
+        let mut filesystem = Filesystem::new(Box::new(MemoryStorage::with_clock(|| Instant::from_epoch_millis(1000))));
+        filesystem.add("b.bin", b"b".to_vec());
+
+        filesystem.create_dir("save").unwrap();
+        filesystem.write("save/1.dat", b"1").unwrap();
+        assert_eq!(filesystem.metadata("save").unwrap().created, Some(Instant::from_epoch_millis(1000)));
+        assert_eq!(filesystem.metadata("save/1.dat").unwrap().created, Some(Instant::from_epoch_millis(1000)));
+
+        // archive files don't have creation time
+        assert_eq!(filesystem.metadata("b.bin").unwrap().created, None);
+    }
 }
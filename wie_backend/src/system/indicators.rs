@@ -0,0 +1,40 @@
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+/// Indicator hardware of emulated handset, given by platform.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HandsetProfile {
+    /// Height of annunciator bar on top of screen, which is excluded from screen size reported to application.
+    pub annunciator_height: u32,
+    pub led_count: usize,
+}
+
+impl Default for HandsetProfile {
+    fn default() -> Self {
+        Self {
+            annunciator_height: 0,
+            led_count: 1,
+        }
+    }
+}
+
+/// State of annunciator bar and leds, rendered by platform.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Indicators {
+    /// Height reserved on top of screen for annunciator, whether it's visible or not
+    pub annunciator_height: u32,
+    pub annunciator_visible: bool,
+    /// State of each annunciator icon by id, icons in state 0 are hidden
+    pub annunciator_icons: BTreeMap<u32, u32>,
+    /// Color of each led in 0xRRGGBB, 0 is off
+    pub leds: Vec<u32>,
+}
+
+impl Indicators {
+    pub fn new(profile: HandsetProfile) -> Self {
+        Self {
+            annunciator_height: profile.annunciator_height,
+            leds: vec![0; profile.led_count],
+            ..Default::default()
+        }
+    }
+}
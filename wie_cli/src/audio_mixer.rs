@@ -0,0 +1,256 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rodio::{
+    cpal::traits::{DeviceTrait, HostTrait},
+    Source,
+};
+
+const SOURCE_BUFFER_FRAMES: usize = 256;
+
+type RenderFn = Box<dyn FnMut(&mut [i16]) + Send>;
+
+struct Buffer {
+    channels: usize,
+    sampling_rate: u32,
+    data: Vec<i16>,
+}
+
+impl Buffer {
+    fn frames(&self) -> usize {
+        self.data.len() / self.channels
+    }
+
+    fn sample(&self, frame: usize, channel: usize) -> f32 {
+        self.data[frame.min(self.frames() - 1) * self.channels + channel] as f32 / 32768.0
+    }
+}
+
+#[derive(Default)]
+struct Voice {
+    buffers: Vec<Buffer>,
+    index: usize,
+    // position in frames of current buffer
+    position: f64,
+}
+
+impl Voice {
+    fn is_finished(&self) -> bool {
+        self.index >= self.buffers.len()
+    }
+
+    // mixes next output frame into `out`, resampling with linear interpolation
+    fn mix_frame(&mut self, out: &mut [f32], output_rate: u32, settings: VoiceSettings) {
+        if self.is_finished() {
+            return;
+        }
+
+        let buffer = &self.buffers[self.index];
+        let frame = self.position as usize;
+        let fraction = self.position.fract() as f32;
+
+        let sample = |channel: usize| {
+            let current = buffer.sample(frame, channel);
+            let next = buffer.sample(frame + 1, channel);
+
+            current + (next - current) * fraction
+        };
+
+        if buffer.channels == out.len() {
+            for (channel, x) in out.iter_mut().enumerate() {
+                *x += sample(channel) * settings.volume;
+            }
+        } else {
+            // downmix to mono, then spread to all output channels
+            let value = (0..buffer.channels).map(sample).sum::<f32>() / buffer.channels as f32;
+            for x in out.iter_mut() {
+                *x += value * settings.volume;
+            }
+        }
+
+        self.position += buffer.sampling_rate as f64 / output_rate as f64;
+        if self.position >= buffer.frames() as f64 {
+            self.position -= buffer.frames() as f64;
+            self.index += 1;
+
+            if self.is_finished() && settings.looping {
+                self.index = 0;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct VoiceSettings {
+    volume: f32,
+    looping: bool,
+}
+
+impl Default for VoiceSettings {
+    fn default() -> Self {
+        Self { volume: 1.0, looping: false }
+    }
+}
+
+struct MixerState {
+    channels: u16,
+    sample_rate: u32,
+    voices: BTreeMap<u32, Voice>,
+    anonymous_voices: Vec<Voice>,
+    settings: BTreeMap<u32, VoiceSettings>,
+}
+
+impl MixerState {
+    fn render(&mut self, out: &mut [i16]) {
+        let mut frame = vec![0.0; self.channels as usize];
+
+        for out_frame in out.chunks_mut(self.channels as usize) {
+            frame.fill(0.0);
+
+            for (id, voice) in self.voices.iter_mut() {
+                let settings = self.settings.get(id).copied().unwrap_or_default();
+                voice.mix_frame(&mut frame, self.sample_rate, settings);
+            }
+            for voice in self.anonymous_voices.iter_mut() {
+                voice.mix_frame(&mut frame, self.sample_rate, VoiceSettings::default());
+            }
+
+            for (x, value) in out_frame.iter_mut().zip(frame.iter()) {
+                *x = (value.clamp(-1.0, 1.0) * 32767.0) as i16;
+            }
+        }
+
+        self.voices.retain(|_, x| !x.is_finished());
+        self.anonymous_voices.retain(|x| !x.is_finished());
+    }
+}
+
+/// Mixes wave data on independent voices into single output stream.
+#[derive(Clone)]
+pub struct AudioMixer {
+    state: Arc<Mutex<MixerState>>,
+}
+
+impl AudioMixer {
+    pub fn new() -> Self {
+        let config = rodio::cpal::default_host()
+            .default_output_device()
+            .and_then(|x| x.default_output_config().ok());
+        let (channels, sample_rate) = config.map(|x| (x.channels(), x.sample_rate().0)).unwrap_or((2, 44100));
+
+        Self {
+            state: Arc::new(Mutex::new(MixerState {
+                channels,
+                sample_rate,
+                voices: BTreeMap::new(),
+                anonymous_voices: Vec::new(),
+                settings: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// Queues wave data on `voice`, or on a new voice if `voice` is `None`.
+    pub fn play(&self, voice: Option<u32>, channels: u8, sampling_rate: u32, data: &[i16]) {
+        let mut state = self.state.lock().unwrap();
+        if channels == 0 || sampling_rate == 0 || data.len() < channels as usize {
+            return;
+        }
+
+        let buffer = Buffer {
+            channels: channels as _,
+            sampling_rate,
+            data: data.to_vec(),
+        };
+
+        match voice {
+            Some(x) => state.voices.entry(x).or_default().buffers.push(buffer),
+            None => state.anonymous_voices.push(Voice {
+                buffers: vec![buffer],
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn stop(&self, voice: u32) {
+        self.state.lock().unwrap().voices.remove(&voice);
+    }
+
+    pub fn set_volume(&self, voice: u32, volume: u8) {
+        self.state.lock().unwrap().settings.entry(voice).or_default().volume = volume.min(100) as f32 / 100.0;
+    }
+
+    pub fn set_looping(&self, voice: u32, looping: bool) {
+        self.state.lock().unwrap().settings.entry(voice).or_default().looping = looping;
+    }
+
+    /// Creates rodio source playing mixer output.
+    pub fn source(&self) -> RenderSource {
+        let state = self.state.lock().unwrap();
+
+        let mixer = self.clone();
+        RenderSource::new(state.channels, state.sample_rate, move |x| mixer.state.lock().unwrap().render(x))
+    }
+}
+
+/// Endless rodio source pulling samples from render callback.
+pub struct RenderSource {
+    channels: u16,
+    sample_rate: u32,
+    render: RenderFn,
+    buffer: Vec<i16>,
+    position: usize,
+}
+
+impl RenderSource {
+    pub fn new<F>(channels: u16, sample_rate: u32, render: F) -> Self
+    where
+        F: FnMut(&mut [i16]) + Send + 'static,
+    {
+        let buffer_size = SOURCE_BUFFER_FRAMES * channels as usize;
+
+        Self {
+            channels,
+            sample_rate,
+            render: Box::new(render),
+            buffer: vec![0; buffer_size],
+            position: buffer_size,
+        }
+    }
+}
+
+impl Iterator for RenderSource {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        if self.position == self.buffer.len() {
+            (self.render)(&mut self.buffer);
+            self.position = 0;
+        }
+
+        let sample = self.buffer[self.position];
+        self.position += 1;
+
+        Some(sample)
+    }
+}
+
+impl Source for RenderSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
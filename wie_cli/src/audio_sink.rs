@@ -1,17 +1,37 @@
-use std::sync::{mpsc::Sender, Mutex};
+use std::sync::Mutex;
 
 use midir::MidiOutputConnection;
 
+use wie_backend::{AudioSink as _, SynthAudioSink};
+
+use crate::audio_mixer::AudioMixer;
+
+/// Mixes wave data on audio output, and sends midi events to midi output or software synthesizer.
 pub struct AudioSink {
     midi_out: Option<Mutex<MidiOutputConnection>>,
-    audio_tx: Sender<(u8, u32, Vec<i16>)>,
+    // used for midi if there's no midi output port
+    synth: Option<SynthAudioSink>,
+    // `None` if there's no audio output, dropping wave data
+    mixer: Option<AudioMixer>,
 }
 
 impl AudioSink {
-    pub fn new(midi_out: Option<MidiOutputConnection>, audio_tx: Sender<(u8, u32, Vec<i16>)>) -> Self {
+    pub fn new(midi_out: Option<MidiOutputConnection>, synth: Option<SynthAudioSink>, mixer: Option<AudioMixer>) -> Self {
         Self {
             midi_out: midi_out.map(Mutex::new),
-            audio_tx,
+            synth,
+            mixer,
+        }
+    }
+
+    fn send_midi<F>(&self, message: &[u8], fallback: F)
+    where
+        F: FnOnce(&SynthAudioSink),
+    {
+        if let Some(x) = self.midi_out.as_ref() {
+            x.lock().unwrap().send(message).unwrap();
+        } else if let Some(x) = self.synth.as_ref() {
+            fallback(x);
         }
     }
 }
@@ -24,30 +44,50 @@ unsafe impl Send for AudioSink {}
 
 impl wie_backend::AudioSink for AudioSink {
     fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
-        self.audio_tx.send((channel, sampling_rate, wave_data.to_vec())).unwrap();
+        if let Some(x) = &self.mixer {
+            x.play(None, channel, sampling_rate, wave_data);
+        }
     }
 
-    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0x90 | channel_id, note, velocity]).unwrap();
+    fn play_voice(&self, voice: u32, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
+        if let Some(x) = &self.mixer {
+            x.play(Some(voice), channel, sampling_rate, wave_data);
         }
     }
 
-    fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0x80 | channel_id, note, velocity]).unwrap();
+    fn stop_voice(&self, voice: u32) {
+        if let Some(x) = &self.mixer {
+            x.stop(voice);
         }
     }
 
-    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0xB0 | channel_id, control, value]).unwrap()
+    fn set_voice_volume(&self, voice: u32, volume: u8) {
+        if let Some(x) = &self.mixer {
+            x.set_volume(voice, volume);
         }
     }
 
-    fn midi_program_change(&self, channel_id: u8, program: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0xC0 | channel_id, program]).unwrap()
+    fn set_voice_looping(&self, voice: u32, looping: bool) {
+        if let Some(x) = &self.mixer {
+            x.set_looping(voice, looping);
         }
     }
+
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.send_midi(&[0x90 | channel_id, note, velocity], |x| x.midi_note_on(channel_id, note, velocity));
+    }
+
+    fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.send_midi(&[0x80 | channel_id, note, velocity], |x| x.midi_note_off(channel_id, note, velocity));
+    }
+
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        self.send_midi(&[0xB0 | channel_id, control, value], |x| {
+            x.midi_control_change(channel_id, control, value)
+        });
+    }
+
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        self.send_midi(&[0xC0 | channel_id, program], |x| x.midi_program_change(channel_id, program));
+    }
 }
@@ -1,23 +1,53 @@
-use std::{fs, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::UNIX_EPOCH,
+};
 
+use anyhow::bail;
 use directories::ProjectDirs;
 
-use wie_backend::RecordId;
+use wie_backend::{AccessMode, Instant, RecordId};
+
+const DATABASE_EXTENSION: &str = "db";
+const MAGIC: &[u8; 4] = b"WIDB";
+const FORMAT_VERSION: u32 = 2;
+const LEGACY_FORMAT_VERSION: u32 = 1;
+
+const ACCESS_SHARED: u32 = 1;
+const ACCESS_WRITABLE: u32 = 2;
+
+// legacy layout stored each record in its own file, with modification counter in this file
+const LEGACY_VERSION_FILE_NAME: &str = "version";
 
 pub struct DatabaseRepository {
     base_path: PathBuf,
+    // opened databases, so that each database file is read only once
+    databases: Mutex<HashMap<PathBuf, Arc<Mutex<DatabaseFile>>>>,
 }
 
 impl DatabaseRepository {
     pub fn new() -> Self {
         let base_dir = ProjectDirs::from("net", "dlunch", "wie").unwrap();
 
-        let base_path = base_dir.data_dir().to_owned();
+        Self::with_base_path(base_dir.data_dir().to_owned())
+    }
 
-        Self { base_path }
+    pub fn with_base_path(base_path: PathBuf) -> Self {
+        Self {
+            base_path,
+            databases: Mutex::new(HashMap::new()),
+        }
     }
 
     fn get_path_for_database(&self, name: &str, app_id: &str) -> PathBuf {
+        self.base_path.join(app_id).join(format!("{}.{}", name, DATABASE_EXTENSION))
+    }
+
+    fn get_legacy_path_for_database(&self, name: &str, app_id: &str) -> PathBuf {
         self.base_path.join(app_id).join(name)
     }
 }
@@ -26,82 +56,488 @@ impl wie_backend::DatabaseRepository for DatabaseRepository {
     fn open(&self, name: &str, app_id: &str) -> Box<dyn wie_backend::Database> {
         let path = self.get_path_for_database(name, app_id);
 
-        Box::new(Database::new(path).unwrap())
+        let file = self
+            .databases
+            .lock()
+            .unwrap()
+            .entry(path.clone())
+            .or_insert_with(|| {
+                let legacy_path = self.get_legacy_path_for_database(name, app_id);
+                if legacy_path.is_dir() {
+                    if let Err(x) = migrate(&legacy_path, &path) {
+                        tracing::error!("Failed to migrate database {:?}: {}", legacy_path, x);
+                    }
+                }
+
+                Arc::new(Mutex::new(DatabaseFile::open(&path)))
+            })
+            .clone();
+
+        Box::new(Database { path, file })
+    }
+
+    fn list(&self, app_id: &str) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.base_path.join(app_id)) else {
+            return Vec::new();
+        };
+
+        let mut names = BTreeSet::new();
+        for entry in entries.filter_map(|x| x.ok()) {
+            let path = entry.path();
+
+            // legacy databases are migrated on open
+            let name = if path.is_dir() {
+                entry.file_name().into_string().ok()
+            } else if path.extension().is_some_and(|x| x == DATABASE_EXTENSION) {
+                path.file_stem().and_then(|x| x.to_str()).map(|x| x.to_owned())
+            } else {
+                None
+            };
+
+            names.extend(name);
+        }
+
+        names.into_iter().collect()
+    }
+
+    fn delete(&self, name: &str, app_id: &str) -> bool {
+        let path = self.get_path_for_database(name, app_id);
+        let legacy_path = self.get_legacy_path_for_database(name, app_id);
+
+        tracing::trace!("Deleting database at {:?}", path);
+
+        self.databases.lock().unwrap().remove(&path);
+        let deleted = fs::remove_file(path).is_ok();
+        let legacy_deleted = legacy_path.is_dir() && fs::remove_dir_all(legacy_path).is_ok();
+
+        deleted || legacy_deleted
     }
 }
 
+/// Database stored in single file, which is replaced atomically on every modification.
+///
+/// Contents are kept in memory after first open, shared by all handles of the database.
 pub struct Database {
-    base_path: PathBuf,
+    path: PathBuf,
+    file: Arc<Mutex<DatabaseFile>>,
 }
 
 impl Database {
-    pub fn new(base_path: PathBuf) -> anyhow::Result<Self> {
-        tracing::trace!("Opening database at {:?}", base_path);
-
-        fs::create_dir_all(&base_path)?;
+    fn save_modified(&self, file: &mut DatabaseFile) -> bool {
+        file.version += 1;
+        file.last_modified = crate::now().raw();
 
-        Ok(Self { base_path })
+        self.save(file)
     }
 
-    fn find_empty_record_id(&mut self) -> RecordId {
-        let mut record_id = 0;
-
-        loop {
-            let path = self.base_path.join(record_id.to_string());
-
-            if !path.exists() {
-                return record_id;
-            }
+    fn save(&self, file: &DatabaseFile) -> bool {
+        if let Err(x) = file.save(&self.path) {
+            tracing::error!("Failed to save database {:?}: {}", self.path, x);
 
-            record_id += 1;
+            return false;
         }
-    }
-    fn get_path_for_record(&self, id: RecordId) -> PathBuf {
-        self.base_path.join(id.to_string())
+
+        true
     }
 }
 
 impl wie_backend::Database for Database {
     fn add(&mut self, data: &[u8]) -> RecordId {
-        let id = self.find_empty_record_id();
+        let mut file = self.file.lock().unwrap();
+        let id = file.next_id;
 
-        tracing::trace!("Adding record {} to database {:?}", id, &self.base_path);
+        tracing::trace!("Adding record {} to database {:?}", id, &self.path);
 
-        let path = self.get_path_for_record(id);
-        fs::write(path, data).unwrap();
+        file.next_id += 1;
+        file.records.insert(id, data.to_vec());
+        self.save_modified(&mut file);
 
         id
     }
 
     fn get(&self, id: RecordId) -> Option<Vec<u8>> {
-        let path = self.get_path_for_record(id);
+        tracing::trace!("Read record {} from database {:?}", id, &self.path);
 
-        tracing::trace!("Read record {} from database {:?}", id, &self.base_path);
-
-        fs::read(path).ok()
+        self.file.lock().unwrap().records.get(&id).cloned()
     }
 
     fn set(&mut self, id: RecordId, data: &[u8]) -> bool {
-        let path = self.get_path_for_record(id);
+        tracing::trace!("Set record {} to database {:?}", id, &self.path);
 
-        tracing::trace!("Set record {} to database {:?}", id, &self.base_path);
+        let mut file = self.file.lock().unwrap();
+        file.records.insert(id, data.to_vec());
+        file.next_id = file.next_id.max(id + 1);
 
-        fs::write(path, data).is_ok()
+        self.save_modified(&mut file)
     }
 
     fn delete(&mut self, id: RecordId) -> bool {
-        let path = self.get_path_for_record(id);
+        tracing::trace!("Delete record {} from database {:?}", id, &self.path);
 
-        tracing::trace!("Delete record {} from database {:?}", id, &self.base_path);
+        let mut file = self.file.lock().unwrap();
+        if file.records.remove(&id).is_none() {
+            return false;
+        }
 
-        fs::remove_file(path).is_ok()
+        self.save_modified(&mut file)
     }
 
     fn get_record_ids(&self) -> Vec<RecordId> {
-        fs::read_dir(&self.base_path)
-            .unwrap()
-            .filter(|x| x.as_ref().unwrap().path().is_file())
-            .map(|x| x.unwrap().file_name().to_str().unwrap().parse().unwrap())
-            .collect()
+        self.file.lock().unwrap().records.keys().copied().collect()
+    }
+
+    fn get_record_size(&self, id: RecordId) -> Option<usize> {
+        self.file.lock().unwrap().records.get(&id).map(|x| x.len())
+    }
+
+    fn count(&self) -> usize {
+        self.file.lock().unwrap().records.len()
+    }
+
+    fn next_id(&self) -> RecordId {
+        self.file.lock().unwrap().next_id
+    }
+
+    fn access_mode(&self) -> AccessMode {
+        self.file.lock().unwrap().access_mode
+    }
+
+    fn set_access_mode(&mut self, mode: AccessMode) -> bool {
+        let mut file = self.file.lock().unwrap();
+        file.access_mode = mode;
+
+        self.save(&file)
+    }
+
+    fn version(&self) -> u32 {
+        self.file.lock().unwrap().version
+    }
+
+    fn last_modified(&self) -> Option<Instant> {
+        let last_modified = self.file.lock().unwrap().last_modified;
+
+        if last_modified != 0 {
+            Some(Instant::from_epoch_millis(last_modified))
+        } else {
+            None
+        }
+    }
+}
+
+// file layout, all integers in little endian:
+// magic, format version, modification counter, last modification time (0 if never modified), next record id,
+// access mode flags, record count, then id, size and data of each record.
+// format version 1 had no next record id and access mode.
+#[derive(Default)]
+struct DatabaseFile {
+    version: u32,
+    last_modified: u64,
+    next_id: RecordId,
+    access_mode: AccessMode,
+    records: BTreeMap<RecordId, Vec<u8>>,
+}
+
+impl DatabaseFile {
+    // creates empty database if it doesn't exist, and replaces corrupt database with empty one
+    fn open(path: &Path) -> Self {
+        tracing::trace!("Opening database at {:?}", path);
+
+        if path.exists() {
+            match Self::load(path) {
+                Ok(x) => return x,
+                Err(x) => {
+                    // keep corrupt file around for recovery
+                    let backup_path = path.with_extension(format!("{}.corrupt", DATABASE_EXTENSION));
+                    tracing::error!("Failed to load database {:?}: {}, moving it to {:?}", path, x, backup_path);
+
+                    if let Err(x) = fs::rename(path, &backup_path) {
+                        tracing::error!("Failed to move corrupt database {:?}: {}", path, x);
+                    }
+                }
+            }
+        }
+
+        let file = Self::default();
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| file.save(path));
+        if let Err(x) = result {
+            tracing::error!("Failed to create database {:?}: {}", path, x);
+        }
+
+        file
+    }
+
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    fn parse(data: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = data;
+
+        if read_bytes(&mut reader, MAGIC.len())? != MAGIC {
+            bail!("Invalid database magic");
+        }
+        let format_version = read_u32(&mut reader)?;
+        if format_version != FORMAT_VERSION && format_version != LEGACY_FORMAT_VERSION {
+            bail!("Unsupported database format version {}", format_version);
+        }
+
+        let version = read_u32(&mut reader)?;
+        let last_modified = read_u64(&mut reader)?;
+        let (next_id, access_mode) = if format_version == LEGACY_FORMAT_VERSION {
+            (None, AccessMode::default())
+        } else {
+            let next_id = read_u32(&mut reader)?;
+            let flags = read_u32(&mut reader)?;
+
+            (
+                Some(next_id),
+                AccessMode {
+                    shared: flags & ACCESS_SHARED != 0,
+                    writable: flags & ACCESS_WRITABLE != 0,
+                },
+            )
+        };
+        let record_count = read_u32(&mut reader)?;
+
+        let mut records = BTreeMap::new();
+        for _ in 0..record_count {
+            let id = read_u32(&mut reader)?;
+            let size = read_u32(&mut reader)?;
+
+            records.insert(id, read_bytes(&mut reader, size as _)?.to_vec());
+        }
+        if !reader.is_empty() {
+            bail!("Trailing data in database");
+        }
+
+        // ids of deleted records of legacy database can't be known, so we can only avoid existing ones
+        let next_id = next_id.unwrap_or_else(|| records.keys().next_back().map(|x| x + 1).unwrap_or(0));
+
+        Ok(Self {
+            version,
+            last_modified,
+            next_id,
+            access_mode,
+            records,
+        })
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+
+        let mut flags = 0;
+        if self.access_mode.shared {
+            flags |= ACCESS_SHARED;
+        }
+        if self.access_mode.writable {
+            flags |= ACCESS_WRITABLE;
+        }
+
+        result.extend_from_slice(MAGIC);
+        result.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        result.extend_from_slice(&self.version.to_le_bytes());
+        result.extend_from_slice(&self.last_modified.to_le_bytes());
+        result.extend_from_slice(&self.next_id.to_le_bytes());
+        result.extend_from_slice(&flags.to_le_bytes());
+        result.extend_from_slice(&(self.records.len() as u32).to_le_bytes());
+
+        for (id, data) in &self.records {
+            result.extend_from_slice(&id.to_le_bytes());
+            result.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            result.extend_from_slice(data);
+        }
+
+        result
+    }
+
+    // written to temporary file and renamed, so crash while writing leaves previous contents intact
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let temp_path = path.with_extension(format!("{}.tmp", DATABASE_EXTENSION));
+
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&self.serialize())?;
+        file.sync_all()?;
+
+        fs::rename(&temp_path, path)?;
+
+        Ok(())
+    }
+}
+
+fn migrate(legacy_path: &Path, path: &Path) -> anyhow::Result<()> {
+    tracing::info!("Migrating database {:?} to {:?}", legacy_path, path);
+
+    let mut file = DatabaseFile::default();
+    for entry in fs::read_dir(legacy_path)? {
+        let entry = entry?;
+
+        let Some(id) = entry.file_name().to_str().and_then(|x| x.parse().ok()) else {
+            continue;
+        };
+        if entry.path().is_file() {
+            file.records.insert(id, fs::read(entry.path())?);
+        }
+    }
+    file.next_id = file.records.keys().next_back().map(|x| x + 1).unwrap_or(0);
+
+    let version_path = legacy_path.join(LEGACY_VERSION_FILE_NAME);
+    if let Ok(x) = fs::read(&version_path) {
+        file.version = x.try_into().map(u32::from_le_bytes).unwrap_or(0);
+
+        let modified = fs::metadata(&version_path)?.modified()?;
+        file.last_modified = modified.duration_since(UNIX_EPOCH)?.as_millis() as _;
+    }
+
+    // if we crash before removing legacy directory, migration is run again on next open
+    file.save(path)?;
+    fs::remove_dir_all(legacy_path)?;
+
+    Ok(())
+}
+
+fn read_bytes<'a>(reader: &mut &'a [u8], size: usize) -> anyhow::Result<&'a [u8]> {
+    if reader.len() < size {
+        bail!("Unexpected end of database");
+    }
+
+    let (result, rest) = reader.split_at(size);
+    *reader = rest;
+
+    Ok(result)
+}
+
+fn read_u32(reader: &mut &[u8]) -> anyhow::Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(reader, 4)?.try_into()?))
+}
+
+fn read_u64(reader: &mut &[u8]) -> anyhow::Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(reader, 8)?.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, process};
+
+    use wie_backend::{AccessMode, DatabaseRepository as _};
+
+    use super::{DatabaseFile, DatabaseRepository, DATABASE_EXTENSION, LEGACY_FORMAT_VERSION, MAGIC};
+
+    fn test_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("wie_database_test_{}_{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+
+        path
+    }
+
+    #[test]
+    fn test_format() {
+        let mut file = DatabaseFile {
+            version: 3,
+            last_modified: 1234,
+            next_id: 5,
+            access_mode: AccessMode {
+                shared: true,
+                writable: false,
+            },
+            ..Default::default()
+        };
+        file.records.insert(1, vec![1, 2, 3]);
+
+        let parsed = DatabaseFile::parse(&file.serialize()).unwrap();
+        assert_eq!(parsed.version, 3);
+        assert_eq!(parsed.last_modified, 1234);
+        assert_eq!(parsed.next_id, 5);
+        assert_eq!(parsed.access_mode, file.access_mode);
+        assert_eq!(parsed.records, file.records);
+
+        let data = file.serialize();
+        assert!(DatabaseFile::parse(&data[..data.len() - 1]).is_err());
+        assert!(DatabaseFile::parse(b"XXXX").is_err());
+    }
+
+    #[test]
+    fn test_legacy_format() {
+        let mut data = Vec::new();
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&LEGACY_FORMAT_VERSION.to_le_bytes());
+        data.extend_from_slice(&7u32.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.push(9);
+
+        let parsed = DatabaseFile::parse(&data).unwrap();
+        assert_eq!(parsed.version, 7);
+        assert_eq!(parsed.next_id, 3);
+        assert_eq!(parsed.records.get(&2), Some(&vec![9]));
+    }
+
+    #[test]
+    fn test_next_id_persisted() {
+        let path = test_path("next_id");
+
+        let repository = DatabaseRepository::with_base_path(path.clone());
+        let mut database = repository.open("save", "app");
+        assert_eq!(database.add(&[1]), 0);
+        assert_eq!(database.add(&[2]), 1);
+        assert!(database.delete(1));
+        assert!(database.set_access_mode(AccessMode {
+            shared: false,
+            writable: true
+        }));
+
+        // new repository reads from file
+        let repository = DatabaseRepository::with_base_path(path.clone());
+        let mut database = repository.open("save", "app");
+        assert_eq!(database.next_id(), 2);
+        assert_eq!(database.add(&[3]), 2);
+        assert_eq!(database.get_record_ids(), [0, 2]);
+        assert!(database.access_mode().writable);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate() {
+        let path = test_path("migrate");
+
+        let legacy_path = path.join("app").join("save");
+        fs::create_dir_all(&legacy_path).unwrap();
+        fs::write(legacy_path.join("0"), [1, 2]).unwrap();
+        fs::write(legacy_path.join("3"), [3]).unwrap();
+        fs::write(legacy_path.join("version"), 5u32.to_le_bytes()).unwrap();
+
+        let repository = DatabaseRepository::with_base_path(path.clone());
+        assert_eq!(repository.list("app"), ["save"]);
+
+        let database = repository.open("save", "app");
+        assert_eq!(database.get_record_ids(), [0, 3]);
+        assert_eq!(database.get(0).unwrap(), [1, 2]);
+        assert_eq!(database.version(), 5);
+        assert_eq!(database.next_id(), 4);
+        assert!(!legacy_path.exists());
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let path = test_path("corrupt");
+
+        let database_path = path.join("app").join(format!("save.{}", DATABASE_EXTENSION));
+        fs::create_dir_all(database_path.parent().unwrap()).unwrap();
+        fs::write(&database_path, b"corrupt").unwrap();
+
+        let repository = DatabaseRepository::with_base_path(path.clone());
+        let database = repository.open("save", "app");
+        assert_eq!(database.count(), 0);
+        assert!(database_path.with_extension(format!("{}.corrupt", DATABASE_EXTENSION)).exists());
+
+        fs::remove_dir_all(&path).unwrap();
     }
 }
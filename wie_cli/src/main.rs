@@ -1,7 +1,10 @@
 extern crate alloc;
 
+mod audio_mixer;
 mod audio_sink;
 mod database;
+mod save;
+mod storage;
 mod window;
 
 use core::str;
@@ -10,100 +13,158 @@ use std::{
     error::Error,
     fs,
     io::stderr,
-    sync::mpsc::{channel, Receiver, Sender},
+    path::Path,
+    sync::mpsc,
     thread,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use midir::MidiOutput;
-use rodio::{buffer::SamplesBuffer, OutputStream, Sink};
+use rodio::{OutputStream, Source};
 use winit::keyboard::{KeyCode as WinitKeyCode, PhysicalKey};
 
-use wie_backend::{extract_zip, Emulator, Event, Instant, KeyCode, Platform, Screen};
+use wie_backend::{
+    extract_zip, BacklightState, Emulator, Event, HandsetProfile, Indicators, Instant, KeyCode, MidiLogAudioSink, Platform, RecordingAudioSink,
+    Screen, SynthAudioSink,
+};
 use wie_j2me::J2MEEmulator;
 use wie_ktf::KtfEmulator;
 use wie_lgt::LgtEmulator;
 use wie_skt::SktEmulator;
 
 use self::{
+    audio_mixer::{AudioMixer, RenderSource},
     audio_sink::AudioSink,
     database::DatabaseRepository,
-    window::{WindowCallbackEvent, WindowImpl},
+    save::{export_save, import_save},
+    storage::StorageRepository,
+    window::{WindowCallbackEvent, WindowHandle, WindowImpl},
 };
 
+const SYNTH_SAMPLE_RATE: u32 = 44100;
+
 struct WieCliPlatform {
-    audio_thread_tx: Sender<(u8, u32, Vec<i16>)>,
+    // wave mixer and software synthesizer playing on audio output. `None` if audio output couldn't be opened
+    audio_output: Option<(AudioMixer, SynthAudioSink)>,
     database_repository: DatabaseRepository,
-    window: Box<dyn Screen>,
+    storage_repository: StorageRepository,
+    window: WindowHandle,
+    handset_profile: HandsetProfile,
+    record_audio: Option<String>,
+    record_midi: Option<String>,
 }
 
 impl WieCliPlatform {
-    fn new(window: Box<dyn Screen>) -> Self {
-        let (tx, rx) = channel();
-        thread::spawn(|| Self::audio_thread(rx));
-
+    fn new(window: WindowHandle, handset_profile: HandsetProfile, record_audio: Option<String>, record_midi: Option<String>) -> Self {
         Self {
-            audio_thread_tx: tx,
+            audio_output: Self::start_audio_output(),
             database_repository: DatabaseRepository::new(),
+            storage_repository: StorageRepository::new(),
             window,
+            handset_profile,
+            record_audio,
+            record_midi,
         }
     }
 
-    fn audio_thread(rx: Receiver<(u8, u32, Vec<i16>)>) {
-        let default_output = OutputStream::try_default();
-        if default_output.is_err() {
-            // do nothing if we can't open output
-            loop {
-                rx.recv().unwrap();
-            }
+    // waits until output is opened, so that audio played right after start isn't lost
+    fn start_audio_output() -> Option<(AudioMixer, SynthAudioSink)> {
+        let mixer = AudioMixer::new();
+        let synth = SynthAudioSink::new(SYNTH_SAMPLE_RATE);
+
+        let (sender, receiver) = mpsc::channel();
+        let (mixer_clone, synth_clone) = (mixer.clone(), synth.clone());
+        thread::spawn(move || Self::audio_thread(mixer_clone, synth_clone, sender));
+
+        if !receiver.recv().unwrap_or(false) {
+            tracing::warn!("Failed to open audio output");
+
+            return None;
         }
 
-        let (_output_stream, stream_handle) = default_output.unwrap();
-        let sink = Sink::try_new(&stream_handle).unwrap();
+        Some((mixer, synth))
+    }
+
+    fn audio_thread(mixer: AudioMixer, synth: SynthAudioSink, started: mpsc::Sender<bool>) {
+        let Ok((_output_stream, stream_handle)) = OutputStream::try_default() else {
+            let _ = started.send(false);
+            return;
+        };
+
+        // software synthesizer is used when there's no midi output port
+        let synth_source = RenderSource::new(1, synth.sample_rate(), move |x| synth.render(x));
+        let result = stream_handle
+            .play_raw(mixer.source().convert_samples())
+            .and_then(|_| stream_handle.play_raw(synth_source.convert_samples()));
+
+        let _ = started.send(result.is_ok());
+        if result.is_err() {
+            return;
+        }
 
+        // keep output stream alive
         loop {
-            let result = rx.recv();
-            if result.is_err() {
-                break;
-            }
-            let (channel, sampling_rate, wave_data) = result.unwrap();
+            thread::park();
+        }
+    }
 
-            let buffer = SamplesBuffer::new(channel as _, sampling_rate as _, wave_data);
+    fn output_audio_sink(&self) -> Box<dyn wie_backend::AudioSink> {
+        let midi_out = (|| {
+            let midi_out = MidiOutput::new("wie_cli")?;
+            let midi_ports = midi_out.ports();
+            let out_port = midi_ports.last().ok_or_else(|| anyhow::anyhow!("No MIDI output port"))?;
 
-            // TODO we should be able to play multiple audio at once
-            sink.append(buffer);
+            Ok::<_, Box<dyn Error>>(midi_out.connect(out_port, "wie_cli")?)
+        })()
+        .ok();
+
+        let (mixer, synth) = self.audio_output.clone().unzip();
+
+        let synth = synth.filter(|_| midi_out.is_none());
+        if synth.is_some() {
+            tracing::info!("No MIDI output port, using software synthesizer");
         }
+
+        Box::new(AudioSink::new(midi_out, synth, mixer))
     }
 }
 
 impl Platform for WieCliPlatform {
     fn screen(&mut self) -> &mut dyn Screen {
-        self.window.as_mut()
+        &mut self.window
     }
 
     fn now(&self) -> Instant {
-        let now = SystemTime::now();
-        let since_the_epoch = now.duration_since(UNIX_EPOCH).unwrap();
-
-        Instant::from_epoch_millis(since_the_epoch.as_millis() as _)
+        now()
     }
 
     fn database_repository(&self) -> &dyn wie_backend::DatabaseRepository {
         &self.database_repository
     }
 
+    fn storage_repository(&self) -> &dyn wie_backend::StorageRepository {
+        &self.storage_repository
+    }
+
     fn audio_sink(&self) -> Box<dyn wie_backend::AudioSink> {
-        let midi_out = (|| {
-            let midi_out = MidiOutput::new("wie_cli")?;
-            let midi_ports = midi_out.ports();
-            let out_port = midi_ports.last().ok_or_else(|| anyhow::anyhow!("No MIDI output port"))?;
+        let mut sink = self.output_audio_sink();
 
-            Ok::<_, Box<dyn Error>>(midi_out.connect(out_port, "wie_cli")?)
-        })()
-        .ok();
+        if let Some(path) = &self.record_midi {
+            match fs::File::create(path) {
+                Ok(file) => sink = Box::new(MidiLogAudioSink::new(sink, file, now)),
+                Err(x) => tracing::error!("Failed to create midi log {}: {}", path, x),
+            }
+        }
 
-        Box::new(AudioSink::new(midi_out, self.audio_thread_tx.clone()))
+        if let Some(path) = &self.record_audio {
+            match fs::File::create(path) {
+                Ok(file) => return Box::new(RecordingAudioSink::new(sink, file, now)),
+                Err(x) => tracing::error!("Failed to create audio recording {}: {}", path, x),
+            }
+        }
+
+        sink
     }
 
     fn write_stdout(&self, buf: &[u8]) {
@@ -111,11 +172,59 @@ impl Platform for WieCliPlatform {
 
         tracing::info!("stdout: {}", str)
     }
+
+    fn vibrate(&mut self, duration: u32, level: u8) {
+        let duration = if level > 0 { duration } else { 0 };
+
+        self.window.vibrate(Duration::from_millis(duration as _))
+    }
+
+    fn set_backlight(&mut self, state: BacklightState, timeout: Option<u32>) {
+        let timeout = timeout.map(|x| Duration::from_millis(x as _));
+
+        self.window.set_dimmed(state == BacklightState::Off, timeout)
+    }
+
+    fn handset_profile(&self) -> HandsetProfile {
+        self.handset_profile
+    }
+
+    fn update_indicators(&mut self, indicators: &Indicators) {
+        self.window.set_indicators(indicators)
+    }
+}
+
+fn now() -> Instant {
+    let now = SystemTime::now();
+    let since_the_epoch = now.duration_since(UNIX_EPOCH).unwrap();
+
+    Instant::from_epoch_millis(since_the_epoch.as_millis() as _)
 }
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-    filename: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(required = true)]
+    filename: Option<String>,
+    /// Record played audio into wave file
+    #[arg(long)]
+    record_audio: Option<String>,
+    /// Record played midi events into standard midi file
+    #[arg(long)]
+    record_midi: Option<String>,
+    /// Height of annunciator bar on top of screen, which is not available to app
+    #[arg(long, default_value_t = 0)]
+    annunciator_height: u32,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Export saved data of an app into portable archive
+    Export { filename: String, output: String },
+    /// Import saved data of an app from exported archive, or files of an app from handset dump directory
+    Import { filename: String, input: String },
 }
 
 fn main() -> anyhow::Result<()> {
@@ -124,12 +233,63 @@ fn main() -> anyhow::Result<()> {
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    start(&Args::parse().filename)
+    let args = Args::parse();
+
+    match args.command {
+        Some(Command::Export { filename, output }) => export_save(
+            &DatabaseRepository::new(),
+            &StorageRepository::new(),
+            &app_id(&filename)?,
+            Path::new(&output),
+        ),
+        Some(Command::Import { filename, input }) => import_save(
+            &DatabaseRepository::new(),
+            &StorageRepository::new(),
+            &app_id(&filename)?,
+            Path::new(&input),
+        ),
+        None => {
+            let handset_profile = HandsetProfile {
+                annunciator_height: args.annunciator_height,
+                ..Default::default()
+            };
+
+            start(&args.filename.unwrap(), handset_profile, args.record_audio, args.record_midi)
+        }
+    }
+}
+
+// must match id which `start` loads app with
+fn app_id(filename: &str) -> anyhow::Result<String> {
+    if filename.ends_with("zip") {
+        let files = extract_zip(&fs::read(filename)?).unwrap();
+
+        if KtfEmulator::loadable_archive(&files) {
+            Ok(KtfEmulator::archive_app_id(&files))
+        } else if LgtEmulator::loadable_archive(&files) {
+            Ok(LgtEmulator::archive_app_id(&files))
+        } else if SktEmulator::loadable_archive(&files) {
+            Ok(SktEmulator::archive_app_id(&files))
+        } else {
+            anyhow::bail!("Unknown archive format");
+        }
+    } else if filename.ends_with("jad") {
+        Ok(J2MEEmulator::jad_app_id(&fs::read(filename)?))
+    } else if filename.ends_with("jar") {
+        Ok(filename.trim_end_matches(".jar").to_owned())
+    } else {
+        anyhow::bail!("Unknown file format");
+    }
 }
 
-pub fn start(filename: &str) -> anyhow::Result<()> {
+pub fn start(filename: &str, handset_profile: HandsetProfile, record_audio: Option<String>, record_midi: Option<String>) -> anyhow::Result<()> {
     let window = WindowImpl::new(240, 320).unwrap(); // TODO hardcoded size
-    let platform = Box::new(WieCliPlatform::new(Box::new(window.handle())));
+    let platform = Box::new(WieCliPlatform::new(
+        window.handle(handset_profile.annunciator_height),
+        handset_profile,
+        record_audio,
+        record_midi,
+    ));
 
     let buf = fs::read(filename)?;
     let mut emulator: Box<dyn Emulator> = if filename.ends_with("zip") {
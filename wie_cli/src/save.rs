@@ -0,0 +1,327 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs::{self, File},
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail};
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use wie_backend::{DatabaseRepository, RecordId, Storage, StorageRepository};
+
+// portable save archive layout:
+// `app_id`: id of the app the save belongs to
+// `databases/{name}/{record id}`: records of each database
+// `files/{path}`: writable files of the app
+const APP_ID_ENTRY: &str = "app_id";
+const DATABASES_PREFIX: &str = "databases/";
+const FILES_PREFIX: &str = "files/";
+
+pub fn export_save(
+    database_repository: &dyn DatabaseRepository,
+    storage_repository: &dyn StorageRepository,
+    app_id: &str,
+    output: &Path,
+) -> anyhow::Result<()> {
+    tracing::info!("Exporting save of {} to {:?}", app_id, output);
+
+    let mut writer = ZipWriter::new(File::create(output)?);
+    let options = SimpleFileOptions::default();
+
+    writer.start_file(APP_ID_ENTRY, options)?;
+    writer.write_all(app_id.as_bytes())?;
+
+    for name in database_repository.list(app_id) {
+        let database = database_repository.open(&name, app_id);
+
+        // empty databases are kept as directory entries
+        writer.add_directory(format!("{}{}/", DATABASES_PREFIX, name), options)?;
+        for id in database.get_record_ids() {
+            writer.start_file(format!("{}{}/{}", DATABASES_PREFIX, name, id), options)?;
+            writer.write_all(&database.get(id).unwrap())?;
+        }
+    }
+
+    let storage = storage_repository.open(app_id);
+    let mut directories = vec![String::new()];
+    while let Some(directory) = directories.pop() {
+        for entry in storage.list(&directory) {
+            let path = if directory.is_empty() {
+                entry
+            } else {
+                format!("{}/{}", directory, entry)
+            };
+
+            if storage.is_dir(&path) {
+                writer.add_directory(format!("{}{}/", FILES_PREFIX, path), options)?;
+                directories.push(path);
+            } else {
+                writer.start_file(format!("{}{}", FILES_PREFIX, path), options)?;
+                writer.write_all(&storage.read(&path).unwrap())?;
+            }
+        }
+    }
+
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Imports save exported by [`export_save`], or files of the app found in handset dump directory.
+///
+/// Databases of the app are replaced, while files are written over existing ones.
+/// Archive is validated before anything is replaced, so invalid archive leaves current save intact.
+pub fn import_save(
+    database_repository: &dyn DatabaseRepository,
+    storage_repository: &dyn StorageRepository,
+    app_id: &str,
+    input: &Path,
+) -> anyhow::Result<()> {
+    tracing::info!("Importing save of {} from {:?}", app_id, input);
+
+    let mut storage = storage_repository.open(app_id);
+    if input.is_dir() {
+        return import_handset_dump(storage.as_mut(), app_id, input);
+    }
+
+    let save = read_save(&mut ZipArchive::new(File::open(input)?)?, app_id)?;
+
+    for name in database_repository.list(app_id) {
+        database_repository.delete(&name, app_id);
+    }
+
+    for (name, records) in &save.databases {
+        let mut database = database_repository.open(name, app_id);
+        for (&id, data) in records {
+            database.set(id, data);
+        }
+    }
+
+    for directory in &save.directories {
+        storage.create_dir(directory);
+    }
+    for (path, data) in &save.files {
+        if !storage.write(path, data) {
+            bail!("Failed to write {}", path);
+        }
+    }
+
+    Ok(())
+}
+
+// contents of save archive, sorted so directories are created before their contents
+#[derive(Default)]
+struct Save {
+    databases: BTreeMap<String, BTreeMap<RecordId, Vec<u8>>>,
+    directories: Vec<String>,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+fn read_save<R: Read + Seek>(archive: &mut ZipArchive<R>, app_id: &str) -> anyhow::Result<Save> {
+    let mut entries = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+
+        entries.insert(file.name().to_owned(), data);
+    }
+
+    let archive_app_id = entries.get(APP_ID_ENTRY).ok_or_else(|| anyhow!("Not a save archive"))?;
+    if archive_app_id != app_id.as_bytes() {
+        bail!("Save belongs to app {}, not {}", String::from_utf8_lossy(archive_app_id), app_id);
+    }
+
+    let mut save = Save::default();
+    for (name, data) in entries {
+        if let Some(entry) = name.strip_prefix(DATABASES_PREFIX) {
+            let (database_name, record_id) = entry.split_once('/').ok_or_else(|| anyhow!("Invalid database entry {}", name))?;
+            validate_path(database_name)?;
+
+            let records = save.databases.entry(database_name.to_owned()).or_default();
+            if !record_id.is_empty() {
+                let id = record_id.parse().map_err(|_| anyhow!("Invalid record id in {}", name))?;
+                records.insert(id, data);
+            }
+        } else if let Some(path) = name.strip_prefix(FILES_PREFIX) {
+            if let Some(directory) = path.strip_suffix('/') {
+                validate_path(directory)?;
+                save.directories.push(directory.to_owned());
+            } else {
+                validate_path(path)?;
+                save.files.push((path.to_owned(), data));
+            }
+        }
+    }
+
+    Ok(save)
+}
+
+// archive paths are joined onto storage directory, so they must not be able to escape it
+fn validate_path(path: &str) -> anyhow::Result<()> {
+    if path.is_empty() || path.starts_with('/') || path.contains(['\\', ':']) || path.split('/').any(|x| x.is_empty() || x == "." || x == "..") {
+        bail!("Invalid path {:?} in save archive", path);
+    }
+
+    Ok(())
+}
+
+// handset dumps keep writable files of each app under a directory named after its app id.
+// only those files are imported; databases are kept in vendor specific (KTF, SKT, LGT) formats we don't parse,
+// so databases of the app are left as is.
+fn import_handset_dump(storage: &mut dyn Storage, app_id: &str, dump_path: &Path) -> anyhow::Result<()> {
+    let app_path = find_directory(dump_path, app_id)?.ok_or_else(|| anyhow!("Directory of app {} not found in {:?}", app_id, dump_path))?;
+
+    tracing::info!("Found files of {} at {:?}", app_id, app_path);
+    tracing::warn!("Databases are not imported from handset dumps");
+
+    let mut directories = vec![(app_path, String::new())];
+    while let Some((directory, prefix)) = directories.pop() {
+        for entry in fs::read_dir(&directory)? {
+            let entry = entry?;
+            let name = entry.file_name().into_string().map_err(|x| anyhow!("Invalid file name {:?}", x))?;
+            let path = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+            validate_path(&path)?;
+
+            if entry.path().is_dir() {
+                storage.create_dir(&path);
+                directories.push((entry.path(), path));
+            } else if !storage.write(&path, &fs::read(entry.path())?) {
+                bail!("Failed to write {}", path);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// breadth first, so the shallowest match is used
+fn find_directory(base_path: &Path, name: &str) -> anyhow::Result<Option<PathBuf>> {
+    let mut queue = VecDeque::from([base_path.to_owned()]);
+    while let Some(directory) = queue.pop_front() {
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            if path.file_name().is_some_and(|x| x == name) {
+                return Ok(Some(path));
+            }
+            queue.push_back(path);
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        io::Write,
+        path::{Path, PathBuf},
+        process,
+    };
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use wie_backend::{DatabaseRepository as _, StorageRepository as _};
+
+    use crate::{database::DatabaseRepository, storage::StorageRepository};
+
+    use super::{export_save, import_save};
+
+    const APP_ID: &str = "test_app";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("wie_save_test_{}_{}", process::id(), name));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    fn repositories(path: &Path) -> (DatabaseRepository, StorageRepository) {
+        (
+            DatabaseRepository::with_base_path(path.join("databases")),
+            StorageRepository::with_base_path(path.join("files")),
+        )
+    }
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        let path = temp_dir("round_trip");
+        let (database_repository, storage_repository) = repositories(&path);
+
+        let mut database = database_repository.open("score", APP_ID);
+        database.add(b"first");
+        database.add(b"second");
+        database_repository.open("empty", APP_ID);
+
+        let mut storage = storage_repository.open(APP_ID);
+        storage.create_dir("save");
+        storage.write("save/1.dat", b"file");
+
+        let archive = path.join("save.zip");
+        export_save(&database_repository, &storage_repository, APP_ID, &archive)?;
+
+        let target = temp_dir("round_trip_target");
+        let (target_database_repository, target_storage_repository) = repositories(&target);
+        target_database_repository.open("stale", APP_ID).add(b"stale");
+
+        import_save(&target_database_repository, &target_storage_repository, APP_ID, &archive)?;
+
+        assert_eq!(target_database_repository.list(APP_ID), ["empty", "score"]);
+        let database = target_database_repository.open("score", APP_ID);
+        assert_eq!(database.get_record_ids(), [0, 1]);
+        assert_eq!(database.get(1).unwrap(), b"second");
+        assert!(target_database_repository.open("empty", APP_ID).get_record_ids().is_empty());
+
+        let storage = target_storage_repository.open(APP_ID);
+        assert!(storage.is_dir("save"));
+        assert_eq!(storage.read("save/1.dat").unwrap(), b"file");
+
+        fs::remove_dir_all(path)?;
+        fs::remove_dir_all(target)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_archive_keeps_save() -> anyhow::Result<()> {
+        let path = temp_dir("invalid");
+        let (database_repository, storage_repository) = repositories(&path);
+        database_repository.open("score", APP_ID).add(b"kept");
+
+        for name in [
+            "files/../escape",
+            "files//absolute",
+            "files/a\\b",
+            "databases/../x/0",
+            "databases/score/invalid",
+        ] {
+            let archive = path.join("invalid.zip");
+            let mut writer = ZipWriter::new(fs::File::create(&archive)?);
+            writer.start_file("app_id", SimpleFileOptions::default())?;
+            writer.write_all(APP_ID.as_bytes())?;
+            writer.start_file(name, SimpleFileOptions::default())?;
+            writer.write_all(b"data")?;
+            writer.finish()?;
+
+            assert!(
+                import_save(&database_repository, &storage_repository, APP_ID, &archive).is_err(),
+                "{}",
+                name
+            );
+            assert_eq!(database_repository.open("score", APP_ID).get(0).unwrap(), b"kept");
+        }
+        assert!(!path.join("escape").exists());
+
+        fs::remove_dir_all(path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{Seek, SeekFrom, Write},
+    path::PathBuf,
+    time::UNIX_EPOCH,
+};
+
+use directories::ProjectDirs;
+
+use wie_backend::Instant;
+
+pub struct StorageRepository {
+    base_path: PathBuf,
+}
+
+impl StorageRepository {
+    pub fn new() -> Self {
+        let base_dir = ProjectDirs::from("net", "dlunch", "wie").unwrap();
+
+        Self::with_base_path(base_dir.data_dir().join("files"))
+    }
+
+    pub fn with_base_path(base_path: PathBuf) -> Self {
+        Self { base_path }
+    }
+}
+
+impl wie_backend::StorageRepository for StorageRepository {
+    fn open(&self, app_id: &str) -> Box<dyn wie_backend::Storage> {
+        let path = self.base_path.join(app_id);
+
+        Box::new(Storage::new(path))
+    }
+}
+
+pub struct Storage {
+    base_path: PathBuf,
+}
+
+impl Storage {
+    // failure is reported to application by failing writes, so that it can run without saving
+    pub fn new(base_path: PathBuf) -> Self {
+        tracing::trace!("Opening storage at {:?}", base_path);
+
+        if let Err(x) = fs::create_dir_all(&base_path) {
+            tracing::error!("Failed to create storage at {:?}: {}", base_path, x);
+        }
+
+        Self { base_path }
+    }
+
+    // paths are normalized by filesystem, so they can't escape base path
+    fn get_path(&self, path: &str) -> PathBuf {
+        self.base_path.join(path)
+    }
+}
+
+impl wie_backend::Storage for Storage {
+    fn read(&self, path: &str) -> Option<Vec<u8>> {
+        tracing::trace!("Read {} from storage {:?}", path, &self.base_path);
+
+        fs::read(self.get_path(path)).ok()
+    }
+
+    fn size(&self, path: &str) -> Option<u64> {
+        let metadata = fs::metadata(self.get_path(path)).ok()?;
+
+        metadata.is_file().then_some(metadata.len())
+    }
+
+    fn created(&self, path: &str) -> Option<Instant> {
+        let metadata = fs::metadata(self.get_path(path)).ok()?;
+        // some filesystems don't keep creation time
+        let created = metadata.created().or_else(|_| metadata.modified()).ok()?;
+
+        Some(Instant::from_epoch_millis(created.duration_since(UNIX_EPOCH).ok()?.as_millis() as _))
+    }
+
+    fn write(&mut self, path: &str, data: &[u8]) -> bool {
+        tracing::trace!("Write {} to storage {:?}", path, &self.base_path);
+
+        fs::write(self.get_path(path), data).is_ok()
+    }
+
+    fn write_at(&mut self, path: &str, offset: u64, data: &[u8]) -> bool {
+        tracing::trace!("Write {} bytes at {} of {} to storage {:?}", data.len(), offset, path, &self.base_path);
+
+        (|| {
+            let mut file = OpenOptions::new().write(true).create(true).truncate(false).open(self.get_path(path))?;
+            file.seek(SeekFrom::Start(offset))?;
+            file.write_all(data)
+        })()
+        .is_ok()
+    }
+
+    fn remove(&mut self, path: &str) -> bool {
+        tracing::trace!("Remove {} from storage {:?}", path, &self.base_path);
+
+        let path = self.get_path(path);
+        if path.is_dir() {
+            fs::remove_dir(path).is_ok()
+        } else {
+            fs::remove_file(path).is_ok()
+        }
+    }
+
+    fn create_dir(&mut self, path: &str) -> bool {
+        tracing::trace!("Create directory {} in storage {:?}", path, &self.base_path);
+
+        fs::create_dir(self.get_path(path)).is_ok()
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        self.get_path(path).is_dir()
+    }
+
+    fn list(&self, path: &str) -> Vec<String> {
+        fs::read_dir(self.get_path(path))
+            .map(|entries| entries.filter_map(|x| x.ok()?.file_name().into_string().ok()).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use wie_backend::Storage as _;
+
+    use super::Storage;
+
+    #[test]
+    fn test_write_at() {
+        let path = env::temp_dir().join(format!("wie_storage_test_{}", process::id()));
+        let _ = fs::remove_dir_all(&path);
+
+        let mut storage = Storage::new(path.clone());
+        assert!(storage.write_at("a.dat", 2, b"cd"));
+        assert!(storage.write_at("a.dat", 0, b"ab"));
+        assert_eq!(storage.read("a.dat").unwrap(), b"abcd");
+        assert_eq!(storage.size("a.dat"), Some(4));
+
+        assert!(storage.create_dir("dir"));
+        assert_eq!(storage.size("dir"), None);
+        assert_eq!(storage.size("missing.dat"), None);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
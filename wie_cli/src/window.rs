@@ -1,6 +1,6 @@
 use alloc::sync::Arc;
-use core::{fmt::Debug, fmt::Formatter, num::NonZeroU32};
-use std::fmt;
+use core::{fmt::Debug, fmt::Formatter, num::NonZeroU32, time::Duration};
+use std::{fmt, time::Instant};
 
 use fast_image_resize::ResizeAlg;
 use fast_image_resize::{PixelType, ResizeOptions, SrcCropping};
@@ -14,12 +14,26 @@ use winit::{
     window::{Window as WinitWindow, WindowId},
 };
 
-use wie_backend::{canvas::Image, Screen};
+use wie_backend::{canvas::Image, Indicators, Rect, Screen};
+
+// content is shaken horizontally by this many pixels while vibrating
+const SHAKE_OFFSET: i32 = 2;
+const SHAKE_INTERVAL_MILLIS: u128 = 30;
+// lit led is shown as a border around content
+const LED_BORDER: usize = 2;
+
+const ANNUNCIATOR_BACKGROUND: u32 = 0x303030;
+const ANNUNCIATOR_FOREGROUND: u32 = 0xe0e0e0;
+const ANNUNCIATOR_ICON_SIZE: u32 = 12;
+const ANNUNCIATOR_ICON_SPACING: u32 = 4;
 
 #[derive(Debug)]
 pub enum WindowInternalEvent {
     RequestRedraw,
-    Paint(Vec<u32>),
+    Paint { region: Rect, data: Vec<u32> },
+    Vibrate { duration: Duration },
+    SetDimmed { dimmed: bool, timeout: Option<Duration> },
+    SetLed { color: u32 },
 }
 
 pub enum WindowCallbackEvent {
@@ -32,6 +46,8 @@ pub enum WindowCallbackEvent {
 pub struct WindowHandle {
     width: u32,
     height: u32,
+    /// Content is painted below area reserved for annunciator.
+    annunciator_height: u32,
     event_loop_proxy: EventLoopProxy<WindowInternalEvent>,
 }
 
@@ -41,6 +57,61 @@ impl WindowHandle {
 
         Ok(())
     }
+
+    /// Shakes window content for `duration`, zero `duration` stops shaking.
+    pub fn vibrate(&self, duration: Duration) {
+        self.send_event(WindowInternalEvent::Vibrate { duration }).unwrap()
+    }
+
+    /// Dims window content to emulate turned off backlight, restored after `timeout` if given.
+    pub fn set_dimmed(&self, dimmed: bool, timeout: Option<Duration>) {
+        self.send_event(WindowInternalEvent::SetDimmed { dimmed, timeout }).unwrap()
+    }
+
+    /// Renders annunciator bar on top of content, and shows first lit led.
+    pub fn set_indicators(&mut self, indicators: &Indicators) {
+        if self.annunciator_height > 0 {
+            let region = Rect::new(0, 0, self.width, self.annunciator_height);
+            let data = render_annunciator(self.width, self.annunciator_height, indicators);
+
+            self.send_event(WindowInternalEvent::Paint { region, data }).unwrap();
+        }
+
+        let color = indicators.leds.iter().copied().find(|&x| x != 0).unwrap_or(0);
+        self.send_event(WindowInternalEvent::SetLed { color }).unwrap()
+    }
+}
+
+/// Draws each visible icon as signal bars, with number of bars given by icon state.
+/// Reserved area is left blank while annunciator is hidden.
+fn render_annunciator(width: u32, height: u32, indicators: &Indicators) -> Vec<u32> {
+    if !indicators.annunciator_visible {
+        return vec![0; (width * height) as usize];
+    }
+
+    let mut data = vec![ANNUNCIATOR_BACKGROUND; (width * height) as usize];
+
+    let icon_size = ANNUNCIATOR_ICON_SIZE.min(height);
+    let top = (height - icon_size) / 2;
+    let visible_icons = indicators.annunciator_icons.values().filter(|&&state| state != 0);
+    for (index, &state) in visible_icons.enumerate() {
+        let left = ANNUNCIATOR_ICON_SPACING + index as u32 * (icon_size + ANNUNCIATOR_ICON_SPACING);
+        if left + icon_size > width {
+            break;
+        }
+
+        let bars = state.min(4);
+        for bar in 0..bars {
+            let bar_height = (bar + 1) * icon_size / 4;
+            for y in top + icon_size - bar_height..top + icon_size {
+                for x in left + bar * 3..left + bar * 3 + 2 {
+                    data[(y * width + x) as usize] = ANNUNCIATOR_FOREGROUND;
+                }
+            }
+        }
+    }
+
+    data
 }
 
 impl Screen for WindowHandle {
@@ -49,13 +120,32 @@ impl Screen for WindowHandle {
     }
 
     fn paint(&mut self, image: &dyn Image) {
-        let data = image
-            .colors()
-            .iter()
-            .map(|x| ((x.a as u32) << 24) | ((x.r as u32) << 16) | ((x.g as u32) << 8) | (x.b as u32))
+        self.paint_region(image, Rect::new(0, 0, image.width(), image.height()))
+    }
+
+    fn paint_region(&mut self, image: &dyn Image, region: Rect) {
+        let region = match Rect::clipped(
+            region.x as _,
+            region.y as _,
+            region.width as _,
+            region.height as _,
+            image.width().min(self.width),
+            image.height().min(self.height - self.annunciator_height),
+        ) {
+            Some(x) => x,
+            None => return,
+        };
+
+        let data = (region.y..region.y + region.height)
+            .flat_map(|y| (region.x..region.x + region.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let color = image.get_pixel(x, y);
+                ((color.a as u32) << 24) | ((color.r as u32) << 16) | ((color.g as u32) << 8) | (color.b as u32)
+            })
             .collect::<Vec<_>>();
 
-        self.send_event(WindowInternalEvent::Paint(data)).unwrap()
+        let region = Rect::new(region.x, region.y + self.annunciator_height, region.width, region.height);
+        self.send_event(WindowInternalEvent::Paint { region, data }).unwrap()
     }
 
     fn width(&self) -> u32 {
@@ -80,10 +170,12 @@ impl WindowImpl {
         Ok(Self { width, height, event_loop })
     }
 
-    pub fn handle(&self) -> WindowHandle {
+    /// Creates handle painting content below `annunciator_height`.
+    pub fn handle(&self, annunciator_height: u32) -> WindowHandle {
         WindowHandle {
             width: self.width,
             height: self.height,
+            annunciator_height: annunciator_height.min(self.height),
             event_loop_proxy: self.event_loop.create_proxy(),
         }
     }
@@ -109,6 +201,11 @@ impl WindowImpl {
             surface: None,
             callback: Box::new(callback),
             last_frame: None,
+            vibration_end: None,
+            dimmed: false,
+            dim_end: None,
+            led: 0,
+            effects: Effects::default(),
         };
 
         Ok(self.event_loop.run_app(&mut handler)?)
@@ -206,6 +303,52 @@ impl Scaler {
     }
 }
 
+/// Visual effects applied on top of content.
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+struct Effects {
+    /// Horizontal offset of content, nonzero while vibrating.
+    offset: i32,
+    dimmed: bool,
+    /// Color of lit led, 0 if off.
+    led: u32,
+}
+
+impl Effects {
+    fn is_active(&self) -> bool {
+        self.offset != 0 || self.dimmed || self.led != 0
+    }
+
+    fn apply(&self, src: &[u32], width: usize) -> Vec<u32> {
+        let height = src.len() / width;
+        let on_border = move |x: usize, y: usize| x < LED_BORDER || y < LED_BORDER || x >= width - LED_BORDER || y >= height - LED_BORDER;
+
+        src.chunks(width)
+            .enumerate()
+            .flat_map(|(y, line)| {
+                (0..width as i32).map(move |x| {
+                    if self.led != 0 && on_border(x as usize, y) {
+                        return self.led;
+                    }
+
+                    let src_x = x - self.offset;
+                    let color = if src_x >= 0 && (src_x as usize) < line.len() {
+                        line[src_x as usize]
+                    } else {
+                        0
+                    };
+
+                    // quarter of brightness on each channel
+                    if self.dimmed {
+                        (color >> 2) & 0x003f3f3f
+                    } else {
+                        color
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
 pub struct ApplicationHandlerImpl<C>
 where
     C: FnMut(WindowCallbackEvent) -> wie_util::Result<()> + 'static,
@@ -234,6 +377,15 @@ where
     context: Option<Context<Arc<WinitWindow>>>,
     surface: Option<Surface<Arc<WinitWindow>, Arc<WinitWindow>>>,
     callback: Box<C>,
+
+    /// End of current vibration.
+    vibration_end: Option<Instant>,
+    dimmed: bool,
+    /// Time when dimming is reverted.
+    dim_end: Option<Instant>,
+    led: u32,
+    /// Effects used on last paint.
+    effects: Effects,
 }
 
 impl<C> ApplicationHandlerImpl<C>
@@ -249,6 +401,37 @@ where
         }
     }
 
+    /// Repaints if visual effects have changed since last paint.
+    fn update_effects(&mut self) {
+        let now = Instant::now();
+
+        if self.vibration_end.is_some_and(|x| now >= x) {
+            self.vibration_end = None;
+        }
+        if self.dim_end.is_some_and(|x| now >= x) {
+            self.dim_end = None;
+            self.dimmed = false;
+        }
+
+        let offset = match self.vibration_end {
+            Some(end) if (end - now).as_millis() / SHAKE_INTERVAL_MILLIS % 2 == 0 => SHAKE_OFFSET,
+            Some(_) => -SHAKE_OFFSET,
+            None => 0,
+        };
+        let effects = Effects {
+            offset,
+            dimmed: self.dimmed,
+            led: self.led,
+        };
+
+        if effects != self.effects {
+            self.effects = effects;
+            if self.surface.is_some() {
+                self.paint_last_frame(None);
+            }
+        }
+    }
+
     /// Sets the native/user scale factor.
     /// After calling this you'll need to call [`Self::on_resize`] to update the surface accordingly.
     fn update_scale_factor(&mut self, native: Option<f64>, user: Option<f64>) {
@@ -303,27 +486,46 @@ where
                 NonZeroU32::new(self.scaled_size.height).unwrap(),
             )
             .unwrap();
-        self.paint_last_frame();
+        self.paint_last_frame(None);
+    }
+
+    /// Copies painted region into the last content frame.
+    fn update_last_frame(&mut self, region: Rect, data: &[u32]) {
+        let width = self.content_size.width as usize;
+        let height = self.content_size.height as usize;
+        let last_frame = self.last_frame.get_or_insert_with(|| vec![0u32; width * height]);
+
+        for (row, line) in data.chunks(region.width as usize).enumerate() {
+            let offset = (region.y as usize + row) * width + region.x as usize;
+            last_frame[offset..offset + line.len()].copy_from_slice(line);
+        }
     }
 
     /// Displays the last content frame to the window.
-    fn paint_last_frame(&mut self) -> Option<()> {
+    /// If `damage` is given, only that region is uploaded when possible.
+    fn paint_last_frame(&mut self, damage: Option<Rect>) -> Option<()> {
         let data = self.last_frame.as_ref()?;
         if data.len() != self.content_size.width as usize * self.content_size.height as usize {
             return None;
         }
-        let data_to_blit = if self.scaled_image_buf.len() == data.len() {
-            data
+        let (data_to_blit, damage) = if self.scaled_image_buf.len() == data.len() {
+            (data, damage)
         } else {
             self.scaler
                 .scale_image(&mut self.scaled_image_buf, data, self.scaled_size, self.content_size);
-            &self.scaled_image_buf
+            (&self.scaled_image_buf, None)
         };
 
-        let mut win_buf = self.surface.as_mut().unwrap().buffer_mut().unwrap();
-        if win_buf.len() == data_to_blit.len() {
-            win_buf.copy_from_slice(data_to_blit);
+        let adjusted;
+        let (data_to_blit, damage) = if self.effects.is_active() {
+            adjusted = self.effects.apply(data_to_blit, self.scaled_size.width as usize);
+            (&adjusted, None)
         } else {
+            (data_to_blit, damage)
+        };
+
+        let mut win_buf = self.surface.as_mut().unwrap().buffer_mut().unwrap();
+        if win_buf.len() != data_to_blit.len() {
             tracing::warn!(
                 "buffer size mismatch, skipping paint: {}, {} (content {:?}, scaled {:?}, win {:?})",
                 win_buf.len(),
@@ -334,6 +536,29 @@ where
             );
             return None;
         }
+
+        // buffer age 1 means window buffer still contains previous frame, so we can copy damaged rows only
+        if let (Some(damage), 1) = (damage, win_buf.age()) {
+            let width = self.content_size.width as usize;
+            for y in damage.y as usize..(damage.y + damage.height) as usize {
+                let start = y * width + damage.x as usize;
+                let end = start + damage.width as usize;
+                win_buf[start..end].copy_from_slice(&data_to_blit[start..end]);
+            }
+
+            win_buf
+                .present_with_damage(&[softbuffer::Rect {
+                    x: damage.x,
+                    y: damage.y,
+                    width: NonZeroU32::new(damage.width).unwrap(),
+                    height: NonZeroU32::new(damage.height).unwrap(),
+                }])
+                .unwrap();
+
+            return Some(());
+        }
+
+        win_buf.copy_from_slice(data_to_blit);
         win_buf.present().unwrap();
         Some(())
     }
@@ -344,6 +569,7 @@ where
     C: FnMut(WindowCallbackEvent) -> wie_util::Result<()> + 'static,
 {
     fn new_events(&mut self, event_loop: &ActiveEventLoop, _cause: StartCause) {
+        self.update_effects();
         self.callback(WindowCallbackEvent::Update, event_loop)
     }
 
@@ -371,9 +597,19 @@ where
             WindowInternalEvent::RequestRedraw => {
                 self.window.as_ref().unwrap().request_redraw();
             }
-            WindowInternalEvent::Paint(data) => {
-                self.last_frame = Some(data);
-                self.paint_last_frame();
+            WindowInternalEvent::Paint { region, data } => {
+                self.update_last_frame(region, &data);
+                self.paint_last_frame(Some(region));
+            }
+            WindowInternalEvent::Vibrate { duration } => {
+                self.vibration_end = Some(Instant::now() + duration);
+            }
+            WindowInternalEvent::SetDimmed { dimmed, timeout } => {
+                self.dimmed = dimmed;
+                self.dim_end = timeout.map(|x| Instant::now() + x);
+            }
+            WindowInternalEvent::SetLed { color } => {
+                self.led = color;
             }
         }
     }
@@ -31,6 +31,10 @@ impl J2MEEmulator {
         Self::load(platform, jar_filename, jar_filename, None, &files)
     }
 
+    pub fn jad_app_id(jad: &[u8]) -> String {
+        J2MEDescriptor::parse(jad).name
+    }
+
     fn load(
         platform: Box<dyn Platform>,
         jar_filename: &str,
@@ -72,6 +76,12 @@ impl J2MEEmulator {
             return Err(JvmSupport::to_wie_err(&jvm, x).await);
         }
 
+        // startApp returns after setting current displayable, so we have to dispatch events to it
+        let result: JvmResult<()> = jvm.invoke_static("wie/MidpEventQueue", "run", "()V", []).await;
+        if let Err(x) = result {
+            return Err(JvmSupport::to_wie_err(&jvm, x).await);
+        }
+
         Ok(())
     }
 }
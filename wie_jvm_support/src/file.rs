@@ -0,0 +1,170 @@
+use alloc::format;
+use core::cmp::{max, min};
+
+use bytemuck::cast_slice;
+
+use java_runtime::classes::java::lang::String;
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, JavaError, Jvm, Result as JvmResult};
+
+use wie_backend::FilesystemError;
+
+use crate::WieJvmContext;
+
+#[derive(Clone, Copy, Default)]
+pub struct OpenOptions {
+    /// Creates file if it doesn't exist.
+    pub create: bool,
+    /// Empties existing file, creating it if it doesn't exist.
+    pub truncate: bool,
+    /// Starts at end of file instead of beginning.
+    pub append: bool,
+}
+
+/// Shared implementation of java file classes backed by [`wie_backend::Filesystem`].
+///
+/// File instance should have `name: Ljava/lang/String;`, `data: [B`, `size: I` and `pos: I` fields.
+/// First `size` bytes of `data` caches file contents, and writes go through to filesystem.
+pub struct JavaFile;
+
+impl JavaFile {
+    pub async fn open<T>(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: &mut ClassInstanceRef<T>,
+        name: ClassInstanceRef<String>,
+        options: OpenOptions,
+    ) -> JvmResult<()> {
+        let filename = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let existing = context.system().filesystem().read(&filename);
+        let data = match existing {
+            Some(x) if !options.truncate => x,
+            None if !options.create && !options.truncate => {
+                return Err(jvm.exception("java/io/IOException", &format!("File not found: {}", filename)).await);
+            }
+            _ => {
+                let result = context.system().filesystem().write(&filename, &[]);
+                if let Err(x) = result {
+                    return Err(Self::io_exception(jvm, &filename, x).await);
+                }
+
+                Default::default()
+            }
+        };
+        let Ok(size) = i32::try_from(data.len()) else {
+            return Err(jvm.exception("java/io/IOException", &format!("File too large: {}", filename)).await);
+        };
+        let pos = if options.append { size } else { 0 };
+
+        let mut data_array = jvm.instantiate_array("B", data.len()).await?;
+        jvm.store_byte_array(&mut data_array, 0, cast_slice(&data).to_vec()).await?;
+
+        jvm.put_field(this, "name", "Ljava/lang/String;", name).await?;
+        jvm.put_field(this, "data", "[B", data_array).await?;
+        jvm.put_field(this, "size", "I", size).await?;
+        jvm.put_field(this, "pos", "I", pos).await?;
+
+        Ok(())
+    }
+
+    /// Reads up to `length` bytes from current position, returning number of bytes read.
+    pub async fn read<T>(
+        jvm: &Jvm,
+        this: &mut ClassInstanceRef<T>,
+        buf: &mut ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<i32> {
+        Self::check_bounds(jvm, buf, offset, length).await?;
+
+        let data_array = jvm.get_field(this, "data", "[B").await?;
+        let size: i32 = jvm.get_field(this, "size", "I").await?;
+        let pos: i32 = jvm.get_field(this, "pos", "I").await?;
+
+        let length_to_read = min(size - pos, length).max(0);
+        if length_to_read == 0 {
+            return Ok(0);
+        }
+
+        let data = jvm.load_byte_array(&data_array, pos as _, length_to_read as _).await?;
+        jvm.store_byte_array(buf, offset as _, data).await?;
+
+        jvm.put_field(this, "pos", "I", pos + length_to_read).await?;
+
+        Ok(length_to_read)
+    }
+
+    /// Writes `length` bytes at current position. Gap after end of file is filled with zeros.
+    pub async fn write<T>(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: &mut ClassInstanceRef<T>,
+        buf: &ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<()> {
+        Self::check_bounds(jvm, buf, offset, length).await?;
+
+        let size: i32 = jvm.get_field(this, "size", "I").await?;
+        let pos: i32 = jvm.get_field(this, "pos", "I").await?;
+        let Some(end) = pos.checked_add(length) else {
+            return Err(jvm.exception("java/io/IOException", "File too large").await);
+        };
+
+        let name = jvm.get_field(this, "name", "Ljava/lang/String;").await?;
+        let filename = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let written = jvm.load_byte_array(buf, offset as _, length as _).await?;
+        let result = context.system().filesystem().write_at(&filename, pos as _, cast_slice(&written));
+        if let Err(x) = result {
+            return Err(Self::io_exception(jvm, &filename, x).await);
+        }
+
+        let mut data_array: ClassInstanceRef<Array<i8>> = jvm.get_field(this, "data", "[B").await?;
+        let capacity = jvm.array_length(&data_array).await?;
+        if end as usize > capacity {
+            // grow geometrically so that sequential writes don't copy whole file each time
+            let mut new_data_array = jvm.instantiate_array("B", max(end as usize, capacity * 2)).await?;
+            let data = jvm.load_byte_array(&data_array, 0, size as _).await?;
+            jvm.store_byte_array(&mut new_data_array, 0, data).await?;
+
+            jvm.put_field(this, "data", "[B", new_data_array.clone()).await?;
+            data_array = new_data_array.into();
+        }
+        jvm.store_byte_array(&mut data_array, pos as _, written).await?;
+
+        jvm.put_field(this, "size", "I", max(size, end)).await?;
+        jvm.put_field(this, "pos", "I", end).await?;
+
+        Ok(())
+    }
+
+    pub async fn size<T>(jvm: &Jvm, this: &ClassInstanceRef<T>) -> JvmResult<i32> {
+        jvm.get_field(this, "size", "I").await
+    }
+
+    /// Returns copy of file contents.
+    pub async fn contents<T>(jvm: &Jvm, this: &ClassInstanceRef<T>) -> JvmResult<ClassInstanceRef<Array<i8>>> {
+        let data_array = jvm.get_field(this, "data", "[B").await?;
+        let size: i32 = jvm.get_field(this, "size", "I").await?;
+
+        let data = jvm.load_byte_array(&data_array, 0, size as _).await?;
+        let mut result = jvm.instantiate_array("B", data.len()).await?;
+        jvm.store_byte_array(&mut result, 0, data).await?;
+
+        Ok(result.into())
+    }
+
+    pub async fn io_exception(jvm: &Jvm, path: &str, error: FilesystemError) -> JavaError {
+        jvm.exception("java/io/IOException", &format!("{}: {:?}", path, error)).await
+    }
+
+    async fn check_bounds(jvm: &Jvm, buf: &ClassInstanceRef<Array<i8>>, offset: i32, length: i32) -> JvmResult<()> {
+        let buf_length = jvm.array_length(buf).await?;
+        if offset < 0 || length < 0 || offset as usize + length as usize > buf_length {
+            return Err(jvm.exception("java/lang/IndexOutOfBoundsException", "Invalid offset or length").await);
+        }
+
+        Ok(())
+    }
+}
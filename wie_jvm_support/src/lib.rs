@@ -2,6 +2,7 @@
 extern crate alloc;
 
 mod context;
+mod file;
 mod jvm_implementation;
 mod runtime;
 
@@ -14,6 +15,7 @@ use wie_backend::System;
 use wie_util::{Result, WieError};
 
 pub use context::{WieJavaClassProto, WieJvmContext};
+pub use file::{JavaFile, OpenOptions};
 pub use jvm_implementation::{JvmImplementation, RustJavaJvmImplementation};
 use runtime::JvmRuntime;
 
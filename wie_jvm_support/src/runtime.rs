@@ -1,4 +1,4 @@
-use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use core::{
     sync::atomic::{AtomicU64, Ordering},
     time::Duration,
@@ -9,7 +9,7 @@ use spin::Mutex;
 use java_runtime::{get_runtime_class_proto, File, FileStat, IOError, Runtime, SpawnCallback, RT_RUSTJAR};
 use jvm::{ClassDefinition, Jvm, Result as JvmResult};
 
-use wie_backend::{AsyncCallable, System};
+use wie_backend::{AsyncCallable, FilesystemError, System};
 use wie_util::WieError;
 
 use crate::{JvmImplementation, JvmSupport, WieJavaClassProto, WieJvmContext, WIE_RUSTJAR};
@@ -115,7 +115,10 @@ where
     async fn open(&self, path: &str) -> Result<Box<dyn File>, IOError> {
         #[derive(Clone)]
         struct FileImpl {
-            data: Arc<Vec<u8>>,
+            system: System,
+            path: String,
+            // `None` if file doesn't exist until first write
+            data: Arc<Mutex<Option<Vec<u8>>>>,
             cursor: Arc<AtomicU64>,
         }
 
@@ -123,10 +126,15 @@ where
         impl File for FileImpl {
             async fn read(&mut self, buf: &mut [u8]) -> Result<usize, IOError> {
                 let cursor = self.cursor.load(Ordering::SeqCst) as usize;
+                let data = self.data.lock();
+                let data = match &*data {
+                    Some(x) => x,
+                    None => return Err(IOError::NotFound),
+                };
 
-                if cursor < self.data.len() {
-                    let to_read = core::cmp::min(buf.len(), self.data.len() - cursor);
-                    buf[..to_read].copy_from_slice(&self.data[cursor..cursor + to_read]);
+                if cursor < data.len() {
+                    let to_read = core::cmp::min(buf.len(), data.len() - cursor);
+                    buf[..to_read].copy_from_slice(&data[cursor..cursor + to_read]);
 
                     self.cursor.fetch_add(to_read as u64, Ordering::SeqCst);
 
@@ -136,30 +144,55 @@ where
                 }
             }
 
-            async fn write(&mut self, _buf: &[u8]) -> Result<usize, IOError> {
-                Err(IOError::Unsupported)
+            async fn write(&mut self, buf: &[u8]) -> Result<usize, IOError> {
+                let cursor = self.cursor.load(Ordering::SeqCst);
+                self.system.filesystem().write_at(&self.path, cursor, buf).map_err(to_io_error)?;
+
+                let mut data = self.data.lock();
+                let data = data.get_or_insert_with(Vec::new);
+
+                let (start, end) = (cursor as usize, cursor as usize + buf.len());
+                if data.len() < end {
+                    data.resize(end, 0);
+                }
+                data[start..end].copy_from_slice(buf);
+
+                self.cursor.fetch_add(buf.len() as u64, Ordering::SeqCst);
+
+                Ok(buf.len())
             }
         }
 
-        let filesystem = self.system.filesystem();
+        // we don't know if file is opened for writing, so missing file in existing directory is created on first write
+        let data = {
+            let filesystem = self.system.filesystem();
+            if filesystem.metadata(path).is_some_and(|x| x.is_directory) {
+                return Err(IOError::Unsupported);
+            }
+
+            let data = filesystem.read(path);
+            let parent = path.rfind('/').map(|x| &path[..x]).unwrap_or("");
+            if data.is_none() && !filesystem.metadata(parent).is_some_and(|x| x.is_directory) {
+                return Err(IOError::NotFound);
+            }
 
-        let file = filesystem.read(path);
+            data
+        };
 
-        file.map(|x| {
-            Box::new(FileImpl {
-                data: Arc::new(x.to_vec()),
-                cursor: Arc::new(AtomicU64::new(0)),
-            }) as _
-        })
-        .ok_or(IOError::NotFound)
+        Ok(Box::new(FileImpl {
+            system: self.system.clone(),
+            path: path.into(),
+            data: Arc::new(Mutex::new(data)),
+            cursor: Arc::new(AtomicU64::new(0)),
+        }))
     }
 
     async fn stat(&self, path: &str) -> Result<FileStat, IOError> {
         let filesystem = self.system.filesystem();
 
-        let file = filesystem.read(path);
+        let metadata = filesystem.metadata(path).ok_or(IOError::NotFound)?;
 
-        file.map(|x| FileStat { size: x.len() as _ }).ok_or(IOError::NotFound)
+        Ok(FileStat { size: metadata.size as _ })
     }
 
     async fn find_rustjar_class(&self, jvm: &Jvm, classpath: &str, class: &str) -> JvmResult<Option<Box<dyn ClassDefinition>>> {
@@ -193,3 +226,10 @@ where
         self.implementation.define_array_class(_jvm, element_type_name).await
     }
 }
+
+fn to_io_error(error: FilesystemError) -> IOError {
+    match error {
+        FilesystemError::NotFound => IOError::NotFound,
+        _ => IOError::Unsupported,
+    }
+}
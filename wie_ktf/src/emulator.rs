@@ -38,6 +38,10 @@ impl KtfEmulator {
         files.contains_key("__adf__")
     }
 
+    pub fn archive_app_id(files: &BTreeMap<String, Vec<u8>>) -> String {
+        KtfAdf::parse(files.get("__adf__").unwrap()).aid
+    }
+
     pub fn loadable_jar(jar: &[u8]) -> bool {
         let files = extract_zip(jar).unwrap();
 
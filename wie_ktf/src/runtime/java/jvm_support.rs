@@ -251,7 +251,7 @@ mod test {
 
     #[test]
     fn test_jvm_support() -> Result<()> {
-        let mut system = System::new(Box::new(TestPlatform), "");
+        let mut system = System::new(Box::new(TestPlatform::new()), "");
 
         let done = Arc::new(AtomicBool::new(false));
 
@@ -66,7 +66,7 @@ pub async fn get_wipic_interfaces(context: &mut dyn WIPICContext) -> Result<u32>
     let interface_4 = write_methods(context, method_table::get_stub_method_table(4))?;
     let interface_5 = write_methods(context, method_table::get_stub_method_table(5))?;
     let interface_6 = write_methods(context, method_table::get_database_method_table())?;
-    let interface_7 = write_methods(context, method_table::get_stub_method_table(7))?;
+    let interface_7 = write_methods(context, method_table::get_fs_method_table())?;
     let interface_8 = write_methods(context, method_table::get_uic_method_table())?;
     let interface_9 = write_methods(context, method_table::get_media_method_table())?;
     let interface_10 = write_methods(context, method_table::get_net_method_table())?;
@@ -2,7 +2,7 @@ use alloc::{vec, vec::Vec};
 
 use wie_util::WieError;
 use wie_wipi_c::{
-    api::{database, graphics, kernel, media, misc, net, uic, util},
+    api::{database, fs, graphics, kernel, media, misc, net, uic, util},
     MethodImpl, WIPICContext, WIPICMethodBody, WIPICWord,
 };
 
@@ -130,9 +130,9 @@ pub fn get_graphics_method_table() -> Vec<WIPICMethodBody> {
         gen_stub(41, "MC_imGetSupportedModes"),
         gen_stub(42, "MC_grpFillPolygon"),
         gen_stub(43, "MC_grpDrawPolygon"),
-        gen_stub(44, "OEMC_grpShowAnnunciator"),
+        graphics::show_annunciator.into_body(),
         gen_stub(45, "OEMC_grpGetAnnunciatorInfo"),
-        gen_stub(46, "OEMC_grpSetAnnunciatorIcon"),
+        graphics::set_annunciator_icon.into_body(),
         gen_stub(47, "OEMC_grpGetIdleHelpLineInfo"),
         gen_stub(48, "OEMC_grpShowHelpLine"),
         gen_stub(49, "OEMC_grpGetCharGlyph"),
@@ -198,14 +198,14 @@ pub fn get_database_method_table() -> Vec<WIPICMethodBody> {
         database::write_record_single.into_body(),
         database::close_database.into_body(),
         database::select_record.into_body(),
-        gen_stub(5, "MC_dbUpdateRecord"),
+        database::update_record.into_body(),
         database::delete_record.into_body(),
         database::list_record.into_body(),
-        gen_stub(8, "MC_dbSortRecords"),
-        gen_stub(9, "MC_dbGetAccessMode"),
-        gen_stub(10, "MC_dbGetNumberOfRecords"),
-        gen_stub(11, "MC_dbGetRecordSize"),
-        gen_stub(12, "MC_dbListDataBases"),
+        database::sort_records.into_body(),
+        database::get_access_mode.into_body(),
+        database::get_number_of_records.into_body(),
+        database::get_record_size.into_body(),
+        database::list_databases.into_body(),
         gen_stub(13, ""),
         gen_stub(14, ""),
         gen_stub(15, ""),
@@ -213,6 +213,30 @@ pub fn get_database_method_table() -> Vec<WIPICMethodBody> {
     ]
 }
 
+pub fn get_fs_method_table() -> Vec<WIPICMethodBody> {
+    let mut table = vec![
+        fs::open.into_body(),
+        fs::close.into_body(),
+        fs::read.into_body(),
+        fs::write.into_body(),
+        fs::seek.into_body(),
+        fs::tell.into_body(),
+        fs::is_exist.into_body(),
+        fs::file_attribute.into_body(),
+        fs::remove.into_body(),
+        fs::rename.into_body(),
+        fs::mkdir.into_body(),
+        fs::rmdir.into_body(),
+        fs::list.into_body(),
+        fs::available.into_body(),
+    ];
+
+    // same length as stub table, so unknown indices don't read past the table
+    table.extend((table.len()..64).map(|x| gen_stub(x as _, "MC_fs")));
+
+    table
+}
+
 pub fn get_uic_method_table() -> Vec<WIPICMethodBody> {
     vec![
         uic::create_application_context.into_body(),
@@ -266,19 +290,19 @@ pub fn get_uic_method_table() -> Vec<WIPICMethodBody> {
 pub fn get_media_method_table() -> Vec<WIPICMethodBody> {
     vec![
         media::clip_create.into_body(),
-        gen_stub(1, "MC_mdaClipFree"),
+        media::clip_free.into_body(),
         gen_stub(2, "MC_mdaSetWaterMark"),
         media::clip_get_type.into_body(),
         media::clip_put_data.into_body(),
         gen_stub(5, "MC_mdaClipPutDataByFile"),
-        gen_stub(6, "MC_mdaClipPutToneData"),
-        gen_stub(7, "MC_mdaClipPutFreqToneData"),
+        media::clip_put_tone_data.into_body(),
+        media::clip_put_freq_tone_data.into_body(),
         media::clip_get_data.into_body(),
         gen_stub(9, "MC_mdaClipAvailableDataSize"),
         gen_stub(10, "MC_mdaClipClearData"),
         media::clip_set_position.into_body(),
-        gen_stub(12, "MC_mdaClipGetVolume"),
-        gen_stub(13, "MC_mdaClipSetVolume"),
+        media::clip_get_volume.into_body(),
+        media::clip_set_volume.into_body(),
         media::play.into_body(),
         media::pause.into_body(),
         media::resume.into_body(),
@@ -286,7 +310,7 @@ pub fn get_media_method_table() -> Vec<WIPICMethodBody> {
         media::record.into_body(),
         gen_stub(19, "MC_mdaGetVolume"),
         gen_stub(20, "MC_mdaSetVolume"),
-        gen_stub(21, "MC_mdaVibrator"),
+        media::vibrator.into_body(),
         gen_stub(22, "MC_mdaReserved1"),
         gen_stub(23, "MC_mdaReserved2"),
         gen_stub(24, "MC_mdaSetMuteState"),
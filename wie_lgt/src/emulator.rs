@@ -33,6 +33,10 @@ impl LgtEmulator {
         files.contains_key("app_info")
     }
 
+    pub fn archive_app_id(files: &BTreeMap<String, Vec<u8>>) -> String {
+        LgtAppInfo::parse(files.get("app_info").unwrap()).aid
+    }
+
     pub fn loadable_jar(jar: &[u8]) -> bool {
         let files = extract_zip(jar).unwrap();
 
@@ -71,7 +75,7 @@ impl LgtEmulator {
     async fn do_start(core: &mut ArmCore, system: &mut System, jar_filename: String, _main_class_name: Option<String>) -> Result<()> {
         let data = {
             let filesystem = system.filesystem();
-            let files = extract_zip(filesystem.read(&jar_filename).unwrap()).unwrap(); // TODO classloader
+            let files = extract_zip(&filesystem.read(&jar_filename).unwrap()).unwrap(); // TODO classloader
             files.get("binary.mod").unwrap().clone()
         };
 
@@ -1,3 +1,4 @@
 pub mod lcdui;
+pub mod media;
 pub mod midlet;
 pub mod rms;
@@ -1,45 +1,315 @@
 use alloc::vec;
 
-use java_class_proto::JavaMethodProto;
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
 
+use wie_backend::Rect;
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
+use crate::classes::wie::MidpEventQueue;
+
 // class javax.microedition.lcdui.Canvas
 pub struct Canvas;
 
 impl Canvas {
+    pub const UP: i32 = 1;
+    pub const DOWN: i32 = 6;
+    pub const LEFT: i32 = 2;
+    pub const RIGHT: i32 = 5;
+    pub const FIRE: i32 = 8;
+    pub const GAME_A: i32 = 9;
+    pub const GAME_B: i32 = 10;
+    pub const GAME_C: i32 = 11;
+    pub const GAME_D: i32 = 12;
+
+    pub const KEY_NUM0: i32 = 48;
+    pub const KEY_NUM1: i32 = 49;
+    pub const KEY_NUM2: i32 = 50;
+    pub const KEY_NUM3: i32 = 51;
+    pub const KEY_NUM4: i32 = 52;
+    pub const KEY_NUM5: i32 = 53;
+    pub const KEY_NUM6: i32 = 54;
+    pub const KEY_NUM7: i32 = 55;
+    pub const KEY_NUM8: i32 = 56;
+    pub const KEY_NUM9: i32 = 57;
+    pub const KEY_STAR: i32 = 42;
+    pub const KEY_POUND: i32 = 35;
+
+    // device specific key codes for keys without standard code, same as wipi
+    pub const KEY_UP: i32 = -1;
+    pub const KEY_DOWN: i32 = -2;
+    pub const KEY_LEFT: i32 = -3;
+    pub const KEY_RIGHT: i32 = -4;
+    pub const KEY_FIRE: i32 = -5;
+
+    // area below canvas reserved for soft key labels unless in full screen mode, left blank as we don't draw commands
+    pub const SOFT_KEY_BAR_HEIGHT: u32 = 16;
+
     pub fn as_proto() -> WieJavaClassProto {
         WieJavaClassProto {
             name: "javax/microedition/lcdui/Canvas",
-            parent_class: Some("java/lang/Object"),
+            parent_class: Some("javax/microedition/lcdui/Displayable"),
             interfaces: vec![],
             methods: vec![
                 JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
                 JavaMethodProto::new("getWidth", "()I", Self::get_width, Default::default()),
                 JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
+                JavaMethodProto::new_abstract("paint", "(Ljavax/microedition/lcdui/Graphics;)V", Default::default()),
+                JavaMethodProto::new("repaint", "()V", Self::repaint, Default::default()),
+                JavaMethodProto::new("repaint", "(IIII)V", Self::repaint_with_area, Default::default()),
+                JavaMethodProto::new("serviceRepaints", "()V", Self::service_repaints, Default::default()),
+                JavaMethodProto::new("keyPressed", "(I)V", Self::key_pressed, Default::default()),
+                JavaMethodProto::new("keyReleased", "(I)V", Self::key_released, Default::default()),
+                JavaMethodProto::new("keyRepeated", "(I)V", Self::key_repeated, Default::default()),
+                JavaMethodProto::new("showNotify", "()V", Self::show_notify, Default::default()),
+                JavaMethodProto::new("hideNotify", "()V", Self::hide_notify, Default::default()),
+                JavaMethodProto::new("setFullScreenMode", "(Z)V", Self::set_full_screen_mode, Default::default()),
+                JavaMethodProto::new("sizeChanged", "(II)V", Self::size_changed, Default::default()),
+                JavaMethodProto::new("getGameAction", "(I)I", Self::get_game_action, Default::default()),
+                JavaMethodProto::new("getKeyCode", "(I)I", Self::get_key_code, Default::default()),
+                JavaMethodProto::new("hasRepeatEvents", "()Z", Self::has_repeat_events, Default::default()),
+                JavaMethodProto::new("isDoubleBuffered", "()Z", Self::is_double_buffered, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("fullScreen", "Z", Default::default()),
+                JavaFieldProto::new("dirtyX", "I", Default::default()),
+                JavaFieldProto::new("dirtyY", "I", Default::default()),
+                JavaFieldProto::new("dirtyW", "I", Default::default()),
+                JavaFieldProto::new("dirtyH", "I", Default::default()),
             ],
-            fields: vec![],
         }
     }
 
     async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
         tracing::debug!("javax.microedition.lcdui.Canvas::<init>({:?})", &this);
 
-        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+        let _: () = jvm
+            .invoke_special(&this, "javax/microedition/lcdui/Displayable", "<init>", "()V", ())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_width(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::getWidth({:?})", &this);
+
+        Ok(Self::size(jvm, context, &this).await?.0 as _)
+    }
+
+    async fn get_height(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::getHeight({:?})", &this);
+
+        Ok(Self::size(jvm, context, &this).await?.1 as _)
+    }
+
+    async fn repaint(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::repaint({:?})", &this);
+
+        let (width, height) = Self::size(jvm, context, &this).await?;
+
+        let _: () = jvm
+            .invoke_virtual(&this, "repaint", "(IIII)V", (0, 0, width as i32, height as i32))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn repaint_with_area(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Canvas::repaint({:?}, {}, {}, {}, {})",
+            &this,
+            x,
+            y,
+            width,
+            height
+        );
+
+        let (canvas_width, canvas_height) = Self::size(jvm, context, &this).await?;
+
+        if let Some(area) = Rect::clipped(x, y, width, height, canvas_width, canvas_height) {
+            let damage = match Self::damage(jvm, &this).await? {
+                Some(damage) => damage.union(&area),
+                None => area,
+            };
+
+            Self::set_damage(jvm, &mut this, damage).await?;
+
+            let mut platform = context.system().platform();
+            let screen = platform.screen();
+            screen.request_redraw().unwrap();
+        }
+
+        Ok(())
+    }
+
+    async fn service_repaints(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::serviceRepaints({:?})", &this);
+
+        let shown: bool = jvm.invoke_virtual(&this, "isShown", "()Z", ()).await?;
+        if !shown {
+            return Ok(());
+        }
+
+        // paint pending repaint right away instead of waiting for redraw event
+        if let Some(damage) = Self::take_damage(jvm, &mut this).await? {
+            MidpEventQueue::paint(jvm, context, &this, damage).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn key_pressed(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, key_code: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::keyPressed({:?}, {})", &this, key_code);
+
+        Ok(())
+    }
+
+    async fn key_released(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, key_code: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::keyReleased({:?}, {})", &this, key_code);
+
+        Ok(())
+    }
+
+    async fn key_repeated(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, key_code: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::keyRepeated({:?}, {})", &this, key_code);
+
+        Ok(())
+    }
+
+    async fn show_notify(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::showNotify({:?})", &this);
 
         Ok(())
     }
 
-    async fn get_width(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Canvas::getWidth({:?})", &this);
+    async fn hide_notify(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::hideNotify({:?})", &this);
 
-        Ok(240)
+        Ok(())
     }
 
-    async fn get_height(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Canvas::getHeight({:?})", &this);
+    async fn set_full_screen_mode(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, mode: bool) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::setFullScreenMode({:?}, {})", &this, mode);
+
+        let full_screen: bool = jvm.get_field(&this, "fullScreen", "Z").await?;
+        if full_screen == mode {
+            return Ok(());
+        }
+
+        jvm.put_field(&mut this, "fullScreen", "Z", mode).await?;
+
+        let (width, height) = Self::size(jvm, context, &this).await?;
+        let shown: bool = jvm.invoke_virtual(&this, "isShown", "()Z", ()).await?;
+        if shown && !mode {
+            // soft key bar area still has contents painted in full screen mode
+            MidpEventQueue::clear(jvm, context, Rect::new(0, height, width, Self::SOFT_KEY_BAR_HEIGHT)).await?;
+        }
+
+        let _: () = jvm.invoke_virtual(&this, "sizeChanged", "(II)V", (width as i32, height as i32)).await?;
+        let _: () = jvm.invoke_virtual(&this, "repaint", "()V", ()).await?;
+
+        Ok(())
+    }
+
+    async fn size_changed(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, width: i32, height: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::sizeChanged({:?}, {}, {})", &this, width, height);
+
+        Ok(())
+    }
+
+    async fn get_game_action(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, key_code: i32) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::getGameAction({:?}, {})", &this, key_code);
+
+        Ok(match key_code {
+            Self::KEY_UP | Self::KEY_NUM2 => Self::UP,
+            Self::KEY_DOWN | Self::KEY_NUM8 => Self::DOWN,
+            Self::KEY_LEFT | Self::KEY_NUM4 => Self::LEFT,
+            Self::KEY_RIGHT | Self::KEY_NUM6 => Self::RIGHT,
+            Self::KEY_FIRE | Self::KEY_NUM5 => Self::FIRE,
+            Self::KEY_NUM1 => Self::GAME_A,
+            Self::KEY_NUM3 => Self::GAME_B,
+            Self::KEY_NUM7 => Self::GAME_C,
+            Self::KEY_NUM9 => Self::GAME_D,
+            Self::KEY_NUM0 | Self::KEY_STAR | Self::KEY_POUND => 0,
+            _ => return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid key code").await),
+        })
+    }
 
-        Ok(320)
+    async fn get_key_code(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, game_action: i32) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::getKeyCode({:?}, {})", &this, game_action);
+
+        Ok(match game_action {
+            Self::UP => Self::KEY_UP,
+            Self::DOWN => Self::KEY_DOWN,
+            Self::LEFT => Self::KEY_LEFT,
+            Self::RIGHT => Self::KEY_RIGHT,
+            Self::FIRE => Self::KEY_FIRE,
+            Self::GAME_A => Self::KEY_NUM1,
+            Self::GAME_B => Self::KEY_NUM3,
+            Self::GAME_C => Self::KEY_NUM7,
+            Self::GAME_D => Self::KEY_NUM9,
+            _ => return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid game action").await),
+        })
+    }
+
+    async fn has_repeat_events(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::hasRepeatEvents({:?})", &this);
+
+        Ok(true)
+    }
+
+    async fn is_double_buffered(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::isDoubleBuffered({:?})", &this);
+
+        Ok(true)
+    }
+
+    /// Size of area available to canvas, which excludes soft key bar unless in full screen mode.
+    pub async fn size(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>) -> JvmResult<(u32, u32)> {
+        let (width, height) = context.system().display_size();
+        let full_screen: bool = jvm.get_field(this, "fullScreen", "Z").await?;
+
+        Ok(if full_screen {
+            (width, height)
+        } else {
+            (width, height.saturating_sub(Self::SOFT_KEY_BAR_HEIGHT))
+        })
+    }
+
+    /// Returns screen area requested by repaint since last call, and clears it.
+    pub async fn take_damage(jvm: &Jvm, this: &mut ClassInstanceRef<Self>) -> JvmResult<Option<Rect>> {
+        let damage = Self::damage(jvm, this).await?;
+
+        Self::set_damage(jvm, this, Rect::new(0, 0, 0, 0)).await?;
+
+        Ok(damage)
+    }
+
+    async fn damage(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<Option<Rect>> {
+        let x: i32 = jvm.get_field(this, "dirtyX", "I").await?;
+        let y: i32 = jvm.get_field(this, "dirtyY", "I").await?;
+        let width: i32 = jvm.get_field(this, "dirtyW", "I").await?;
+        let height: i32 = jvm.get_field(this, "dirtyH", "I").await?;
+
+        let damage = Rect::new(x as _, y as _, width as _, height as _);
+
+        Ok(if damage.is_empty() { None } else { Some(damage) })
+    }
+
+    async fn set_damage(jvm: &Jvm, this: &mut ClassInstanceRef<Self>, damage: Rect) -> JvmResult<()> {
+        jvm.put_field(this, "dirtyX", "I", damage.x as i32).await?;
+        jvm.put_field(this, "dirtyY", "I", damage.y as i32).await?;
+        jvm.put_field(this, "dirtyW", "I", damage.width as i32).await?;
+        jvm.put_field(this, "dirtyH", "I", damage.height as i32).await?;
+
+        Ok(())
     }
 }
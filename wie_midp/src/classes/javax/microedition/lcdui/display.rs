@@ -1,12 +1,19 @@
 use alloc::vec;
 
-use java_class_proto::JavaMethodProto;
-use java_constants::MethodAccessFlags;
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use java_constants::{FieldAccessFlags, MethodAccessFlags};
+use java_runtime::classes::java::lang::Runnable;
 use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
 
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
-use crate::classes::javax::microedition::{lcdui::Displayable, midlet::MIDlet};
+use crate::classes::{
+    javax::microedition::{
+        lcdui::{Displayable, Image},
+        midlet::MIDlet,
+    },
+    wie::MidpEventQueue,
+};
 
 // class javax.microedition.lcdui.Display
 pub struct Display;
@@ -19,12 +26,21 @@ impl Display {
             interfaces: vec![],
             methods: vec![
                 JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
+                JavaMethodProto::new(
+                    "getCurrent",
+                    "()Ljavax/microedition/lcdui/Displayable;",
+                    Self::get_current,
+                    Default::default(),
+                ),
                 JavaMethodProto::new(
                     "setCurrent",
                     "(Ljavax/microedition/lcdui/Displayable;)V",
                     Self::set_current,
                     Default::default(),
                 ),
+                JavaMethodProto::new("callSerially", "(Ljava/lang/Runnable;)V", Self::call_serially, Default::default()),
+                JavaMethodProto::new("isColor", "()Z", Self::is_color, Default::default()),
+                JavaMethodProto::new("numColors", "()I", Self::num_colors, Default::default()),
                 JavaMethodProto::new(
                     "getDisplay",
                     "(Ljavax/microedition/midlet/MIDlet;)Ljavax/microedition/lcdui/Display;",
@@ -32,34 +48,133 @@ impl Display {
                     MethodAccessFlags::STATIC,
                 ),
             ],
-            fields: vec![],
+            fields: vec![
+                // we run single midlet, so there's only one display
+                JavaFieldProto::new("display", "Ljavax/microedition/lcdui/Display;", FieldAccessFlags::STATIC),
+                JavaFieldProto::new("current", "Ljavax/microedition/lcdui/Displayable;", Default::default()),
+                JavaFieldProto::new("eventQueue", "Lwie/MidpEventQueue;", Default::default()),
+                // canvases are painted onto this image, which is then presented to the screen
+                JavaFieldProto::new("screenImage", "Ljavax/microedition/lcdui/Image;", Default::default()),
+            ],
         }
     }
 
-    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+    async fn init(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
         tracing::debug!("javax.microedition.lcdui.Display::<init>({:?})", &this);
 
         let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
 
+        let (width, height) = context.system().display_size();
+        let screen_image: ClassInstanceRef<Image> = jvm
+            .invoke_static(
+                "javax/microedition/lcdui/Image",
+                "createImage",
+                "(II)Ljavax/microedition/lcdui/Image;",
+                (width as i32, height as i32),
+            )
+            .await?;
+        let event_queue = jvm.new_class("wie/MidpEventQueue", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "screenImage", "Ljavax/microedition/lcdui/Image;", screen_image)
+            .await?;
+        jvm.put_field(&mut this, "eventQueue", "Lwie/MidpEventQueue;", event_queue).await?;
+
         Ok(())
     }
 
+    async fn get_current(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Displayable>> {
+        tracing::debug!("javax.microedition.lcdui.Display::getCurrent({:?})", &this);
+
+        jvm.get_field(&this, "current", "Ljavax/microedition/lcdui/Displayable;").await
+    }
+
     async fn set_current(
-        _jvm: &Jvm,
+        jvm: &Jvm,
         _context: &mut WieJvmContext,
-        this: ClassInstanceRef<Self>,
+        mut this: ClassInstanceRef<Self>,
         displayable: ClassInstanceRef<Displayable>,
     ) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.lcdui.Display::setCurrent({:?}, {:?})", &this, displayable);
+        tracing::debug!("javax.microedition.lcdui.Display::setCurrent({:?}, {:?})", &this, &displayable);
+
+        // null leaves current displayable as is
+        if displayable.is_null() {
+            return Ok(());
+        }
+
+        let previous: ClassInstanceRef<Displayable> = jvm.get_field(&this, "current", "Ljavax/microedition/lcdui/Displayable;").await?;
+        if !previous.is_null() {
+            let same: bool = jvm
+                .invoke_virtual(&previous, "equals", "(Ljava/lang/Object;)Z", (displayable.clone(),))
+                .await?;
+            if same {
+                return Ok(());
+            }
+
+            if jvm.is_instance(&**previous, "javax/microedition/lcdui/Canvas").await? {
+                let _: () = jvm.invoke_virtual(&previous, "hideNotify", "()V", ()).await?;
+            }
+        }
+
+        jvm.put_field(&mut this, "current", "Ljavax/microedition/lcdui/Displayable;", displayable.clone())
+            .await?;
+
+        if jvm.is_instance(&**displayable, "javax/microedition/lcdui/Canvas").await? {
+            let _: () = jvm.invoke_virtual(&displayable, "showNotify", "()V", ()).await?;
+            let _: () = jvm.invoke_virtual(&displayable, "repaint", "()V", ()).await?;
+        }
 
         Ok(())
     }
 
+    async fn call_serially(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        runnable: ClassInstanceRef<Runnable>,
+    ) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Display::callSerially({:?}, {:?})", &this, &runnable);
+
+        let event_queue: ClassInstanceRef<MidpEventQueue> = jvm.get_field(&this, "eventQueue", "Lwie/MidpEventQueue;").await?;
+
+        MidpEventQueue::enqueue_call_serially_event(jvm, &event_queue, runnable).await
+    }
+
+    async fn is_color(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Display::isColor({:?})", &this);
+
+        Ok(true)
+    }
+
+    async fn num_colors(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Display::numColors({:?})", &this);
+
+        Ok(1 << 24)
+    }
+
     async fn get_display(jvm: &Jvm, _context: &mut WieJvmContext, midlet: ClassInstanceRef<MIDlet>) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.lcdui.Display::getDisplay({:?})", midlet);
+        tracing::debug!("javax.microedition.lcdui.Display::getDisplay({:?})", &midlet);
+
+        Self::display(jvm).await
+    }
+
+    /// Returns the display, creating it on first call.
+    pub async fn display(jvm: &Jvm) -> JvmResult<ClassInstanceRef<Self>> {
+        let display: ClassInstanceRef<Self> = jvm
+            .get_static_field("javax/microedition/lcdui/Display", "display", "Ljavax/microedition/lcdui/Display;")
+            .await?;
+        if !display.is_null() {
+            return Ok(display);
+        }
 
-        let instance = jvm.new_class("javax/microedition/lcdui/Display", "()V", ()).await?;
+        let display = jvm.new_class("javax/microedition/lcdui/Display", "()V", ()).await?;
+        jvm.put_static_field(
+            "javax/microedition/lcdui/Display",
+            "display",
+            "Ljavax/microedition/lcdui/Display;",
+            display.clone(),
+        )
+        .await?;
 
-        Ok(instance.into())
+        Ok(display.into())
     }
 }
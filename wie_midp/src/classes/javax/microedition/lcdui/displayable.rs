@@ -5,6 +5,8 @@ use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
 
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
+use crate::classes::javax::microedition::lcdui::Display;
+
 // class javax.microedition.lcdui.Displayable
 pub struct Displayable;
 
@@ -14,7 +16,12 @@ impl Displayable {
             name: "javax/microedition/lcdui/Displayable",
             parent_class: Some("java/lang/Object"),
             interfaces: vec![],
-            methods: vec![JavaMethodProto::new("<init>", "()V", Self::init, Default::default())],
+            methods: vec![
+                JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
+                JavaMethodProto::new("getWidth", "()I", Self::get_width, Default::default()),
+                JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
+                JavaMethodProto::new("isShown", "()Z", Self::is_shown, Default::default()),
+            ],
             fields: vec![],
         }
     }
@@ -26,4 +33,28 @@ impl Displayable {
 
         Ok(())
     }
+
+    async fn get_width(_jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Displayable::getWidth({:?})", &this);
+
+        Ok(context.system().display_size().0 as _)
+    }
+
+    async fn get_height(_jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Displayable::getHeight({:?})", &this);
+
+        Ok(context.system().display_size().1 as _)
+    }
+
+    async fn is_shown(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Displayable::isShown({:?})", &this);
+
+        let display = Display::display(jvm).await?;
+        let current: ClassInstanceRef<Self> = jvm.get_field(&display, "current", "Ljavax/microedition/lcdui/Displayable;").await?;
+        if current.is_null() {
+            return Ok(false);
+        }
+
+        jvm.invoke_virtual(&current, "equals", "(Ljava/lang/Object;)Z", (this,)).await
+    }
 }
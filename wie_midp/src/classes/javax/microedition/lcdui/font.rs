@@ -1,16 +1,26 @@
-use alloc::vec;
+use alloc::{string::String as RustString, vec, vec::Vec};
 
-use java_class_proto::JavaMethodProto;
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_constants::MethodAccessFlags;
 use java_runtime::classes::java::lang::String;
-use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, JavaChar, Jvm, Result as JvmResult};
 
+use wie_backend::canvas;
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 // class javax.microedition.lcdui.Font
 pub struct Font;
 
 impl Font {
+    pub const FACE_SYSTEM: i32 = 0;
+    pub const STYLE_PLAIN: i32 = 0;
+    pub const STYLE_BOLD: i32 = 1;
+    pub const STYLE_ITALIC: i32 = 2;
+    pub const STYLE_UNDERLINED: i32 = 4;
+    pub const SIZE_SMALL: i32 = 8;
+    pub const SIZE_MEDIUM: i32 = 0;
+    pub const SIZE_LARGE: i32 = 16;
+
     pub fn as_proto() -> WieJavaClassProto {
         WieJavaClassProto {
             name: "javax/microedition/lcdui/Font",
@@ -19,7 +29,18 @@ impl Font {
             methods: vec![
                 JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
                 JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
+                JavaMethodProto::new("getBaselinePosition", "()I", Self::get_baseline_position, Default::default()),
+                JavaMethodProto::new("getFace", "()I", Self::get_face, Default::default()),
+                JavaMethodProto::new("getStyle", "()I", Self::get_style, Default::default()),
+                JavaMethodProto::new("getSize", "()I", Self::get_size, Default::default()),
+                JavaMethodProto::new("isPlain", "()Z", Self::is_plain, Default::default()),
+                JavaMethodProto::new("isBold", "()Z", Self::is_bold, Default::default()),
+                JavaMethodProto::new("isItalic", "()Z", Self::is_italic, Default::default()),
+                JavaMethodProto::new("isUnderlined", "()Z", Self::is_underlined, Default::default()),
                 JavaMethodProto::new("stringWidth", "(Ljava/lang/String;)I", Self::string_width, Default::default()),
+                JavaMethodProto::new("substringWidth", "(Ljava/lang/String;II)I", Self::substring_width, Default::default()),
+                JavaMethodProto::new("charWidth", "(C)I", Self::char_width, Default::default()),
+                JavaMethodProto::new("charsWidth", "([CII)I", Self::chars_width, Default::default()),
                 JavaMethodProto::new(
                     "getFont",
                     "(III)Ljavax/microedition/lcdui/Font;",
@@ -33,7 +54,11 @@ impl Font {
                     MethodAccessFlags::STATIC,
                 ),
             ],
-            fields: vec![],
+            fields: vec![
+                JavaFieldProto::new("face", "I", Default::default()),
+                JavaFieldProto::new("style", "I", Default::default()),
+                JavaFieldProto::new("size", "I", Default::default()),
+            ],
         }
     }
 
@@ -45,31 +70,176 @@ impl Font {
         Ok(())
     }
 
-    async fn string_width(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, _text: ClassInstanceRef<String>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::stringWidth({:?})", &this);
+    async fn get_height(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getHeight({:?})", &this);
+
+        let pt_size = Self::pt_size(jvm, &this).await?;
+
+        Ok(canvas::font_height(pt_size) as _)
+    }
+
+    async fn get_baseline_position(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getBaselinePosition({:?})", &this);
+
+        let pt_size = Self::pt_size(jvm, &this).await?;
+
+        Ok(canvas::font_ascent(pt_size) as _)
+    }
+
+    async fn get_face(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getFace({:?})", &this);
+
+        jvm.get_field(&this, "face", "I").await
+    }
+
+    async fn get_style(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getStyle({:?})", &this);
 
-        Ok(10)
+        jvm.get_field(&this, "style", "I").await
     }
 
-    async fn get_height(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::getHeight({:?})", &this);
+    async fn get_size(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getSize({:?})", &this);
+
+        jvm.get_field(&this, "size", "I").await
+    }
+
+    async fn is_plain(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isPlain({:?})", &this);
+
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
+
+        Ok(style == Self::STYLE_PLAIN)
+    }
+
+    async fn is_bold(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isBold({:?})", &this);
+
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
+
+        Ok(style & Self::STYLE_BOLD != 0)
+    }
+
+    async fn is_italic(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isItalic({:?})", &this);
+
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
 
-        Ok(10)
+        Ok(style & Self::STYLE_ITALIC != 0)
+    }
+
+    async fn is_underlined(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isUnderlined({:?})", &this);
+
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
+
+        Ok(style & Self::STYLE_UNDERLINED != 0)
+    }
+
+    async fn string_width(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, string: ClassInstanceRef<String>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::stringWidth({:?}, {:?})", &this, &string);
+
+        let string = JavaLangString::to_rust_string(jvm, &string).await?;
+
+        Self::width(jvm, &this, &string).await
+    }
+
+    async fn substring_width(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        string: ClassInstanceRef<String>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<i32> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Font::substringWidth({:?}, {:?}, {}, {})",
+            &this,
+            &string,
+            offset,
+            length
+        );
+
+        let string = JavaLangString::to_rust_string(jvm, &string).await?;
+        let substring = string.chars().skip(offset as _).take(length as _).collect::<RustString>();
+
+        Self::width(jvm, &this, &substring).await
+    }
+
+    async fn char_width(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, char: JavaChar) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::charWidth({:?}, {})", &this, char);
+
+        let string = RustString::from_utf16_lossy(&[char]);
+
+        Self::width(jvm, &this, &string).await
+    }
+
+    async fn chars_width(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        chars: ClassInstanceRef<Array<JavaChar>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<i32> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Font::charsWidth({:?}, {:?}, {}, {})",
+            &this,
+            &chars,
+            offset,
+            length
+        );
+
+        let chars: Vec<JavaChar> = jvm.load_array(&chars, offset as _, length as _).await?;
+        let string = RustString::from_utf16_lossy(&chars);
+
+        Self::width(jvm, &this, &string).await
     }
 
     async fn get_font(jvm: &Jvm, _context: &mut WieJvmContext, face: i32, style: i32, size: i32) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::getFont( {}, {}, {})", face, style, size);
+        tracing::debug!("javax.microedition.lcdui.Font::getFont({}, {}, {})", face, style, size);
+
+        if ![Self::SIZE_SMALL, Self::SIZE_MEDIUM, Self::SIZE_LARGE].contains(&size)
+            || style & !(Self::STYLE_BOLD | Self::STYLE_ITALIC | Self::STYLE_UNDERLINED) != 0
+        {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid font").await);
+        }
 
-        let instance = jvm.new_class("javax/microedition/lcdui/Font", "()V", ()).await?;
+        let mut instance = jvm.new_class("javax/microedition/lcdui/Font", "()V", ()).await?;
+
+        // we have only one face, which is used for every face
+        jvm.put_field(&mut instance, "face", "I", face).await?;
+        jvm.put_field(&mut instance, "style", "I", style).await?;
+        jvm.put_field(&mut instance, "size", "I", size).await?;
 
         Ok(instance.into())
     }
 
     async fn get_default_font(jvm: &Jvm, _context: &mut WieJvmContext) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::getDefaultFont()");
+        tracing::debug!("javax.microedition.lcdui.Font::getDefaultFont()");
+
+        jvm.invoke_static(
+            "javax/microedition/lcdui/Font",
+            "getFont",
+            "(III)Ljavax/microedition/lcdui/Font;",
+            (Self::FACE_SYSTEM, Self::STYLE_PLAIN, Self::SIZE_MEDIUM),
+        )
+        .await
+    }
 
-        let instance = jvm.new_class("javax/microedition/lcdui/Font", "()V", ()).await?;
+    pub async fn pt_size(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<f32> {
+        let size: i32 = jvm.get_field(this, "size", "I").await?;
 
-        Ok(instance.into())
+        Ok(match size {
+            Self::SIZE_SMALL => 8.0,
+            Self::SIZE_LARGE => 12.0,
+            _ => 10.0,
+        })
+    }
+
+    async fn width(jvm: &Jvm, this: &ClassInstanceRef<Self>, string: &str) -> JvmResult<i32> {
+        let pt_size = Self::pt_size(jvm, this).await?;
+
+        Ok(canvas::string_width(string, pt_size) as _)
     }
 }
@@ -1,53 +1,427 @@
-use alloc::vec;
+use alloc::{string::String as RustString, vec, vec::Vec};
 
-use java_class_proto::JavaMethodProto;
+use java_class_proto::{JavaFieldProto, JavaMethodProto, TypeConverter};
 use java_runtime::classes::java::lang::String;
-use jvm::{runtime::JavaLangString, ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, JavaChar, JavaValue, Jvm, Result as JvmResult};
 
+use wie_backend::canvas::{
+    arc_points, arc_spans, font_ascent, line_points, render_text, round_rect_points, round_rect_spans, triangle_spans, ArgbPixel, Color,
+    CompositeMode, Image as BackendImage, PixelType, Rgb8Pixel, Span, VecImageBuffer,
+};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
-use crate::classes::javax::microedition::lcdui::{Font, Image};
+use crate::classes::javax::microedition::lcdui::{
+    image::{region_in_bounds, transform_region, TRANS_NONE},
+    Font, Image,
+};
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, Eq, PartialEq)]
+    struct Anchor: i32 {
+        const HCENTER = 1;
+        const VCENTER = 2;
+        const LEFT = 4;
+        const RIGHT = 8;
+        const TOP = 16;
+        const BOTTOM = 32;
+        const BASELINE = 64;
+    }
+}
+
+impl Anchor {
+    // offset of the top left corner from the anchor point, `None` if anchor is invalid.
+    // `baseline` is given only for text, as images can't be anchored at baseline and text can't be anchored at vertical center.
+    fn offset(self, width: i32, height: i32, baseline: Option<i32>) -> Option<(i32, i32)> {
+        if self.is_empty() {
+            return Some((0, 0));
+        }
+
+        let horizontal = self & (Self::LEFT | Self::RIGHT | Self::HCENTER);
+        let vertical = self - horizontal;
+
+        let x = if horizontal == Self::LEFT {
+            0
+        } else if horizontal == Self::RIGHT {
+            -width
+        } else if horizontal == Self::HCENTER {
+            -width / 2
+        } else {
+            return None;
+        };
+
+        let y = if vertical == Self::TOP {
+            0
+        } else if vertical == Self::BOTTOM {
+            -height
+        } else if vertical == Self::VCENTER && baseline.is_none() {
+            -height / 2
+        } else if vertical == Self::BASELINE {
+            -baseline?
+        } else {
+            return None;
+        };
+
+        Some((x, y))
+    }
+}
+
+impl TypeConverter<Anchor> for Anchor {
+    fn to_rust(_: &Jvm, raw: JavaValue) -> Anchor {
+        let raw: i32 = raw.into();
+        Anchor::from_bits_retain(raw)
+    }
+
+    fn from_rust(_: &Jvm, rust: Anchor) -> JavaValue {
+        rust.bits().into()
+    }
+}
+
+// clip and translation resolved against the target image
+struct DrawState {
+    image: ClassInstanceRef<Image>,
+    color: Color,
+    dotted: bool,
+    translate_x: i32,
+    translate_y: i32,
+    clip_left: i32,
+    clip_top: i32,
+    clip_right: i32,
+    clip_bottom: i32,
+}
+
+impl DrawState {
+    // translates and clips the rectangle, returning it in image coordinates
+    fn clip(&self, x: i32, y: i32, width: i32, height: i32) -> Option<(u32, u32, u32, u32)> {
+        let left = (x + self.translate_x).max(self.clip_left);
+        let top = (y + self.translate_y).max(self.clip_top);
+        let right = (x + self.translate_x + width).min(self.clip_right);
+        let bottom = (y + self.translate_y + height).min(self.clip_bottom);
+
+        if left < right && top < bottom {
+            Some((left as _, top as _, (right - left) as _, (bottom - top) as _))
+        } else {
+            None
+        }
+    }
+}
 
 // class javax.microedition.lcdui.Graphics
 pub struct Graphics;
 
 impl Graphics {
+    pub const SOLID: i32 = 0;
+    pub const DOTTED: i32 = 1;
+
     pub fn as_proto() -> WieJavaClassProto {
         WieJavaClassProto {
             name: "javax/microedition/lcdui/Graphics",
             parent_class: Some("java/lang/Object"),
             interfaces: vec![],
             methods: vec![
-                JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
+                JavaMethodProto::new("<init>", "(Ljavax/microedition/lcdui/Image;)V", Self::init, Default::default()),
+                JavaMethodProto::new("translate", "(II)V", Self::translate, Default::default()),
+                JavaMethodProto::new("getTranslateX", "()I", Self::get_translate_x, Default::default()),
+                JavaMethodProto::new("getTranslateY", "()I", Self::get_translate_y, Default::default()),
+                JavaMethodProto::new("getColor", "()I", Self::get_color, Default::default()),
+                JavaMethodProto::new("getRedComponent", "()I", Self::get_red_component, Default::default()),
+                JavaMethodProto::new("getGreenComponent", "()I", Self::get_green_component, Default::default()),
+                JavaMethodProto::new("getBlueComponent", "()I", Self::get_blue_component, Default::default()),
+                JavaMethodProto::new("getGrayScale", "()I", Self::get_gray_scale, Default::default()),
+                JavaMethodProto::new("setGrayScale", "(I)V", Self::set_gray_scale, Default::default()),
+                JavaMethodProto::new("setColor", "(I)V", Self::set_color, Default::default()),
+                JavaMethodProto::new("setColor", "(III)V", Self::set_color_rgb, Default::default()),
+                JavaMethodProto::new("getFont", "()Ljavax/microedition/lcdui/Font;", Self::get_font, Default::default()),
+                JavaMethodProto::new("setFont", "(Ljavax/microedition/lcdui/Font;)V", Self::set_font, Default::default()),
+                JavaMethodProto::new("getStrokeStyle", "()I", Self::get_stroke_style, Default::default()),
+                JavaMethodProto::new("setStrokeStyle", "(I)V", Self::set_stroke_style, Default::default()),
+                JavaMethodProto::new("getClipX", "()I", Self::get_clip_x, Default::default()),
+                JavaMethodProto::new("getClipY", "()I", Self::get_clip_y, Default::default()),
+                JavaMethodProto::new("getClipWidth", "()I", Self::get_clip_width, Default::default()),
+                JavaMethodProto::new("getClipHeight", "()I", Self::get_clip_height, Default::default()),
+                JavaMethodProto::new("clipRect", "(IIII)V", Self::clip_rect, Default::default()),
+                JavaMethodProto::new("setClip", "(IIII)V", Self::set_clip, Default::default()),
+                JavaMethodProto::new("drawLine", "(IIII)V", Self::draw_line, Default::default()),
                 JavaMethodProto::new("fillRect", "(IIII)V", Self::fill_rect, Default::default()),
                 JavaMethodProto::new("drawRect", "(IIII)V", Self::draw_rect, Default::default()),
-                JavaMethodProto::new("drawLine", "(IIII)V", Self::draw_line, Default::default()),
+                JavaMethodProto::new("drawRoundRect", "(IIIIII)V", Self::draw_round_rect, Default::default()),
+                JavaMethodProto::new("fillRoundRect", "(IIIIII)V", Self::fill_round_rect, Default::default()),
+                JavaMethodProto::new("fillArc", "(IIIIII)V", Self::fill_arc, Default::default()),
+                JavaMethodProto::new("drawArc", "(IIIIII)V", Self::draw_arc, Default::default()),
+                JavaMethodProto::new("fillTriangle", "(IIIIII)V", Self::fill_triangle, Default::default()),
                 JavaMethodProto::new("drawString", "(Ljava/lang/String;III)V", Self::draw_string, Default::default()),
+                JavaMethodProto::new("drawSubstring", "(Ljava/lang/String;IIIII)V", Self::draw_substring, Default::default()),
+                JavaMethodProto::new("drawChar", "(CIII)V", Self::draw_char, Default::default()),
+                JavaMethodProto::new("drawChars", "([CIIIII)V", Self::draw_chars, Default::default()),
                 JavaMethodProto::new(
                     "drawImage",
                     "(Ljavax/microedition/lcdui/Image;III)V",
                     Self::draw_image,
                     Default::default(),
                 ),
-                JavaMethodProto::new("setFont", "(Ljavax/microedition/lcdui/Font;)V", Self::set_font, Default::default()),
-                JavaMethodProto::new("setColor", "(I)V", Self::set_color, Default::default()),
-                JavaMethodProto::new("setColor", "(III)V", Self::set_color_rgb, Default::default()),
+                JavaMethodProto::new(
+                    "drawRegion",
+                    "(Ljavax/microedition/lcdui/Image;IIIIIIII)V",
+                    Self::draw_region,
+                    Default::default(),
+                ),
+                JavaMethodProto::new("copyArea", "(IIIIIII)V", Self::copy_area, Default::default()),
+                JavaMethodProto::new("drawRGB", "([IIIIIIIZ)V", Self::draw_rgb, Default::default()),
+                JavaMethodProto::new("getDisplayColor", "(I)I", Self::get_display_color, Default::default()),
                 JavaMethodProto::new("reset", "()V", Self::reset, Default::default()),
             ],
-            fields: vec![],
+            fields: vec![
+                JavaFieldProto::new("img", "Ljavax/microedition/lcdui/Image;", Default::default()),
+                JavaFieldProto::new("color", "I", Default::default()),
+                JavaFieldProto::new("font", "Ljavax/microedition/lcdui/Font;", Default::default()),
+                JavaFieldProto::new("strokeStyle", "I", Default::default()),
+                JavaFieldProto::new("translateX", "I", Default::default()),
+                JavaFieldProto::new("translateY", "I", Default::default()),
+                // clip is kept in image coordinates, so it isn't affected by later translation
+                JavaFieldProto::new("clipX", "I", Default::default()),
+                JavaFieldProto::new("clipY", "I", Default::default()),
+                JavaFieldProto::new("clipWidth", "I", Default::default()),
+                JavaFieldProto::new("clipHeight", "I", Default::default()),
+            ],
         }
     }
 
-    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::debug!("javax.microedition.lcdui.Graphics::<init>({:?})", &this);
+    async fn init(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, image: ClassInstanceRef<Image>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::<init>({:?}, {:?})", &this, &image);
 
         let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
 
+        jvm.put_field(&mut this, "img", "Ljavax/microedition/lcdui/Image;", image).await?;
+
+        Self::reset(jvm, context, this).await
+    }
+
+    async fn translate(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, x: i32, y: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::translate({:?}, {}, {})", &this, x, y);
+
+        let translate_x: i32 = jvm.get_field(&this, "translateX", "I").await?;
+        let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
+
+        jvm.put_field(&mut this, "translateX", "I", translate_x + x).await?;
+        jvm.put_field(&mut this, "translateY", "I", translate_y + y).await?;
+
         Ok(())
     }
 
+    async fn get_translate_x(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getTranslateX({:?})", &this);
+
+        jvm.get_field(&this, "translateX", "I").await
+    }
+
+    async fn get_translate_y(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getTranslateY({:?})", &this);
+
+        jvm.get_field(&this, "translateY", "I").await
+    }
+
+    async fn get_color(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getColor({:?})", &this);
+
+        jvm.get_field(&this, "color", "I").await
+    }
+
+    async fn get_red_component(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getRedComponent({:?})", &this);
+
+        let color: i32 = jvm.get_field(&this, "color", "I").await?;
+
+        Ok((color >> 16) & 0xff)
+    }
+
+    async fn get_green_component(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getGreenComponent({:?})", &this);
+
+        let color: i32 = jvm.get_field(&this, "color", "I").await?;
+
+        Ok((color >> 8) & 0xff)
+    }
+
+    async fn get_blue_component(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getBlueComponent({:?})", &this);
+
+        let color: i32 = jvm.get_field(&this, "color", "I").await?;
+
+        Ok(color & 0xff)
+    }
+
+    async fn get_gray_scale(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getGrayScale({:?})", &this);
+
+        let color: i32 = jvm.get_field(&this, "color", "I").await?;
+        let (r, g, b) = ((color >> 16) & 0xff, (color >> 8) & 0xff, color & 0xff);
+
+        // luminance, which is exact for colors set by setGrayScale
+        Ok((r * 299 + g * 587 + b * 114 + 500) / 1000)
+    }
+
+    async fn set_gray_scale(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, value: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::setGrayScale({:?}, {})", &this, value);
+
+        if !(0..=0xff).contains(&value) {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid gray scale").await);
+        }
+
+        jvm.put_field(&mut this, "color", "I", (value << 16) | (value << 8) | value).await
+    }
+
+    async fn set_color(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, color: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::setColor({:?}, {})", &this, color);
+
+        jvm.put_field(&mut this, "color", "I", color & 0xffffff).await
+    }
+
+    async fn set_color_rgb(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        red: i32,
+        green: i32,
+        blue: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::setColor({:?}, {}, {}, {})", &this, red, green, blue);
+
+        if [red, green, blue].iter().any(|x| !(0..=0xff).contains(x)) {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid color").await);
+        }
+
+        jvm.put_field(&mut this, "color", "I", (red << 16) | (green << 8) | blue).await
+    }
+
+    async fn get_font(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Font>> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getFont({:?})", &this);
+
+        jvm.get_field(&this, "font", "Ljavax/microedition/lcdui/Font;").await
+    }
+
+    async fn set_font(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, font: ClassInstanceRef<Font>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::setFont({:?}, {:?})", &this, &font);
+
+        let font = if font.is_null() {
+            jvm.invoke_static("javax/microedition/lcdui/Font", "getDefaultFont", "()Ljavax/microedition/lcdui/Font;", ())
+                .await?
+        } else {
+            font
+        };
+
+        jvm.put_field(&mut this, "font", "Ljavax/microedition/lcdui/Font;", font).await
+    }
+
+    async fn get_stroke_style(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getStrokeStyle({:?})", &this);
+
+        jvm.get_field(&this, "strokeStyle", "I").await
+    }
+
+    async fn set_stroke_style(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, style: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::setStrokeStyle({:?}, {})", &this, style);
+
+        if style != Self::SOLID && style != Self::DOTTED {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid stroke style").await);
+        }
+
+        jvm.put_field(&mut this, "strokeStyle", "I", style).await
+    }
+
+    async fn get_clip_x(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getClipX({:?})", &this);
+
+        let clip_x: i32 = jvm.get_field(&this, "clipX", "I").await?;
+        let translate_x: i32 = jvm.get_field(&this, "translateX", "I").await?;
+
+        Ok(clip_x - translate_x)
+    }
+
+    async fn get_clip_y(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getClipY({:?})", &this);
+
+        let clip_y: i32 = jvm.get_field(&this, "clipY", "I").await?;
+        let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
+
+        Ok(clip_y - translate_y)
+    }
+
+    async fn get_clip_width(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getClipWidth({:?})", &this);
+
+        jvm.get_field(&this, "clipWidth", "I").await
+    }
+
+    async fn get_clip_height(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getClipHeight({:?})", &this);
+
+        jvm.get_field(&this, "clipHeight", "I").await
+    }
+
+    async fn clip_rect(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::clipRect({:?}, {}, {}, {}, {})",
+            &this,
+            x,
+            y,
+            width,
+            height
+        );
+
+        let translate_x: i32 = jvm.get_field(&this, "translateX", "I").await?;
+        let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
+        let clip_x: i32 = jvm.get_field(&this, "clipX", "I").await?;
+        let clip_y: i32 = jvm.get_field(&this, "clipY", "I").await?;
+        let clip_width: i32 = jvm.get_field(&this, "clipWidth", "I").await?;
+        let clip_height: i32 = jvm.get_field(&this, "clipHeight", "I").await?;
+
+        let left = clip_x.max(x + translate_x);
+        let top = clip_y.max(y + translate_y);
+        let right = (clip_x + clip_width).min(x + translate_x + width);
+        let bottom = (clip_y + clip_height).min(y + translate_y + height);
+
+        Self::put_clip(jvm, &mut this, left, top, (right - left).max(0), (bottom - top).max(0)).await
+    }
+
+    async fn set_clip(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::setClip({:?}, {}, {}, {}, {})",
+            &this,
+            x,
+            y,
+            width,
+            height
+        );
+
+        let translate_x: i32 = jvm.get_field(&this, "translateX", "I").await?;
+        let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
+
+        Self::put_clip(jvm, &mut this, x + translate_x, y + translate_y, width, height).await
+    }
+
+    async fn draw_line(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, x1: i32, y1: i32, x2: i32, y2: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::drawLine({:?}, {}, {}, {}, {})", &this, x1, y1, x2, y2);
+
+        Self::draw_points(jvm, &this, line_points(x1, y1, x2, y2)).await
+    }
+
     async fn fill_rect(
-        _jvm: &Jvm,
+        jvm: &Jvm,
         _context: &mut WieJvmContext,
         this: ClassInstanceRef<Self>,
         x: i32,
@@ -55,8 +429,8 @@ impl Graphics {
         width: i32,
         height: i32,
     ) -> JvmResult<()> {
-        tracing::warn!(
-            "stub javax.microedition.lcdui.Graphics::fillRect({:?}, {}, {}, {}, {})",
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::fillRect({:?}, {}, {}, {}, {})",
             &this,
             x,
             y,
@@ -64,11 +438,19 @@ impl Graphics {
             height
         );
 
-        Ok(())
+        let spans = (y..y + height)
+            .map(|y| Span {
+                y,
+                x_start: x,
+                x_end: x + width,
+            })
+            .collect();
+
+        Self::fill_spans(jvm, &this, spans).await
     }
 
     async fn draw_rect(
-        _jvm: &Jvm,
+        jvm: &Jvm,
         _context: &mut WieJvmContext,
         this: ClassInstanceRef<Self>,
         x: i32,
@@ -76,8 +458,8 @@ impl Graphics {
         width: i32,
         height: i32,
     ) -> JvmResult<()> {
-        tracing::warn!(
-            "stub javax.microedition.lcdui.Graphics::drawRect({:?}, {}, {}, {}, {})",
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawRect({:?}, {}, {}, {}, {})",
             &this,
             x,
             y,
@@ -85,20 +467,137 @@ impl Graphics {
             height
         );
 
-        Ok(())
+        Self::draw_points(jvm, &this, round_rect_points(x, y, width, height, 0, 0)).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn draw_round_rect(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        arc_width: i32,
+        arc_height: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawRoundRect({:?}, {}, {}, {}, {}, {}, {})",
+            &this,
+            x,
+            y,
+            width,
+            height,
+            arc_width,
+            arc_height
+        );
+
+        Self::draw_points(jvm, &this, round_rect_points(x, y, width, height, arc_width, arc_height)).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn fill_round_rect(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        arc_width: i32,
+        arc_height: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::fillRoundRect({:?}, {}, {}, {}, {}, {}, {})",
+            &this,
+            x,
+            y,
+            width,
+            height,
+            arc_width,
+            arc_height
+        );
+
+        Self::fill_spans(jvm, &this, round_rect_spans(x, y, width, height, arc_width, arc_height)).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn fill_arc(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        start_angle: i32,
+        arc_angle: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::fillArc({:?}, {}, {}, {}, {}, {}, {})",
+            &this,
+            x,
+            y,
+            width,
+            height,
+            start_angle,
+            arc_angle
+        );
+
+        Self::fill_spans(jvm, &this, arc_spans(x, y, width, height, start_angle, arc_angle)).await
     }
 
-    async fn draw_line(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, x1: i32, y1: i32, x2: i32, y2: i32) -> JvmResult<()> {
-        tracing::warn!(
-            "stub javax.microedition.lcdui.Graphics::drawLine({:?}, {}, {}, {}, {})",
+    #[allow(clippy::too_many_arguments)]
+    async fn draw_arc(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        start_angle: i32,
+        arc_angle: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawArc({:?}, {}, {}, {}, {}, {}, {})",
+            &this,
+            x,
+            y,
+            width,
+            height,
+            start_angle,
+            arc_angle
+        );
+
+        Self::draw_points(jvm, &this, arc_points(x, y, width, height, start_angle, arc_angle)).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn fill_triangle(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        x1: i32,
+        y1: i32,
+        x2: i32,
+        y2: i32,
+        x3: i32,
+        y3: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::fillTriangle({:?}, {}, {}, {}, {}, {}, {})",
             &this,
             x1,
             y1,
             x2,
-            y2
+            y2,
+            x3,
+            y3
         );
 
-        Ok(())
+        Self::fill_spans(jvm, &this, triangle_spans([(x1, y1), (x2, y2), (x3, y3)])).await
     }
 
     async fn draw_string(
@@ -108,71 +607,579 @@ impl Graphics {
         string: ClassInstanceRef<String>,
         x: i32,
         y: i32,
-        anchor: i32,
+        anchor: Anchor,
     ) -> JvmResult<()> {
-        tracing::warn!(
-            "stub javax.microedition.lcdui.Graphics::drawString({:?}, {:?}, {}, {}, {})",
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawString({:?}, {:?}, {}, {}, {})",
             &this,
-            string,
+            &string,
             x,
             y,
-            anchor
+            anchor.bits()
         );
 
+        if string.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "string is null").await);
+        }
+
         let string = JavaLangString::to_rust_string(jvm, &string).await?;
-        tracing::warn!("draw_string: {:?}", string);
 
-        Ok(())
+        Self::draw_text(jvm, &this, &string, x, y, anchor).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn draw_substring(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        string: ClassInstanceRef<String>,
+        offset: i32,
+        length: i32,
+        x: i32,
+        y: i32,
+        anchor: Anchor,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawSubstring({:?}, {:?}, {}, {}, {}, {}, {})",
+            &this,
+            &string,
+            offset,
+            length,
+            x,
+            y,
+            anchor.bits()
+        );
+
+        if string.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "string is null").await);
+        }
+
+        let string = JavaLangString::to_rust_string(jvm, &string).await?;
+        if offset < 0 || length < 0 || (offset + length) as usize > string.chars().count() {
+            return Err(jvm.exception("java/lang/StringIndexOutOfBoundsException", "Invalid substring").await);
+        }
+
+        let substring = string.chars().skip(offset as _).take(length as _).collect::<RustString>();
+
+        Self::draw_text(jvm, &this, &substring, x, y, anchor).await
+    }
+
+    async fn draw_char(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        char: JavaChar,
+        x: i32,
+        y: i32,
+        anchor: Anchor,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawChar({:?}, {}, {}, {}, {})",
+            &this,
+            char,
+            x,
+            y,
+            anchor.bits()
+        );
+
+        let string = RustString::from_utf16_lossy(&[char]);
+
+        Self::draw_text(jvm, &this, &string, x, y, anchor).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn draw_chars(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        data: ClassInstanceRef<Array<JavaChar>>,
+        offset: i32,
+        length: i32,
+        x: i32,
+        y: i32,
+        anchor: Anchor,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawChars({:?}, {:?}, {}, {}, {}, {}, {})",
+            &this,
+            &data,
+            offset,
+            length,
+            x,
+            y,
+            anchor.bits()
+        );
+
+        if data.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "data is null").await);
+        }
+
+        let chars: Vec<JavaChar> = jvm.load_array(&data, offset as _, length as _).await?;
+        let string = RustString::from_utf16_lossy(&chars);
+
+        Self::draw_text(jvm, &this, &string, x, y, anchor).await
     }
 
     async fn draw_image(
-        _jvm: &Jvm,
+        jvm: &Jvm,
         _context: &mut WieJvmContext,
         this: ClassInstanceRef<Self>,
         image: ClassInstanceRef<Image>,
         x: i32,
         y: i32,
-        anchor: i32,
+        anchor: Anchor,
     ) -> JvmResult<()> {
-        tracing::warn!(
-            "stub javax.microedition.lcdui.Graphics::drawImage({:?}, {:?}, {}, {}, {})",
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawImage({:?}, {:?}, {}, {}, {})",
             &this,
             &image,
             x,
             y,
-            anchor
+            anchor.bits()
         );
 
-        Ok(())
+        if image.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "image is null").await);
+        }
+
+        let src = Image::image(jvm, &image).await?;
+        let Some((offset_x, offset_y)) = anchor.offset(src.width() as _, src.height() as _, None) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid anchor").await);
+        };
+
+        Self::draw_src(jvm, &this, &*src, x + offset_x, y + offset_y, CompositeMode::SourceOver).await
     }
 
-    async fn set_font(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, font: ClassInstanceRef<Font>) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.lcdui.Graphics::setFont({:?}, {:?})", &this, font);
+    #[allow(clippy::too_many_arguments)]
+    async fn draw_region(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        image: ClassInstanceRef<Image>,
+        x_src: i32,
+        y_src: i32,
+        width: i32,
+        height: i32,
+        transform: i32,
+        x_dest: i32,
+        y_dest: i32,
+        anchor: Anchor,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawRegion({:?}, {:?}, {}, {}, {}, {}, {}, {}, {}, {})",
+            &this,
+            &image,
+            x_src,
+            y_src,
+            width,
+            height,
+            transform,
+            x_dest,
+            y_dest,
+            anchor.bits()
+        );
+
+        if image.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "image is null").await);
+        }
 
-        Ok(())
+        let src = Image::image(jvm, &image).await?;
+        if !region_in_bounds(x_src, y_src, width, height, src.width(), src.height()) {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Region exceeds source image").await);
+        }
+
+        let Some(region) = transform_region(&*src, x_src as _, y_src as _, width as _, height as _, transform) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid transform").await);
+        };
+        let Some((offset_x, offset_y)) = anchor.offset(region.width() as _, region.height() as _, None) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid anchor").await);
+        };
+
+        Self::draw_src(jvm, &this, &region, x_dest + offset_x, y_dest + offset_y, CompositeMode::SourceOver).await
     }
 
-    async fn set_color(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, color: i32) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.lcdui.Graphics::setColor({:?}, {})", &this, color);
+    #[allow(clippy::too_many_arguments)]
+    async fn copy_area(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        x_src: i32,
+        y_src: i32,
+        width: i32,
+        height: i32,
+        x_dest: i32,
+        y_dest: i32,
+        anchor: Anchor,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::copyArea({:?}, {}, {}, {}, {}, {}, {}, {})",
+            &this,
+            x_src,
+            y_src,
+            width,
+            height,
+            x_dest,
+            y_dest,
+            anchor.bits()
+        );
 
-        Ok(())
+        let image: ClassInstanceRef<Image> = jvm.get_field(&this, "img", "Ljavax/microedition/lcdui/Image;").await?;
+        let translate_x: i32 = jvm.get_field(&this, "translateX", "I").await?;
+        let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
+
+        // source area is not clipped, but must lie within the image
+        let src = Image::image(jvm, &image).await?;
+        let (x, y) = (x_src.saturating_add(translate_x), y_src.saturating_add(translate_y));
+        if !region_in_bounds(x, y, width, height, src.width(), src.height()) {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Area exceeds image").await);
+        }
+
+        let area = transform_region(&*src, x as _, y as _, width as _, height as _, TRANS_NONE).unwrap();
+        let Some((offset_x, offset_y)) = anchor.offset(width, height, None) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid anchor").await);
+        };
+
+        Self::draw_src(jvm, &this, &area, x_dest + offset_x, y_dest + offset_y, CompositeMode::Copy).await
     }
 
-    async fn set_color_rgb(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, red: i32, green: i32, blue: i32) -> JvmResult<()> {
-        tracing::warn!(
-            "stub javax.microedition.lcdui.Graphics::setColor({:?}, {}, {}, {})",
+    #[allow(clippy::too_many_arguments)]
+    async fn draw_rgb(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        rgb_data: ClassInstanceRef<Array<i32>>,
+        offset: i32,
+        scan_length: i32,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        process_alpha: bool,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Graphics::drawRGB({:?}, {:?}, {}, {}, {}, {}, {}, {}, {})",
             &this,
-            red,
-            green,
-            blue
+            &rgb_data,
+            offset,
+            scan_length,
+            x,
+            y,
+            width,
+            height,
+            process_alpha
         );
 
-        Ok(())
+        if rgb_data.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "rgbData is null").await);
+        }
+        if width <= 0 || height <= 0 {
+            return Ok(());
+        }
+        let Some(size) = width.checked_mul(height) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid size").await);
+        };
+
+        // rows are loaded one by one, as scan length can be larger than width, or even negative
+        let mut pixels = Vec::with_capacity(size as _);
+        for row in 0..height {
+            let row_offset = row.checked_mul(scan_length).and_then(|x| x.checked_add(offset));
+            let Some(row_offset) = row_offset.filter(|&x| x >= 0) else {
+                return Err(jvm.exception("java/lang/ArrayIndexOutOfBoundsException", "Invalid offset").await);
+            };
+
+            let row_data: Vec<i32> = jvm.load_array(&rgb_data, row_offset as _, width as _).await?;
+            pixels.extend(row_data.into_iter().map(|x| if process_alpha { x as u32 } else { x as u32 | 0xff000000 }));
+        }
+
+        let src = VecImageBuffer::<ArgbPixel>::from_raw(width as _, height as _, pixels);
+
+        Self::draw_src(jvm, &this, &src, x, y, CompositeMode::SourceOver).await
+    }
+
+    async fn get_display_color(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, color: i32) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::getDisplayColor({:?}, {})", &this, color);
+
+        // we render in full 24-bit color
+        Ok(color & 0xffffff)
+    }
+
+    async fn reset(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::reset({:?})", &this);
+
+        let image: ClassInstanceRef<Image> = jvm.get_field(&this, "img", "Ljavax/microedition/lcdui/Image;").await?;
+        let width: i32 = jvm.get_field(&image, "w", "I").await?;
+        let height: i32 = jvm.get_field(&image, "h", "I").await?;
+
+        let font: ClassInstanceRef<Font> = jvm
+            .invoke_static("javax/microedition/lcdui/Font", "getDefaultFont", "()Ljavax/microedition/lcdui/Font;", ())
+            .await?;
+
+        jvm.put_field(&mut this, "color", "I", 0).await?;
+        jvm.put_field(&mut this, "font", "Ljavax/microedition/lcdui/Font;", font).await?;
+        jvm.put_field(&mut this, "strokeStyle", "I", Self::SOLID).await?;
+        jvm.put_field(&mut this, "translateX", "I", 0).await?;
+        jvm.put_field(&mut this, "translateY", "I", 0).await?;
+
+        Self::put_clip(jvm, &mut this, 0, 0, width, height).await
     }
 
-    async fn reset(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.lcdui.Graphics::reset({:?})", &this);
+    async fn put_clip(jvm: &Jvm, this: &mut ClassInstanceRef<Self>, x: i32, y: i32, width: i32, height: i32) -> JvmResult<()> {
+        jvm.put_field(this, "clipX", "I", x).await?;
+        jvm.put_field(this, "clipY", "I", y).await?;
+        jvm.put_field(this, "clipWidth", "I", width).await?;
+        jvm.put_field(this, "clipHeight", "I", height).await?;
 
         Ok(())
     }
+
+    async fn draw_state(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<DrawState> {
+        let image: ClassInstanceRef<Image> = jvm.get_field(this, "img", "Ljavax/microedition/lcdui/Image;").await?;
+        let width: i32 = jvm.get_field(&image, "w", "I").await?;
+        let height: i32 = jvm.get_field(&image, "h", "I").await?;
+
+        let color: i32 = jvm.get_field(this, "color", "I").await?;
+        let stroke_style: i32 = jvm.get_field(this, "strokeStyle", "I").await?;
+        let translate_x: i32 = jvm.get_field(this, "translateX", "I").await?;
+        let translate_y: i32 = jvm.get_field(this, "translateY", "I").await?;
+        let clip_x: i32 = jvm.get_field(this, "clipX", "I").await?;
+        let clip_y: i32 = jvm.get_field(this, "clipY", "I").await?;
+        let clip_width: i32 = jvm.get_field(this, "clipWidth", "I").await?;
+        let clip_height: i32 = jvm.get_field(this, "clipHeight", "I").await?;
+
+        Ok(DrawState {
+            image,
+            color: Rgb8Pixel::to_color(color as _),
+            dotted: stroke_style == Self::DOTTED,
+            translate_x,
+            translate_y,
+            clip_left: clip_x.max(0),
+            clip_top: clip_y.max(0),
+            clip_right: (clip_x + clip_width).min(width),
+            clip_bottom: (clip_y + clip_height).min(height),
+        })
+    }
+
+    async fn fill_spans(jvm: &Jvm, this: &ClassInstanceRef<Self>, spans: Vec<Span>) -> JvmResult<()> {
+        let state = Self::draw_state(jvm, this).await?;
+
+        // only rows inside the clip can be touched
+        let mut canvas = Image::canvas_rows(jvm, &state.image, state.clip_top, state.clip_bottom - state.clip_top).await?;
+
+        for span in spans {
+            if let Some((x, y, width, _)) = state.clip(span.x_start, span.y, span.x_end - span.x_start, 1) {
+                canvas.fill_rect(x, y, width, 1, state.color, CompositeMode::Copy);
+            }
+        }
+
+        canvas.flush().await
+    }
+
+    async fn draw_points(jvm: &Jvm, this: &ClassInstanceRef<Self>, points: Vec<(i32, i32)>) -> JvmResult<()> {
+        let state = Self::draw_state(jvm, this).await?;
+        let mut canvas = Image::canvas_rows(jvm, &state.image, state.clip_top, state.clip_bottom - state.clip_top).await?;
+
+        for (i, (x, y)) in points.into_iter().enumerate() {
+            // dotted stroke skips every other pixel
+            if state.dotted && i % 2 == 1 {
+                continue;
+            }
+
+            if let Some((x, y, _, _)) = state.clip(x, y, 1, 1) {
+                canvas.put_pixel(x, y, state.color);
+            }
+        }
+
+        canvas.flush().await
+    }
+
+    // draws whole `src` with its top left corner at (x, y)
+    async fn draw_src(jvm: &Jvm, this: &ClassInstanceRef<Self>, src: &dyn BackendImage, x: i32, y: i32, mode: CompositeMode) -> JvmResult<()> {
+        let state = Self::draw_state(jvm, this).await?;
+
+        let Some((dest_x, dest_y, width, height)) = state.clip(x, y, src.width() as _, src.height() as _) else {
+            return Ok(());
+        };
+        let src_x = dest_x as i32 - (x + state.translate_x);
+        let src_y = dest_y as i32 - (y + state.translate_y);
+
+        let mut canvas = Image::canvas_rows(jvm, &state.image, dest_y as _, height as _).await?;
+        canvas.draw(dest_x, dest_y, width, height, src, src_x as _, src_y as _, mode, None);
+        canvas.flush().await
+    }
+
+    async fn draw_text(jvm: &Jvm, this: &ClassInstanceRef<Self>, string: &str, x: i32, y: i32, anchor: Anchor) -> JvmResult<()> {
+        let font: ClassInstanceRef<Font> = jvm.get_field(this, "font", "Ljavax/microedition/lcdui/Font;").await?;
+        let color: i32 = jvm.get_field(this, "color", "I").await?;
+
+        let pt_size = Font::pt_size(jvm, &font).await?;
+        let text = render_text(string, pt_size, Rgb8Pixel::to_color(color as _));
+
+        let Some((offset_x, offset_y)) = anchor.offset(text.width() as _, text.height() as _, Some(font_ascent(pt_size) as _)) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid anchor").await);
+        };
+
+        Self::draw_src(jvm, this, &text, x + offset_x, y + offset_y, CompositeMode::SourceOver).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec};
+
+    use java_runtime::classes::java::lang::String;
+    use jvm::{runtime::JavaLangString, ClassInstanceRef};
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_backend::canvas::Color;
+    use wie_util::Result;
+
+    use crate::{
+        classes::javax::microedition::lcdui::{image::TRANS_ROT90, Graphics, Image},
+        get_protos,
+    };
+
+    const RED: Color = Color {
+        a: 0xff,
+        r: 0xff,
+        g: 0,
+        b: 0,
+    };
+    const BLUE: Color = Color {
+        a: 0xff,
+        r: 0,
+        g: 0,
+        b: 0xff,
+    };
+
+    #[test]
+    fn test_graphics() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let image: ClassInstanceRef<Image> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createImage",
+                    "(II)Ljavax/microedition/lcdui/Image;",
+                    (10, 10),
+                )
+                .await?;
+            let graphics: ClassInstanceRef<Graphics> = jvm
+                .invoke_virtual(&image, "getGraphics", "()Ljavax/microedition/lcdui/Graphics;", ())
+                .await?;
+
+            let _: () = jvm.invoke_virtual(&graphics, "setColor", "(I)V", (0xff0000,)).await?;
+            let _: () = jvm.invoke_virtual(&graphics, "fillRect", "(IIII)V", (0, 0, 10, 10)).await?;
+
+            let rendered = Image::image(&jvm, &image).await?;
+            assert_eq!(rendered.get_pixel(0, 0), RED);
+            assert_eq!(rendered.get_pixel(9, 9), RED);
+
+            // clip is given in translated coordinates
+            let _: () = jvm.invoke_virtual(&graphics, "translate", "(II)V", (2, 2)).await?;
+            let _: () = jvm.invoke_virtual(&graphics, "setClip", "(IIII)V", (0, 0, 3, 3)).await?;
+            let clip_x: i32 = jvm.invoke_virtual(&graphics, "getClipX", "()I", ()).await?;
+            assert_eq!(clip_x, 0);
+
+            let _: () = jvm.invoke_virtual(&graphics, "setColor", "(III)V", (0, 0, 0xff)).await?;
+            let _: () = jvm.invoke_virtual(&graphics, "fillRect", "(IIII)V", (-2, -2, 10, 10)).await?;
+
+            let rendered = Image::image(&jvm, &image).await?;
+            assert_eq!(rendered.get_pixel(1, 1), RED);
+            assert_eq!(rendered.get_pixel(2, 2), BLUE);
+            assert_eq!(rendered.get_pixel(4, 4), BLUE);
+            assert_eq!(rendered.get_pixel(5, 5), RED);
+
+            // text can't be anchored at vertical center
+            let string: ClassInstanceRef<String> = JavaLangString::from_rust_string(&jvm, "text").await?.into();
+            let result: jvm::Result<()> = jvm
+                .invoke_virtual(&graphics, "drawString", "(Ljava/lang/String;III)V", (string, 0, 0, 2 | 4))
+                .await;
+            assert!(result.is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_draw_pixels() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let image: ClassInstanceRef<Image> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createImage",
+                    "(II)Ljavax/microedition/lcdui/Image;",
+                    (4, 4),
+                )
+                .await?;
+            let graphics: ClassInstanceRef<Graphics> = jvm
+                .invoke_virtual(&image, "getGraphics", "()Ljavax/microedition/lcdui/Graphics;", ())
+                .await?;
+
+            let _: () = jvm.invoke_virtual(&graphics, "setColor", "(I)V", (0xff0000,)).await?;
+            let _: () = jvm.invoke_virtual(&graphics, "fillRect", "(IIII)V", (0, 0, 4, 4)).await?;
+
+            // alpha is ignored without processAlpha
+            let mut rgb = jvm.instantiate_array("I", 2).await?;
+            jvm.store_array(&mut rgb, 0, vec![0x000000ff, 0x000000ff]).await?;
+            let _: () = jvm
+                .invoke_virtual(&graphics, "drawRGB", "([IIIIIIIZ)V", (rgb, 0, 2, 0, 0, 2, 1, false))
+                .await?;
+
+            let rendered = Image::image(&jvm, &image).await?;
+            assert_eq!(rendered.get_pixel(0, 0), BLUE);
+            assert_eq!(rendered.get_pixel(1, 0), BLUE);
+            assert_eq!(rendered.get_pixel(2, 0), RED);
+            assert_eq!(rendered.get_pixel(0, 1), RED);
+
+            // TOP | LEFT
+            let _: () = jvm
+                .invoke_virtual(&graphics, "copyArea", "(IIIIIII)V", (0, 0, 2, 1, 0, 2, 16 | 4))
+                .await?;
+
+            let rendered = Image::image(&jvm, &image).await?;
+            assert_eq!(rendered.get_pixel(0, 2), BLUE);
+            assert_eq!(rendered.get_pixel(1, 2), BLUE);
+            assert_eq!(rendered.get_pixel(2, 2), RED);
+            assert_eq!(rendered.get_pixel(0, 3), RED);
+
+            let result: jvm::Result<()> = jvm.invoke_virtual(&graphics, "copyArea", "(IIIIIII)V", (3, 3, 2, 2, 0, 0, 16 | 4)).await;
+            assert!(result.is_err());
+
+            let mut rgb = jvm.instantiate_array("I", 2).await?;
+            jvm.store_array(&mut rgb, 0, vec![0xff0000ffu32 as i32, 0xffff0000u32 as i32]).await?;
+            let src: ClassInstanceRef<Image> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createRGBImage",
+                    "([IIIZ)Ljavax/microedition/lcdui/Image;",
+                    (rgb, 2, 1, true),
+                )
+                .await?;
+
+            // 2x1 region rotated into 1x2
+            let _: () = jvm
+                .invoke_virtual(
+                    &graphics,
+                    "drawRegion",
+                    "(Ljavax/microedition/lcdui/Image;IIIIIIII)V",
+                    (src.clone(), 0, 0, 2, 1, TRANS_ROT90, 3, 0, 16 | 4),
+                )
+                .await?;
+
+            let rendered = Image::image(&jvm, &image).await?;
+            assert_eq!(rendered.get_pixel(3, 0), BLUE);
+            assert_eq!(rendered.get_pixel(3, 1), RED);
+
+            let result: jvm::Result<()> = jvm
+                .invoke_virtual(
+                    &graphics,
+                    "drawRegion",
+                    "(Ljavax/microedition/lcdui/Image;IIIIIIII)V",
+                    (src, 1, 0, 2, 1, TRANS_ROT90, 3, 0, 16 | 4),
+                )
+                .await;
+            assert!(result.is_err());
+
+            Ok(())
+        })
+    }
 }
@@ -1,14 +1,37 @@
-use alloc::vec;
+use alloc::{boxed::Box, format, sync::Arc, vec, vec::Vec};
+use core::{
+    mem::size_of,
+    ops::{Deref, DerefMut},
+};
 
-use java_class_proto::JavaMethodProto;
+use bytemuck::{cast_vec, pod_collect_to_vec};
+use spin::Mutex;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_constants::MethodAccessFlags;
 use java_runtime::classes::java::lang::String;
-use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{
+    runtime::{JavaIoInputStream, JavaLangClassLoader, JavaLangString},
+    Array, ClassInstanceRef, Jvm, Result as JvmResult,
+};
 
+use wie_backend::canvas::{
+    decode_image, ArgbPixel, Canvas, Image as BackendImage, ImageBuffer, ImageBufferCanvas, ImageMemory, MemoryImageBuffer, PixelType, VecImageBuffer,
+};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 use crate::classes::javax::microedition::lcdui::Graphics;
 
+// transforms of javax.microedition.lcdui.game.Sprite
+pub const TRANS_NONE: i32 = 0;
+pub const TRANS_MIRROR_ROT180: i32 = 1;
+pub const TRANS_MIRROR: i32 = 2;
+pub const TRANS_ROT180: i32 = 3;
+pub const TRANS_MIRROR_ROT270: i32 = 4;
+pub const TRANS_ROT90: i32 = 5;
+pub const TRANS_ROT270: i32 = 6;
+pub const TRANS_MIRROR_ROT90: i32 = 7;
+
 // class javax.microedition.lcdui.Image
 pub struct Image;
 
@@ -22,12 +45,14 @@ impl Image {
                 JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
                 JavaMethodProto::new("getWidth", "()I", Self::get_width, Default::default()),
                 JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
+                JavaMethodProto::new("isMutable", "()Z", Self::is_mutable, Default::default()),
                 JavaMethodProto::new(
                     "getGraphics",
                     "()Ljavax/microedition/lcdui/Graphics;",
                     Self::get_graphics,
                     Default::default(),
                 ),
+                JavaMethodProto::new("getRGB", "([IIIIIII)V", Self::get_rgb, Default::default()),
                 JavaMethodProto::new(
                     "createImage",
                     "(II)Ljavax/microedition/lcdui/Image;",
@@ -46,8 +71,32 @@ impl Image {
                     Self::create_image_from_name,
                     MethodAccessFlags::STATIC,
                 ),
+                JavaMethodProto::new(
+                    "createImage",
+                    "(Ljavax/microedition/lcdui/Image;)Ljavax/microedition/lcdui/Image;",
+                    Self::create_image_from_image,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "createImage",
+                    "(Ljavax/microedition/lcdui/Image;IIIII)Ljavax/microedition/lcdui/Image;",
+                    Self::create_image_from_region,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "createRGBImage",
+                    "([IIIZ)Ljavax/microedition/lcdui/Image;",
+                    Self::create_rgb_image,
+                    MethodAccessFlags::STATIC,
+                ),
+            ],
+            fields: vec![
+                JavaFieldProto::new("w", "I", Default::default()),
+                JavaFieldProto::new("h", "I", Default::default()),
+                // pixels in 0xAARRGGBB
+                JavaFieldProto::new("imgData", "[I", Default::default()),
+                JavaFieldProto::new("mutable", "Z", Default::default()),
             ],
-            fields: vec![],
         }
     }
 
@@ -59,32 +108,108 @@ impl Image {
         Ok(())
     }
 
-    async fn get_width(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Image::getWidth({:?})", &this);
+    async fn get_width(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Image::getWidth({:?})", &this);
+
+        jvm.get_field(&this, "w", "I").await
+    }
+
+    async fn get_height(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Image::getHeight({:?})", &this);
 
-        Ok(100)
+        jvm.get_field(&this, "h", "I").await
     }
 
-    async fn get_height(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Image::getHeight({:?})", &this);
+    async fn is_mutable(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Image::isMutable({:?})", &this);
 
-        Ok(100)
+        jvm.get_field(&this, "mutable", "Z").await
     }
 
     async fn get_graphics(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Graphics>> {
-        tracing::warn!("stub javax.microedition.lcdui.Image::getGraphics({:?})", &this);
+        tracing::debug!("javax.microedition.lcdui.Image::getGraphics({:?})", &this);
+
+        let mutable: bool = jvm.get_field(&this, "mutable", "Z").await?;
+        if !mutable {
+            return Err(jvm.exception("java/lang/IllegalStateException", "Image is immutable").await);
+        }
 
-        let graphics = jvm.new_class("javax/microedition/lcdui/Graphics", "()V", ()).await?;
+        let graphics = jvm
+            .new_class("javax/microedition/lcdui/Graphics", "(Ljavax/microedition/lcdui/Image;)V", (this,))
+            .await?;
 
         Ok(graphics.into())
     }
 
+    #[allow(clippy::too_many_arguments)]
+    async fn get_rgb(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        mut rgb_data: ClassInstanceRef<Array<i32>>,
+        offset: i32,
+        scan_length: i32,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Image::getRGB({:?}, {:?}, {}, {}, {}, {}, {}, {})",
+            &this,
+            &rgb_data,
+            offset,
+            scan_length,
+            x,
+            y,
+            width,
+            height
+        );
+
+        if rgb_data.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "rgbData is null").await);
+        }
+
+        let (image_width, image_height, data) = Self::data(jvm, &this).await?;
+        let in_bounds = region_in_bounds(x, y, width.max(0), height.max(0), image_width, image_height);
+        if !in_bounds || scan_length.unsigned_abs() < width.max(0) as u32 {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid region").await);
+        }
+        if width <= 0 || height <= 0 {
+            return Ok(());
+        }
+
+        // scan length can be negative, so each row is stored separately
+        let length = jvm.array_length(&rgb_data).await? as i32;
+        for row in 0..height {
+            let row_offset = row.checked_mul(scan_length).and_then(|x| x.checked_add(offset));
+            let Some(row_offset) = row_offset.filter(|&x| x >= 0 && x <= length - width) else {
+                return Err(jvm.exception("java/lang/ArrayIndexOutOfBoundsException", "Invalid offset").await);
+            };
+
+            let start = ((y + row) as u32 * image_width + x as u32) as usize;
+            let row_data = data[start..start + width as usize].iter().map(|&x| x as i32).collect::<Vec<_>>();
+            jvm.store_array(&mut rgb_data, row_offset as _, row_data).await?;
+        }
+
+        Ok(())
+    }
+
     async fn create_image(jvm: &Jvm, _context: &mut WieJvmContext, width: i32, height: i32) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.lcdui.Image::createImage({}, {})", width, height);
+        tracing::debug!("javax.microedition.lcdui.Image::createImage({}, {})", width, height);
+
+        let size = width.checked_mul(height);
+        let Some(size) = size.filter(|_| width > 0 && height > 0) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid image size").await);
+        };
+
+        // mutable images are initially filled with white
+        let data = vec![0xffffffffu32; size as usize];
 
-        let image = jvm.new_class("javax/microedition/lcdui/Image", "()V", ()).await?;
+        let mut image = Self::create_image_instance(jvm, &VecImageBuffer::<ArgbPixel>::from_raw(width as _, height as _, data)).await?;
+        jvm.put_field(&mut image, "mutable", "Z", true).await?;
 
-        Ok(image.into())
+        Ok(image)
     }
 
     async fn create_image_from_data(
@@ -94,18 +219,452 @@ impl Image {
         offset: i32,
         length: i32,
     ) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.lcdui.Image::createImage({:?}, {}, {})", data, offset, length);
+        tracing::debug!("javax.microedition.lcdui.Image::createImage({:?}, {}, {})", &data, offset, length);
 
-        let image = jvm.new_class("javax/microedition/lcdui/Image", "()V", ()).await?;
+        if data.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "imageData is null").await);
+        }
+
+        let data_length = jvm.array_length(&data).await? as i32;
+        if offset < 0 || length < 0 || offset > data_length - length {
+            return Err(jvm.exception("java/lang/ArrayIndexOutOfBoundsException", "").await);
+        }
 
-        Ok(image.into())
+        let image_data = jvm.load_byte_array(&data, offset as _, length as _).await?;
+        let image = match decode_image(&cast_vec(image_data)) {
+            Ok(image) => image,
+            Err(_) => return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid image data").await),
+        };
+
+        Self::create_image_instance(jvm, &*image).await
     }
 
     async fn create_image_from_name(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.lcdui.Image::createImage({:?})", name);
+        tracing::debug!("javax.microedition.lcdui.Image::createImage({:?})", &name);
+
+        if name.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "name is null").await);
+        }
+
+        let name = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        // names are absolute as in Class.getResourceAsStream, while class loader expects path inside jar
+        let resource_name = name.strip_prefix('/').unwrap_or(&name);
+
+        let class_loader = jvm.current_class_loader().await?;
+        let stream = JavaLangClassLoader::get_resource_as_stream(jvm, &class_loader, resource_name).await?;
+        let stream = match stream {
+            Some(stream) => stream,
+            None => return Err(jvm.exception("java/io/IOException", &format!("Resource not found: {}", name)).await),
+        };
+
+        let image_data = JavaIoInputStream::read_until_end(jvm, &stream).await?;
+        let image = match decode_image(&image_data) {
+            Ok(image) => image,
+            Err(_) => return Err(jvm.exception("java/io/IOException", &format!("Invalid image: {}", name)).await),
+        };
+
+        Self::create_image_instance(jvm, &*image).await
+    }
+
+    async fn create_image_from_image(jvm: &Jvm, _context: &mut WieJvmContext, source: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Self>> {
+        tracing::debug!("javax.microedition.lcdui.Image::createImage({:?})", &source);
+
+        if source.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "source is null").await);
+        }
+
+        // immutable image can't change, so it can be shared
+        let mutable: bool = jvm.get_field(&source, "mutable", "Z").await?;
+        if !mutable {
+            return Ok(source);
+        }
+
+        let image = Self::image(jvm, &source).await?;
+
+        Self::create_image_instance(jvm, &*image).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create_image_from_region(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        source: ClassInstanceRef<Self>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        transform: i32,
+    ) -> JvmResult<ClassInstanceRef<Self>> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Image::createImage({:?}, {}, {}, {}, {}, {})",
+            &source,
+            x,
+            y,
+            width,
+            height,
+            transform
+        );
+
+        if source.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "source is null").await);
+        }
+
+        let image = Self::image(jvm, &source).await?;
+        if width == 0 || height == 0 || !region_in_bounds(x, y, width, height, image.width(), image.height()) {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid region").await);
+        }
+
+        let result = match transform_region(&*image, x as _, y as _, width as _, height as _, transform) {
+            Some(result) => result,
+            None => return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid transform").await),
+        };
+
+        Self::create_image_instance(jvm, &result).await
+    }
+
+    async fn create_rgb_image(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        rgb: ClassInstanceRef<Array<i32>>,
+        width: i32,
+        height: i32,
+        process_alpha: bool,
+    ) -> JvmResult<ClassInstanceRef<Self>> {
+        tracing::debug!(
+            "javax.microedition.lcdui.Image::createRGBImage({:?}, {}, {}, {})",
+            &rgb,
+            width,
+            height,
+            process_alpha
+        );
+
+        if rgb.is_null() {
+            return Err(jvm.exception("java/lang/NullPointerException", "rgb is null").await);
+        }
+        let size = width.checked_mul(height);
+        let Some(size) = size.filter(|_| width > 0 && height > 0) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid image size").await);
+        };
+
+        let length = jvm.array_length(&rgb).await? as i32;
+        if size > length {
+            return Err(jvm.exception("java/lang/ArrayIndexOutOfBoundsException", "").await);
+        }
+
+        let data: Vec<i32> = jvm.load_array(&rgb, 0, size as _).await?;
+        let data = data
+            .into_iter()
+            .map(|x| if process_alpha { x as u32 } else { x as u32 | 0xff000000 })
+            .collect::<Vec<_>>();
+
+        Self::create_image_instance(jvm, &VecImageBuffer::<ArgbPixel>::from_raw(width as _, height as _, data)).await
+    }
+
+    pub async fn image(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<Box<dyn BackendImage>> {
+        let (width, height, data) = Self::data(jvm, this).await?;
+
+        Ok(Box::new(VecImageBuffer::<ArgbPixel>::from_raw(width, height, data)))
+    }
+
+    pub async fn canvas<'a>(jvm: &'a Jvm, this: &'a ClassInstanceRef<Self>) -> JvmResult<ImageCanvas<'a>> {
+        let height: i32 = jvm.get_field(this, "h", "I").await?;
+
+        Self::canvas_rows(jvm, this, 0, height).await
+    }
+
+    /// Canvas over rows `y..y + height` of the image. Drawing outside those rows is discarded.
+    pub async fn canvas_rows<'a>(jvm: &'a Jvm, this: &'a ClassInstanceRef<Self>, y: i32, height: i32) -> JvmResult<ImageCanvas<'a>> {
+        let width: i32 = jvm.get_field(this, "w", "I").await?;
+        let image_height: i32 = jvm.get_field(this, "h", "I").await?;
+
+        let start = y.clamp(0, image_height);
+        let end = y.saturating_add(height.max(0)).clamp(start, image_height);
+
+        let java_img_data = jvm.get_field(this, "imgData", "[I").await?;
+        let img_data_len = jvm.array_length(&java_img_data).await?;
+        let offset = (start as usize * width as usize).min(img_data_len);
+        let length = (end as usize * width as usize).min(img_data_len) - offset;
+        let data: Vec<i32> = jvm.load_array(&java_img_data, offset, length).await?;
+
+        // memory is addressed in bytes, while imgData holds one pixel per element
+        let memory = ImageDataMemory(Arc::new(Mutex::new(ImageDataRows {
+            offset: (offset * size_of::<i32>()) as _,
+            data: pod_collect_to_vec(&data),
+            dirty: None,
+        })));
+
+        let bytes_per_line = width as u32 * size_of::<i32>() as u32;
+        let buffer = MemoryImageBuffer::<ArgbPixel, _>::new(width as _, image_height as _, bytes_per_line, memory.clone());
+
+        Ok(ImageCanvas::new(jvm, this, Box::new(ImageBufferCanvas::new(buffer)), memory))
+    }
+
+    /// Creates immutable image instance with contents of `image`.
+    pub async fn create_image_instance(jvm: &Jvm, image: &dyn BackendImage) -> JvmResult<ClassInstanceRef<Self>> {
+        let mut instance = jvm.new_class("javax/microedition/lcdui/Image", "()V", ()).await?;
+
+        let data = image.colors().into_iter().map(|x| ArgbPixel::from_color(x) as i32).collect::<Vec<_>>();
+
+        let mut data_array = jvm.instantiate_array("I", data.len() as _).await?;
+        jvm.store_array(&mut data_array, 0, data).await?;
+
+        jvm.put_field(&mut instance, "w", "I", image.width() as i32).await?;
+        jvm.put_field(&mut instance, "h", "I", image.height() as i32).await?;
+        jvm.put_field(&mut instance, "imgData", "[I", data_array).await?;
+
+        Ok(instance.into())
+    }
+
+    async fn data(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<(u32, u32, Vec<u32>)> {
+        let width: i32 = jvm.get_field(this, "w", "I").await?;
+        let height: i32 = jvm.get_field(this, "h", "I").await?;
+
+        let data_array = jvm.get_field(this, "imgData", "[I").await?;
+        let data: Vec<i32> = jvm.load_array(&data_array, 0, width as usize * height as usize).await?;
+
+        Ok((width as _, height as _, cast_vec(data)))
+    }
+}
+
+/// Whether region at (`x`, `y`) lies within image of given size. Negative sizes and overflowing regions are rejected.
+pub fn region_in_bounds(x: i32, y: i32, width: i32, height: i32, image_width: u32, image_height: u32) -> bool {
+    let right = x.checked_add(width);
+    let bottom = y.checked_add(height);
+
+    x >= 0
+        && y >= 0
+        && width >= 0
+        && height >= 0
+        && right.is_some_and(|x| x as u32 <= image_width)
+        && bottom.is_some_and(|y| y as u32 <= image_height)
+}
+
+/// Copies region of `src` with sprite transform applied, `None` if `transform` is invalid.
+pub fn transform_region(src: &dyn BackendImage, x: u32, y: u32, width: u32, height: u32, transform: i32) -> Option<VecImageBuffer<ArgbPixel>> {
+    let (result_width, result_height) = match transform {
+        TRANS_NONE | TRANS_MIRROR | TRANS_ROT180 | TRANS_MIRROR_ROT180 => (width, height),
+        TRANS_ROT90 | TRANS_ROT270 | TRANS_MIRROR_ROT90 | TRANS_MIRROR_ROT270 => (height, width),
+        _ => return None,
+    };
+
+    let mut result = VecImageBuffer::<ArgbPixel>::new(result_width, result_height);
+    for v in 0..height {
+        for u in 0..width {
+            let (dx, dy) = match transform {
+                TRANS_NONE => (u, v),
+                TRANS_MIRROR => (width - 1 - u, v),
+                TRANS_ROT180 => (width - 1 - u, height - 1 - v),
+                TRANS_MIRROR_ROT180 => (u, height - 1 - v),
+                TRANS_ROT90 => (height - 1 - v, u),
+                TRANS_ROT270 => (v, width - 1 - u),
+                TRANS_MIRROR_ROT90 => (height - 1 - v, width - 1 - u),
+                _ => (v, u), // TRANS_MIRROR_ROT270
+            };
+
+            result.put_pixel(dx, dy, src.get_pixel(x + u, y + v));
+        }
+    }
+
+    Some(result)
+}
+
+/// Rows of `imgData` loaded from the java array as bytes, with the byte range modified since load.
+struct ImageDataRows {
+    offset: u32,
+    data: Vec<u8>,
+    dirty: Option<(usize, usize)>,
+}
+
+impl ImageDataRows {
+    fn range(&self, offset: u32, length: usize) -> Option<(usize, usize)> {
+        let start = offset.checked_sub(self.offset)? as usize;
+        let end = start.checked_add(length)?;
+
+        (end <= self.data.len()).then_some((start, end))
+    }
+}
+
+#[derive(Clone)]
+struct ImageDataMemory(Arc<Mutex<ImageDataRows>>);
+
+impl ImageMemory for ImageDataMemory {
+    fn read(&self, offset: u32, buf: &mut [u8]) {
+        let rows = self.0.lock();
+        match rows.range(offset, buf.len()) {
+            Some((start, end)) => buf.copy_from_slice(&rows.data[start..end]),
+            None => buf.fill(0),
+        }
+    }
+
+    fn write(&mut self, offset: u32, data: &[u8]) {
+        let mut rows = self.0.lock();
+        if let Some((start, end)) = rows.range(offset, data.len()) {
+            rows.data[start..end].copy_from_slice(data);
+            rows.dirty = Some(match rows.dirty {
+                Some((dirty_start, dirty_end)) => (dirty_start.min(start), dirty_end.max(end)),
+                None => (start, end),
+            });
+        }
+    }
+}
+
+pub struct ImageCanvas<'a> {
+    image: &'a ClassInstanceRef<Image>,
+    jvm: &'a Jvm,
+    canvas: Box<dyn Canvas>,
+    memory: ImageDataMemory,
+}
+
+impl<'a> ImageCanvas<'a> {
+    fn new(jvm: &'a Jvm, image: &'a ClassInstanceRef<Image>, canvas: Box<dyn Canvas>, memory: ImageDataMemory) -> Self {
+        Self { image, jvm, canvas, memory }
+    }
+
+    // We don't have async drop yet.. changes are discarded if not flushed
+    pub async fn flush(self) -> JvmResult<()> {
+        let (offset, data) = {
+            let rows = self.memory.0.lock();
+            let Some((start, end)) = rows.dirty else {
+                return Ok(());
+            };
+
+            // widen to whole pixels, as imgData can only be stored per element
+            let (start, end) = (start / size_of::<i32>() * size_of::<i32>(), end.next_multiple_of(size_of::<i32>()));
+            let pixels: Vec<i32> = pod_collect_to_vec(&rows.data[start..end]);
+
+            ((rows.offset as usize + start) / size_of::<i32>(), pixels)
+        };
+
+        let mut img_data = self.jvm.get_field(self.image, "imgData", "[I").await?;
+        self.jvm.store_array(&mut img_data, offset, data).await
+    }
+}
+
+impl Deref for ImageCanvas<'_> {
+    type Target = Box<dyn Canvas>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.canvas
+    }
+}
+
+impl DerefMut for ImageCanvas<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.canvas
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    use jvm::ClassInstanceRef;
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::{
+        classes::javax::microedition::lcdui::{Graphics, Image},
+        get_protos,
+    };
+
+    use super::TRANS_ROT90;
+
+    #[test]
+    fn test_rgb_image() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let mut rgb = jvm.instantiate_array("I", 4).await?;
+            jvm.store_array(&mut rgb, 0, vec![0x11ff0000, 0x2200ff00, 0x330000ff, 0x44ffffff]).await?;
+
+            let image: ClassInstanceRef<Image> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createRGBImage",
+                    "([IIIZ)Ljavax/microedition/lcdui/Image;",
+                    (rgb, 2, 2, false),
+                )
+                .await?;
+
+            let mutable: bool = jvm.invoke_virtual(&image, "isMutable", "()Z", ()).await?;
+            assert!(!mutable);
+
+            let graphics: jvm::Result<ClassInstanceRef<Graphics>> = jvm
+                .invoke_virtual(&image, "getGraphics", "()Ljavax/microedition/lcdui/Graphics;", ())
+                .await;
+            assert!(graphics.is_err());
+
+            // alpha is ignored without processAlpha
+            let result = jvm.instantiate_array("I", 4).await?;
+            let _: () = jvm
+                .invoke_virtual(&image, "getRGB", "([IIIIIII)V", (result.clone(), 0, 2, 0, 0, 2, 2))
+                .await?;
+            let pixels: Vec<i32> = jvm.load_array(&result, 0, 4).await?;
+            assert_eq!(pixels, [0xffff0000u32 as i32, 0xff00ff00u32 as i32, 0xff0000ffu32 as i32, -1]);
+
+            let rotated: ClassInstanceRef<Image> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createImage",
+                    "(Ljavax/microedition/lcdui/Image;IIIII)Ljavax/microedition/lcdui/Image;",
+                    (image, 0, 0, 2, 1, TRANS_ROT90),
+                )
+                .await?;
+            let width: i32 = jvm.invoke_virtual(&rotated, "getWidth", "()I", ()).await?;
+            let height: i32 = jvm.invoke_virtual(&rotated, "getHeight", "()I", ()).await?;
+            assert_eq!((width, height), (1, 2));
+
+            let _: () = jvm
+                .invoke_virtual(&rotated, "getRGB", "([IIIIIII)V", (result.clone(), 0, 1, 0, 0, 1, 2))
+                .await?;
+            let pixels: Vec<i32> = jvm.load_array(&result, 0, 2).await?;
+            assert_eq!(pixels, [0xffff0000u32 as i32, 0xff00ff00u32 as i32]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_invalid_size() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            // width * height overflows i32
+            let image: jvm::Result<ClassInstanceRef<Image>> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createImage",
+                    "(II)Ljavax/microedition/lcdui/Image;",
+                    (0x10000, 0x10000),
+                )
+                .await;
+            assert!(image.is_err());
+
+            let rgb = jvm.instantiate_array("I", 4).await?;
+            let image: jvm::Result<ClassInstanceRef<Image>> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createRGBImage",
+                    "([IIIZ)Ljavax/microedition/lcdui/Image;",
+                    (rgb.clone(), 0x10000, 0x10001, false),
+                )
+                .await;
+            assert!(image.is_err());
+
+            let image: ClassInstanceRef<Image> = jvm
+                .invoke_static(
+                    "javax/microedition/lcdui/Image",
+                    "createRGBImage",
+                    "([IIIZ)Ljavax/microedition/lcdui/Image;",
+                    (rgb.clone(), 2, 2, false),
+                )
+                .await?;
 
-        let image = jvm.new_class("javax/microedition/lcdui/Image", "()V", ()).await?;
+            // x + width overflows i32
+            let result: jvm::Result<()> = jvm
+                .invoke_virtual(&image, "getRGB", "([IIIIIII)V", (rgb, 0, i32::MAX, 1, 0, i32::MAX, 1))
+                .await;
+            assert!(result.is_err());
 
-        Ok(image.into())
+            Ok(())
+        })
     }
 }
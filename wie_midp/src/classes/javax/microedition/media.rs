@@ -0,0 +1,9 @@
+pub mod control;
+
+mod control_interface;
+mod manager;
+mod media_exception;
+mod player;
+mod player_listener;
+
+pub use {control_interface::Control, manager::Manager, media_exception::MediaException, player::Player, player_listener::PlayerListener};
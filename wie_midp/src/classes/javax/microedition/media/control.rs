@@ -0,0 +1,3 @@
+mod volume_control;
+
+pub use volume_control::VolumeControl;
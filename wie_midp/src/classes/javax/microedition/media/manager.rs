@@ -0,0 +1,103 @@
+use alloc::{format, vec};
+
+use java_class_proto::JavaMethodProto;
+use java_constants::MethodAccessFlags;
+use java_runtime::classes::java::{io::InputStream, lang::String};
+use jvm::{
+    runtime::{JavaIoInputStream, JavaLangString},
+    ClassInstanceRef, Jvm, Result as JvmResult,
+};
+
+use wie_backend::{Tone, Waveform};
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use crate::classes::javax::microedition::media::Player;
+
+// class javax.microedition.media.Manager
+pub struct Manager;
+
+impl Manager {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "javax/microedition/media/Manager",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new(
+                    "createPlayer",
+                    "(Ljava/io/InputStream;Ljava/lang/String;)Ljavax/microedition/media/Player;",
+                    Self::create_player,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "createPlayer",
+                    "(Ljava/lang/String;)Ljavax/microedition/media/Player;",
+                    Self::create_player_with_locator,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new("playTone", "(III)V", Self::play_tone, MethodAccessFlags::STATIC),
+            ],
+            fields: vec![],
+        }
+    }
+
+    async fn create_player(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        stream: ClassInstanceRef<InputStream>,
+        r#type: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<Player>> {
+        tracing::debug!("javax.microedition.media.Manager::createPlayer({:?}, {:?})", &stream, &r#type);
+
+        if stream.is_null() {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "stream is null").await);
+        }
+
+        let content_type = if r#type.is_null() {
+            None
+        } else {
+            Some(JavaLangString::to_rust_string(jvm, &r#type).await?)
+        };
+
+        let data = JavaIoInputStream::read_until_end(jvm, &stream).await?;
+        let handle = context.system().audio().load(&data, content_type.as_deref());
+        if let Err(x) = handle {
+            let message = format!("Unsupported media: {:?}", x);
+            return Err(jvm.exception("javax/microedition/media/MediaException", &message).await);
+        }
+
+        let player = jvm
+            .new_class("wie/MidpPlayer", "(ILjava/lang/String;)V", (handle.unwrap() as i32, r#type))
+            .await?;
+
+        Ok(player.into())
+    }
+
+    async fn create_player_with_locator(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        locator: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<Player>> {
+        tracing::warn!("stub javax.microedition.media.Manager::createPlayer({:?})", &locator);
+
+        Err(jvm
+            .exception("javax/microedition/media/MediaException", "Locators are not supported")
+            .await)
+    }
+
+    async fn play_tone(jvm: &Jvm, context: &mut WieJvmContext, note: i32, duration: i32, volume: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.media.Manager::playTone({}, {}, {})", note, duration, volume);
+
+        if !(0..=127).contains(&note) || duration <= 0 {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid note or duration").await);
+        }
+
+        let tone = Tone::note(note as _, duration as _, volume.clamp(0, 100) as _);
+        if let Err(x) = context.system().audio().play_tone(&[tone], Waveform::Square) {
+            let message = format!("Failed to play tone: {:?}", x);
+            return Err(jvm.exception("javax/microedition/media/MediaException", &message).await);
+        }
+
+        Ok(())
+    }
+}
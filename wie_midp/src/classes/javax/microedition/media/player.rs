@@ -0,0 +1,45 @@
+use alloc::vec;
+
+use java_class_proto::JavaMethodProto;
+
+use wie_jvm_support::WieJavaClassProto;
+
+// interface javax.microedition.media.Player
+pub struct Player;
+
+impl Player {
+    pub const CLOSED: i32 = 0;
+    pub const UNREALIZED: i32 = 100;
+    pub const REALIZED: i32 = 200;
+    pub const PREFETCHED: i32 = 300;
+    pub const STARTED: i32 = 400;
+
+    pub const TIME_UNKNOWN: i64 = -1;
+
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "javax/microedition/media/Player",
+            parent_class: None,
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new_abstract("realize", "()V", Default::default()),
+                JavaMethodProto::new_abstract("prefetch", "()V", Default::default()),
+                JavaMethodProto::new_abstract("start", "()V", Default::default()),
+                JavaMethodProto::new_abstract("stop", "()V", Default::default()),
+                JavaMethodProto::new_abstract("deallocate", "()V", Default::default()),
+                JavaMethodProto::new_abstract("close", "()V", Default::default()),
+                JavaMethodProto::new_abstract("getState", "()I", Default::default()),
+                JavaMethodProto::new_abstract("getDuration", "()J", Default::default()),
+                JavaMethodProto::new_abstract("getMediaTime", "()J", Default::default()),
+                JavaMethodProto::new_abstract("setMediaTime", "(J)J", Default::default()),
+                JavaMethodProto::new_abstract("setLoopCount", "(I)V", Default::default()),
+                JavaMethodProto::new_abstract("getContentType", "()Ljava/lang/String;", Default::default()),
+                JavaMethodProto::new_abstract("addPlayerListener", "(Ljavax/microedition/media/PlayerListener;)V", Default::default()),
+                JavaMethodProto::new_abstract("removePlayerListener", "(Ljavax/microedition/media/PlayerListener;)V", Default::default()),
+                JavaMethodProto::new_abstract("getControl", "(Ljava/lang/String;)Ljavax/microedition/media/Control;", Default::default()),
+                JavaMethodProto::new_abstract("getControls", "()[Ljavax/microedition/media/Control;", Default::default()),
+            ],
+            fields: vec![],
+        }
+    }
+}
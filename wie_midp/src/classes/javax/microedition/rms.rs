@@ -1,3 +1,17 @@
+mod invalid_record_id_exception;
+mod record_comparator;
+mod record_enumeration;
+mod record_filter;
+mod record_listener;
 mod record_store;
+mod record_store_exception;
+mod record_store_full_exception;
+mod record_store_not_found_exception;
+mod record_store_not_open_exception;
 
-pub use record_store::RecordStore;
+pub use {
+    invalid_record_id_exception::InvalidRecordIDException, record_comparator::RecordComparator, record_enumeration::RecordEnumeration,
+    record_filter::RecordFilter, record_listener::RecordListener, record_store::RecordStore, record_store_exception::RecordStoreException,
+    record_store_full_exception::RecordStoreFullException, record_store_not_found_exception::RecordStoreNotFoundException,
+    record_store_not_open_exception::RecordStoreNotOpenException,
+};
@@ -0,0 +1,24 @@
+use alloc::vec;
+
+use java_class_proto::JavaMethodProto;
+
+use wie_jvm_support::WieJavaClassProto;
+
+// interface javax.microedition.rms.RecordComparator
+pub struct RecordComparator;
+
+impl RecordComparator {
+    pub const EQUIVALENT: i32 = 0;
+    pub const FOLLOWS: i32 = 1;
+    pub const PRECEDES: i32 = -1;
+
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "javax/microedition/rms/RecordComparator",
+            parent_class: None,
+            interfaces: vec![],
+            methods: vec![JavaMethodProto::new_abstract("compare", "([B[B)I", Default::default())],
+            fields: vec![],
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use alloc::vec;
+
+use java_class_proto::JavaMethodProto;
+
+use wie_jvm_support::WieJavaClassProto;
+
+// interface javax.microedition.rms.RecordEnumeration
+pub struct RecordEnumeration;
+
+impl RecordEnumeration {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "javax/microedition/rms/RecordEnumeration",
+            parent_class: None,
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new_abstract("numRecords", "()I", Default::default()),
+                JavaMethodProto::new_abstract("nextRecord", "()[B", Default::default()),
+                JavaMethodProto::new_abstract("nextRecordId", "()I", Default::default()),
+                JavaMethodProto::new_abstract("previousRecord", "()[B", Default::default()),
+                JavaMethodProto::new_abstract("previousRecordId", "()I", Default::default()),
+                JavaMethodProto::new_abstract("hasNextElement", "()Z", Default::default()),
+                JavaMethodProto::new_abstract("hasPreviousElement", "()Z", Default::default()),
+                JavaMethodProto::new_abstract("reset", "()V", Default::default()),
+                JavaMethodProto::new_abstract("rebuild", "()V", Default::default()),
+                JavaMethodProto::new_abstract("keepUpdated", "(Z)V", Default::default()),
+                JavaMethodProto::new_abstract("isKeptUpdated", "()Z", Default::default()),
+                JavaMethodProto::new_abstract("destroy", "()V", Default::default()),
+            ],
+            fields: vec![],
+        }
+    }
+}
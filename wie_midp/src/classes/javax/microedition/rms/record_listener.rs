@@ -0,0 +1,24 @@
+use alloc::vec;
+
+use java_class_proto::JavaMethodProto;
+
+use wie_jvm_support::WieJavaClassProto;
+
+// interface javax.microedition.rms.RecordListener
+pub struct RecordListener;
+
+impl RecordListener {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "javax/microedition/rms/RecordListener",
+            parent_class: None,
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new_abstract("recordAdded", "(Ljavax/microedition/rms/RecordStore;I)V", Default::default()),
+                JavaMethodProto::new_abstract("recordChanged", "(Ljavax/microedition/rms/RecordStore;I)V", Default::default()),
+                JavaMethodProto::new_abstract("recordDeleted", "(Ljavax/microedition/rms/RecordStore;I)V", Default::default()),
+            ],
+            fields: vec![],
+        }
+    }
+}
@@ -1,12 +1,23 @@
-use alloc::vec;
+use alloc::{borrow::ToOwned, boxed::Box, format, vec, vec::Vec};
 
-use java_class_proto::JavaMethodProto;
-use java_constants::MethodAccessFlags;
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use java_constants::{FieldAccessFlags, MethodAccessFlags};
 use java_runtime::classes::java::lang::String;
-use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, Jvm, Result as JvmResult};
 
+use wie_backend::{AccessMode, Database, RecordId};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
+use crate::classes::javax::microedition::rms::{RecordComparator, RecordEnumeration, RecordFilter, RecordListener};
+
+// backend record ids start from 0, while midp record ids start from 1
+const RECORD_ID_OFFSET: i32 = 1;
+const MAX_NAME_LENGTH: usize = 32;
+const AUTHMODE_PRIVATE: i32 = 0;
+const AUTHMODE_ANY: i32 = 1;
+// TODO use available space of platform storage
+const CAPACITY: i32 = 0x100000;
+
 // class javax.microedition.rms.RecordStore
 pub struct RecordStore;
 
@@ -17,72 +28,472 @@ impl RecordStore {
             parent_class: Some("java/lang/Object"),
             interfaces: vec![],
             methods: vec![
-                JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
-                JavaMethodProto::new("addRecord", "([BII)I", Self::add_record, Default::default()),
-                JavaMethodProto::new("getRecord", "(I)[B", Self::get_record, Default::default()),
-                JavaMethodProto::new("setRecord", "(I[BII)V", Self::set_record, Default::default()),
-                JavaMethodProto::new("getNumRecords", "()I", Self::get_num_records, Default::default()),
-                JavaMethodProto::new("closeRecordStore", "()V", Self::close_record_store, Default::default()),
+                JavaMethodProto::new("<clinit>", "()V", Self::cl_init, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("<init>", "(Ljava/lang/String;)V", Self::init, Default::default()),
                 JavaMethodProto::new(
                     "openRecordStore",
                     "(Ljava/lang/String;Z)Ljavax/microedition/rms/RecordStore;",
                     Self::open_record_store,
                     MethodAccessFlags::STATIC,
                 ),
+                JavaMethodProto::new(
+                    "openRecordStore",
+                    "(Ljava/lang/String;ZIZ)Ljavax/microedition/rms/RecordStore;",
+                    Self::open_record_store_with_mode,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "openRecordStore",
+                    "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)Ljavax/microedition/rms/RecordStore;",
+                    Self::open_record_store_of_suite,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "deleteRecordStore",
+                    "(Ljava/lang/String;)V",
+                    Self::delete_record_store,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "listRecordStores",
+                    "()[Ljava/lang/String;",
+                    Self::list_record_stores,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new("closeRecordStore", "()V", Self::close_record_store, Default::default()),
+                JavaMethodProto::new("setMode", "(IZ)V", Self::set_mode, Default::default()),
+                JavaMethodProto::new("getName", "()Ljava/lang/String;", Self::get_name, Default::default()),
+                JavaMethodProto::new("getVersion", "()I", Self::get_version, Default::default()),
+                JavaMethodProto::new("getLastModified", "()J", Self::get_last_modified, Default::default()),
+                JavaMethodProto::new("getNumRecords", "()I", Self::get_num_records, Default::default()),
+                JavaMethodProto::new("getSize", "()I", Self::get_size, Default::default()),
+                JavaMethodProto::new("getSizeAvailable", "()I", Self::get_size_available, Default::default()),
+                JavaMethodProto::new("getNextRecordID", "()I", Self::get_next_record_id, Default::default()),
+                JavaMethodProto::new("addRecord", "([BII)I", Self::add_record, Default::default()),
+                JavaMethodProto::new("deleteRecord", "(I)V", Self::delete_record, Default::default()),
+                JavaMethodProto::new("getRecordSize", "(I)I", Self::get_record_size, Default::default()),
+                JavaMethodProto::new("getRecord", "(I)[B", Self::get_record, Default::default()),
+                JavaMethodProto::new("getRecord", "(I[BI)I", Self::get_record_into, Default::default()),
+                JavaMethodProto::new("setRecord", "(I[BII)V", Self::set_record, Default::default()),
+                JavaMethodProto::new(
+                    "enumerateRecords",
+                    "(Ljavax/microedition/rms/RecordFilter;Ljavax/microedition/rms/RecordComparator;Z)Ljavax/microedition/rms/RecordEnumeration;",
+                    Self::enumerate_records,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "addRecordListener",
+                    "(Ljavax/microedition/rms/RecordListener;)V",
+                    Self::add_record_listener,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "removeRecordListener",
+                    "(Ljavax/microedition/rms/RecordListener;)V",
+                    Self::remove_record_listener,
+                    Default::default(),
+                ),
+            ],
+            fields: vec![
+                // record stores are shared between openRecordStore calls with same name
+                JavaFieldProto::new("openStores", "Ljava/util/Vector;", FieldAccessFlags::STATIC),
+                JavaFieldProto::new("name", "Ljava/lang/String;", Default::default()),
+                JavaFieldProto::new("openCount", "I", Default::default()),
+                JavaFieldProto::new("listeners", "Ljava/util/Vector;", Default::default()),
             ],
-            fields: vec![],
         }
     }
 
-    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.rms.RecordStore::<init>({:?})", &this);
+    async fn cl_init(jvm: &Jvm, _context: &mut WieJvmContext) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::<clinit>");
+
+        let open_stores = jvm.new_class("java/util/Vector", "()V", ()).await?;
+        jvm.put_static_field("javax/microedition/rms/RecordStore", "openStores", "Ljava/util/Vector;", open_stores)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, name: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::<init>({:?}, {:?})", &this, &name);
 
         let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
 
+        let listeners = jvm.new_class("java/util/Vector", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "name", "Ljava/lang/String;", name).await?;
+        jvm.put_field(&mut this, "openCount", "I", 0).await?;
+        jvm.put_field(&mut this, "listeners", "Ljava/util/Vector;", listeners).await?;
+
         Ok(())
     }
 
-    async fn add_record(
-        _jvm: &Jvm,
+    async fn open_record_store(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+        create: bool,
+    ) -> JvmResult<ClassInstanceRef<Self>> {
+        tracing::debug!("javax.microedition.rms.RecordStore::openRecordStore({:?}, {:?})", &name, create);
+
+        let name_str = JavaLangString::to_rust_string(jvm, &name).await?;
+        if name_str.is_empty() || name_str.chars().count() > MAX_NAME_LENGTH {
+            return Err(jvm
+                .exception("java/lang/IllegalArgumentException", &format!("Invalid record store name: {}", name_str))
+                .await);
+        }
+
+        let mut store = match Self::find_open_store(jvm, &name_str).await? {
+            Some(x) => x,
+            None => {
+                if !database_exists(context, &name_str) {
+                    if !create {
+                        return Err(jvm.exception("javax/microedition/rms/RecordStoreNotFoundException", &name_str).await);
+                    }
+
+                    // opening creates empty database
+                    open_database(context, &name_str);
+                }
+
+                let store = jvm
+                    .new_class("javax/microedition/rms/RecordStore", "(Ljava/lang/String;)V", (name,))
+                    .await?;
+
+                let open_stores = jvm
+                    .get_static_field("javax/microedition/rms/RecordStore", "openStores", "Ljava/util/Vector;")
+                    .await?;
+                let _: () = jvm
+                    .invoke_virtual(&open_stores, "addElement", "(Ljava/lang/Object;)V", (store.clone(),))
+                    .await?;
+
+                store.into()
+            }
+        };
+
+        let open_count: i32 = jvm.get_field(&store, "openCount", "I").await?;
+        jvm.put_field(&mut store, "openCount", "I", open_count + 1).await?;
+
+        Ok(store)
+    }
+
+    async fn open_record_store_with_mode(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+        create: bool,
+        auth_mode: i32,
+        writable: bool,
+    ) -> JvmResult<ClassInstanceRef<Self>> {
+        tracing::debug!(
+            "javax.microedition.rms.RecordStore::openRecordStore({:?}, {:?}, {}, {})",
+            &name,
+            create,
+            auth_mode,
+            writable
+        );
+
+        let Some(mode) = access_mode(auth_mode, writable) else {
+            return Err(jvm
+                .exception("java/lang/IllegalArgumentException", &format!("Invalid auth mode: {}", auth_mode))
+                .await);
+        };
+
+        let name_str = JavaLangString::to_rust_string(jvm, &name).await?;
+        let existed = database_exists(context, &name_str);
+
+        let store = jvm
+            .invoke_static(
+                "javax/microedition/rms/RecordStore",
+                "openRecordStore",
+                "(Ljava/lang/String;Z)Ljavax/microedition/rms/RecordStore;",
+                (name, create),
+            )
+            .await?;
+
+        // mode is applied only on creation
+        if !existed {
+            open_database(context, &name_str).set_access_mode(mode);
+        }
+
+        Ok(store)
+    }
+
+    async fn open_record_store_of_suite(
+        jvm: &Jvm,
         _context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+        vendor: ClassInstanceRef<String>,
+        suite: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<Self>> {
+        tracing::warn!(
+            "stub javax.microedition.rms.RecordStore::openRecordStore({:?}, {:?}, {:?})",
+            &name,
+            &vendor,
+            &suite
+        );
+
+        // TODO open record store of other suite
+        jvm.invoke_static(
+            "javax/microedition/rms/RecordStore",
+            "openRecordStore",
+            "(Ljava/lang/String;Z)Ljavax/microedition/rms/RecordStore;",
+            (name, false),
+        )
+        .await
+    }
+
+    async fn delete_record_store(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::deleteRecordStore({:?})", &name);
+
+        let name = JavaLangString::to_rust_string(jvm, &name).await?;
+        if Self::find_open_store(jvm, &name).await?.is_some() {
+            return Err(jvm
+                .exception("javax/microedition/rms/RecordStoreException", &format!("Record store is open: {}", name))
+                .await);
+        }
+
+        let app_id = context.system().app_id().to_owned();
+        if !context.system().platform().database_repository().delete(&name, &app_id) {
+            return Err(jvm.exception("javax/microedition/rms/RecordStoreNotFoundException", &name).await);
+        }
+
+        Ok(())
+    }
+
+    async fn list_record_stores(jvm: &Jvm, context: &mut WieJvmContext) -> JvmResult<ClassInstanceRef<Array<String>>> {
+        tracing::debug!("javax.microedition.rms.RecordStore::listRecordStores()");
+
+        let app_id = context.system().app_id().to_owned();
+        let names = context.system().platform().database_repository().list(&app_id);
+
+        // null means there's no record store
+        if names.is_empty() {
+            return Ok(None.into());
+        }
+
+        let mut result = jvm.instantiate_array("Ljava/lang/String;", names.len()).await?;
+        let mut name_strings = Vec::with_capacity(names.len());
+        for name in names {
+            name_strings.push(JavaLangString::from_rust_string(jvm, &name).await?);
+        }
+        jvm.store_array(&mut result, 0, name_strings).await?;
+
+        Ok(result.into())
+    }
+
+    async fn close_record_store(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::closeRecordStore({:?})", &this);
+
+        let open_count: i32 = jvm.get_field(&this, "openCount", "I").await?;
+        if open_count <= 0 {
+            return Err(jvm.exception("javax/microedition/rms/RecordStoreNotOpenException", "").await);
+        }
+
+        jvm.put_field(&mut this, "openCount", "I", open_count - 1).await?;
+        if open_count == 1 {
+            let open_stores = jvm
+                .get_static_field("javax/microedition/rms/RecordStore", "openStores", "Ljava/util/Vector;")
+                .await?;
+            let _: bool = jvm
+                .invoke_virtual(&open_stores, "removeElement", "(Ljava/lang/Object;)Z", (this.clone(),))
+                .await?;
+
+            let listeners = jvm.get_field(&this, "listeners", "Ljava/util/Vector;").await?;
+            let _: () = jvm.invoke_virtual(&listeners, "removeAllElements", "()V", ()).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn set_mode(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, auth_mode: i32, writable: bool) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::setMode({:?}, {}, {})", &this, auth_mode, writable);
+
+        let Some(mode) = access_mode(auth_mode, writable) else {
+            return Err(jvm
+                .exception("java/lang/IllegalArgumentException", &format!("Invalid auth mode: {}", auth_mode))
+                .await);
+        };
+
+        // record stores aren't shared between suites, so mode is only stored
+        let mut database = Self::database(jvm, context, &this).await?;
+        database.set_access_mode(mode);
+
+        Ok(())
+    }
+
+    async fn get_name(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getName({:?})", &this);
+
+        Self::check_open(jvm, &this).await?;
+
+        jvm.get_field(&this, "name", "Ljava/lang/String;").await
+    }
+
+    async fn get_version(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getVersion({:?})", &this);
+
+        let database = Self::database(jvm, context, &this).await?;
+
+        Ok(database.version() as _)
+    }
+
+    async fn get_last_modified(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i64> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getLastModified({:?})", &this);
+
+        let database = Self::database(jvm, context, &this).await?;
+
+        Ok(database.last_modified().map(|x| x.raw() as i64).unwrap_or(0))
+    }
+
+    async fn get_num_records(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getNumRecords({:?})", &this);
+
+        let database = Self::database(jvm, context, &this).await?;
+
+        Ok(database.count() as _)
+    }
+
+    async fn get_size(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getSize({:?})", &this);
+
+        let database = Self::database(jvm, context, &this).await?;
+
+        Ok(database_size(database.as_ref()))
+    }
+
+    async fn get_size_available(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getSizeAvailable({:?})", &this);
+
+        let database = Self::database(jvm, context, &this).await?;
+
+        Ok((CAPACITY - database_size(database.as_ref())).max(0))
+    }
+
+    async fn get_next_record_id(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getNextRecordID({:?})", &this);
+
+        let database = Self::database(jvm, context, &this).await?;
+
+        Ok(database.next_id() as i32 + RECORD_ID_OFFSET)
+    }
+
+    async fn add_record(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
         this: ClassInstanceRef<Self>,
         data: ClassInstanceRef<Array<i8>>,
         offset: i32,
         length: i32,
     ) -> JvmResult<i32> {
-        tracing::warn!(
-            "stub javax.microedition.rms.RecordStore::addRecord({:?}, {:?}, {}, {})",
+        tracing::debug!(
+            "javax.microedition.rms.RecordStore::addRecord({:?}, {:?}, {}, {})",
             &this,
             &data,
             offset,
             length
         );
 
-        Ok(0)
+        let mut database = Self::database(jvm, context, &this).await?;
+        let data = Self::load_data(jvm, &data, offset, length).await?;
+
+        if database_size(database.as_ref()) + data.len() as i32 > CAPACITY {
+            return Err(jvm.exception("javax/microedition/rms/RecordStoreFullException", "").await);
+        }
+
+        let record_id = database.add(&data) as i32 + RECORD_ID_OFFSET;
+        drop(database);
+
+        Self::notify_listeners(jvm, &this, "recordAdded", record_id).await?;
+
+        Ok(record_id)
+    }
+
+    async fn delete_record(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, record_id: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::deleteRecord({:?}, {})", &this, record_id);
+
+        let mut database = Self::database(jvm, context, &this).await?;
+
+        let deleted = to_backend_id(record_id).is_some_and(|x| database.delete(x));
+        drop(database);
+        if !deleted {
+            return Err(jvm.exception("javax/microedition/rms/InvalidRecordIDException", "").await);
+        }
+
+        Self::notify_listeners(jvm, &this, "recordDeleted", record_id).await?;
+
+        Ok(())
+    }
+
+    async fn get_record_size(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, record_id: i32) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.rms.RecordStore::getRecordSize({:?}, {})", &this, record_id);
+
+        let database = Self::database(jvm, context, &this).await?;
+
+        match to_backend_id(record_id).and_then(|x| database.get_record_size(x)) {
+            Some(x) => Ok(x as _),
+            None => Err(jvm.exception("javax/microedition/rms/InvalidRecordIDException", "").await),
+        }
     }
 
     async fn get_record(
-        _jvm: &Jvm,
-        _context: &mut WieJvmContext,
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
         this: ClassInstanceRef<Self>,
         record_id: i32,
     ) -> JvmResult<ClassInstanceRef<Array<i8>>> {
-        tracing::warn!("stub javax.microedition.rms.RecordStore::getRecord({:?}, {})", &this, record_id);
+        tracing::debug!("javax.microedition.rms.RecordStore::getRecord({:?}, {})", &this, record_id);
+
+        let data = Self::record(jvm, context, &this, record_id).await?;
+        if data.is_empty() {
+            return Ok(None.into());
+        }
 
-        Ok(None.into())
+        let mut result = jvm.instantiate_array("B", data.len()).await?;
+        jvm.store_byte_array(&mut result, 0, data).await?;
+
+        Ok(result.into())
+    }
+
+    async fn get_record_into(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        record_id: i32,
+        mut buffer: ClassInstanceRef<Array<i8>>,
+        offset: i32,
+    ) -> JvmResult<i32> {
+        tracing::debug!(
+            "javax.microedition.rms.RecordStore::getRecord({:?}, {}, {:?}, {})",
+            &this,
+            record_id,
+            &buffer,
+            offset
+        );
+
+        let data = Self::record(jvm, context, &this, record_id).await?;
+
+        let buffer_length = jvm.array_length(&buffer).await?;
+        if offset < 0 || offset as usize + data.len() > buffer_length {
+            return Err(jvm.exception("java/lang/ArrayIndexOutOfBoundsException", "").await);
+        }
+
+        let length = data.len();
+        jvm.store_byte_array(&mut buffer, offset as _, data).await?;
+
+        Ok(length as _)
     }
 
     async fn set_record(
-        _jvm: &Jvm,
-        _context: &mut WieJvmContext,
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
         this: ClassInstanceRef<Self>,
         record_id: i32,
         data: ClassInstanceRef<Array<i8>>,
         offset: i32,
         length: i32,
     ) -> JvmResult<()> {
-        tracing::warn!(
-            "stub javax.microedition.rms.RecordStore::setRecord({:?}, {}, {:?}, {}, {})",
+        tracing::debug!(
+            "javax.microedition.rms.RecordStore::setRecord({:?}, {}, {:?}, {}, {})",
             &this,
             record_id,
             &data,
@@ -90,31 +501,332 @@ impl RecordStore {
             length
         );
 
+        let mut database = Self::database(jvm, context, &this).await?;
+        let Some((id, old_size)) = to_backend_id(record_id).and_then(|x| Some((x, database.get_record_size(x)? as i32))) else {
+            return Err(jvm.exception("javax/microedition/rms/InvalidRecordIDException", "").await);
+        };
+
+        let data = Self::load_data(jvm, &data, offset, length).await?;
+        if database_size(database.as_ref()) - old_size + data.len() as i32 > CAPACITY {
+            return Err(jvm.exception("javax/microedition/rms/RecordStoreFullException", "").await);
+        }
+
+        database.set(id, &data);
+        drop(database);
+
+        Self::notify_listeners(jvm, &this, "recordChanged", record_id).await?;
+
         Ok(())
     }
 
-    async fn get_num_records(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.rms.RecordStore::getNumRecords({:?})", &this);
+    async fn enumerate_records(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        filter: ClassInstanceRef<RecordFilter>,
+        comparator: ClassInstanceRef<RecordComparator>,
+        keep_updated: bool,
+    ) -> JvmResult<ClassInstanceRef<RecordEnumeration>> {
+        tracing::debug!(
+            "javax.microedition.rms.RecordStore::enumerateRecords({:?}, {:?}, {:?}, {})",
+            &this,
+            &filter,
+            &comparator,
+            keep_updated
+        );
+
+        Self::database(jvm, context, &this).await?;
 
-        Ok(0)
+        let enumeration = jvm
+            .new_class(
+                "wie/MidpRecordEnumeration",
+                "(Ljavax/microedition/rms/RecordStore;Ljavax/microedition/rms/RecordFilter;Ljavax/microedition/rms/RecordComparator;Z)V",
+                (this, filter, comparator, keep_updated),
+            )
+            .await?;
+
+        Ok(enumeration.into())
     }
 
-    async fn close_record_store(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.rms.RecordStore::closeRecordStore({:?})", &this);
+    async fn add_record_listener(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        listener: ClassInstanceRef<RecordListener>,
+    ) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::addRecordListener({:?}, {:?})", &this, &listener);
 
-        Ok(())
+        let listeners = jvm.get_field(&this, "listeners", "Ljava/util/Vector;").await?;
+        if listener.is_null()
+            || jvm
+                .invoke_virtual(&listeners, "contains", "(Ljava/lang/Object;)Z", (listener.clone(),))
+                .await?
+        {
+            return Ok(());
+        }
+
+        jvm.invoke_virtual(&listeners, "addElement", "(Ljava/lang/Object;)V", (listener,)).await
     }
 
-    async fn open_record_store(
+    async fn remove_record_listener(
         jvm: &Jvm,
         _context: &mut WieJvmContext,
-        name: ClassInstanceRef<String>,
-        create: bool,
-    ) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.rms.RecordStore::openRecordStore({:?}, {:?})", name, create);
+        this: ClassInstanceRef<Self>,
+        listener: ClassInstanceRef<RecordListener>,
+    ) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.rms.RecordStore::removeRecordListener({:?}, {:?})", &this, &listener);
+
+        let listeners = jvm.get_field(&this, "listeners", "Ljava/util/Vector;").await?;
+        let _: bool = jvm
+            .invoke_virtual(&listeners, "removeElement", "(Ljava/lang/Object;)Z", (listener,))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns ids of all records in ascending order.
+    pub async fn record_ids(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>) -> JvmResult<Vec<i32>> {
+        let database = Self::database(jvm, context, this).await?;
+
+        let mut ids = database
+            .get_record_ids()
+            .into_iter()
+            .map(|x| x as i32 + RECORD_ID_OFFSET)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+
+        Ok(ids)
+    }
+
+    async fn record(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>, record_id: i32) -> JvmResult<Vec<i8>> {
+        let database = Self::database(jvm, context, this).await?;
+
+        match to_backend_id(record_id).and_then(|x| database.get(x)) {
+            Some(x) => Ok(x.into_iter().map(|x| x as i8).collect()),
+            None => Err(jvm.exception("javax/microedition/rms/InvalidRecordIDException", "").await),
+        }
+    }
+
+    async fn database(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>) -> JvmResult<Box<dyn Database>> {
+        Self::check_open(jvm, this).await?;
+
+        let name = jvm.get_field(this, "name", "Ljava/lang/String;").await?;
+        let name = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        Ok(open_database(context, &name))
+    }
+
+    async fn check_open(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<()> {
+        let open_count: i32 = jvm.get_field(this, "openCount", "I").await?;
+        if open_count <= 0 {
+            return Err(jvm.exception("javax/microedition/rms/RecordStoreNotOpenException", "").await);
+        }
+
+        Ok(())
+    }
+
+    async fn find_open_store(jvm: &Jvm, name: &str) -> JvmResult<Option<ClassInstanceRef<Self>>> {
+        let open_stores = jvm
+            .get_static_field("javax/microedition/rms/RecordStore", "openStores", "Ljava/util/Vector;")
+            .await?;
+
+        let size: i32 = jvm.invoke_virtual(&open_stores, "size", "()I", ()).await?;
+        for i in 0..size {
+            let store: ClassInstanceRef<Self> = jvm.invoke_virtual(&open_stores, "elementAt", "(I)Ljava/lang/Object;", (i,)).await?;
+
+            let store_name = jvm.get_field(&store, "name", "Ljava/lang/String;").await?;
+            if JavaLangString::to_rust_string(jvm, &store_name).await? == name {
+                return Ok(Some(store));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn load_data(jvm: &Jvm, data: &ClassInstanceRef<Array<i8>>, offset: i32, length: i32) -> JvmResult<Vec<u8>> {
+        if data.is_null() {
+            if length == 0 {
+                return Ok(Vec::new());
+            }
+            return Err(jvm.exception("java/lang/NullPointerException", "").await);
+        }
+
+        let data_length = jvm.array_length(data).await? as i32;
+        if offset < 0 || length < 0 || offset + length > data_length {
+            return Err(jvm.exception("java/lang/ArrayIndexOutOfBoundsException", "").await);
+        }
+
+        let data = jvm.load_byte_array(data, offset as _, length as _).await?;
+
+        Ok(data.into_iter().map(|x| x as u8).collect())
+    }
+
+    async fn notify_listeners(jvm: &Jvm, this: &ClassInstanceRef<Self>, method: &str, record_id: i32) -> JvmResult<()> {
+        let listeners = jvm.get_field(this, "listeners", "Ljava/util/Vector;").await?;
+
+        let size: i32 = jvm.invoke_virtual(&listeners, "size", "()I", ()).await?;
+        for i in 0..size {
+            let listener: ClassInstanceRef<RecordListener> = jvm.invoke_virtual(&listeners, "elementAt", "(I)Ljava/lang/Object;", (i,)).await?;
+
+            let _: () = jvm
+                .invoke_virtual(&listener, method, "(Ljavax/microedition/rms/RecordStore;I)V", (this.clone(), record_id))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn open_database(context: &mut WieJvmContext, name: &str) -> Box<dyn Database> {
+    let app_id = context.system().app_id().to_owned();
+
+    context.system().platform().database_repository().open(name, &app_id)
+}
+
+fn access_mode(auth_mode: i32, writable: bool) -> Option<AccessMode> {
+    let shared = match auth_mode {
+        AUTHMODE_PRIVATE => false,
+        AUTHMODE_ANY => true,
+        _ => return None,
+    };
+
+    Some(AccessMode { shared, writable })
+}
+
+fn database_exists(context: &mut WieJvmContext, name: &str) -> bool {
+    let app_id = context.system().app_id().to_owned();
+
+    context.system().platform().database_repository().exists(name, &app_id)
+}
+
+fn to_backend_id(record_id: i32) -> Option<RecordId> {
+    if record_id >= RECORD_ID_OFFSET {
+        Some((record_id - RECORD_ID_OFFSET) as _)
+    } else {
+        None
+    }
+}
+
+fn database_size(database: &dyn Database) -> i32 {
+    database
+        .get_record_ids()
+        .into_iter()
+        .filter_map(|x| database.get_record_size(x))
+        .map(|x| x as i32)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec};
+
+    use java_runtime::classes::java::lang::String;
+    use jvm::{runtime::JavaLangString, Array, ClassInstanceRef};
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::{
+        classes::javax::microedition::rms::{RecordComparator, RecordEnumeration, RecordFilter},
+        get_protos,
+    };
+
+    use super::RecordStore;
+
+    #[test]
+    fn test_record_store() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let name: ClassInstanceRef<String> = JavaLangString::from_rust_string(&jvm, "save").await?.into();
+
+            let result: jvm::Result<ClassInstanceRef<RecordStore>> = jvm
+                .invoke_static(
+                    "javax/microedition/rms/RecordStore",
+                    "openRecordStore",
+                    "(Ljava/lang/String;Z)Ljavax/microedition/rms/RecordStore;",
+                    (name.clone(), false),
+                )
+                .await;
+            assert!(result.is_err());
+
+            let store: ClassInstanceRef<RecordStore> = jvm
+                .invoke_static(
+                    "javax/microedition/rms/RecordStore",
+                    "openRecordStore",
+                    "(Ljava/lang/String;Z)Ljavax/microedition/rms/RecordStore;",
+                    (name.clone(), true),
+                )
+                .await?;
+
+            let mut data = jvm.instantiate_array("B", 3).await?;
+            jvm.store_byte_array(&mut data, 0, vec![1, 2, 3]).await?;
+            let first: i32 = jvm.invoke_virtual(&store, "addRecord", "([BII)I", (data.clone(), 0, 3)).await?;
+            let second: i32 = jvm.invoke_virtual(&store, "addRecord", "([BII)I", (data.clone(), 1, 2)).await?;
+            assert_eq!((first, second), (1, 2));
+
+            let _: () = jvm.invoke_virtual(&store, "setRecord", "(I[BII)V", (first, data.clone(), 2, 1)).await?;
+            let record: ClassInstanceRef<Array<i8>> = jvm.invoke_virtual(&store, "getRecord", "(I)[B", (first,)).await?;
+            assert_eq!(jvm.load_byte_array(&record, 0, 1).await?, [3]);
+
+            let size: i32 = jvm.invoke_virtual(&store, "getSize", "()I", ()).await?;
+            assert_eq!(size, 3);
+            let version: i32 = jvm.invoke_virtual(&store, "getVersion", "()I", ()).await?;
+            assert_eq!(version, 3);
+
+            let _: () = jvm.invoke_virtual(&store, "deleteRecord", "(I)V", (first,)).await?;
+            let result: jvm::Result<i32> = jvm.invoke_virtual(&store, "getRecordSize", "(I)I", (first,)).await;
+            assert!(result.is_err());
+
+            let filter: ClassInstanceRef<RecordFilter> = None.into();
+            let comparator: ClassInstanceRef<RecordComparator> = None.into();
+            let enumeration: ClassInstanceRef<RecordEnumeration> = jvm
+                .invoke_virtual(
+                    &store,
+                    "enumerateRecords",
+                    "(Ljavax/microedition/rms/RecordFilter;Ljavax/microedition/rms/RecordComparator;Z)Ljavax/microedition/rms/RecordEnumeration;",
+                    (filter, comparator, false),
+                )
+                .await?;
+            let count: i32 = jvm.invoke_virtual(&enumeration, "numRecords", "()I", ()).await?;
+            assert_eq!(count, 1);
+            let id: i32 = jvm.invoke_virtual(&enumeration, "nextRecordId", "()I", ()).await?;
+            assert_eq!(id, second);
+            let has_next: bool = jvm.invoke_virtual(&enumeration, "hasNextElement", "()Z", ()).await?;
+            assert!(!has_next);
+
+            let _: () = jvm.invoke_virtual(&store, "setMode", "(IZ)V", (1, true)).await?;
+            let result: jvm::Result<()> = jvm.invoke_virtual(&store, "setMode", "(IZ)V", (2, true)).await;
+            assert!(result.is_err());
+
+            // ids are not reused after deleting last record
+            let _: () = jvm.invoke_virtual(&store, "deleteRecord", "(I)V", (second,)).await?;
+            let next_id: i32 = jvm.invoke_virtual(&store, "getNextRecordID", "()I", ()).await?;
+            assert_eq!(next_id, 3);
+            let third: i32 = jvm.invoke_virtual(&store, "addRecord", "([BII)I", (data.clone(), 0, 1)).await?;
+            assert_eq!(third, 3);
+
+            let _: () = jvm.invoke_virtual(&store, "closeRecordStore", "()V", ()).await?;
+            let result: jvm::Result<i32> = jvm.invoke_virtual(&store, "getNumRecords", "()I", ()).await;
+            assert!(result.is_err());
+
+            let names: ClassInstanceRef<Array<String>> = jvm
+                .invoke_static("javax/microedition/rms/RecordStore", "listRecordStores", "()[Ljava/lang/String;", ())
+                .await?;
+            assert_eq!(jvm.array_length(&names).await?, 1);
 
-        let store = jvm.new_class("javax/microedition/rms/RecordStore", "()V", ()).await?;
+            let _: () = jvm
+                .invoke_static(
+                    "javax/microedition/rms/RecordStore",
+                    "deleteRecordStore",
+                    "(Ljava/lang/String;)V",
+                    (name.clone(),),
+                )
+                .await?;
+            let names: ClassInstanceRef<Array<String>> = jvm
+                .invoke_static("javax/microedition/rms/RecordStore", "listRecordStores", "()[Ljava/lang/String;", ())
+                .await?;
+            assert!(names.is_null());
 
-        Ok(store.into())
+            Ok(())
+        })
     }
 }
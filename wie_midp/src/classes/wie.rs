@@ -0,0 +1,8 @@
+mod midp_event_queue;
+mod midp_player;
+mod midp_record_enumeration;
+mod midp_volume_control;
+
+pub use {
+    midp_event_queue::MidpEventQueue, midp_player::MidpPlayer, midp_record_enumeration::MidpRecordEnumeration, midp_volume_control::MidpVolumeControl,
+};
@@ -0,0 +1,468 @@
+use alloc::vec;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use java_constants::MethodAccessFlags;
+use java_runtime::classes::java::lang::Runnable;
+use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
+
+use wie_backend::{Event, KeyCode, Rect};
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use crate::classes::javax::microedition::lcdui::{Canvas, Display, Graphics, Image};
+
+const EVENT_REPAINT: i32 = 1;
+const EVENT_KEY_PRESSED: i32 = 2;
+const EVENT_KEY_RELEASED: i32 = 3;
+const EVENT_KEY_REPEATED: i32 = 4;
+
+fn midp_key_code(key_code: KeyCode) -> i32 {
+    match key_code {
+        KeyCode::UP => Canvas::KEY_UP,
+        KeyCode::DOWN => Canvas::KEY_DOWN,
+        KeyCode::LEFT => Canvas::KEY_LEFT,
+        KeyCode::RIGHT => Canvas::KEY_RIGHT,
+        KeyCode::OK => Canvas::KEY_FIRE,
+        KeyCode::NUM0 => Canvas::KEY_NUM0,
+        KeyCode::NUM1 => Canvas::KEY_NUM1,
+        KeyCode::NUM2 => Canvas::KEY_NUM2,
+        KeyCode::NUM3 => Canvas::KEY_NUM3,
+        KeyCode::NUM4 => Canvas::KEY_NUM4,
+        KeyCode::NUM5 => Canvas::KEY_NUM5,
+        KeyCode::NUM6 => Canvas::KEY_NUM6,
+        KeyCode::NUM7 => Canvas::KEY_NUM7,
+        KeyCode::NUM8 => Canvas::KEY_NUM8,
+        KeyCode::NUM9 => Canvas::KEY_NUM9,
+        KeyCode::HASH => Canvas::KEY_POUND,
+        KeyCode::STAR => Canvas::KEY_STAR,
+    }
+}
+
+// class wie.MidpEventQueue, drives current displayable of javax.microedition.lcdui.Display
+pub struct MidpEventQueue;
+
+impl MidpEventQueue {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "wie/MidpEventQueue",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
+                JavaMethodProto::new("getNextEvent", "([I)V", Self::get_next_event, Default::default()),
+                JavaMethodProto::new("dispatchEvent", "([I)V", Self::dispatch_event, Default::default()),
+                JavaMethodProto::new("run", "()V", Self::run, MethodAccessFlags::STATIC),
+            ],
+            fields: vec![
+                JavaFieldProto::new("callSeriallyEvents", "Ljava/util/Vector;", Default::default()),
+                // bitmask of backend key codes being held, to tell repeats from presses
+                JavaFieldProto::new("pressedKeys", "I", Default::default()),
+            ],
+        }
+    }
+
+    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpEventQueue::<init>({:?})", &this);
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        let call_serially_events = jvm.new_class("java/util/Vector", "()V", ()).await?;
+        jvm.put_field(&mut this, "callSeriallyEvents", "Ljava/util/Vector;", call_serially_events)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_next_event(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        mut event: ClassInstanceRef<Array<i32>>,
+    ) -> JvmResult<()> {
+        tracing::debug!("wie.MidpEventQueue::getNextEvent({:?}, {:?})", &this, &event);
+
+        loop {
+            // run before taking input, so that serial calls are not starved while key is held
+            Self::run_call_serially_events(jvm, &mut this).await?;
+
+            let maybe_event = context.system().event_queue().pop();
+
+            if let Some(x) = maybe_event {
+                let pressed_keys: i32 = jvm.get_field(&this, "pressedKeys", "I").await?;
+
+                let event_data = match x {
+                    Event::Redraw => vec![EVENT_REPAINT, 0],
+                    Event::Keydown(x) => {
+                        // backend sends keydown again while key is held
+                        let mask = 1 << x as i32;
+                        jvm.put_field(&mut this, "pressedKeys", "I", pressed_keys | mask).await?;
+
+                        let event_type = if pressed_keys & mask != 0 {
+                            EVENT_KEY_REPEATED
+                        } else {
+                            EVENT_KEY_PRESSED
+                        };
+
+                        vec![event_type, midp_key_code(x)]
+                    }
+                    Event::Keyup(x) => {
+                        let mask = 1 << x as i32;
+                        jvm.put_field(&mut this, "pressedKeys", "I", pressed_keys & !mask).await?;
+
+                        vec![EVENT_KEY_RELEASED, midp_key_code(x)]
+                    }
+                };
+
+                jvm.store_array(&mut event, 0, event_data).await?;
+
+                break;
+            } else {
+                let until = context.system().platform().now() + 16;
+                context.system().sleep(until).await; // TODO we need to wait for events
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn dispatch_event(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        event: ClassInstanceRef<Array<i32>>,
+    ) -> JvmResult<()> {
+        tracing::debug!("wie.MidpEventQueue::dispatchEvent({:?}, {:?})", &this, &event);
+
+        let event = jvm.load_array(&event, 0, 2).await?;
+
+        // only canvas receives events, as we don't implement high level ui
+        let display = Display::display(jvm).await?;
+        let mut canvas: ClassInstanceRef<Canvas> = jvm.get_field(&display, "current", "Ljavax/microedition/lcdui/Displayable;").await?;
+        if canvas.is_null() || !jvm.is_instance(&**canvas, "javax/microedition/lcdui/Canvas").await? {
+            return Ok(());
+        }
+
+        match event[0] {
+            EVENT_REPAINT => {
+                let damage = match Canvas::take_damage(jvm, &mut canvas).await? {
+                    Some(damage) => damage,
+                    None => {
+                        let (width, height) = Canvas::size(jvm, context, &canvas).await?;
+                        Rect::new(0, 0, width, height)
+                    }
+                };
+
+                Self::paint(jvm, context, &canvas, damage).await?;
+            }
+            EVENT_KEY_PRESSED => {
+                let _: () = jvm.invoke_virtual(&canvas, "keyPressed", "(I)V", (event[1],)).await?;
+            }
+            EVENT_KEY_RELEASED => {
+                let _: () = jvm.invoke_virtual(&canvas, "keyReleased", "(I)V", (event[1],)).await?;
+            }
+            EVENT_KEY_REPEATED => {
+                let _: () = jvm.invoke_virtual(&canvas, "keyRepeated", "(I)V", (event[1],)).await?;
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    async fn run(jvm: &Jvm, _context: &mut WieJvmContext) -> JvmResult<()> {
+        tracing::debug!("wie.MidpEventQueue::run()");
+
+        let display = Display::display(jvm).await?;
+        let event_queue: ClassInstanceRef<Self> = jvm.get_field(&display, "eventQueue", "Lwie/MidpEventQueue;").await?;
+
+        let event = jvm.instantiate_array("I", 2).await?;
+        loop {
+            let _: () = jvm.invoke_virtual(&event_queue, "getNextEvent", "([I)V", [event.clone().into()]).await?;
+            let _: () = jvm.invoke_virtual(&event_queue, "dispatchEvent", "([I)V", [event.clone().into()]).await?;
+        }
+    }
+
+    /// Paints `damage` area of `canvas` onto display's screen image, and presents it to the screen.
+    pub async fn paint(jvm: &Jvm, context: &mut WieJvmContext, canvas: &ClassInstanceRef<Canvas>, damage: Rect) -> JvmResult<()> {
+        let display = Display::display(jvm).await?;
+        let screen_image: ClassInstanceRef<Image> = jvm.get_field(&display, "screenImage", "Ljavax/microedition/lcdui/Image;").await?;
+
+        let graphics: ClassInstanceRef<Graphics> = jvm
+            .new_class(
+                "javax/microedition/lcdui/Graphics",
+                "(Ljavax/microedition/lcdui/Image;)V",
+                (screen_image.clone(),),
+            )
+            .await?
+            .into();
+        let _: () = jvm
+            .invoke_virtual(
+                &graphics,
+                "setClip",
+                "(IIII)V",
+                (damage.x as i32, damage.y as i32, damage.width as i32, damage.height as i32),
+            )
+            .await?;
+
+        let _: () = jvm
+            .invoke_virtual(canvas, "paint", "(Ljavax/microedition/lcdui/Graphics;)V", (graphics,))
+            .await?;
+
+        Self::present(jvm, context, &screen_image, damage).await
+    }
+
+    /// Fills `area` of display's screen image with black, and presents it to the screen.
+    pub async fn clear(jvm: &Jvm, context: &mut WieJvmContext, area: Rect) -> JvmResult<()> {
+        let display = Display::display(jvm).await?;
+        let screen_image: ClassInstanceRef<Image> = jvm.get_field(&display, "screenImage", "Ljavax/microedition/lcdui/Image;").await?;
+
+        let graphics: ClassInstanceRef<Graphics> = jvm
+            .new_class(
+                "javax/microedition/lcdui/Graphics",
+                "(Ljavax/microedition/lcdui/Image;)V",
+                (screen_image.clone(),),
+            )
+            .await?
+            .into();
+        let _: () = jvm.invoke_virtual(&graphics, "setColor", "(I)V", (0,)).await?;
+        let _: () = jvm
+            .invoke_virtual(
+                &graphics,
+                "fillRect",
+                "(IIII)V",
+                (area.x as i32, area.y as i32, area.width as i32, area.height as i32),
+            )
+            .await?;
+
+        Self::present(jvm, context, &screen_image, area).await
+    }
+
+    async fn present(jvm: &Jvm, context: &mut WieJvmContext, screen_image: &ClassInstanceRef<Image>, area: Rect) -> JvmResult<()> {
+        let image = Image::image(jvm, screen_image).await?;
+
+        let mut platform = context.system().platform();
+        let screen = platform.screen();
+        screen.paint_region(&*image, area);
+
+        Ok(())
+    }
+
+    // runs serial calls queued so far, calls queued while running are left for next round
+    async fn run_call_serially_events(jvm: &Jvm, this: &mut ClassInstanceRef<Self>) -> JvmResult<()> {
+        let call_serially_events = jvm.get_field(this, "callSeriallyEvents", "Ljava/util/Vector;").await?;
+        if jvm.invoke_virtual(&call_serially_events, "isEmpty", "()Z", ()).await? {
+            return Ok(());
+        }
+
+        let new_call_serially_events = jvm.new_class("java/util/Vector", "()V", ()).await?;
+        jvm.put_field(this, "callSeriallyEvents", "Ljava/util/Vector;", new_call_serially_events)
+            .await?;
+
+        while !jvm.invoke_virtual(&call_serially_events, "isEmpty", "()Z", ()).await? {
+            let event: ClassInstanceRef<Runnable> = jvm.invoke_virtual(&call_serially_events, "remove", "(I)Ljava/lang/Object;", (0,)).await?;
+            let _: () = jvm.invoke_virtual(&event, "run", "()V", ()).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn enqueue_call_serially_event(jvm: &Jvm, this: &ClassInstanceRef<Self>, event: ClassInstanceRef<Runnable>) -> JvmResult<()> {
+        let call_serially_events = jvm.get_field(this, "callSeriallyEvents", "Ljava/util/Vector;").await?;
+        jvm.invoke_virtual(&call_serially_events, "addElement", "(Ljava/lang/Object;)V", [event.into()])
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec};
+
+    use java_class_proto::{JavaFieldProto, JavaMethodProto};
+    use java_constants::MethodAccessFlags;
+    use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_backend::{Event, KeyCode};
+    use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+    use wie_util::Result;
+
+    use crate::{
+        classes::{
+            javax::microedition::lcdui::{Canvas, Display, Graphics},
+            wie::MidpEventQueue,
+        },
+        get_protos,
+    };
+
+    use super::{EVENT_KEY_PRESSED, EVENT_KEY_RELEASED, EVENT_KEY_REPEATED, EVENT_REPAINT};
+
+    // canvas recording calls made by event queue
+    struct TestCanvas;
+
+    impl TestCanvas {
+        fn as_proto() -> WieJavaClassProto {
+            WieJavaClassProto {
+                name: "wie/TestCanvas",
+                parent_class: Some("javax/microedition/lcdui/Canvas"),
+                interfaces: vec![],
+                methods: vec![
+                    JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
+                    JavaMethodProto::new("paint", "(Ljavax/microedition/lcdui/Graphics;)V", Self::paint, Default::default()),
+                    JavaMethodProto::new("keyPressed", "(I)V", Self::key_pressed, Default::default()),
+                    JavaMethodProto::new("keyReleased", "(I)V", Self::key_released, Default::default()),
+                    JavaMethodProto::new("keyRepeated", "(I)V", Self::key_repeated, Default::default()),
+                    JavaMethodProto::new("sizeChanged", "(II)V", Self::size_changed, Default::default()),
+                    JavaMethodProto::new("pushEvent", "(I)V", Self::push_event, MethodAccessFlags::STATIC),
+                ],
+                fields: vec![
+                    // clip height of last paint
+                    JavaFieldProto::new("paintHeight", "I", Default::default()),
+                    JavaFieldProto::new("pressedKey", "I", Default::default()),
+                    JavaFieldProto::new("releasedKey", "I", Default::default()),
+                    JavaFieldProto::new("repeatCount", "I", Default::default()),
+                    JavaFieldProto::new("changedHeight", "I", Default::default()),
+                ],
+            }
+        }
+
+        async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+            jvm.invoke_special(&this, "javax/microedition/lcdui/Canvas", "<init>", "()V", ()).await
+        }
+
+        async fn paint(
+            jvm: &Jvm,
+            _context: &mut WieJvmContext,
+            mut this: ClassInstanceRef<Self>,
+            graphics: ClassInstanceRef<Graphics>,
+        ) -> JvmResult<()> {
+            let height: i32 = jvm.invoke_virtual(&graphics, "getClipHeight", "()I", ()).await?;
+
+            jvm.put_field(&mut this, "paintHeight", "I", height).await
+        }
+
+        async fn key_pressed(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, key_code: i32) -> JvmResult<()> {
+            jvm.put_field(&mut this, "pressedKey", "I", key_code).await
+        }
+
+        async fn key_released(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, key_code: i32) -> JvmResult<()> {
+            jvm.put_field(&mut this, "releasedKey", "I", key_code).await
+        }
+
+        async fn key_repeated(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, _key_code: i32) -> JvmResult<()> {
+            let count: i32 = jvm.get_field(&this, "repeatCount", "I").await?;
+
+            jvm.put_field(&mut this, "repeatCount", "I", count + 1).await
+        }
+
+        async fn size_changed(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, _width: i32, height: i32) -> JvmResult<()> {
+            jvm.put_field(&mut this, "changedHeight", "I", height).await
+        }
+
+        // 0: redraw, 1: up key down, 2: up key up
+        async fn push_event(_jvm: &Jvm, context: &mut WieJvmContext, event: i32) -> JvmResult<()> {
+            let event = match event {
+                0 => Event::Redraw,
+                1 => Event::Keydown(KeyCode::UP),
+                _ => Event::Keyup(KeyCode::UP),
+            };
+            context.system().event_queue().push(event);
+
+            Ok(())
+        }
+    }
+
+    struct TestRunnable;
+
+    impl TestRunnable {
+        fn as_proto() -> WieJavaClassProto {
+            WieJavaClassProto {
+                name: "wie/TestRunnable",
+                parent_class: Some("java/lang/Object"),
+                interfaces: vec!["java/lang/Runnable"],
+                methods: vec![
+                    JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
+                    JavaMethodProto::new("run", "()V", Self::run, Default::default()),
+                ],
+                fields: vec![JavaFieldProto::new("runCount", "I", Default::default())],
+            }
+        }
+
+        async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+            jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await
+        }
+
+        async fn run(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+            let count: i32 = jvm.get_field(&this, "runCount", "I").await?;
+
+            jvm.put_field(&mut this, "runCount", "I", count + 1).await
+        }
+    }
+
+    async fn next_event(jvm: &Jvm, event_queue: &ClassInstanceRef<MidpEventQueue>) -> JvmResult<[i32; 2]> {
+        let event = jvm.instantiate_array("I", 2).await?;
+        let _: () = jvm.invoke_virtual(event_queue, "getNextEvent", "([I)V", [event.clone().into()]).await?;
+        let _: () = jvm.invoke_virtual(event_queue, "dispatchEvent", "([I)V", [event.clone().into()]).await?;
+
+        let event = jvm.load_array(&event, 0, 2).await?;
+
+        Ok([event[0], event[1]])
+    }
+
+    #[test]
+    fn test_event_queue() -> Result<()> {
+        let protos = Box::new([get_protos().into(), [TestCanvas::as_proto(), TestRunnable::as_proto()].into()]);
+
+        run_jvm_test(TestPlatform::new(), protos, |jvm| async move {
+            let display = Display::display(&jvm).await?;
+            let event_queue: ClassInstanceRef<MidpEventQueue> = jvm.get_field(&display, "eventQueue", "Lwie/MidpEventQueue;").await?;
+
+            let canvas: ClassInstanceRef<Canvas> = jvm.new_class("wie/TestCanvas", "()V", ()).await?.into();
+            let _: () = jvm
+                .invoke_virtual(&display, "setCurrent", "(Ljavax/microedition/lcdui/Displayable;)V", (canvas.clone(),))
+                .await?;
+
+            let height: i32 = jvm.invoke_virtual(&canvas, "getHeight", "()I", ()).await?;
+            assert_eq!(height, 320 - Canvas::SOFT_KEY_BAR_HEIGHT as i32);
+
+            // repaint requested by setCurrent
+            let _: () = jvm.invoke_static("wie/TestCanvas", "pushEvent", "(I)V", (0,)).await?;
+            assert_eq!(next_event(&jvm, &event_queue).await?[0], EVENT_REPAINT);
+            let paint_height: i32 = jvm.get_field(&canvas, "paintHeight", "I").await?;
+            assert_eq!(paint_height, height);
+
+            // serial call runs even if input events are always pending
+            let runnable: ClassInstanceRef<TestRunnable> = jvm.new_class("wie/TestRunnable", "()V", ()).await?.into();
+            let _: () = jvm
+                .invoke_virtual(&display, "callSerially", "(Ljava/lang/Runnable;)V", (runnable.clone(),))
+                .await?;
+            let _: () = jvm.invoke_static("wie/TestCanvas", "pushEvent", "(I)V", (1,)).await?;
+            let _: () = jvm.invoke_static("wie/TestCanvas", "pushEvent", "(I)V", (1,)).await?;
+            let _: () = jvm.invoke_static("wie/TestCanvas", "pushEvent", "(I)V", (2,)).await?;
+
+            assert_eq!(next_event(&jvm, &event_queue).await?, [EVENT_KEY_PRESSED, Canvas::KEY_UP]);
+            let run_count: i32 = jvm.get_field(&runnable, "runCount", "I").await?;
+            assert_eq!(run_count, 1);
+            let pressed_key: i32 = jvm.get_field(&canvas, "pressedKey", "I").await?;
+            assert_eq!(pressed_key, Canvas::KEY_UP);
+
+            assert_eq!(next_event(&jvm, &event_queue).await?, [EVENT_KEY_REPEATED, Canvas::KEY_UP]);
+            let repeat_count: i32 = jvm.get_field(&canvas, "repeatCount", "I").await?;
+            assert_eq!(repeat_count, 1);
+
+            assert_eq!(next_event(&jvm, &event_queue).await?, [EVENT_KEY_RELEASED, Canvas::KEY_UP]);
+            let released_key: i32 = jvm.get_field(&canvas, "releasedKey", "I").await?;
+            assert_eq!(released_key, Canvas::KEY_UP);
+
+            // full screen canvas covers soft key bar
+            let _: () = jvm.invoke_virtual(&canvas, "setFullScreenMode", "(Z)V", (true,)).await?;
+            let changed_height: i32 = jvm.get_field(&canvas, "changedHeight", "I").await?;
+            assert_eq!(changed_height, 320);
+
+            let _: () = jvm.invoke_static("wie/TestCanvas", "pushEvent", "(I)V", (0,)).await?;
+            assert_eq!(next_event(&jvm, &event_queue).await?[0], EVENT_REPAINT);
+            let paint_height: i32 = jvm.get_field(&canvas, "paintHeight", "I").await?;
+            assert_eq!(paint_height, 320);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,579 @@
+use alloc::{boxed::Box, vec};
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto, MethodBody};
+use java_runtime::classes::java::lang::{Object, String};
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, JavaError, JavaValue, Jvm, Result as JvmResult};
+
+use wie_backend::{AudioHandle, PlaybackEvent, PlaybackState};
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use crate::classes::javax::microedition::media::{Control, Player, PlayerListener};
+
+// events passed to PlayerListener.playerUpdate
+const EVENT_STARTED: &str = "started";
+const EVENT_STOPPED: &str = "stopped";
+const EVENT_END_OF_MEDIA: &str = "endOfMedia";
+const EVENT_CLOSED: &str = "closed";
+
+// class wie.MidpPlayer, implementation of javax.microedition.media.Player returned by Manager
+pub struct MidpPlayer;
+
+impl MidpPlayer {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "wie/MidpPlayer",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec!["javax/microedition/media/Player"],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(ILjava/lang/String;)V", Self::init, Default::default()),
+                JavaMethodProto::new("realize", "()V", Self::realize, Default::default()),
+                JavaMethodProto::new("prefetch", "()V", Self::prefetch, Default::default()),
+                JavaMethodProto::new("start", "()V", Self::start, Default::default()),
+                JavaMethodProto::new("stop", "()V", Self::stop, Default::default()),
+                JavaMethodProto::new("deallocate", "()V", Self::deallocate, Default::default()),
+                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
+                JavaMethodProto::new("getState", "()I", Self::get_state, Default::default()),
+                JavaMethodProto::new("getDuration", "()J", Self::get_duration, Default::default()),
+                JavaMethodProto::new("getMediaTime", "()J", Self::get_media_time, Default::default()),
+                JavaMethodProto::new("setMediaTime", "(J)J", Self::set_media_time, Default::default()),
+                JavaMethodProto::new("setLoopCount", "(I)V", Self::set_loop_count, Default::default()),
+                JavaMethodProto::new("getContentType", "()Ljava/lang/String;", Self::get_content_type, Default::default()),
+                JavaMethodProto::new(
+                    "addPlayerListener",
+                    "(Ljavax/microedition/media/PlayerListener;)V",
+                    Self::add_player_listener,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "removePlayerListener",
+                    "(Ljavax/microedition/media/PlayerListener;)V",
+                    Self::remove_player_listener,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "getControl",
+                    "(Ljava/lang/String;)Ljavax/microedition/media/Control;",
+                    Self::get_control,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "getControls",
+                    "()[Ljavax/microedition/media/Control;",
+                    Self::get_controls,
+                    Default::default(),
+                ),
+            ],
+            fields: vec![
+                JavaFieldProto::new("handle", "I", Default::default()),
+                JavaFieldProto::new("contentType", "Ljava/lang/String;", Default::default()),
+                JavaFieldProto::new("state", "I", Default::default()),
+                JavaFieldProto::new("loopCount", "I", Default::default()),
+                // TODO support multiple listeners
+                JavaFieldProto::new("listener", "Ljavax/microedition/media/PlayerListener;", Default::default()),
+                // created on first getControl
+                JavaFieldProto::new("volumeControl", "Ljavax/microedition/media/control/VolumeControl;", Default::default()),
+            ],
+        }
+    }
+
+    async fn init(
+        jvm: &Jvm,
+        _: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        handle: i32,
+        content_type: ClassInstanceRef<String>,
+    ) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::<init>({:?}, {}, {:?})", &this, handle, &content_type);
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "handle", "I", handle).await?;
+        jvm.put_field(&mut this, "contentType", "Ljava/lang/String;", content_type).await?;
+        jvm.put_field(&mut this, "state", "I", Player::UNREALIZED).await?;
+        jvm.put_field(&mut this, "loopCount", "I", 1).await?;
+
+        Ok(())
+    }
+
+    async fn realize(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::realize({:?})", &this);
+
+        Self::check_closed(jvm, &this).await?;
+
+        let state: i32 = jvm.get_field(&this, "state", "I").await?;
+        if state < Player::REALIZED {
+            jvm.put_field(&mut this, "state", "I", Player::REALIZED).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn prefetch(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::prefetch({:?})", &this);
+
+        Self::check_closed(jvm, &this).await?;
+
+        // data is already loaded in Manager.createPlayer
+        let state: i32 = jvm.get_field(&this, "state", "I").await?;
+        if state < Player::PREFETCHED {
+            jvm.put_field(&mut this, "state", "I", Player::PREFETCHED).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn start(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::start({:?})", &this);
+
+        Self::check_closed(jvm, &this).await?;
+
+        let state: i32 = jvm.get_field(&this, "state", "I").await?;
+        if state == Player::STARTED {
+            return Ok(());
+        }
+
+        let handle = Self::handle(jvm, &this).await?;
+
+        // stopped player resumes from where it was stopped
+        let paused = matches!(context.system().audio().state(handle), Ok(PlaybackState::Paused));
+        let result = if paused {
+            context.system().audio().resume(handle)
+        } else {
+            let loop_count: i32 = jvm.get_field(&this, "loopCount", "I").await?;
+            let loop_count = if loop_count < 0 { None } else { Some(loop_count as u32) };
+
+            context.system().audio().play(handle, loop_count)
+        };
+        if let Err(x) = result {
+            tracing::error!("Failed to play audio: {:?}", x);
+            return Err(jvm.exception("javax/microedition/media/MediaException", "Failed to start playback").await);
+        }
+
+        if !paused {
+            context.spawn(
+                jvm,
+                Box::new(CompletionProxy {
+                    player: this.clone(),
+                    handle,
+                }),
+            )?;
+        }
+
+        jvm.put_field(&mut this, "state", "I", Player::STARTED).await?;
+        Self::notify(jvm, &this, EVENT_STARTED).await?;
+
+        Ok(())
+    }
+
+    async fn stop(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::stop({:?})", &this);
+
+        Self::check_closed(jvm, &this).await?;
+
+        let state: i32 = jvm.get_field(&this, "state", "I").await?;
+        if state != Player::STARTED {
+            return Ok(());
+        }
+
+        let handle = Self::handle(jvm, &this).await?;
+        let _ = context.system().audio().pause(handle);
+
+        jvm.put_field(&mut this, "state", "I", Player::PREFETCHED).await?;
+        Self::notify(jvm, &this, EVENT_STOPPED).await?;
+
+        Ok(())
+    }
+
+    async fn deallocate(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::deallocate({:?})", &this);
+
+        Self::check_closed(jvm, &this).await?;
+
+        let state: i32 = jvm.get_field(&this, "state", "I").await?;
+        if state == Player::STARTED {
+            let _: () = jvm.invoke_virtual(&this, "stop", "()V", ()).await?;
+        }
+
+        // deallocated player starts from the beginning
+        let handle = Self::handle(jvm, &this).await?;
+        let _ = context.system().audio().stop(handle);
+
+        if state >= Player::REALIZED {
+            jvm.put_field(&mut this, "state", "I", Player::REALIZED).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn close(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::close({:?})", &this);
+
+        let state: i32 = jvm.get_field(&this, "state", "I").await?;
+        if state == Player::CLOSED {
+            return Ok(());
+        }
+
+        let handle = Self::handle(jvm, &this).await?;
+        let _ = context.system().audio().stop(handle);
+
+        jvm.put_field(&mut this, "state", "I", Player::CLOSED).await?;
+        Self::notify(jvm, &this, EVENT_CLOSED).await?;
+
+        Ok(())
+    }
+
+    async fn get_state(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("wie.MidpPlayer::getState({:?})", &this);
+
+        jvm.get_field(&this, "state", "I").await
+    }
+
+    async fn get_duration(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i64> {
+        tracing::debug!("wie.MidpPlayer::getDuration({:?})", &this);
+
+        Self::check_closed(jvm, &this).await?;
+
+        let handle = Self::handle(jvm, &this).await?;
+        let duration = context.system().audio().duration(handle);
+
+        // in microseconds
+        Ok(match duration {
+            Ok(Some(x)) => x as i64 * 1000,
+            _ => Player::TIME_UNKNOWN,
+        })
+    }
+
+    async fn get_media_time(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i64> {
+        tracing::debug!("wie.MidpPlayer::getMediaTime({:?})", &this);
+
+        let handle = Self::handle(jvm, &this).await?;
+        let position = context.system().audio().position(handle).unwrap_or(0);
+
+        // in microseconds
+        Ok(position as i64 * 1000)
+    }
+
+    async fn set_media_time(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, time: i64) -> JvmResult<i64> {
+        tracing::debug!("wie.MidpPlayer::setMediaTime({:?}, {})", &this, time);
+
+        Self::check_realized(jvm, &this).await?;
+
+        // negative time is treated as 0, and time past the end is clamped by backend
+        let handle = Self::handle(jvm, &this).await?;
+        // completion proxy spawned on start keeps watching the same playback across seek
+        let position = match context.system().audio().seek(handle, time.max(0) as u64 / 1000) {
+            Ok(x) => x,
+            Err(x) => {
+                tracing::error!("Failed to seek audio: {:?}", x);
+                return Err(jvm.exception("javax/microedition/media/MediaException", "Failed to set media time").await);
+            }
+        };
+
+        Ok(position as i64 * 1000)
+    }
+
+    async fn set_loop_count(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, count: i32) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::setLoopCount({:?}, {})", &this, count);
+
+        if count == 0 {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "count is 0").await);
+        }
+
+        // -1 is infinite
+        jvm.put_field(&mut this, "loopCount", "I", count).await?;
+
+        Ok(())
+    }
+
+    async fn get_content_type(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("wie.MidpPlayer::getContentType({:?})", &this);
+
+        jvm.get_field(&this, "contentType", "Ljava/lang/String;").await
+    }
+
+    async fn add_player_listener(
+        jvm: &Jvm,
+        _: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        listener: ClassInstanceRef<PlayerListener>,
+    ) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::addPlayerListener({:?}, {:?})", &this, &listener);
+
+        jvm.put_field(&mut this, "listener", "Ljavax/microedition/media/PlayerListener;", listener)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_player_listener(
+        jvm: &Jvm,
+        _: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        listener: ClassInstanceRef<PlayerListener>,
+    ) -> JvmResult<()> {
+        tracing::debug!("wie.MidpPlayer::removePlayerListener({:?}, {:?})", &this, &listener);
+
+        let current: ClassInstanceRef<PlayerListener> = jvm.get_field(&this, "listener", "Ljavax/microedition/media/PlayerListener;").await?;
+        if !current.is_null() && !listener.is_null() && jvm.invoke_virtual(&current, "equals", "(Ljava/lang/Object;)Z", (listener,)).await? {
+            let null: ClassInstanceRef<PlayerListener> = None.into();
+            jvm.put_field(&mut this, "listener", "Ljavax/microedition/media/PlayerListener;", null)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_control(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        r#type: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<Control>> {
+        tracing::debug!("wie.MidpPlayer::getControl({:?}, {:?})", &this, &r#type);
+
+        Self::check_realized(jvm, &this).await?;
+
+        if r#type.is_null() {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "type is null").await);
+        }
+
+        // type may omit package name for controls in javax.microedition.media.control
+        let r#type = JavaLangString::to_rust_string(jvm, &r#type).await?;
+        match r#type.as_str() {
+            "VolumeControl" | "javax.microedition.media.control.VolumeControl" => Self::volume_control(jvm, context, this).await,
+            _ => Ok(None.into()),
+        }
+    }
+
+    async fn get_controls(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Array<Control>>> {
+        tracing::debug!("wie.MidpPlayer::getControls({:?})", &this);
+
+        Self::check_realized(jvm, &this).await?;
+
+        let volume_control = Self::volume_control(jvm, context, this).await?;
+
+        let mut result = jvm.instantiate_array("Ljavax/microedition/media/Control;", 1).await?;
+        jvm.store_array(&mut result, 0, vec![volume_control]).await?;
+
+        Ok(result.into())
+    }
+
+    async fn volume_control(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Control>> {
+        let volume_control: ClassInstanceRef<Control> = jvm
+            .get_field(&this, "volumeControl", "Ljavax/microedition/media/control/VolumeControl;")
+            .await?;
+        if !volume_control.is_null() {
+            return Ok(volume_control);
+        }
+
+        let handle = Self::handle(jvm, &this).await?;
+        let level = context.system().audio().volume(handle).unwrap_or(100);
+
+        let volume_control: ClassInstanceRef<Control> = jvm
+            .new_class("wie/MidpVolumeControl", "(Lwie/MidpPlayer;I)V", (this.clone(), level as i32))
+            .await?
+            .into();
+        jvm.put_field(
+            &mut this,
+            "volumeControl",
+            "Ljavax/microedition/media/control/VolumeControl;",
+            volume_control.clone(),
+        )
+        .await?;
+
+        Ok(volume_control)
+    }
+
+    pub(crate) async fn handle(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<AudioHandle> {
+        let handle: i32 = jvm.get_field(this, "handle", "I").await?;
+
+        Ok(handle as _)
+    }
+
+    async fn check_closed(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<()> {
+        let state: i32 = jvm.get_field(this, "state", "I").await?;
+        if state == Player::CLOSED {
+            return Err(jvm.exception("java/lang/IllegalStateException", "Player is closed").await);
+        }
+
+        Ok(())
+    }
+
+    async fn check_realized(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<()> {
+        Self::check_closed(jvm, this).await?;
+
+        let state: i32 = jvm.get_field(this, "state", "I").await?;
+        if state == Player::UNREALIZED {
+            return Err(jvm.exception("java/lang/IllegalStateException", "Player is not realized").await);
+        }
+
+        Ok(())
+    }
+
+    async fn notify(jvm: &Jvm, this: &ClassInstanceRef<Self>, event: &str) -> JvmResult<()> {
+        let data: ClassInstanceRef<Object> = None.into();
+
+        Self::notify_with_data(jvm, this, event, data).await
+    }
+
+    pub(crate) async fn notify_with_data<T>(jvm: &Jvm, this: &ClassInstanceRef<Self>, event: &str, data: ClassInstanceRef<T>) -> JvmResult<()> {
+        let listener: ClassInstanceRef<PlayerListener> = jvm.get_field(this, "listener", "Ljavax/microedition/media/PlayerListener;").await?;
+        if listener.is_null() {
+            return Ok(());
+        }
+
+        let event = JavaLangString::from_rust_string(jvm, event).await?;
+        jvm.invoke_virtual(
+            &listener,
+            "playerUpdate",
+            "(Ljavax/microedition/media/Player;Ljava/lang/String;Ljava/lang/Object;)V",
+            (this.clone(), event, data),
+        )
+        .await
+    }
+}
+
+// sends endOfMedia event and rewinds player when playback finishes
+struct CompletionProxy {
+    player: ClassInstanceRef<MidpPlayer>,
+    handle: AudioHandle,
+}
+
+#[async_trait::async_trait]
+impl MethodBody<JavaError, WieJvmContext> for CompletionProxy {
+    #[tracing::instrument(name = "player_listener", skip_all)]
+    async fn call(&self, jvm: &Jvm, context: &mut WieJvmContext, _: Box<[JavaValue]>) -> Result<JavaValue, JavaError> {
+        let completion = context.system().audio().completion(self.handle).unwrap();
+        if completion.wait(context.system()).await != PlaybackEvent::Finished {
+            // stopped by close or deallocate, which don't need further events
+            return Ok(JavaValue::Void);
+        }
+
+        jvm.attach_thread().await?;
+
+        let mut player = self.player.clone();
+        jvm.put_field(&mut player, "state", "I", Player::PREFETCHED).await?;
+        MidpPlayer::notify(jvm, &player, EVENT_END_OF_MEDIA).await?;
+
+        jvm.detach_thread().await?;
+
+        Ok(JavaValue::Void)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec};
+
+    use jvm::{runtime::JavaLangString, Array, ClassInstanceRef};
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::{
+        classes::javax::microedition::media::{Control, Player},
+        get_protos,
+    };
+
+    #[test]
+    fn test_player() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            // tone sequence playing C4 for 2 seconds
+            let mut data = jvm.instantiate_array("B", 4).await?;
+            jvm.store_byte_array(&mut data, 0, vec![-2, 1, 60, 64]).await?;
+            let stream = jvm.new_class("java/io/ByteArrayInputStream", "([B)V", (data,)).await?;
+            let r#type = JavaLangString::from_rust_string(&jvm, "audio/x-tone-seq").await?;
+
+            let player: ClassInstanceRef<Player> = jvm
+                .invoke_static(
+                    "javax/microedition/media/Manager",
+                    "createPlayer",
+                    "(Ljava/io/InputStream;Ljava/lang/String;)Ljavax/microedition/media/Player;",
+                    (stream, r#type),
+                )
+                .await?;
+
+            let volume_control_type = JavaLangString::from_rust_string(&jvm, "VolumeControl").await?;
+            let result: jvm::Result<ClassInstanceRef<Control>> = jvm
+                .invoke_virtual(
+                    &player,
+                    "getControl",
+                    "(Ljava/lang/String;)Ljavax/microedition/media/Control;",
+                    (volume_control_type.clone(),),
+                )
+                .await;
+            assert!(result.is_err());
+
+            let _: () = jvm.invoke_virtual(&player, "realize", "()V", ()).await?;
+
+            let duration: i64 = jvm.invoke_virtual(&player, "getDuration", "()J", ()).await?;
+            assert_eq!(duration, 2_000_000);
+
+            let time: i64 = jvm.invoke_virtual(&player, "setMediaTime", "(J)J", (500_000i64,)).await?;
+            assert_eq!(time, 500_000);
+            let time: i64 = jvm.invoke_virtual(&player, "getMediaTime", "()J", ()).await?;
+            assert_eq!(time, 500_000);
+
+            let time: i64 = jvm.invoke_virtual(&player, "setMediaTime", "(J)J", (-1i64,)).await?;
+            assert_eq!(time, 0);
+            let time: i64 = jvm.invoke_virtual(&player, "setMediaTime", "(J)J", (10_000_000i64,)).await?;
+            assert_eq!(time, 2_000_000);
+
+            let control: ClassInstanceRef<Control> = jvm
+                .invoke_virtual(
+                    &player,
+                    "getControl",
+                    "(Ljava/lang/String;)Ljavax/microedition/media/Control;",
+                    (volume_control_type,),
+                )
+                .await?;
+            assert!(!control.is_null());
+
+            let level: i32 = jvm.invoke_virtual(&control, "setLevel", "(I)I", (150,)).await?;
+            assert_eq!(level, 100);
+            let level: i32 = jvm.invoke_virtual(&control, "setLevel", "(I)I", (50,)).await?;
+            assert_eq!(level, 50);
+
+            let _: () = jvm.invoke_virtual(&control, "setMute", "(Z)V", (true,)).await?;
+            let muted: bool = jvm.invoke_virtual(&control, "isMuted", "()Z", ()).await?;
+            assert!(muted);
+            let level: i32 = jvm.invoke_virtual(&control, "getLevel", "()I", ()).await?;
+            assert_eq!(level, 50);
+
+            // same control is returned for fully qualified name
+            let qualified_type = JavaLangString::from_rust_string(&jvm, "javax.microedition.media.control.VolumeControl").await?;
+            let qualified: ClassInstanceRef<Control> = jvm
+                .invoke_virtual(
+                    &player,
+                    "getControl",
+                    "(Ljava/lang/String;)Ljavax/microedition/media/Control;",
+                    (qualified_type,),
+                )
+                .await?;
+            let level: i32 = jvm.invoke_virtual(&qualified, "getLevel", "()I", ()).await?;
+            assert_eq!(level, 50);
+
+            let tone_control_type = JavaLangString::from_rust_string(&jvm, "ToneControl").await?;
+            let tone_control: ClassInstanceRef<Control> = jvm
+                .invoke_virtual(
+                    &player,
+                    "getControl",
+                    "(Ljava/lang/String;)Ljavax/microedition/media/Control;",
+                    (tone_control_type,),
+                )
+                .await?;
+            assert!(tone_control.is_null());
+
+            let controls: ClassInstanceRef<Array<Control>> = jvm
+                .invoke_virtual(&player, "getControls", "()[Ljavax/microedition/media/Control;", ())
+                .await?;
+            assert_eq!(jvm.array_length(&controls).await?, 1);
+
+            let _: () = jvm.invoke_virtual(&player, "close", "()V", ()).await?;
+            let result: jvm::Result<i64> = jvm.invoke_virtual(&player, "getDuration", "()J", ()).await;
+            assert!(result.is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,271 @@
+use alloc::{vec, vec::Vec};
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
+
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use crate::classes::javax::microedition::rms::{RecordComparator, RecordFilter, RecordStore};
+
+// position before first element, set on reset
+const POSITION_RESET: i32 = -1;
+
+// class wie.MidpRecordEnumeration, implementation of javax.microedition.rms.RecordEnumeration returned by RecordStore
+pub struct MidpRecordEnumeration;
+
+impl MidpRecordEnumeration {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "wie/MidpRecordEnumeration",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec!["javax/microedition/rms/RecordEnumeration"],
+            methods: vec![
+                JavaMethodProto::new(
+                    "<init>",
+                    "(Ljavax/microedition/rms/RecordStore;Ljavax/microedition/rms/RecordFilter;Ljavax/microedition/rms/RecordComparator;Z)V",
+                    Self::init,
+                    Default::default(),
+                ),
+                JavaMethodProto::new("numRecords", "()I", Self::num_records, Default::default()),
+                JavaMethodProto::new("nextRecord", "()[B", Self::next_record, Default::default()),
+                JavaMethodProto::new("nextRecordId", "()I", Self::next_record_id, Default::default()),
+                JavaMethodProto::new("previousRecord", "()[B", Self::previous_record, Default::default()),
+                JavaMethodProto::new("previousRecordId", "()I", Self::previous_record_id, Default::default()),
+                JavaMethodProto::new("hasNextElement", "()Z", Self::has_next_element, Default::default()),
+                JavaMethodProto::new("hasPreviousElement", "()Z", Self::has_previous_element, Default::default()),
+                JavaMethodProto::new("reset", "()V", Self::reset, Default::default()),
+                JavaMethodProto::new("rebuild", "()V", Self::rebuild, Default::default()),
+                JavaMethodProto::new("keepUpdated", "(Z)V", Self::keep_updated, Default::default()),
+                JavaMethodProto::new("isKeptUpdated", "()Z", Self::is_kept_updated, Default::default()),
+                JavaMethodProto::new("destroy", "()V", Self::destroy, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("store", "Ljavax/microedition/rms/RecordStore;", Default::default()),
+                JavaFieldProto::new("filter", "Ljavax/microedition/rms/RecordFilter;", Default::default()),
+                JavaFieldProto::new("comparator", "Ljavax/microedition/rms/RecordComparator;", Default::default()),
+                JavaFieldProto::new("keepUpdated", "Z", Default::default()),
+                JavaFieldProto::new("ids", "[I", Default::default()),
+                JavaFieldProto::new("position", "I", Default::default()),
+                JavaFieldProto::new("destroyed", "Z", Default::default()),
+            ],
+        }
+    }
+
+    async fn init(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        store: ClassInstanceRef<RecordStore>,
+        filter: ClassInstanceRef<RecordFilter>,
+        comparator: ClassInstanceRef<RecordComparator>,
+        keep_updated: bool,
+    ) -> JvmResult<()> {
+        tracing::debug!(
+            "wie.MidpRecordEnumeration::<init>({:?}, {:?}, {:?}, {:?}, {})",
+            &this,
+            &store,
+            &filter,
+            &comparator,
+            keep_updated
+        );
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "store", "Ljavax/microedition/rms/RecordStore;", store).await?;
+        jvm.put_field(&mut this, "filter", "Ljavax/microedition/rms/RecordFilter;", filter)
+            .await?;
+        jvm.put_field(&mut this, "comparator", "Ljavax/microedition/rms/RecordComparator;", comparator)
+            .await?;
+        jvm.put_field(&mut this, "keepUpdated", "Z", keep_updated).await?;
+
+        let _: () = jvm.invoke_virtual(&this, "rebuild", "()V", ()).await?;
+
+        Ok(())
+    }
+
+    async fn num_records(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("wie.MidpRecordEnumeration::numRecords({:?})", &this);
+
+        let ids = Self::ids(jvm, context, &this).await?;
+
+        Ok(ids.len() as _)
+    }
+
+    async fn next_record(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Array<i8>>> {
+        tracing::debug!("wie.MidpRecordEnumeration::nextRecord({:?})", &this);
+
+        let id = Self::next_record_id(jvm, context, this.clone()).await?;
+        let store = jvm.get_field(&this, "store", "Ljavax/microedition/rms/RecordStore;").await?;
+
+        jvm.invoke_virtual(&store, "getRecord", "(I)[B", (id,)).await
+    }
+
+    async fn next_record_id(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("wie.MidpRecordEnumeration::nextRecordId({:?})", &this);
+
+        let ids = Self::ids(jvm, context, &this).await?;
+        let position: i32 = jvm.get_field(&this, "position", "I").await?;
+
+        let next = if position == POSITION_RESET { 0 } else { position + 1 };
+        if next as usize >= ids.len() {
+            return Err(jvm.exception("javax/microedition/rms/InvalidRecordIDException", "").await);
+        }
+
+        jvm.put_field(&mut this, "position", "I", next).await?;
+
+        Ok(ids[next as usize])
+    }
+
+    async fn previous_record(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Array<i8>>> {
+        tracing::debug!("wie.MidpRecordEnumeration::previousRecord({:?})", &this);
+
+        let id = Self::previous_record_id(jvm, context, this.clone()).await?;
+        let store = jvm.get_field(&this, "store", "Ljavax/microedition/rms/RecordStore;").await?;
+
+        jvm.invoke_virtual(&store, "getRecord", "(I)[B", (id,)).await
+    }
+
+    async fn previous_record_id(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("wie.MidpRecordEnumeration::previousRecordId({:?})", &this);
+
+        let ids = Self::ids(jvm, context, &this).await?;
+        let position: i32 = jvm.get_field(&this, "position", "I").await?;
+
+        let previous = if position == POSITION_RESET {
+            ids.len() as i32 - 1
+        } else {
+            position - 1
+        };
+        if previous < 0 || previous as usize >= ids.len() {
+            return Err(jvm.exception("javax/microedition/rms/InvalidRecordIDException", "").await);
+        }
+
+        jvm.put_field(&mut this, "position", "I", previous).await?;
+
+        Ok(ids[previous as usize])
+    }
+
+    async fn has_next_element(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("wie.MidpRecordEnumeration::hasNextElement({:?})", &this);
+
+        let ids = Self::ids(jvm, context, &this).await?;
+        let position: i32 = jvm.get_field(&this, "position", "I").await?;
+
+        Ok(((position + 1) as usize) < ids.len())
+    }
+
+    async fn has_previous_element(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("wie.MidpRecordEnumeration::hasPreviousElement({:?})", &this);
+
+        let ids = Self::ids(jvm, context, &this).await?;
+        let position: i32 = jvm.get_field(&this, "position", "I").await?;
+
+        Ok(if position == POSITION_RESET { !ids.is_empty() } else { position > 0 })
+    }
+
+    async fn reset(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpRecordEnumeration::reset({:?})", &this);
+
+        Self::check_destroyed(jvm, &this).await?;
+
+        jvm.put_field(&mut this, "position", "I", POSITION_RESET).await
+    }
+
+    async fn rebuild(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpRecordEnumeration::rebuild({:?})", &this);
+
+        Self::check_destroyed(jvm, &this).await?;
+
+        let store = jvm.get_field(&this, "store", "Ljavax/microedition/rms/RecordStore;").await?;
+        let filter: ClassInstanceRef<RecordFilter> = jvm.get_field(&this, "filter", "Ljavax/microedition/rms/RecordFilter;").await?;
+        let comparator: ClassInstanceRef<RecordComparator> = jvm.get_field(&this, "comparator", "Ljavax/microedition/rms/RecordComparator;").await?;
+
+        let mut records = Vec::new();
+        for id in RecordStore::record_ids(jvm, context, &store).await? {
+            let data: ClassInstanceRef<Array<i8>> = jvm.invoke_virtual(&store, "getRecord", "(I)[B", (id,)).await?;
+
+            if !filter.is_null() && !jvm.invoke_virtual(&filter, "matches", "([B)Z", (data.clone(),)).await? {
+                continue;
+            }
+
+            records.push((id, data));
+        }
+
+        if !comparator.is_null() {
+            // insertion sort, as comparator has to be called asynchronously
+            for i in 1..records.len() {
+                let mut j = i;
+                while j > 0 {
+                    let result: i32 = jvm
+                        .invoke_virtual(&comparator, "compare", "([B[B)I", (records[j - 1].1.clone(), records[j].1.clone()))
+                        .await?;
+                    if result != RecordComparator::FOLLOWS {
+                        break;
+                    }
+
+                    records.swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+        }
+
+        let ids = records.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+        let mut ids_array = jvm.instantiate_array("I", ids.len()).await?;
+        jvm.store_array(&mut ids_array, 0, ids).await?;
+
+        jvm.put_field(&mut this, "ids", "[I", ids_array).await?;
+        jvm.put_field(&mut this, "position", "I", POSITION_RESET).await?;
+
+        Ok(())
+    }
+
+    async fn keep_updated(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, keep_updated: bool) -> JvmResult<()> {
+        tracing::debug!("wie.MidpRecordEnumeration::keepUpdated({:?}, {})", &this, keep_updated);
+
+        Self::check_destroyed(jvm, &this).await?;
+
+        jvm.put_field(&mut this, "keepUpdated", "Z", keep_updated).await
+    }
+
+    async fn is_kept_updated(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("wie.MidpRecordEnumeration::isKeptUpdated({:?})", &this);
+
+        Self::check_destroyed(jvm, &this).await?;
+
+        jvm.get_field(&this, "keepUpdated", "Z").await
+    }
+
+    async fn destroy(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.MidpRecordEnumeration::destroy({:?})", &this);
+
+        jvm.put_field(&mut this, "destroyed", "Z", true).await
+    }
+
+    async fn ids(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>) -> JvmResult<Vec<i32>> {
+        Self::check_destroyed(jvm, this).await?;
+
+        // rebuild on each access instead of listening to record store
+        let keep_updated: bool = jvm.get_field(this, "keepUpdated", "Z").await?;
+        if keep_updated {
+            let position: i32 = jvm.get_field(this, "position", "I").await?;
+            Self::rebuild(jvm, context, this.clone()).await?;
+
+            let mut this = this.clone();
+            jvm.put_field(&mut this, "position", "I", position).await?;
+        }
+
+        let ids_array = jvm.get_field(this, "ids", "[I").await?;
+        let length = jvm.array_length(&ids_array).await?;
+
+        jvm.load_array(&ids_array, 0, length).await
+    }
+
+    async fn check_destroyed(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<()> {
+        let destroyed: bool = jvm.get_field(this, "destroyed", "Z").await?;
+        if destroyed {
+            return Err(jvm.exception("java/lang/IllegalStateException", "Enumeration is destroyed").await);
+        }
+
+        Ok(())
+    }
+}
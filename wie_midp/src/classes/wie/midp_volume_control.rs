@@ -0,0 +1,105 @@
+use alloc::vec;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
+
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use crate::classes::wie::MidpPlayer;
+
+// event passed to PlayerListener.playerUpdate, with this control as data
+const EVENT_VOLUME_CHANGED: &str = "volumeChanged";
+
+// class wie.MidpVolumeControl, implementation of javax.microedition.media.control.VolumeControl returned by MidpPlayer
+pub struct MidpVolumeControl;
+
+impl MidpVolumeControl {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "wie/MidpVolumeControl",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec!["javax/microedition/media/control/VolumeControl"],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(Lwie/MidpPlayer;I)V", Self::init, Default::default()),
+                JavaMethodProto::new("setMute", "(Z)V", Self::set_mute, Default::default()),
+                JavaMethodProto::new("isMuted", "()Z", Self::is_muted, Default::default()),
+                JavaMethodProto::new("setLevel", "(I)I", Self::set_level, Default::default()),
+                JavaMethodProto::new("getLevel", "()I", Self::get_level, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("player", "Lwie/MidpPlayer;", Default::default()),
+                JavaFieldProto::new("level", "I", Default::default()),
+                JavaFieldProto::new("muted", "Z", Default::default()),
+            ],
+        }
+    }
+
+    async fn init(
+        jvm: &Jvm,
+        _: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        player: ClassInstanceRef<MidpPlayer>,
+        level: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!("wie.MidpVolumeControl::<init>({:?}, {:?}, {})", &this, &player, level);
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "player", "Lwie/MidpPlayer;", player).await?;
+        jvm.put_field(&mut this, "level", "I", level).await?;
+
+        Ok(())
+    }
+
+    async fn set_mute(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, mute: bool) -> JvmResult<()> {
+        tracing::debug!("wie.MidpVolumeControl::setMute({:?}, {})", &this, mute);
+
+        let muted: bool = jvm.get_field(&this, "muted", "Z").await?;
+        if muted == mute {
+            return Ok(());
+        }
+
+        jvm.put_field(&mut this, "muted", "Z", mute).await?;
+        Self::apply(jvm, context, &this).await
+    }
+
+    async fn is_muted(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("wie.MidpVolumeControl::isMuted({:?})", &this);
+
+        jvm.get_field(&this, "muted", "Z").await
+    }
+
+    async fn set_level(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, level: i32) -> JvmResult<i32> {
+        tracing::debug!("wie.MidpVolumeControl::setLevel({:?}, {})", &this, level);
+
+        let level = level.clamp(0, 100);
+        let current: i32 = jvm.get_field(&this, "level", "I").await?;
+        if current == level {
+            return Ok(level);
+        }
+
+        jvm.put_field(&mut this, "level", "I", level).await?;
+        // level set while muted takes effect on unmute
+        Self::apply(jvm, context, &this).await?;
+
+        Ok(level)
+    }
+
+    async fn get_level(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("wie.MidpVolumeControl::getLevel({:?})", &this);
+
+        jvm.get_field(&this, "level", "I").await
+    }
+
+    async fn apply(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>) -> JvmResult<()> {
+        let player: ClassInstanceRef<MidpPlayer> = jvm.get_field(this, "player", "Lwie/MidpPlayer;").await?;
+        let level: i32 = jvm.get_field(this, "level", "I").await?;
+        let muted: bool = jvm.get_field(this, "muted", "Z").await?;
+
+        let handle = MidpPlayer::handle(jvm, &player).await?;
+        let volume = if muted { 0 } else { level as u8 };
+        let _ = context.system().audio().set_volume(handle, volume);
+
+        MidpPlayer::notify_with_data(jvm, &player, EVENT_VOLUME_CHANGED, this.clone()).await
+    }
+}
@@ -5,7 +5,7 @@ pub mod classes;
 
 use wie_jvm_support::WieJavaClassProto;
 
-pub fn get_protos() -> [WieJavaClassProto; 8] {
+pub fn get_protos() -> [WieJavaClassProto; 27] {
     [
         classes::javax::microedition::lcdui::Canvas::as_proto(),
         classes::javax::microedition::lcdui::Display::as_proto(),
@@ -13,7 +13,26 @@ pub fn get_protos() -> [WieJavaClassProto; 8] {
         classes::javax::microedition::lcdui::Font::as_proto(),
         classes::javax::microedition::lcdui::Graphics::as_proto(),
         classes::javax::microedition::lcdui::Image::as_proto(),
+        classes::javax::microedition::media::Control::as_proto(),
+        classes::javax::microedition::media::Manager::as_proto(),
+        classes::javax::microedition::media::MediaException::as_proto(),
+        classes::javax::microedition::media::Player::as_proto(),
+        classes::javax::microedition::media::PlayerListener::as_proto(),
+        classes::javax::microedition::media::control::VolumeControl::as_proto(),
         classes::javax::microedition::midlet::MIDlet::as_proto(),
+        classes::javax::microedition::rms::InvalidRecordIDException::as_proto(),
+        classes::javax::microedition::rms::RecordComparator::as_proto(),
+        classes::javax::microedition::rms::RecordEnumeration::as_proto(),
+        classes::javax::microedition::rms::RecordFilter::as_proto(),
+        classes::javax::microedition::rms::RecordListener::as_proto(),
         classes::javax::microedition::rms::RecordStore::as_proto(),
+        classes::javax::microedition::rms::RecordStoreException::as_proto(),
+        classes::javax::microedition::rms::RecordStoreFullException::as_proto(),
+        classes::javax::microedition::rms::RecordStoreNotFoundException::as_proto(),
+        classes::javax::microedition::rms::RecordStoreNotOpenException::as_proto(),
+        classes::wie::MidpEventQueue::as_proto(),
+        classes::wie::MidpPlayer::as_proto(),
+        classes::wie::MidpRecordEnumeration::as_proto(),
+        classes::wie::MidpVolumeControl::as_proto(),
     ]
 }
@@ -39,6 +39,12 @@ impl SktEmulator {
         files.iter().any(|x| x.0.ends_with(".msd"))
     }
 
+    pub fn archive_app_id(files: &BTreeMap<String, Vec<u8>>) -> String {
+        let msd_file = files.iter().find(|x| x.0.ends_with(".msd")).unwrap();
+
+        SktMsd::parse(msd_file.0, msd_file.1).id
+    }
+
     pub fn loadable_jar(jar: &[u8]) -> bool {
         jar.starts_with(b"\x20\x00\x00\x00\x00\x00\x00\x00")
     }
@@ -96,7 +102,8 @@ impl SktEmulator {
 
         let main_class = main_class.unwrap();
 
-        let result: JvmResult<()> = if jvm.is_instance(&*main_class, "javax/microedition/midlet/MIDlet").await.unwrap() {
+        let is_midlet = jvm.is_instance(&*main_class, "javax/microedition/midlet/MIDlet").await.unwrap();
+        let result: JvmResult<()> = if is_midlet {
             jvm.invoke_virtual(&main_class, "startApp", "()V", [None.into()]).await
         } else {
             jvm.invoke_virtual(&main_class, "startApp", "([Ljava/lang/String;)V", [None.into()]).await
@@ -106,6 +113,14 @@ impl SktEmulator {
             return Err(JvmSupport::to_wie_err(&jvm, x).await);
         }
 
+        if is_midlet {
+            // startApp returns after setting current displayable, so we have to dispatch events to it
+            let result: JvmResult<()> = jvm.invoke_static("wie/MidpEventQueue", "run", "()V", []).await;
+            if let Err(x) = result {
+                return Err(JvmSupport::to_wie_err(&jvm, x).await);
+            }
+        }
+
         Ok(())
     }
 }
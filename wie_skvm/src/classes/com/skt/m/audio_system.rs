@@ -32,10 +32,13 @@ impl AudioSystem {
         }
     }
 
-    async fn get_audio_clip(_jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<AudioClip>> {
-        tracing::warn!("stub com.skt.m.AudioSystem::getAudioClip({:?})", name);
+    async fn get_audio_clip(jvm: &Jvm, _context: &mut WieJvmContext, format: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<AudioClip>> {
+        tracing::debug!("com.skt.m.AudioSystem::getAudioClip({:?})", &format);
 
-        Ok(None.into())
+        // format name is used as a hint when data is opened
+        let clip = jvm.new_class("wie/SkvmAudioClip", "(Ljava/lang/String;)V", (format,)).await?;
+
+        Ok(clip.into())
     }
 
     async fn get_max_volume(_jvm: &Jvm, _context: &mut WieJvmContext, format: ClassInstanceRef<String>) -> JvmResult<i32> {
@@ -4,6 +4,7 @@ use java_class_proto::JavaMethodProto;
 use java_constants::MethodAccessFlags;
 use jvm::{Jvm, Result as JvmResult};
 
+use wie_backend::BacklightState;
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 // class com.skt.m.BackLight
@@ -20,8 +21,12 @@ impl BackLight {
         }
     }
 
-    async fn on(_jvm: &Jvm, _: &mut WieJvmContext, timeout: i32) -> JvmResult<()> {
-        tracing::warn!("stub com.skt.m.BackLight::on({:?})", timeout);
+    async fn on(_jvm: &Jvm, context: &mut WieJvmContext, timeout: i32) -> JvmResult<()> {
+        tracing::debug!("com.skt.m.BackLight::on({:?})", timeout);
+
+        // TODO check meaning of non-positive timeout
+        let timeout = if timeout > 0 { Some(timeout as _) } else { None };
+        context.system().platform().set_backlight(BacklightState::On, timeout);
 
         Ok(())
     }
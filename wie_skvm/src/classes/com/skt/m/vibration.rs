@@ -5,6 +5,9 @@ use java_constants::MethodAccessFlags;
 use jvm::{Jvm, Result as JvmResult};
 
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+const LEVEL_NUM: i32 = 10;
+
 // class com.skt.m.Vibration
 pub struct Vibration;
 
@@ -23,13 +26,16 @@ impl Vibration {
     }
 
     async fn get_level_num(_jvm: &Jvm, _context: &mut WieJvmContext) -> JvmResult<i32> {
-        tracing::warn!("stub com.skt.m.Vibration::getLevelNum()");
+        tracing::debug!("com.skt.m.Vibration::getLevelNum()");
 
-        Ok(10)
+        Ok(LEVEL_NUM)
     }
 
-    async fn start(_jvm: &Jvm, _context: &mut WieJvmContext, level: i32, timeout: i32) -> JvmResult<()> {
-        tracing::warn!("stub com.skt.m.Vibration::start({}, {})", level, timeout);
+    async fn start(_jvm: &Jvm, context: &mut WieJvmContext, level: i32, timeout: i32) -> JvmResult<()> {
+        tracing::debug!("com.skt.m.Vibration::start({}, {})", level, timeout);
+
+        let level = level.clamp(0, LEVEL_NUM) * 100 / LEVEL_NUM;
+        context.system().platform().vibrate(timeout.max(0) as _, level as _);
 
         Ok(())
     }
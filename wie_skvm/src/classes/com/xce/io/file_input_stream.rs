@@ -1,12 +1,12 @@
 use alloc::vec;
 
-use java_class_proto::JavaMethodProto;
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_runtime::classes::java::lang::String;
-use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
 
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
-use crate::classes::com::xce::io::x_file::XFile;
+use crate::classes::com::xce::io::x_file::{XFile, MODE_READ};
 
 // class com.xce.io.FileInputStream
 pub struct FileInputStream;
@@ -20,34 +20,91 @@ impl FileInputStream {
             methods: vec![
                 JavaMethodProto::new("<init>", "(Ljava/lang/String;)V", Self::init, Default::default()),
                 JavaMethodProto::new("<init>", "(Lcom/xce/io/XFile;)V", Self::init_with_file, Default::default()),
+                JavaMethodProto::new("read", "()I", Self::read, Default::default()),
+                JavaMethodProto::new("read", "([BII)I", Self::read_array, Default::default()),
                 JavaMethodProto::new("available", "()I", Self::available, Default::default()),
                 JavaMethodProto::new("close", "()V", Self::close, Default::default()),
             ],
-            fields: vec![],
+            fields: vec![JavaFieldProto::new("file", "Lcom/xce/io/XFile;", Default::default())],
         }
     }
 
-    async fn init(_jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.FileInputStream::<init>({:?})", name);
+    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, name: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileInputStream::<init>({:?}, {:?})", this, name);
+
+        let file = jvm.new_class("com/xce/io/XFile", "(Ljava/lang/String;I)V", (name, MODE_READ)).await?;
+
+        let _: () = jvm
+            .invoke_special(&this, "com/xce/io/FileInputStream", "<init>", "(Lcom/xce/io/XFile;)V", (file,))
+            .await?;
 
         Ok(())
     }
 
-    async fn init_with_file(_jvm: &Jvm, _context: &mut WieJvmContext, file: ClassInstanceRef<XFile>) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.FileInputStream::<init>({:?})", file);
+    async fn init_with_file(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        file: ClassInstanceRef<XFile>,
+    ) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileInputStream::<init>({:?}, {:?})", this, file);
+
+        let _: () = jvm.invoke_special(&this, "java/io/InputStream", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "file", "Lcom/xce/io/XFile;", file).await?;
 
         Ok(())
     }
 
-    async fn available(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
-        tracing::warn!("stub com.xce.io.FileInputStream::available({:?})", this);
+    async fn read(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("com.xce.io.FileInputStream::read({:?})", this);
+
+        let file: ClassInstanceRef<XFile> = jvm.get_field(&this, "file", "Lcom/xce/io/XFile;").await?;
 
-        Ok(0)
+        let buf = jvm.instantiate_array("B", 1).await?;
+        let read: i32 = jvm.invoke_virtual(&file, "read", "([BII)I", (buf.clone(), 0, 1)).await?;
+        if read <= 0 {
+            return Ok(-1);
+        }
+
+        let byte = jvm.load_byte_array(&buf, 0, 1).await?[0];
+
+        Ok(byte as u8 as i32)
     }
 
-    async fn close(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.FileInputStream::close({:?})", this);
+    async fn read_array(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        buf: ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<i32> {
+        tracing::debug!("com.xce.io.FileInputStream::read({:?}, {:?}, {}, {})", this, buf, offset, length);
 
-        Ok(())
+        let file: ClassInstanceRef<XFile> = jvm.get_field(&this, "file", "Lcom/xce/io/XFile;").await?;
+
+        let read: i32 = jvm.invoke_virtual(&file, "read", "([BII)I", (buf, offset, length)).await?;
+        if read == 0 && length > 0 {
+            return Ok(-1);
+        }
+
+        Ok(read)
+    }
+
+    async fn available(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("com.xce.io.FileInputStream::available({:?})", this);
+
+        let file = jvm.get_field(&this, "file", "Lcom/xce/io/XFile;").await?;
+
+        XFile::remaining(jvm, &file).await
+    }
+
+    async fn close(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileInputStream::close({:?})", this);
+
+        let file: ClassInstanceRef<XFile> = jvm.get_field(&this, "file", "Lcom/xce/io/XFile;").await?;
+
+        jvm.invoke_virtual(&file, "close", "()V", ()).await
     }
 }
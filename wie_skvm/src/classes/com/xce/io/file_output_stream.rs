@@ -1,12 +1,12 @@
 use alloc::vec;
 
-use java_class_proto::JavaMethodProto;
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_runtime::classes::java::lang::String;
-use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
 
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
-use crate::classes::com::xce::io::x_file::XFile;
+use crate::classes::com::xce::io::x_file::{XFile, MODE_WRITE};
 
 // class com.xce.io.FileOutputStream
 pub struct FileOutputStream;
@@ -21,33 +21,71 @@ impl FileOutputStream {
                 JavaMethodProto::new("<init>", "(Ljava/lang/String;)V", Self::init, Default::default()),
                 JavaMethodProto::new("<init>", "(Lcom/xce/io/XFile;)V", Self::init_with_file, Default::default()),
                 JavaMethodProto::new("write", "(I)V", Self::write, Default::default()),
+                JavaMethodProto::new("write", "([BII)V", Self::write_array, Default::default()),
                 JavaMethodProto::new("close", "()V", Self::close, Default::default()),
             ],
-            fields: vec![],
+            fields: vec![JavaFieldProto::new("file", "Lcom/xce/io/XFile;", Default::default())],
         }
     }
 
-    async fn init(_jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.FileOutputStream::<init>({:?})", name);
+    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, name: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileOutputStream::<init>({:?}, {:?})", this, name);
+
+        let file = jvm.new_class("com/xce/io/XFile", "(Ljava/lang/String;I)V", (name, MODE_WRITE)).await?;
+
+        let _: () = jvm
+            .invoke_special(&this, "com/xce/io/FileOutputStream", "<init>", "(Lcom/xce/io/XFile;)V", (file,))
+            .await?;
 
         Ok(())
     }
 
-    async fn init_with_file(_jvm: &Jvm, _context: &mut WieJvmContext, file: ClassInstanceRef<XFile>) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.FileOutputStream::<init>({:?})", file);
+    async fn init_with_file(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        file: ClassInstanceRef<XFile>,
+    ) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileOutputStream::<init>({:?}, {:?})", this, file);
+
+        let _: () = jvm.invoke_special(&this, "java/io/OutputStream", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "file", "Lcom/xce/io/XFile;", file).await?;
 
         Ok(())
     }
 
-    async fn write(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, byte: i32) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.FileOutputStream::write({:?}, {:?})", this, byte);
+    async fn write(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, byte: i32) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileOutputStream::write({:?}, {:?})", this, byte);
 
-        Ok(())
+        let mut buf = jvm.instantiate_array("B", 1).await?;
+        jvm.store_byte_array(&mut buf, 0, vec![byte as i8]).await?;
+
+        jvm.invoke_virtual(&this, "write", "([BII)V", (buf, 0, 1)).await
     }
 
-    async fn close(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.FileOutputStream::close({:?})", this);
+    async fn write_array(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        buf: ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileOutputStream::write({:?}, {:?}, {}, {})", this, buf, offset, length);
+
+        let file: ClassInstanceRef<XFile> = jvm.get_field(&this, "file", "Lcom/xce/io/XFile;").await?;
+
+        let _: i32 = jvm.invoke_virtual(&file, "write", "([BII)I", (buf, offset, length)).await?;
 
         Ok(())
     }
+
+    async fn close(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("com.xce.io.FileOutputStream::close({:?})", this);
+
+        let file: ClassInstanceRef<XFile> = jvm.get_field(&this, "file", "Lcom/xce/io/XFile;").await?;
+
+        jvm.invoke_virtual(&file, "close", "()V", ()).await
+    }
 }
@@ -1,11 +1,21 @@
-use alloc::vec;
+use alloc::{format, vec, vec::Vec};
 
-use java_class_proto::JavaMethodProto;
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_constants::MethodAccessFlags;
 use java_runtime::classes::java::lang::String;
-use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, Jvm, Result as JvmResult};
 
-use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+use wie_jvm_support::{JavaFile, OpenOptions, WieJavaClassProto, WieJvmContext};
+
+// TODO values are not verified against SKVM SDK. file streams open XFile with these, so they stay consistent with each other
+pub(super) const MODE_READ: i32 = 1;
+pub(super) const MODE_WRITE: i32 = 2;
+const MODE_READ_WRITE: i32 = 3;
+const MODE_APPEND: i32 = 4;
+
+const SEEK_SET: i32 = 0;
+const SEEK_CUR: i32 = 1;
+const SEEK_END: i32 = 2;
 
 // class com.xce.io.XFile
 pub struct XFile;
@@ -21,59 +31,287 @@ impl XFile {
                 JavaMethodProto::new("exists", "(Ljava/lang/String;)Z", Self::exists, MethodAccessFlags::STATIC),
                 JavaMethodProto::new("filesize", "(Ljava/lang/String;)I", Self::filesize, MethodAccessFlags::STATIC),
                 JavaMethodProto::new("unlink", "(Ljava/lang/String;)I", Self::unlink, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("list", "(Ljava/lang/String;)[Ljava/lang/String;", Self::list, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("read", "([BII)I", Self::read, Default::default()),
                 JavaMethodProto::new("write", "([BII)I", Self::write, Default::default()),
+                JavaMethodProto::new("seek", "(II)I", Self::seek, Default::default()),
                 JavaMethodProto::new("close", "()V", Self::close, Default::default()),
             ],
-            fields: vec![],
+            fields: vec![
+                JavaFieldProto::new("name", "Ljava/lang/String;", Default::default()),
+                JavaFieldProto::new("mode", "I", Default::default()),
+                JavaFieldProto::new("data", "[B", Default::default()),
+                JavaFieldProto::new("size", "I", Default::default()),
+                JavaFieldProto::new("pos", "I", Default::default()),
+            ],
         }
     }
 
     async fn init(
-        _jvm: &Jvm,
-        _context: &mut WieJvmContext,
-        this: ClassInstanceRef<Self>,
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
         name: ClassInstanceRef<String>,
         mode: i32,
     ) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.XFile::<init>({:?}, {:?}, {:?})", this, name, mode);
+        tracing::debug!("com.xce.io.XFile::<init>({:?}, {:?}, {:?})", this, name, mode);
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        let options = match mode {
+            MODE_READ => OpenOptions::default(),
+            MODE_WRITE => OpenOptions {
+                truncate: true,
+                ..Default::default()
+            },
+            MODE_READ_WRITE => OpenOptions {
+                create: true,
+                ..Default::default()
+            },
+            MODE_APPEND => OpenOptions {
+                create: true,
+                append: true,
+                ..Default::default()
+            },
+            _ => {
+                return Err(jvm
+                    .exception("java/lang/IllegalArgumentException", &format!("Invalid mode: {}", mode))
+                    .await)
+            }
+        };
+
+        JavaFile::open(jvm, context, &mut this, name, options).await?;
+        jvm.put_field(&mut this, "mode", "I", mode).await?;
 
         Ok(())
     }
 
-    async fn exists(_jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<bool> {
-        tracing::warn!("stub com.xce.io.XFile::exists({:?})", name);
+    async fn exists(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<bool> {
+        tracing::debug!("com.xce.io.XFile::exists({:?})", name);
+
+        let filename = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let exists = context.system().filesystem().exists(&filename);
+
+        Ok(exists)
+    }
+
+    async fn filesize(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<i32> {
+        tracing::debug!("com.xce.io.XFile::filesize({:?})", name);
+
+        let filename = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let metadata = context.system().filesystem().metadata(&filename);
+
+        Ok(metadata.map(|x| x.size as i32).unwrap_or(-1))
+    }
+
+    async fn unlink(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<i32> {
+        tracing::debug!("com.xce.io.XFile::unlink({:?})", name);
+
+        let filename = JavaLangString::to_rust_string(jvm, &name).await?;
 
-        Ok(false)
+        let result = context.system().filesystem().remove(&filename);
+
+        Ok(if result.is_ok() { 0 } else { -1 })
     }
 
-    async fn filesize(_jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<i32> {
-        tracing::warn!("stub com.xce.io.XFile::filesize({:?})", name);
+    async fn list(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<Array<String>>> {
+        tracing::debug!("com.xce.io.XFile::list({:?})", name);
+
+        let path = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let entries = context.system().filesystem().list(&path);
+        let Ok(entries) = entries else {
+            return Ok(None.into());
+        };
+
+        let mut names = Vec::with_capacity(entries.len());
+        for entry in entries {
+            names.push(JavaLangString::from_rust_string(jvm, &entry).await?);
+        }
+
+        let mut result = jvm.instantiate_array("Ljava/lang/String;", names.len()).await?;
+        jvm.store_array(&mut result, 0, names).await?;
 
-        Ok(0)
+        Ok(result.into())
     }
 
-    async fn unlink(_jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<i32> {
-        tracing::warn!("stub com.xce.io.XFile::unlink({:?})", name);
+    async fn read(
+        jvm: &Jvm,
+        _: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        mut buf: ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<i32> {
+        tracing::debug!("com.xce.io.XFile::read({:?}, {:?}, {}, {})", this, buf, offset, length);
 
-        Ok(0)
+        JavaFile::read(jvm, &mut this, &mut buf, offset, length).await
     }
 
     async fn write(
-        _jvm: &Jvm,
-        _context: &mut WieJvmContext,
-        this: ClassInstanceRef<Self>,
-        data: ClassInstanceRef<Array<i8>>,
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        buf: ClassInstanceRef<Array<i8>>,
         offset: i32,
         length: i32,
     ) -> JvmResult<i32> {
-        tracing::warn!("stub com.xce.io.XFile::write({:?}, {:?}, {}, {})", this, data, offset, length);
+        tracing::debug!("com.xce.io.XFile::write({:?}, {:?}, {}, {})", this, buf, offset, length);
+
+        let mode: i32 = jvm.get_field(&this, "mode", "I").await?;
+        if mode == MODE_READ {
+            return Err(jvm.exception("java/io/IOException", "File is opened read only").await);
+        }
+
+        JavaFile::write(jvm, context, &mut this, &buf, offset, length).await?;
+
+        Ok(length)
+    }
+
+    async fn seek(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, offset: i32, whence: i32) -> JvmResult<i32> {
+        tracing::debug!("com.xce.io.XFile::seek({:?}, {}, {})", this, offset, whence);
+
+        let pos: i32 = jvm.get_field(&this, "pos", "I").await?;
+        let size = JavaFile::size(jvm, &this).await?;
+
+        let new_pos = match whence {
+            SEEK_SET => Some(offset),
+            SEEK_CUR => pos.checked_add(offset),
+            SEEK_END => size.checked_add(offset),
+            _ => None,
+        };
+        let Some(new_pos) = new_pos.filter(|&x| x >= 0) else {
+            return Ok(-1);
+        };
 
-        Ok(0)
+        jvm.put_field(&mut this, "pos", "I", new_pos).await?;
+
+        Ok(new_pos)
     }
 
     async fn close(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.io.XFile::close({:?})", this);
+        // writes are flushed immediately, nothing to do here
+        tracing::debug!("com.xce.io.XFile::close({:?})", this);
 
         Ok(())
     }
+
+    /// Returns number of bytes left to read from current position.
+    pub async fn remaining(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<i32> {
+        let pos: i32 = jvm.get_field(this, "pos", "I").await?;
+        let size = JavaFile::size(jvm, this).await?;
+
+        Ok((size - pos).max(0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    use java_runtime::classes::java::lang::String;
+    use jvm::{runtime::JavaLangString, Array, ClassInstanceRef};
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::get_protos;
+
+    #[test]
+    fn test_file_stream() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let name = JavaLangString::from_rust_string(&jvm, "save.dat").await?;
+
+            let output_stream = jvm
+                .new_class("com/xce/io/FileOutputStream", "(Ljava/lang/String;)V", (name.clone(),))
+                .await?;
+            let mut data = jvm.instantiate_array("B", 4).await?;
+            jvm.store_byte_array(&mut data, 0, vec![1, 2, 3, 4]).await?;
+            let _: () = jvm.invoke_virtual(&output_stream, "write", "([BII)V", (data, 1, 3)).await?;
+            let _: () = jvm.invoke_virtual(&output_stream, "write", "(I)V", (0xff,)).await?;
+            let _: () = jvm.invoke_virtual(&output_stream, "close", "()V", ()).await?;
+
+            let exists: bool = jvm
+                .invoke_static("com/xce/io/XFile", "exists", "(Ljava/lang/String;)Z", (name.clone(),))
+                .await?;
+            assert!(exists);
+            let size: i32 = jvm
+                .invoke_static("com/xce/io/XFile", "filesize", "(Ljava/lang/String;)I", (name.clone(),))
+                .await?;
+            assert_eq!(size, 4);
+
+            let input_stream = jvm
+                .new_class("com/xce/io/FileInputStream", "(Ljava/lang/String;)V", (name.clone(),))
+                .await?;
+            let available: i32 = jvm.invoke_virtual(&input_stream, "available", "()I", ()).await?;
+            assert_eq!(available, 4);
+
+            let mut result = vec![];
+            loop {
+                let byte: i32 = jvm.invoke_virtual(&input_stream, "read", "()I", ()).await?;
+                if byte == -1 {
+                    break;
+                }
+                result.push(byte);
+            }
+            assert_eq!(result, [2, 3, 4, 0xff]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_x_file() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let name = JavaLangString::from_rust_string(&jvm, "test.dat").await?;
+
+            // read only open of missing file fails
+            let result = jvm.new_class("com/xce/io/XFile", "(Ljava/lang/String;I)V", (name.clone(), 1)).await;
+            assert!(result.is_err());
+
+            let file = jvm.new_class("com/xce/io/XFile", "(Ljava/lang/String;I)V", (name.clone(), 3)).await?;
+            let mut data = jvm.instantiate_array("B", 4).await?;
+            jvm.store_byte_array(&mut data, 0, vec![1, 2, 3, 4]).await?;
+            let written: i32 = jvm.invoke_virtual(&file, "write", "([BII)I", (data.clone(), 0, 4)).await?;
+            assert_eq!(written, 4);
+
+            let pos: i32 = jvm.invoke_virtual(&file, "seek", "(II)I", (-3, 2)).await?;
+            assert_eq!(pos, 1);
+            let read: i32 = jvm.invoke_virtual(&file, "read", "([BII)I", (data.clone(), 0, 4)).await?;
+            assert_eq!(read, 3);
+            assert_eq!(jvm.load_byte_array(&data, 0, 3).await?, [2, 3, 4]);
+
+            let pos: i32 = jvm.invoke_virtual(&file, "seek", "(II)I", (i32::MAX, 1)).await?;
+            assert_eq!(pos, -1);
+            let _: () = jvm.invoke_virtual(&file, "close", "()V", ()).await?;
+
+            // append mode starts at end of file
+            let file = jvm.new_class("com/xce/io/XFile", "(Ljava/lang/String;I)V", (name.clone(), 4)).await?;
+            let written: i32 = jvm.invoke_virtual(&file, "write", "([BII)I", (data.clone(), 0, 2)).await?;
+            assert_eq!(written, 2);
+            let _: () = jvm.invoke_virtual(&file, "close", "()V", ()).await?;
+            let size: i32 = jvm
+                .invoke_static("com/xce/io/XFile", "filesize", "(Ljava/lang/String;)I", (name.clone(),))
+                .await?;
+            assert_eq!(size, 6);
+
+            let root = JavaLangString::from_rust_string(&jvm, "").await?;
+            let list: ClassInstanceRef<Array<String>> = jvm
+                .invoke_static("com/xce/io/XFile", "list", "(Ljava/lang/String;)[Ljava/lang/String;", (root,))
+                .await?;
+            let list: Vec<ClassInstanceRef<String>> = jvm.load_array(&list, 0, 1).await?;
+            assert_eq!(JavaLangString::to_rust_string(&jvm, &list[0]).await?, "test.dat");
+
+            let result: i32 = jvm
+                .invoke_static("com/xce/io/XFile", "unlink", "(Ljava/lang/String;)I", (name.clone(),))
+                .await?;
+            assert_eq!(result, 0);
+            let exists: bool = jvm.invoke_static("com/xce/io/XFile", "exists", "(Ljava/lang/String;)Z", (name,)).await?;
+            assert!(!exists);
+
+            Ok(())
+        })
+    }
 }
@@ -2,9 +2,11 @@ use alloc::vec;
 
 use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_constants::{FieldAccessFlags, MethodAccessFlags};
-use jvm::{Jvm, Result as JvmResult};
+use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
 
+use wie_backend::Rect;
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+use wie_midp::classes::javax::microedition::lcdui::{Display, Image};
 
 // class com.xce.lcdui.XDisplay
 pub struct XDisplay;
@@ -27,19 +29,33 @@ impl XDisplay {
         }
     }
 
-    async fn cl_init(jvm: &Jvm, _: &mut WieJvmContext) -> JvmResult<()> {
-        tracing::debug!("com.xce.lcdui.Toolkit::<clinit>()");
+    async fn cl_init(jvm: &Jvm, context: &mut WieJvmContext) -> JvmResult<()> {
+        tracing::debug!("com.xce.lcdui.XDisplay::<clinit>()");
 
-        // TODO: temp
-        jvm.put_static_field("com/xce/lcdui/XDisplay", "width", "I", 240).await?;
-        jvm.put_static_field("com/xce/lcdui/XDisplay", "height", "I", 320).await?;
-        jvm.put_static_field("com/xce/lcdui/XDisplay", "height2", "I", 320).await?;
+        let (width, height) = context.system().display_size();
+        jvm.put_static_field("com/xce/lcdui/XDisplay", "width", "I", width as i32).await?;
+        jvm.put_static_field("com/xce/lcdui/XDisplay", "height", "I", height as i32).await?;
+        jvm.put_static_field("com/xce/lcdui/XDisplay", "height2", "I", height as i32).await?;
 
         Ok(())
     }
 
-    async fn refresh(_jvm: &Jvm, _: &mut WieJvmContext, x: i32, y: i32, width: i32, height: i32) -> JvmResult<()> {
-        tracing::warn!("stub com.xce.lcdui.XDisplay::refresh({}, {}, {}, {})", x, y, width, height);
+    async fn refresh(jvm: &Jvm, context: &mut WieJvmContext, x: i32, y: i32, width: i32, height: i32) -> JvmResult<()> {
+        tracing::debug!("com.xce.lcdui.XDisplay::refresh({}, {}, {}, {})", x, y, width, height);
+
+        // Toolkit graphics draws on display's screen image
+        let (screen_width, screen_height) = context.system().display_size();
+        let Some(region) = Rect::clipped(x, y, width, height, screen_width, screen_height) else {
+            return Ok(());
+        };
+
+        let display = Display::display(jvm).await?;
+        let screen_image: ClassInstanceRef<Image> = jvm.get_field(&display, "screenImage", "Ljavax/microedition/lcdui/Image;").await?;
+        let image = Image::image(jvm, &screen_image).await?;
+
+        let mut platform = context.system().platform();
+        let screen = platform.screen();
+        screen.paint_region(&*image, region);
 
         Ok(())
     }
@@ -0,0 +1,233 @@
+use alloc::{vec, vec::Vec};
+
+use bytemuck::cast_vec;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use java_runtime::classes::java::lang::String;
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, Jvm, Result as JvmResult};
+
+use wie_backend::AudioHandle;
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+// class wie.SkvmAudioClip, implementation of com.skt.m.AudioClip returned by AudioSystem
+pub struct SkvmAudioClip;
+
+impl SkvmAudioClip {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "wie/SkvmAudioClip",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec!["com/skt/m/AudioClip"],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(Ljava/lang/String;)V", Self::init, Default::default()),
+                JavaMethodProto::new("open", "([BII)V", Self::open, Default::default()),
+                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
+                JavaMethodProto::new("play", "()V", Self::play, Default::default()),
+                JavaMethodProto::new("loop", "()V", Self::r#loop, Default::default()),
+                JavaMethodProto::new("stop", "()V", Self::stop, Default::default()),
+                JavaMethodProto::new("pause", "()V", Self::pause, Default::default()),
+                JavaMethodProto::new("resume", "()V", Self::resume, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("format", "Ljava/lang/String;", Default::default()),
+                JavaFieldProto::new("handle", "I", Default::default()),
+            ],
+        }
+    }
+
+    async fn init(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, format: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::<init>({:?}, {:?})", &this, &format);
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "format", "Ljava/lang/String;", format).await?;
+
+        Ok(())
+    }
+
+    async fn open(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        data: ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::open({:?}, {:?}, {}, {})", &this, &data, offset, length);
+
+        let data_length = jvm.array_length(&data).await? as i32;
+        if offset < 0 || length < 0 || offset > data_length - length {
+            return Err(jvm.exception("java/lang/ArrayIndexOutOfBoundsException", "Invalid range").await);
+        }
+
+        Self::unload(jvm, context, &mut this).await?;
+
+        let data: Vec<i8> = jvm.load_byte_array(&data, offset as _, length as _).await?;
+        let format = jvm.get_field(&this, "format", "Ljava/lang/String;").await?;
+        let format = JavaLangString::to_rust_string(jvm, &format).await?;
+
+        let result = context.system().audio().load(&cast_vec(data), Some(&format));
+        match result {
+            Ok(handle) => jvm.put_field(&mut this, "handle", "I", handle as i32).await?,
+            Err(x) => tracing::error!("Failed to load audio: {:?}", x),
+        }
+
+        Ok(())
+    }
+
+    async fn close(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::close({:?})", &this);
+
+        Self::unload(jvm, context, &mut this).await
+    }
+
+    async fn play(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::play({:?})", &this);
+
+        if let Some(handle) = Self::handle(jvm, &this).await? {
+            if let Err(x) = context.system().audio().play(handle, Some(1)) {
+                tracing::error!("Failed to play audio: {:?}", x);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn r#loop(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::loop({:?})", &this);
+
+        if let Some(handle) = Self::handle(jvm, &this).await? {
+            if let Err(x) = context.system().audio().play(handle, None) {
+                tracing::error!("Failed to play audio: {:?}", x);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn stop(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::stop({:?})", &this);
+
+        if let Some(handle) = Self::handle(jvm, &this).await? {
+            let _ = context.system().audio().stop(handle);
+        }
+
+        Ok(())
+    }
+
+    async fn pause(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::pause({:?})", &this);
+
+        if let Some(handle) = Self::handle(jvm, &this).await? {
+            let _ = context.system().audio().pause(handle);
+        }
+
+        Ok(())
+    }
+
+    async fn resume(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("wie.SkvmAudioClip::resume({:?})", &this);
+
+        if let Some(handle) = Self::handle(jvm, &this).await? {
+            let _ = context.system().audio().resume(handle);
+        }
+
+        Ok(())
+    }
+
+    async fn handle(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<Option<AudioHandle>> {
+        let handle: i32 = jvm.get_field(this, "handle", "I").await?;
+
+        Ok(if handle == 0 { None } else { Some(handle as _) })
+    }
+
+    async fn unload(jvm: &Jvm, context: &mut WieJvmContext, this: &mut ClassInstanceRef<Self>) -> JvmResult<()> {
+        if let Some(handle) = Self::handle(jvm, this).await? {
+            let _ = context.system().audio().unload(handle);
+            jvm.put_field(this, "handle", "I", 0).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec};
+
+    use jvm::{runtime::JavaLangString, ClassInstanceRef};
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::{classes::com::skt::m::AudioClip, get_protos};
+
+    #[test]
+    fn test_audio_clip() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let format = JavaLangString::from_rust_string(&jvm, "tone").await?;
+            let clip: ClassInstanceRef<AudioClip> = jvm
+                .invoke_static(
+                    "com/skt/m/AudioSystem",
+                    "getAudioClip",
+                    "(Ljava/lang/String;)Lcom/skt/m/AudioClip;",
+                    (format,),
+                )
+                .await?;
+            assert!(!clip.is_null());
+
+            // tone sequence with single note
+            let mut data = jvm.instantiate_array("B", 6).await?;
+            jvm.store_byte_array(&mut data, 0, vec![0, -2, 1, 60, 64, 0]).await?;
+
+            let result: jvm::Result<()> = jvm.invoke_virtual(&clip, "open", "([BII)V", (data.clone(), 4, 4)).await;
+            assert!(result.is_err());
+
+            let _: () = jvm.invoke_virtual(&clip, "open", "([BII)V", (data, 1, 4)).await?;
+            let handle: i32 = jvm.get_field(&clip, "handle", "I").await?;
+            assert_ne!(handle, 0);
+
+            let _: () = jvm.invoke_virtual(&clip, "play", "()V", ()).await?;
+            let _: () = jvm.invoke_virtual(&clip, "pause", "()V", ()).await?;
+            let _: () = jvm.invoke_virtual(&clip, "resume", "()V", ()).await?;
+            let _: () = jvm.invoke_virtual(&clip, "stop", "()V", ()).await?;
+            let _: () = jvm.invoke_virtual(&clip, "loop", "()V", ()).await?;
+            let _: () = jvm.invoke_virtual(&clip, "close", "()V", ()).await?;
+
+            let handle: i32 = jvm.get_field(&clip, "handle", "I").await?;
+            assert_eq!(handle, 0);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_audio_clip_recording() -> Result<()> {
+        let (platform, recording) = TestPlatform::with_audio_recording();
+
+        run_jvm_test(platform, Box::new([get_protos().into()]), move |jvm| async move {
+            let format = JavaLangString::from_rust_string(&jvm, "tone").await?;
+            let clip: ClassInstanceRef<AudioClip> = jvm
+                .invoke_static(
+                    "com/skt/m/AudioSystem",
+                    "getAudioClip",
+                    "(Ljava/lang/String;)Lcom/skt/m/AudioClip;",
+                    (format,),
+                )
+                .await?;
+
+            let mut data = jvm.instantiate_array("B", 6).await?;
+            jvm.store_byte_array(&mut data, 0, vec![0, -2, 1, 60, 64, 0]).await?;
+            let _: () = jvm.invoke_virtual(&clip, "open", "([BII)V", (data, 1, 4)).await?;
+
+            let _: () = jvm.invoke_virtual(&clip, "play", "()V", ()).await?;
+            let _: () = jvm.invoke_static("java/lang/Thread", "sleep", "(J)V", (100i64,)).await?;
+
+            let wave = recording.wave();
+            assert!(wave.len() > 44);
+            assert!(wave[44..].chunks(2).any(|x| i16::from_le_bytes([x[0], x[1]]) != 0));
+
+            Ok(())
+        })
+    }
+}
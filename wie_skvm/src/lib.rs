@@ -5,7 +5,7 @@ use wie_jvm_support::WieJavaClassProto;
 
 pub mod classes;
 
-pub fn get_protos() -> [WieJavaClassProto; 12] {
+pub fn get_protos() -> [WieJavaClassProto; 13] {
     [
         classes::com::skt::m::AudioClip::as_proto(),
         classes::com::skt::m::AudioSystem::as_proto(),
@@ -19,5 +19,6 @@ pub fn get_protos() -> [WieJavaClassProto; 12] {
         classes::com::xce::io::XFile::as_proto(),
         classes::com::xce::lcdui::Toolkit::as_proto(),
         classes::com::xce::lcdui::XDisplay::as_proto(),
+        classes::wie::SkvmAudioClip::as_proto(),
     ]
 }
@@ -1,4 +1,5 @@
 pub mod database;
+pub mod fs;
 pub mod graphics;
 pub mod kernel;
 pub mod media;
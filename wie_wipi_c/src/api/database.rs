@@ -1,9 +1,9 @@
-use alloc::{borrow::ToOwned, boxed::Box, str, string::String, vec};
+use alloc::{borrow::ToOwned, boxed::Box, str, string::String, vec, vec::Vec};
 use core::mem::size_of;
 
 use bytemuck::{Pod, Zeroable};
 
-use wie_backend::Database;
+use wie_backend::{Database, RecordId};
 use wie_util::{read_generic, write_generic, Result};
 
 use crate::{context::WIPICContext, WIPICWord};
@@ -12,6 +12,8 @@ use crate::{context::WIPICContext, WIPICWord};
 #[repr(C)]
 struct DatabaseHandle {
     name: [u8; 32], // TODO hardcoded max size
+    record_size: i32,
+    mode: i32,
 }
 
 pub async fn open_database(context: &mut dyn WIPICContext, name: String, record_size: i32, create: i32, mode: i32) -> Result<i32> {
@@ -23,7 +25,11 @@ pub async fn open_database(context: &mut dyn WIPICContext, name: String, record_
     }
 
     let name_bytes = name.as_bytes();
-    let mut handle = DatabaseHandle { name: [0; 32] };
+    let mut handle = DatabaseHandle {
+        name: [0; 32],
+        record_size,
+        mode,
+    };
 
     handle.name[..name_bytes.len()].copy_from_slice(name_bytes);
 
@@ -70,11 +76,31 @@ pub async fn write_record_single(context: &mut dyn WIPICContext, db_id: i32, buf
     context.read_bytes(buf_ptr, &mut buf)?;
     let mut db = get_database_from_db_id(context, db_id);
 
-    db.set(1, &buf);
+    if !db.set(1, &buf) {
+        return Ok(-1); // M_E_ERROR
+    }
 
     Ok(1)
 }
 
+pub async fn update_record(context: &mut dyn WIPICContext, db_id: i32, rec_id: i32, buf_ptr: WIPICWord, buf_len: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_dbUpdateRecord({:#x}, {}, {:#x}, {})", db_id, rec_id, buf_ptr, buf_len);
+
+    let mut db = get_database_from_db_id(context, db_id);
+    if db.get_record_size(rec_id as _).is_none() {
+        return Ok(-22); // M_E_BADRECID
+    }
+
+    let mut buf = vec![0; buf_len as _];
+    context.read_bytes(buf_ptr, &mut buf)?;
+
+    if !db.set(rec_id as _, &buf) {
+        return Ok(-1); // M_E_ERROR
+    }
+
+    Ok(0) // success
+}
+
 pub async fn delete_record(context: &mut dyn WIPICContext, db_id: i32, rec_id: i32) -> Result<i32> {
     tracing::debug!("MC_dbDeleteRecord({:#x}, {})", db_id, rec_id);
 
@@ -128,6 +154,120 @@ pub async fn select_record(context: &mut dyn WIPICContext, db_id: i32, rec_id: i
     }
 }
 
+// TODO check signature against sdk. we assume comparator takes pointers to two records and returns negative, zero or positive
+//      like strcmp, and ids are written to buffer in ascending order of comparator
+// writes record ids sorted by guest comparator into buffer, returns number of records
+pub async fn sort_records(context: &mut dyn WIPICContext, db_id: i32, fn_compare: WIPICWord, buf_ptr: WIPICWord, buf_len: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_dbSortRecords({:#x}, {:#x}, {:#x}, {})", db_id, fn_compare, buf_ptr, buf_len);
+
+    let db = get_database_from_db_id(context, db_id);
+    let data = db
+        .get_record_ids()
+        .into_iter()
+        .map(|id| (id, db.get(id).unwrap_or_default()))
+        .collect::<Vec<_>>();
+    drop(db);
+    if data.len() * size_of::<WIPICWord>() > buf_len as usize {
+        return Ok(-18); // M_E_SHORTBUF
+    }
+
+    // records are freed even if comparator or copying fails
+    let mut records = Vec::with_capacity(data.len());
+    let result = sort_with_guest_comparator(context, data, fn_compare, &mut records).await;
+    for &(_, ptr, size) in &records {
+        context.free_raw(ptr, size)?;
+    }
+    let sorted = result?;
+
+    let mut cursor = 0;
+    for &id in &sorted {
+        write_generic(context, buf_ptr + cursor, id)?;
+        cursor += size_of::<WIPICWord>() as u32;
+    }
+
+    Ok(sorted.len() as _)
+}
+
+// comparator is guest code, so each record is copied into guest memory. `records` gets (id, pointer, size) of each copy
+async fn sort_with_guest_comparator(
+    context: &mut dyn WIPICContext,
+    data: Vec<(RecordId, Vec<u8>)>,
+    fn_compare: WIPICWord,
+    records: &mut Vec<(RecordId, WIPICWord, WIPICWord)>,
+) -> Result<Vec<RecordId>> {
+    for (id, data) in data {
+        let size = data.len().max(1) as WIPICWord;
+
+        let ptr = context.alloc_raw(size)?;
+        records.push((id, ptr, size));
+        context.write_bytes(ptr, &data)?;
+    }
+
+    // insertion sort, as comparator can't be called from sort_by
+    let mut sorted: Vec<(RecordId, WIPICWord)> = Vec::with_capacity(records.len());
+    for &(id, ptr, _) in records.iter() {
+        let mut index = sorted.len();
+        while index > 0 {
+            let result = context.call_function(fn_compare, &[sorted[index - 1].1, ptr]).await? as i32;
+            if result <= 0 {
+                break;
+            }
+            index -= 1;
+        }
+        sorted.insert(index, (id, ptr));
+    }
+
+    Ok(sorted.into_iter().map(|(id, _)| id).collect())
+}
+
+pub async fn get_access_mode(context: &mut dyn WIPICContext, db_id: i32) -> Result<i32> {
+    tracing::debug!("MC_dbGetAccessMode({:#x})", db_id);
+
+    let handle: DatabaseHandle = read_generic(context, db_id as _)?;
+
+    Ok(handle.mode)
+}
+
+pub async fn get_number_of_records(context: &mut dyn WIPICContext, db_id: i32) -> Result<i32> {
+    tracing::debug!("MC_dbGetNumberOfRecords({:#x})", db_id);
+
+    let db = get_database_from_db_id(context, db_id);
+
+    Ok(db.count() as _)
+}
+
+pub async fn get_record_size(context: &mut dyn WIPICContext, db_id: i32, rec_id: i32) -> Result<i32> {
+    tracing::debug!("MC_dbGetRecordSize({:#x}, {})", db_id, rec_id);
+
+    let db = get_database_from_db_id(context, db_id);
+
+    if let Some(x) = db.get_record_size(rec_id as _) {
+        Ok(x as _)
+    } else {
+        Ok(-22) // M_E_BADRECID
+    }
+}
+
+// writes null terminated names of databases into buffer, returns number of databases
+pub async fn list_databases(context: &mut dyn WIPICContext, buf_ptr: WIPICWord, buf_len: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_dbListDataBases({:#x}, {})", buf_ptr, buf_len);
+
+    let app_id = context.system().app_id().to_owned();
+    let names = context.system().platform().database_repository().list(&app_id);
+
+    let mut buf = vec![];
+    for name in &names {
+        buf.extend_from_slice(name.as_bytes());
+        buf.push(0);
+    }
+    if buf.len() > buf_len as usize {
+        return Ok(-18); // M_E_SHORTBUF
+    }
+    context.write_bytes(buf_ptr, &buf)?;
+
+    Ok(names.len() as _)
+}
+
 pub async fn unk16(_context: &mut dyn WIPICContext) -> Result<i32> {
     tracing::warn!("stub MC_dbUnk16()");
 
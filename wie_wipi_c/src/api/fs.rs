@@ -0,0 +1,387 @@
+use alloc::{string::String, vec};
+
+use bytemuck::{Pod, Zeroable};
+
+use wie_backend::{FileHandle, FilesystemError};
+use wie_util::{write_generic, Result};
+
+use crate::{context::WIPICContext, WIPICWord};
+
+// M_E_* error codes, shared with other kernel apis like misc.rs
+const M_E_SUCCESS: i32 = 0;
+const M_E_ERROR: i32 = -1;
+const M_E_BADFD: i32 = -5;
+const M_E_ACCESS: i32 = -7;
+const M_E_INVALID: i32 = -9;
+const M_E_EXIST: i32 = -10;
+const M_E_NOENT: i32 = -12;
+const M_E_NOSPACE: i32 = -13;
+const M_E_ISDIR: i32 = -14;
+const M_E_NOTDIR: i32 = -15;
+const M_E_LONGNAME: i32 = -16;
+const M_E_NOTEMPTY: i32 = -17;
+const M_E_SHORTBUF: i32 = -18;
+
+const MC_FILE_OPEN_RDONLY: i32 = 1;
+const MC_FILE_OPEN_WRONLY: i32 = 2;
+const MC_FILE_OPEN_WRTRUNC: i32 = 3;
+const MC_FILE_OPEN_RDWR: i32 = 4;
+
+const MC_FILE_SEEK_SET: i32 = 0;
+const MC_FILE_SEEK_CUR: i32 = 1;
+const MC_FILE_SEEK_END: i32 = 2;
+
+const MC_FILE_ATTR_FILE: WIPICWord = 1;
+const MC_FILE_ATTR_DIR: WIPICWord = 2;
+
+const MAX_NAME_LENGTH: usize = 64;
+
+#[derive(Pod, Zeroable, Copy, Clone)]
+#[repr(C)]
+struct FileInfo {
+    attrib: WIPICWord,
+    creation_time: WIPICWord,
+    size: WIPICWord,
+    reserved: WIPICWord,
+}
+
+pub async fn open(context: &mut dyn WIPICContext, name: String, flag: i32, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsOpen({}, {}, {})", name, flag, mode);
+
+    // TODO handle mode, which selects private or shared area
+    if name.len() >= MAX_NAME_LENGTH {
+        return Ok(M_E_LONGNAME);
+    }
+
+    let mut filesystem = context.system().filesystem();
+    let result = match flag {
+        MC_FILE_OPEN_RDONLY | MC_FILE_OPEN_RDWR => match filesystem.metadata(&name) {
+            Some(x) if x.is_directory => Err(FilesystemError::IsDirectory),
+            Some(_) => Ok(()),
+            None => Err(FilesystemError::NotFound),
+        },
+        MC_FILE_OPEN_WRONLY if filesystem.exists(&name) => Ok(()),
+        MC_FILE_OPEN_WRONLY | MC_FILE_OPEN_WRTRUNC => filesystem.write(&name, &[]),
+        _ => return Ok(M_E_INVALID),
+    };
+    drop(filesystem);
+
+    if let Err(x) = result {
+        return Ok(error_code(x));
+    }
+
+    let handle = FileHandle {
+        path: name,
+        position: 0,
+        readable: flag == MC_FILE_OPEN_RDONLY || flag == MC_FILE_OPEN_RDWR,
+        writable: flag != MC_FILE_OPEN_RDONLY,
+    };
+    let fd = context.system().filesystem().open_handle(handle);
+
+    Ok(fd as _)
+}
+
+pub async fn close(context: &mut dyn WIPICContext, fd: i32) -> Result<i32> {
+    tracing::debug!("MC_fsClose({})", fd);
+
+    if context.system().filesystem().close_handle(fd as _).is_none() {
+        return Ok(M_E_BADFD);
+    }
+
+    Ok(M_E_SUCCESS)
+}
+
+pub async fn read(context: &mut dyn WIPICContext, fd: i32, buf_ptr: WIPICWord, buf_len: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_fsRead({}, {:#x}, {})", fd, buf_ptr, buf_len);
+
+    let Some(handle) = get_handle(context, fd) else {
+        return Ok(M_E_BADFD);
+    };
+    if !handle.readable {
+        return Ok(M_E_ACCESS);
+    }
+
+    let Some(data) = context.system().filesystem().read(&handle.path) else {
+        return Ok(M_E_NOENT);
+    };
+
+    let start = (handle.position as usize).min(data.len());
+    let end = (start + buf_len as usize).min(data.len());
+    context.write_bytes(buf_ptr, &data[start..end])?;
+
+    set_position(context, fd, end as _);
+
+    Ok((end - start) as _)
+}
+
+pub async fn write(context: &mut dyn WIPICContext, fd: i32, buf_ptr: WIPICWord, buf_len: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_fsWrite({}, {:#x}, {})", fd, buf_ptr, buf_len);
+
+    let Some(handle) = get_handle(context, fd) else {
+        return Ok(M_E_BADFD);
+    };
+    if !handle.writable {
+        return Ok(M_E_ACCESS);
+    }
+
+    let mut buf = vec![0; buf_len as _];
+    context.read_bytes(buf_ptr, &mut buf)?;
+
+    let result = context.system().filesystem().write_at(&handle.path, handle.position, &buf);
+    if let Err(x) = result {
+        return Ok(error_code(x));
+    }
+
+    set_position(context, fd, handle.position + buf_len as u64);
+
+    Ok(buf_len as _)
+}
+
+pub async fn seek(context: &mut dyn WIPICContext, fd: i32, offset: i32, whence: i32) -> Result<i32> {
+    tracing::debug!("MC_fsSeek({}, {}, {})", fd, offset, whence);
+
+    let Some(handle) = get_handle(context, fd) else {
+        return Ok(M_E_BADFD);
+    };
+
+    let size = context.system().filesystem().metadata(&handle.path).map(|x| x.size as i64).unwrap_or(0);
+    let pos = match whence {
+        MC_FILE_SEEK_SET => offset as i64,
+        MC_FILE_SEEK_CUR => handle.position as i64 + offset as i64,
+        MC_FILE_SEEK_END => size + offset as i64,
+        _ => return Ok(M_E_INVALID),
+    };
+    if pos < 0 || pos > i32::MAX as i64 {
+        return Ok(M_E_INVALID);
+    }
+
+    set_position(context, fd, pos as _);
+
+    Ok(pos as _)
+}
+
+pub async fn tell(context: &mut dyn WIPICContext, fd: i32) -> Result<i32> {
+    tracing::debug!("MC_fsTell({})", fd);
+
+    let Some(handle) = get_handle(context, fd) else {
+        return Ok(M_E_BADFD);
+    };
+
+    Ok(handle.position as _)
+}
+
+pub async fn is_exist(context: &mut dyn WIPICContext, name: String, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsIsExist({}, {})", name, mode);
+
+    if context.system().filesystem().exists(&name) {
+        Ok(M_E_SUCCESS)
+    } else {
+        Ok(M_E_NOENT)
+    }
+}
+
+pub async fn file_attribute(context: &mut dyn WIPICContext, name: String, info_ptr: WIPICWord, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsFileAttribute({}, {:#x}, {})", name, info_ptr, mode);
+
+    let Some(metadata) = context.system().filesystem().metadata(&name) else {
+        return Ok(M_E_NOENT);
+    };
+
+    let info = FileInfo {
+        attrib: if metadata.is_directory { MC_FILE_ATTR_DIR } else { MC_FILE_ATTR_FILE },
+        // in seconds since epoch, as 32-bit field can't hold milliseconds
+        creation_time: metadata.created.map(|x| (x.raw() / 1000) as _).unwrap_or(0),
+        size: metadata.size as _,
+        reserved: 0,
+    };
+    write_generic(context, info_ptr, info)?;
+
+    Ok(M_E_SUCCESS)
+}
+
+pub async fn remove(context: &mut dyn WIPICContext, name: String, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsRemove({}, {})", name, mode);
+
+    let mut filesystem = context.system().filesystem();
+    let result = match filesystem.metadata(&name) {
+        Some(x) if x.is_directory => Err(FilesystemError::IsDirectory),
+        _ => filesystem.remove(&name),
+    };
+
+    Ok(result.map(|_| M_E_SUCCESS).unwrap_or_else(error_code))
+}
+
+pub async fn rename(context: &mut dyn WIPICContext, old_name: String, new_name: String, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsRename({}, {}, {})", old_name, new_name, mode);
+
+    let result = context.system().filesystem().rename(&old_name, &new_name);
+
+    Ok(result.map(|_| M_E_SUCCESS).unwrap_or_else(error_code))
+}
+
+pub async fn mkdir(context: &mut dyn WIPICContext, name: String, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsMkDir({}, {})", name, mode);
+
+    let result = context.system().filesystem().create_dir(&name);
+
+    Ok(result.map(|_| M_E_SUCCESS).unwrap_or_else(error_code))
+}
+
+pub async fn rmdir(context: &mut dyn WIPICContext, name: String, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsRmDir({}, {})", name, mode);
+
+    let mut filesystem = context.system().filesystem();
+    let result = match filesystem.metadata(&name) {
+        Some(x) if !x.is_directory => Err(FilesystemError::NotDirectory),
+        _ => filesystem.remove(&name),
+    };
+
+    Ok(result.map(|_| M_E_SUCCESS).unwrap_or_else(error_code))
+}
+
+// writes null terminated names of entries into buffer, returns number of entries
+pub async fn list(context: &mut dyn WIPICContext, name: String, buf_ptr: WIPICWord, buf_len: WIPICWord, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsList({}, {:#x}, {}, {})", name, buf_ptr, buf_len, mode);
+
+    let entries = match context.system().filesystem().list(&name) {
+        Ok(x) => x,
+        Err(x) => return Ok(error_code(x)),
+    };
+
+    let mut buf = vec![];
+    for entry in &entries {
+        buf.extend_from_slice(entry.as_bytes());
+        buf.push(0);
+    }
+    if buf.len() > buf_len as usize {
+        return Ok(M_E_SHORTBUF);
+    }
+    context.write_bytes(buf_ptr, &buf)?;
+
+    Ok(entries.len() as _)
+}
+
+pub async fn available(context: &mut dyn WIPICContext, mode: i32) -> Result<i32> {
+    tracing::debug!("MC_fsAvailable({})", mode);
+
+    let available = context.system().filesystem().available_space();
+
+    Ok(available.min(i32::MAX as u64) as _)
+}
+
+fn get_handle(context: &mut dyn WIPICContext, fd: i32) -> Option<FileHandle> {
+    context.system().filesystem().handle(fd as _).cloned()
+}
+
+fn set_position(context: &mut dyn WIPICContext, fd: i32, position: u64) {
+    if let Some(x) = context.system().filesystem().handle(fd as _) {
+        x.position = position;
+    }
+}
+
+fn error_code(error: FilesystemError) -> i32 {
+    match error {
+        FilesystemError::NotFound => M_E_NOENT,
+        FilesystemError::AlreadyExists => M_E_EXIST,
+        FilesystemError::ReadOnly => M_E_ACCESS,
+        FilesystemError::IsDirectory => M_E_ISDIR,
+        FilesystemError::NotDirectory => M_E_NOTDIR,
+        FilesystemError::DirectoryNotEmpty => M_E_NOTEMPTY,
+        FilesystemError::NoSpace => M_E_NOSPACE,
+        FilesystemError::StorageFailure => M_E_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use wie_util::{read_generic, ByteRead, ByteWrite, Result};
+
+    use crate::{context::test::TestContext, WIPICContext};
+
+    use super::{
+        close, file_attribute, list, mkdir, open, read, seek, tell, write, FileInfo, MC_FILE_ATTR_FILE, MC_FILE_OPEN_RDONLY, MC_FILE_OPEN_WRTRUNC,
+        MC_FILE_SEEK_SET, M_E_ACCESS, M_E_BADFD, M_E_SHORTBUF, M_E_SUCCESS,
+    };
+
+    #[futures_test::test]
+    async fn test_read_write() -> Result<()> {
+        let mut context = TestContext::new();
+        let buf = context.alloc_raw(16)?;
+
+        let fd = open(&mut context, "test.dat".into(), MC_FILE_OPEN_WRTRUNC, 0).await?;
+        assert!(fd > 0);
+
+        context.write_bytes(buf, b"hello world")?;
+        assert_eq!(write(&mut context, fd, buf, 11).await?, 11);
+        assert_eq!(tell(&mut context, fd).await?, 11);
+        assert_eq!(read(&mut context, fd, buf, 11).await?, M_E_ACCESS);
+
+        assert_eq!(seek(&mut context, fd, 6, MC_FILE_SEEK_SET).await?, 6);
+        context.write_bytes(buf, b"WORLD")?;
+        assert_eq!(write(&mut context, fd, buf, 5).await?, 5);
+        assert_eq!(close(&mut context, fd).await?, M_E_SUCCESS);
+
+        let fd = open(&mut context, "test.dat".into(), MC_FILE_OPEN_RDONLY, 0).await?;
+        assert_eq!(seek(&mut context, fd, 3, MC_FILE_SEEK_SET).await?, 3);
+
+        let mut result = [0; 16];
+        assert_eq!(read(&mut context, fd, buf, 16).await?, 8);
+        context.read_bytes(buf, &mut result[..8])?;
+        assert_eq!(&result[..8], b"lo WORLD");
+        assert_eq!(read(&mut context, fd, buf, 16).await?, 0);
+
+        assert_eq!(close(&mut context, fd).await?, M_E_SUCCESS);
+
+        // closed and never opened handles
+        assert_eq!(close(&mut context, fd).await?, M_E_BADFD);
+        assert_eq!(read(&mut context, 0x1234, buf, 16).await?, M_E_BADFD);
+        assert_eq!(tell(&mut context, -1).await?, M_E_BADFD);
+
+        Ok(())
+    }
+
+    #[futures_test::test]
+    async fn test_list() -> Result<()> {
+        let mut context = TestContext::new();
+        let buf = context.alloc_raw(32)?;
+
+        assert_eq!(mkdir(&mut context, "save".into(), 0).await?, M_E_SUCCESS);
+        for name in ["save/b.dat", "save/a.dat"] {
+            let fd = open(&mut context, name.into(), MC_FILE_OPEN_WRTRUNC, 0).await?;
+            close(&mut context, fd).await?;
+        }
+
+        assert_eq!(list(&mut context, "save".into(), buf, 32, 0).await?, 2);
+        let mut result = vec![0; 12];
+        context.read_bytes(buf, &mut result)?;
+        assert_eq!(result, b"a.dat\0b.dat\0");
+
+        assert_eq!(list(&mut context, "save".into(), buf, 11, 0).await?, M_E_SHORTBUF);
+
+        Ok(())
+    }
+
+    #[futures_test::test]
+    async fn test_file_attribute() -> Result<()> {
+        let mut context = TestContext::new();
+        let buf = context.alloc_raw(16)?;
+
+        let fd = open(&mut context, "test.dat".into(), MC_FILE_OPEN_WRTRUNC, 0).await?;
+        context.write_bytes(buf, b"data")?;
+        write(&mut context, fd, buf, 4).await?;
+        close(&mut context, fd).await?;
+
+        let info_ptr = context.alloc_raw(size_of::<FileInfo>() as _)?;
+        assert_eq!(file_attribute(&mut context, "test.dat".into(), info_ptr, 0).await?, M_E_SUCCESS);
+
+        let info: FileInfo = read_generic(&context, info_ptr)?;
+        let created = context.system().filesystem().metadata("test.dat").unwrap().created.unwrap();
+        assert_eq!(info.attrib, MC_FILE_ATTR_FILE);
+        assert_eq!(info.size, 4);
+        assert_eq!(info.creation_time, (created.raw() / 1000) as u32);
+
+        Ok(())
+    }
+}
@@ -2,11 +2,15 @@ mod framebuffer;
 mod grp_context;
 mod image;
 
+use alloc::{boxed::Box, vec::Vec};
 use core::mem::size_of;
 
 use bytemuck::Zeroable;
 
-use wie_backend::canvas::{Color, PixelType, Rgb8Pixel};
+use wie_backend::{
+    canvas::{is_color_key, Color, CompositeMode, Image, PixelType, Rgb8Pixel},
+    Rect,
+};
 use wie_util::{read_generic, write_generic, Result};
 
 use crate::{context::WIPICContext, WIPICMemoryId, WIPICWord};
@@ -17,18 +21,13 @@ use self::{
     image::WIPICImage,
 };
 
-const FRAMEBUFFER_DEPTH: u32 = 16; // XXX hardcode to 16bpp as some game requires 16bpp framebuffer
-
 pub async fn get_screen_framebuffer(context: &mut dyn WIPICContext, a0: WIPICWord) -> Result<WIPICMemoryId> {
     tracing::debug!("MC_grpGetScreenFrameBuffer({:#x})", a0);
 
-    let (width, height) = {
-        let mut platform = context.system().platform();
-        let screen = platform.screen();
-        (screen.width(), screen.height())
-    };
+    let (width, height) = context.system().display_size();
+    let depth = context.system().display_depth();
 
-    let framebuffer = WIPICFramebuffer::new(context, width, height, FRAMEBUFFER_DEPTH)?;
+    let framebuffer = WIPICFramebuffer::new(context, width, height, depth)?;
 
     let memory = context.alloc(size_of::<WIPICFramebuffer>() as WIPICWord)?;
     write_generic(context, context.data_ptr(memory)?, framebuffer)?;
@@ -60,14 +59,15 @@ pub async fn set_context(context: &mut dyn WIPICContext, p_grp_ctx: WIPICWord, o
         }
         WIPICGraphicsContextIdx::TransPixelIdx => {
             grp_ctx.transpxl = pv;
+            grp_ctx.set(op, true);
         }
         WIPICGraphicsContextIdx::AlphaIdx => {
             grp_ctx.alpha = pv;
-            // grp_ctx.pixel_op_func_ptr = todo!();
-            // grp_ctx.param1 = todo!();
+            grp_ctx.set(op, pv < 0xff);
         }
         WIPICGraphicsContextIdx::PixelopIdx => {
             grp_ctx.pixel_op_func_ptr = pv;
+            grp_ctx.set(op, pv != 0);
         }
         WIPICGraphicsContextIdx::PixelParam1Idx => {
             grp_ctx.param1 = pv;
@@ -78,6 +78,9 @@ pub async fn set_context(context: &mut dyn WIPICContext, p_grp_ctx: WIPICWord, o
         WIPICGraphicsContextIdx::StyleIdx => {
             grp_ctx.style = pv;
         }
+        WIPICGraphicsContextIdx::XorModeIdx => {
+            grp_ctx.set(op, pv != 0);
+        }
         WIPICGraphicsContextIdx::OffsetIdx => {
             grp_ctx.offset = read_generic(context, pv)?;
         }
@@ -94,21 +97,44 @@ pub async fn put_pixel(context: &mut dyn WIPICContext, dst_fb: WIPICMemoryId, x:
     tracing::debug!("MC_grpPutPixel({:#x}, {}, {}, {:?})", dst_fb.0, x, y, p_gctx);
 
     let framebuffer: WIPICFramebuffer = read_generic(context, context.data_ptr(dst_fb)?)?;
-    let gctx: WIPICGraphicsContext = read_generic(context, p_gctx)?;
+    let gctx = WIPICGraphicsContext::read(context, p_gctx)?;
 
-    let mut canvas = framebuffer.canvas(context)?;
-    canvas.put_pixel(x as _, y as _, Rgb8Pixel::to_color(gctx.fgpxl));
-    Ok(())
+    if gctx.pixel_op().is_some() {
+        return apply_pixel_op(context, &framebuffer, x, y, 1, 1, None, 0, 0, &gctx).await;
+    }
+
+    framebuffer.draw(context, |canvas| {
+        canvas.fill_rect(
+            x as _,
+            y as _,
+            1,
+            1,
+            Rgb8Pixel::to_color(gctx.fgpxl),
+            gctx.composite_mode(CompositeMode::Copy),
+        )
+    })
 }
 
 pub async fn fill_rect(context: &mut dyn WIPICContext, dst_fb: WIPICMemoryId, x: i32, y: i32, w: i32, h: i32, p_gctx: WIPICWord) -> Result<()> {
     tracing::debug!("MC_grpFillRect({:#x}, {}, {}, {}, {}, {:#x})", dst_fb.0, x, y, w, h, p_gctx);
 
     let framebuffer: WIPICFramebuffer = read_generic(context, context.data_ptr(dst_fb)?)?;
-    let gctx: WIPICGraphicsContext = read_generic(context, p_gctx)?;
-    let mut canvas = framebuffer.canvas(context)?;
-    canvas.fill_rect(x as _, y as _, w as _, h as _, Rgb8Pixel::to_color(gctx.fgpxl));
-    Ok(())
+    let gctx = WIPICGraphicsContext::read(context, p_gctx)?;
+
+    if gctx.pixel_op().is_some() {
+        return apply_pixel_op(context, &framebuffer, x, y, w, h, None, 0, 0, &gctx).await;
+    }
+
+    framebuffer.draw(context, |canvas| {
+        canvas.fill_rect(
+            x as _,
+            y as _,
+            w as _,
+            h as _,
+            Rgb8Pixel::to_color(gctx.fgpxl),
+            gctx.composite_mode(CompositeMode::Copy),
+        )
+    })
 }
 
 pub async fn create_image(
@@ -157,33 +183,14 @@ pub async fn draw_image(
 
     let framebuffer: WIPICFramebuffer = read_generic(context, context.data_ptr(framebuffer)?)?;
     let image: WIPICImage = read_generic(context, context.data_ptr(image)?)?;
+    let gctx = WIPICGraphicsContext::read(context, graphics_context)?;
 
-    let src_image = image.img.image(context)?;
-    let mut canvas = framebuffer.canvas(context)?;
-
-    canvas.draw(dx as _, dy as _, w as _, h as _, &*src_image, sx as _, sy as _);
-
-    Ok(())
+    let src_image = image.image(context)?;
+    draw_with_context(context, &framebuffer, dx, dy, w, h, src_image, sx, sy, &gctx).await
 }
 
-pub async fn flush(
-    context: &mut dyn WIPICContext,
-    a0: WIPICWord,
-    framebuffer: WIPICMemoryId,
-    a2: WIPICWord,
-    a3: WIPICWord,
-    a4: WIPICWord,
-    a5: WIPICWord,
-) -> Result<()> {
-    tracing::debug!(
-        "MC_grpFlushLcd({:#x}, {:#x}, {:#x}, {:#x}, {:#x}, {:#x})",
-        a0,
-        framebuffer.0,
-        a2,
-        a3,
-        a4,
-        a5
-    );
+pub async fn flush(context: &mut dyn WIPICContext, a0: WIPICWord, framebuffer: WIPICMemoryId, x: i32, y: i32, w: i32, h: i32) -> Result<()> {
+    tracing::debug!("MC_grpFlushLcd({:#x}, {:#x}, {}, {}, {}, {})", a0, framebuffer.0, x, y, w, h);
 
     let framebuffer: WIPICFramebuffer = read_generic(context, context.data_ptr(framebuffer)?)?;
 
@@ -192,7 +199,11 @@ pub async fn flush(
     let mut platform = context.system().platform();
     let screen = platform.screen();
 
-    screen.paint(&*src_canvas);
+    // flush whole screen if area is empty or out of bounds
+    let region = Rect::clipped(x, y, w, h, src_canvas.width(), src_canvas.height())
+        .unwrap_or_else(|| Rect::new(0, 0, src_canvas.width(), src_canvas.height()));
+
+    screen.paint_region(&*src_canvas, region);
 
     Ok(())
 }
@@ -218,21 +229,10 @@ pub async fn get_display_info(context: &mut dyn WIPICContext, reserved: WIPICWor
 
     assert_eq!(reserved, 0);
 
-    let mut platform = context.system().platform();
-    let screen = platform.screen();
-
-    let info = WIPICDisplayInfo {
-        bpp: FRAMEBUFFER_DEPTH,
-        depth: 16,
-        width: screen.width(),
-        height: screen.height(),
-        bpl: 2 * screen.width(),
-        color_type: 1, // 1==MC_GRP_DIRECT_COLOR_TYPE
-        red_mask: 0xf800,
-        green_mask: 0x7e0,
-        blue_mask: 0x1f,
-    };
-    drop(platform);
+    let (width, height) = context.system().display_size();
+    let depth = context.system().display_depth();
+    let indexed = context.system().display_palette().is_some();
+    let info = WIPICDisplayInfo::new(width, height, depth, indexed)?;
 
     write_generic(context, out_ptr, info)?;
     Ok(1)
@@ -259,19 +259,17 @@ pub async fn copy_area(
     }
 
     let framebuffer: WIPICFramebuffer = read_generic(context, context.data_ptr(dst)?)?;
+    let gctx = WIPICGraphicsContext::read(context, pgc)?;
 
     let image = framebuffer.image(context)?;
-    let mut canvas = framebuffer.canvas(context)?;
-
-    canvas.draw(dx as _, dy as _, w as _, h as _, &*image, x as _, y as _);
-
-    Ok(())
+    draw_with_context(context, &framebuffer, dx, dy, w, h, image, x, y, &gctx).await
 }
 
 pub async fn create_offscreen_framebuffer(context: &mut dyn WIPICContext, w: i32, h: i32) -> Result<WIPICMemoryId> {
     tracing::debug!("MC_grpCreateOffScreenFrameBuffer({}, {})", w, h);
 
-    let framebuffer = WIPICFramebuffer::new(context, w as _, h as _, FRAMEBUFFER_DEPTH)?;
+    let depth = context.system().display_depth();
+    let framebuffer = WIPICFramebuffer::new(context, w as _, h as _, depth)?;
 
     let memory = context.alloc(size_of::<WIPICFramebuffer>() as WIPICWord)?;
     write_generic(context, context.data_ptr(memory)?, framebuffer)?;
@@ -307,13 +305,105 @@ pub async fn copy_frame_buffer(
 
     let src_framebuffer: WIPICFramebuffer = read_generic(context, context.data_ptr(src)?)?;
     let dst_framebuffer: WIPICFramebuffer = read_generic(context, context.data_ptr(dst)?)?;
+    let gctx = WIPICGraphicsContext::read(context, pgc)?;
 
     let src_image = src_framebuffer.image(context)?;
-    let mut dst_canvas = dst_framebuffer.canvas(context)?;
+    draw_with_context(context, &dst_framebuffer, dx, dy, w, h, src_image, sx, sy, &gctx).await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn draw_with_context(
+    context: &mut dyn WIPICContext,
+    framebuffer: &WIPICFramebuffer,
+    dx: i32,
+    dy: i32,
+    w: i32,
+    h: i32,
+    src: Box<dyn Image>,
+    sx: i32,
+    sy: i32,
+    gctx: &WIPICGraphicsContext,
+) -> Result<()> {
+    if gctx.pixel_op().is_some() {
+        return apply_pixel_op(context, framebuffer, dx, dy, w, h, Some(src), sx, sy, gctx).await;
+    }
 
-    dst_canvas.draw(dx as _, dy as _, w as _, h as _, &*src_image, sx as _, sy as _);
+    framebuffer.draw(context, |canvas| {
+        canvas.draw(
+            dx as _,
+            dy as _,
+            w as _,
+            h as _,
+            &*src,
+            sx as _,
+            sy as _,
+            gctx.composite_mode(CompositeMode::SourceOver),
+            gctx.color_key(),
+        )
+    })
+}
 
-    Ok(())
+// calls application-provided MC_GrpPixelOpProc for each pixel. if `src` is none, foreground pixel is used as source.
+#[allow(clippy::too_many_arguments)]
+async fn apply_pixel_op(
+    context: &mut dyn WIPICContext,
+    framebuffer: &WIPICFramebuffer,
+    dx: i32,
+    dy: i32,
+    w: i32,
+    h: i32,
+    src: Option<Box<dyn Image>>,
+    sx: i32,
+    sy: i32,
+    gctx: &WIPICGraphicsContext,
+) -> Result<()> {
+    let pixel_op = gctx.pixel_op().unwrap();
+    let dst = framebuffer.image(context)?;
+    let color_key = gctx.color_key();
+
+    let mut pixels = Vec::new();
+    for y in 0..h.max(0) {
+        for x in 0..w.max(0) {
+            let (px, py) = (dx + x, dy + y);
+            if px < 0 || py < 0 || px as u32 >= dst.width() || py as u32 >= dst.height() {
+                continue;
+            }
+
+            let src_color = match &src {
+                Some(src) => {
+                    let (src_x, src_y) = (sx + x, sy + y);
+                    if src_x < 0 || src_y < 0 || src_x as u32 >= src.width() || src_y as u32 >= src.height() {
+                        continue;
+                    }
+
+                    let color = src.get_pixel(src_x as _, src_y as _);
+                    if color_key.is_some_and(|color_key| is_color_key(&**src, color, color_key)) {
+                        continue;
+                    }
+                    color
+                }
+                None => Rgb8Pixel::to_color(gctx.fgpxl),
+            };
+            let dst_color = dst.get_pixel(px as _, py as _);
+
+            pixels.push((px as u32, py as u32, Rgb8Pixel::from_color(src_color), Rgb8Pixel::from_color(dst_color)));
+        }
+    }
+    drop(src);
+    drop(dst);
+
+    let mut results = Vec::with_capacity(pixels.len());
+    for (x, y, src_pixel, dst_pixel) in pixels {
+        let result = context.call_function(pixel_op, &[src_pixel, dst_pixel, gctx.param1]).await?;
+
+        results.push((x, y, Rgb8Pixel::to_color(result)));
+    }
+
+    framebuffer.draw(context, |canvas| {
+        for (x, y, color) in results {
+            canvas.put_pixel(x, y, color);
+        }
+    })
 }
 
 pub async fn get_font(_: &mut dyn WIPICContext, face: i32, size: i32, style: i32) -> Result<i32> {
@@ -327,3 +417,66 @@ pub async fn get_font_height(_: &mut dyn WIPICContext, font: i32) -> Result<i32>
 
     Ok(10)
 }
+
+pub async fn show_annunciator(context: &mut dyn WIPICContext, show: WIPICWord) -> Result<()> {
+    tracing::debug!("OEMC_grpShowAnnunciator({})", show);
+
+    context.system().update_indicators(|x| x.annunciator_visible = show != 0);
+
+    Ok(())
+}
+
+pub async fn set_annunciator_icon(context: &mut dyn WIPICContext, icon: WIPICWord, state: WIPICWord) -> Result<()> {
+    tracing::debug!("OEMC_grpSetAnnunciatorIcon({}, {})", icon, state);
+
+    context.system().update_indicators(|x| {
+        x.annunciator_icons.insert(icon, state);
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use core::mem::size_of;
+
+    use wie_backend::HandsetProfile;
+    use wie_util::{read_generic, Result};
+
+    use test_utils::TestPlatform;
+
+    use crate::{context::test::TestContext, WIPICContext};
+
+    use super::{get_display_info, set_annunciator_icon, show_annunciator, WIPICDisplayInfo};
+
+    #[futures_test::test]
+    async fn test_annunciator() -> Result<()> {
+        let platform = TestPlatform::with_handset_profile(HandsetProfile {
+            annunciator_height: 16,
+            led_count: 1,
+        });
+        let indicators = platform.indicators();
+        let mut context = TestContext::with_platform(platform);
+
+        // area is reserved even before annunciator is shown
+        let ptr_display_info = context.alloc_raw(size_of::<WIPICDisplayInfo>() as _)?;
+        get_display_info(&mut context, 0, ptr_display_info).await?;
+        let display_info: WIPICDisplayInfo = read_generic(&context, ptr_display_info)?;
+        assert_eq!((display_info.width, display_info.height), (240, 304));
+
+        show_annunciator(&mut context, 1).await?;
+        set_annunciator_icon(&mut context, 3, 2).await?;
+
+        {
+            let indicators = indicators.lock();
+            let indicators = indicators.as_ref().unwrap();
+            assert!(indicators.annunciator_visible);
+            assert_eq!(indicators.annunciator_icons.get(&3), Some(&2));
+        }
+
+        show_annunciator(&mut context, 0).await?;
+        assert!(!indicators.lock().as_ref().unwrap().annunciator_visible);
+
+        Ok(())
+    }
+}
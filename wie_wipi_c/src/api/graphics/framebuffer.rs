@@ -1,13 +1,19 @@
-use alloc::{boxed::Box, vec, vec::Vec};
-use core::ops::{Deref, DerefMut};
+use alloc::{boxed::Box, format, vec, vec::Vec};
 
-use bytemuck::{pod_collect_to_vec, Pod, Zeroable};
+use bytemuck::{Pod, Zeroable};
+use spin::Mutex;
 
-use wie_backend::canvas::{ArgbPixel, Canvas, Image, ImageBufferCanvas, Rgb565Pixel, VecImageBuffer};
-use wie_util::Result;
+use wie_backend::canvas::{
+    ArgbPixel, BitmaskImageBuffer, Canvas, Color, Image, ImageBufferCanvas, ImageMemory, IndexedImageBuffer, MemoryImageBuffer, Rgb332Pixel,
+    Rgb444Pixel, Rgb565Pixel,
+};
+use wie_util::{Result, WieError};
 
 use crate::{context::WIPICContext, WIPICMemoryId, WIPICWord};
 
+pub const MC_GRP_DIRECT_COLOR_TYPE: WIPICWord = 1;
+pub const MC_GRP_INDEXED_COLOR_TYPE: WIPICWord = 2;
+
 /// MC_GrpDisplayInfo
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -23,6 +29,32 @@ pub struct WIPICDisplayInfo {
     pub green_mask: WIPICWord,
 }
 
+impl WIPICDisplayInfo {
+    /// Indexed color displays have no color masks, as pixels are indices into the display palette.
+    pub fn new(width: WIPICWord, height: WIPICWord, depth: WIPICWord, indexed: bool) -> Result<Self> {
+        let (red_mask, green_mask, blue_mask) = match depth {
+            8 if indexed => (0, 0, 0),
+            8 => (0xe0, 0x1c, 0x3),
+            12 => (0xf00, 0xf0, 0xf),
+            16 => (0xf800, 0x7e0, 0x1f),
+            32 => (0xff0000, 0xff00, 0xff),
+            _ => return Err(WieError::Unimplemented(format!("Unsupported display depth: {}", depth))),
+        };
+
+        Ok(Self {
+            bpp: WIPICFramebuffer::bytes_per_pixel(depth) * 8,
+            depth,
+            width,
+            height,
+            bpl: WIPICFramebuffer::bytes_per_line(width, depth),
+            color_type: if indexed { MC_GRP_INDEXED_COLOR_TYPE } else { MC_GRP_DIRECT_COLOR_TYPE },
+            red_mask,
+            blue_mask,
+            green_mask,
+        })
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct WIPICFramebuffer {
@@ -45,35 +77,44 @@ impl WIPICFramebuffer {
     }
 
     pub fn new(context: &mut dyn WIPICContext, width: WIPICWord, height: WIPICWord, bpp: WIPICWord) -> Result<Self> {
-        let bytes_per_pixel = bpp / 8;
+        let bpl = Self::bytes_per_line(width, bpp);
 
-        let buf = context.alloc(width * height * bytes_per_pixel)?;
+        let buf = context.alloc(bpl * height)?;
 
         Ok(Self {
             width,
             height,
-            bpl: width * bytes_per_pixel,
-            bpp: bytes_per_pixel * 8,
+            bpl,
+            bpp,
             buf,
         })
     }
 
     pub fn from_image(context: &mut dyn WIPICContext, image: &dyn Image) -> Result<Self> {
-        let buf = context.alloc(image.width() * image.height() * image.bytes_per_pixel())?;
+        let bpp = image.bits_per_pixel();
+        let bpl = Self::bytes_per_line(image.width(), bpp);
 
-        context.write_bytes(context.data_ptr(buf)?, image.raw())?;
+        // raw data of bitmask images keeps their own line stride, so we copy line by line
+        let raw = image.raw();
+        let image_bpl = raw.len() as WIPICWord / image.height().max(1);
+
+        let buf = context.alloc(bpl * image.height())?;
+        let base = context.data_ptr(buf)?;
+        for (y, line) in raw.chunks(image_bpl.max(1) as _).enumerate() {
+            context.write_bytes(base + y as WIPICWord * bpl, &line[..bpl.min(image_bpl) as usize])?;
+        }
 
         Ok(Self {
             width: image.width(),
             height: image.height(),
-            bpl: image.width() * image.bytes_per_pixel(),
-            bpp: image.bytes_per_pixel() * 8,
+            bpl,
+            bpp,
             buf,
         })
     }
 
     pub fn data(&self, context: &dyn WIPICContext) -> Result<Vec<u8>> {
-        let size = self.width * self.height * self.bpp / 8;
+        let size = self.bpl * self.height;
         let mut buf = vec![0; size as _];
         context.read_bytes(context.data_ptr(self.buf)?, &mut buf)?;
 
@@ -82,44 +123,97 @@ impl WIPICFramebuffer {
 
     pub fn image(&self, context: &mut dyn WIPICContext) -> Result<Box<dyn Image>> {
         let data = self.data(context)?;
+        let palette = self.palette(context);
 
-        Ok(match self.bpp {
-            16 => Box::new(VecImageBuffer::<Rgb565Pixel>::from_raw(
-                self.width as _,
-                self.height as _,
-                pod_collect_to_vec(&data),
-            )),
-            32 => Box::new(VecImageBuffer::<ArgbPixel>::from_raw(
-                self.width as _,
-                self.height as _,
-                pod_collect_to_vec(&data),
-            )),
-            _ => unimplemented!("Unsupported pixel format: {}", self.bpp),
+        Ok(match (self.bpp, palette) {
+            (1, _) => Box::new(BitmaskImageBuffer::new(self.width, self.height, self.bpl, data)),
+            (8, Some(palette)) => Box::new(IndexedImageBuffer::new(self.width, self.height, self.bpl, palette, data)),
+            (8, None) => Box::new(MemoryImageBuffer::<Rgb332Pixel, _>::new(self.width, self.height, self.bpl, data)),
+            (12, _) => Box::new(MemoryImageBuffer::<Rgb444Pixel, _>::new(self.width, self.height, self.bpl, data)),
+            (16, _) => Box::new(MemoryImageBuffer::<Rgb565Pixel, _>::new(self.width, self.height, self.bpl, data)),
+            (32, _) => Box::new(MemoryImageBuffer::<ArgbPixel, _>::new(self.width, self.height, self.bpl, data)),
+            _ => return Err(WieError::Unimplemented(format!("Unsupported pixel format: {}", self.bpp))),
         })
     }
 
-    pub fn canvas<'a>(&'a self, context: &'a mut dyn WIPICContext) -> Result<FramebufferCanvas<'a>> {
-        let data = self.data(context)?;
+    /// Draws on canvas backed directly by framebuffer memory. Returns the first memory access error occurred while drawing.
+    pub fn draw<F>(&self, context: &mut dyn WIPICContext, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut dyn Canvas),
+    {
+        let palette = self.palette(context);
+        let error = Mutex::new(None);
+        let memory = FramebufferMemory {
+            base: context.data_ptr(self.buf)?,
+            context,
+            error: &error,
+        };
 
-        let canvas: Box<dyn Canvas> = match self.bpp {
-            16 => Box::new(ImageBufferCanvas::new(VecImageBuffer::<Rgb565Pixel>::from_raw(
-                self.width as _,
-                self.height as _,
-                pod_collect_to_vec(&data),
+        let mut canvas: Box<dyn Canvas + '_> = match (self.bpp, palette) {
+            (1, _) => Box::new(ImageBufferCanvas::new(BitmaskImageBuffer::new(self.width, self.height, self.bpl, memory))),
+            (8, Some(palette)) => Box::new(ImageBufferCanvas::new(IndexedImageBuffer::new(
+                self.width,
+                self.height,
+                self.bpl,
+                palette,
+                memory,
+            ))),
+            (8, None) => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<Rgb332Pixel, _>::new(
+                self.width,
+                self.height,
+                self.bpl,
+                memory,
+            ))),
+            (12, _) => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<Rgb444Pixel, _>::new(
+                self.width,
+                self.height,
+                self.bpl,
+                memory,
             ))),
-            32 => Box::new(ImageBufferCanvas::new(VecImageBuffer::<ArgbPixel>::from_raw(
-                self.width as _,
-                self.height as _,
-                pod_collect_to_vec(&data),
+            (16, _) => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<Rgb565Pixel, _>::new(
+                self.width,
+                self.height,
+                self.bpl,
+                memory,
             ))),
-            _ => unimplemented!("Unsupported pixel format: {}", self.bpp),
+            (32, _) => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<ArgbPixel, _>::new(
+                self.width,
+                self.height,
+                self.bpl,
+                memory,
+            ))),
+            _ => return Err(WieError::Unimplemented(format!("Unsupported pixel format: {}", self.bpp))),
         };
 
-        Ok(FramebufferCanvas {
-            framebuffer: self,
-            context,
-            canvas,
-        })
+        f(&mut *canvas);
+        drop(canvas);
+
+        match error.into_inner() {
+            Some(x) => Err(x),
+            None => Ok(()),
+        }
+    }
+
+    // 8-bit framebuffers index into the display palette on indexed color displays
+    fn palette(&self, context: &mut dyn WIPICContext) -> Option<Vec<Color>> {
+        if self.bpp != 8 {
+            return None;
+        }
+
+        context.system().display_palette()
+    }
+
+    /// Bytes used to store a pixel of given depth. 12-bit pixels are stored in 16 bits.
+    pub fn bytes_per_pixel(bpp: WIPICWord) -> WIPICWord {
+        bpp.div_ceil(8)
+    }
+
+    pub fn bytes_per_line(width: WIPICWord, bpp: WIPICWord) -> WIPICWord {
+        if bpp < 8 {
+            (width * bpp).div_ceil(8)
+        } else {
+            width * Self::bytes_per_pixel(bpp)
+        }
     }
 
     pub fn write(&self, context: &mut dyn WIPICContext, data: &[u8]) -> Result<()> {
@@ -127,28 +221,98 @@ impl WIPICFramebuffer {
     }
 }
 
-pub struct FramebufferCanvas<'a> {
-    framebuffer: &'a WIPICFramebuffer,
+struct FramebufferMemory<'a> {
     context: &'a mut dyn WIPICContext,
-    canvas: Box<dyn Canvas>,
+    base: WIPICWord,
+    error: &'a Mutex<Option<WieError>>,
 }
 
-impl Drop for FramebufferCanvas<'_> {
-    fn drop(&mut self) {
-        self.framebuffer.write(self.context, self.canvas.image().raw()).unwrap()
+impl FramebufferMemory<'_> {
+    // ImageMemory can't fail, so we keep the first error and let `WIPICFramebuffer::draw` return it
+    fn record(&self, result: Result<()>) {
+        if let Err(x) = result {
+            self.error.lock().get_or_insert(x);
+        }
     }
 }
 
-impl Deref for FramebufferCanvas<'_> {
-    type Target = Box<dyn Canvas>;
+impl ImageMemory for FramebufferMemory<'_> {
+    fn read(&self, offset: u32, buf: &mut [u8]) {
+        let result = self.context.read_bytes(self.base + offset, buf);
+        if result.is_err() {
+            buf.fill(0);
+        }
+        self.record(result.map(|_| ()));
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.canvas
+    fn write(&mut self, offset: u32, data: &[u8]) {
+        let result = self.context.write_bytes(self.base + offset, data);
+        self.record(result);
     }
 }
 
-impl DerefMut for FramebufferCanvas<'_> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.canvas
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use wie_backend::canvas::{BitmaskImageBuffer, Color, CompositeMode, ImageBuffer, Rgb444Pixel, VecImageBuffer};
+    use wie_util::Result;
+
+    use test_utils::TestPlatform;
+
+    use crate::context::test::TestContext;
+
+    use super::{WIPICDisplayInfo, WIPICFramebuffer, MC_GRP_INDEXED_COLOR_TYPE};
+
+    #[test]
+    fn test_from_image() -> Result<()> {
+        let mut context = TestContext::new();
+
+        let color = Color {
+            a: 0xff,
+            r: 0x11,
+            g: 0x22,
+            b: 0x33,
+        };
+        let mut image = VecImageBuffer::<Rgb444Pixel>::new(3, 2);
+        image.put_pixel(2, 1, color);
+
+        let framebuffer = WIPICFramebuffer::from_image(&mut context, &image)?;
+        assert_eq!((framebuffer.bpp, framebuffer.bpl), (12, 6));
+        assert_eq!(framebuffer.image(&mut context)?.get_pixel(2, 1), color);
+
+        // 10 pixels wide bitmask with padded lines
+        let mut mask = BitmaskImageBuffer::new(10, 2, 4, vec![0; 8]);
+        mask.put_pixel(9, 1, color);
+
+        let framebuffer = WIPICFramebuffer::from_image(&mut context, &mask)?;
+        assert_eq!((framebuffer.bpp, framebuffer.bpl), (1, 2));
+        assert_eq!(framebuffer.image(&mut context)?.get_pixel(9, 1).a, 0xff);
+        assert_eq!(framebuffer.image(&mut context)?.get_pixel(8, 1).a, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_indexed_framebuffer() -> Result<()> {
+        let black = Color { a: 0xff, r: 0, g: 0, b: 0 };
+        let red = Color {
+            a: 0xff,
+            r: 0xff,
+            g: 0,
+            b: 0,
+        };
+        let mut context = TestContext::with_platform(TestPlatform::with_palette(vec![black, red]));
+
+        let framebuffer = WIPICFramebuffer::new(&mut context, 2, 1, 8)?;
+        framebuffer.draw(&mut context, |canvas| canvas.fill_rect(1, 0, 1, 1, red, CompositeMode::Copy))?;
+
+        assert_eq!(framebuffer.data(&context)?, [0, 1]);
+        assert_eq!(framebuffer.image(&mut context)?.get_pixel(1, 0), red);
+
+        let info = WIPICDisplayInfo::new(2, 1, 8, true)?;
+        assert_eq!((info.color_type, info.red_mask), (MC_GRP_INDEXED_COLOR_TYPE, 0));
+
+        Ok(())
     }
 }
@@ -2,8 +2,17 @@ use core::mem;
 
 use bytemuck::{Pod, Zeroable};
 
+use wie_backend::canvas::{Color, CompositeMode, PixelType, Rgb8Pixel};
+use wie_util::{read_generic, Result};
+
 use crate::{method::TypeConverter, WIPICContext, WIPICWord};
 
+// predefined pixel ops, which select builtin raster operation instead of calling MC_GrpPixelOpProc.
+// they're below any code address, so they can't be mistaken for application function.
+pub const MC_GRP_PIXELOP_XOR: WIPICWord = 1;
+pub const MC_GRP_PIXELOP_OR: WIPICWord = 2;
+pub const MC_GRP_PIXELOP_AND: WIPICWord = 3;
+
 /// _MC_GrpContext
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -24,8 +33,74 @@ pub struct WIPICGraphicsContext {
     pub style: WIPICWord,
 }
 
+impl WIPICGraphicsContext {
+    /// Reads graphics context from `ptr`, falling back to default context if `ptr` is null.
+    pub fn read(context: &mut dyn WIPICContext, ptr: WIPICWord) -> Result<Self> {
+        if ptr == 0 {
+            Ok(Self::zeroed())
+        } else {
+            read_generic(context, ptr)
+        }
+    }
+
+    pub fn is_set(&self, idx: WIPICGraphicsContextIdx) -> bool {
+        self.mask & idx.bit() != 0
+    }
+
+    pub fn set(&mut self, idx: WIPICGraphicsContextIdx, enabled: bool) {
+        if enabled {
+            self.mask |= idx.bit();
+        } else {
+            self.mask &= !idx.bit();
+        }
+    }
+
+    /// Composite mode for builtin operations. `default` is used if no operation is set on this context.
+    pub fn composite_mode(&self, default: CompositeMode) -> CompositeMode {
+        if self.is_set(WIPICGraphicsContextIdx::XorModeIdx) {
+            CompositeMode::Xor
+        } else if let Some(x) = self.builtin_pixel_op() {
+            x
+        } else if self.is_set(WIPICGraphicsContextIdx::AlphaIdx) {
+            CompositeMode::Alpha(self.alpha.min(0xff) as u8)
+        } else {
+            default
+        }
+    }
+
+    pub fn color_key(&self) -> Option<Color> {
+        if self.is_set(WIPICGraphicsContextIdx::TransPixelIdx) {
+            Some(Rgb8Pixel::to_color(self.transpxl))
+        } else {
+            None
+        }
+    }
+
+    /// MC_GrpPixelOpProc set by application, called with (srcpxl, orgpxl, param1)
+    pub fn pixel_op(&self) -> Option<WIPICWord> {
+        if self.is_set(WIPICGraphicsContextIdx::PixelopIdx) && self.pixel_op_func_ptr != 0 && self.builtin_pixel_op().is_none() {
+            Some(self.pixel_op_func_ptr)
+        } else {
+            None
+        }
+    }
+
+    fn builtin_pixel_op(&self) -> Option<CompositeMode> {
+        if !self.is_set(WIPICGraphicsContextIdx::PixelopIdx) {
+            return None;
+        }
+
+        match self.pixel_op_func_ptr {
+            MC_GRP_PIXELOP_XOR => Some(CompositeMode::Xor),
+            MC_GRP_PIXELOP_OR => Some(CompositeMode::Or),
+            MC_GRP_PIXELOP_AND => Some(CompositeMode::And),
+            _ => None,
+        }
+    }
+}
+
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
 pub enum WIPICGraphicsContextIdx {
     ClipIdx = 0,
@@ -46,6 +121,16 @@ pub enum WIPICGraphicsContextIdx {
     Invalid = 0xff,
 }
 
+impl WIPICGraphicsContextIdx {
+    // we record each property set by MC_grpSetContext on `mask` using its index
+    fn bit(self) -> WIPICWord {
+        match self {
+            Self::Invalid => 0,
+            _ => 1 << (self as WIPICWord),
+        }
+    }
+}
+
 impl TypeConverter<WIPICGraphicsContextIdx> for WIPICGraphicsContextIdx {
     fn to_rust(_context: &mut dyn WIPICContext, raw: WIPICWord) -> WIPICGraphicsContextIdx {
         if raw >= (Self::ClipIdx as WIPICWord) && raw <= (Self::OutlineIdx as WIPICWord) {
@@ -61,3 +146,34 @@ impl TypeConverter<WIPICGraphicsContextIdx> for WIPICGraphicsContextIdx {
         rust as WIPICWord
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bytemuck::Zeroable;
+
+    use wie_backend::canvas::CompositeMode;
+
+    use super::{WIPICGraphicsContext, WIPICGraphicsContextIdx, MC_GRP_PIXELOP_AND, MC_GRP_PIXELOP_OR, MC_GRP_PIXELOP_XOR};
+
+    #[test]
+    fn test_composite_mode() {
+        let mut gctx = WIPICGraphicsContext::zeroed();
+        assert_eq!(gctx.composite_mode(CompositeMode::Copy), CompositeMode::Copy);
+
+        gctx.set(WIPICGraphicsContextIdx::PixelopIdx, true);
+        for (pixel_op, mode) in [
+            (MC_GRP_PIXELOP_XOR, CompositeMode::Xor),
+            (MC_GRP_PIXELOP_OR, CompositeMode::Or),
+            (MC_GRP_PIXELOP_AND, CompositeMode::And),
+        ] {
+            gctx.pixel_op_func_ptr = pixel_op;
+            assert_eq!(gctx.composite_mode(CompositeMode::Copy), mode);
+            assert_eq!(gctx.pixel_op(), None);
+        }
+
+        // other values are application functions
+        gctx.pixel_op_func_ptr = 0x1000;
+        assert_eq!(gctx.composite_mode(CompositeMode::Copy), CompositeMode::Copy);
+        assert_eq!(gctx.pixel_op(), Some(0x1000));
+    }
+}
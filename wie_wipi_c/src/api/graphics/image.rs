@@ -1,8 +1,8 @@
-use alloc::vec;
+use alloc::{boxed::Box, vec};
 
 use bytemuck::{Pod, Zeroable};
 
-use wie_backend::canvas::decode_image;
+use wie_backend::canvas::{decode_image, ArgbPixel, Color, Image, ImageBuffer, VecImageBuffer};
 use wie_util::Result;
 
 use crate::{context::WIPICContext, WIPICMemoryId, WIPICWord};
@@ -46,4 +46,30 @@ impl WIPICImage {
             len,
         })
     }
+
+    /// Returns image with mask applied as alpha, if there's mask.
+    pub fn image(&self, context: &mut dyn WIPICContext) -> Result<Box<dyn Image>> {
+        let image = self.img.image(context)?;
+        if self.mask.buf.0 == 0 {
+            return Ok(image);
+        }
+
+        let mask = self.mask.image(context)?;
+
+        let mut result = VecImageBuffer::<ArgbPixel>::new(image.width(), image.height());
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                let color = image.get_pixel(x, y);
+                let a = if x < mask.width() && y < mask.height() {
+                    mask.get_pixel(x, y).a
+                } else {
+                    color.a
+                };
+
+                result.put_pixel(x, y, Color { a, ..color });
+            }
+        }
+
+        Ok(Box::new(result))
+    }
 }
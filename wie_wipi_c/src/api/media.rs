@@ -1,17 +1,38 @@
-use alloc::{string::String, vec};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
 use core::mem::size_of;
 
-use bytemuck::{Pod, Zeroable};
+use bytemuck::{pod_collect_to_vec, Pod, Zeroable};
 
-use wie_util::{read_generic, write_generic, Result};
+use wie_backend::{AudioFormat, AudioHandle, PlaybackEvent, Tone, Waveform};
+use wie_util::{read_generic, write_generic, Result, WieError};
 
-use crate::{context::WIPICContext, WIPICWord};
+use crate::{context::WIPICContext, method::MethodBody, WIPICWord};
+
+// status passed to MC_MdaCallback when clip reaches the end of data, pinned by `test_sdk_values`.
+// callback isn't called on MC_mdaStop, as application already knows it has stopped the clip.
+const MDA_STATUS_END_OF_DATA: WIPICWord = 1;
+
+// tone data longer than this is rejected, as count is given by application
+const MAX_TONE_COUNT: WIPICWord = 0x10000;
+
+// format hints passed to `Audio::load`, stored in `MdaClip::format_hint` as index + 1, or 0 if there's no hint
+const FORMAT_HINTS: [&str; 4] = ["smaf", "midi", "wav", "tone"];
+
+fn format_hint(name: &str) -> u32 {
+    match AudioFormat::detect(&[], Some(name)) {
+        Some(AudioFormat::Smaf) => 1,
+        Some(AudioFormat::Smf) => 2,
+        Some(AudioFormat::Wave) => 3,
+        Some(AudioFormat::ToneSequence) => 4,
+        None => 0,
+    }
+}
 
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 struct MdaClip {
     clip_id: i32,
-    h_proc: i32,
+    h_proc: WIPICWord, // MC_MdaCallback
     r#type: u8,
     in_use: u8, // bool
     _padding1: [u8; 2],
@@ -72,16 +93,38 @@ struct MdaClip {
     mda_id: i32,
     device_info: i32,
 
-    // not in sdk, for internal usage
+    // not in sdk, for internal usage. trails the sdk layout, so applications built against sdk never read or write it
     handle: u32,
+    // format given on MC_mdaClipCreate, kept out of `type` as application may read it
+    format_hint: u32,
 }
 
 pub async fn clip_create(context: &mut dyn WIPICContext, r#type: String, buf_size: WIPICWord, callback: WIPICWord) -> Result<WIPICWord> {
     tracing::debug!("MC_mdaClipCreate({}, {:#x}, {:#x})", r#type, buf_size, callback);
 
-    let clip = context.alloc_raw(size_of::<MdaClip>() as u32)?;
+    let ptr_clip = context.alloc_raw(size_of::<MdaClip>() as u32)?;
+
+    let clip = MdaClip {
+        h_proc: callback,
+        format_hint: format_hint(&r#type),
+        ..MdaClip::zeroed()
+    };
+    write_generic(context, ptr_clip, clip)?;
+
+    Ok(ptr_clip)
+}
+
+pub async fn clip_free(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaClipFree({:#x})", ptr_clip);
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+    if clip.handle != 0 {
+        let _ = context.system().audio().unload(clip.handle);
+    }
 
-    Ok(clip)
+    context.free_raw(ptr_clip, size_of::<MdaClip>() as _)?;
+
+    Ok(0)
 }
 
 pub async fn clip_get_type(_context: &mut dyn WIPICContext, clip: WIPICWord, buf: WIPICWord, buf_size: WIPICWord) -> Result<WIPICWord> {
@@ -90,6 +133,16 @@ pub async fn clip_get_type(_context: &mut dyn WIPICContext, clip: WIPICWord, buf
     Ok(0)
 }
 
+pub async fn vibrator(context: &mut dyn WIPICContext, on_off: WIPICWord, timeout: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaVibrator({}, {})", on_off, timeout);
+
+    // level can't be specified, so we use max level
+    let duration = if on_off != 0 { timeout } else { 0 };
+    context.system().platform().vibrate(duration, 100);
+
+    Ok(0)
+}
+
 pub async fn get_mute_state(_context: &mut dyn WIPICContext, source: WIPICWord) -> Result<WIPICWord> {
     tracing::warn!("stub MC_mdaGetMuteState({:#x})", source);
 
@@ -114,19 +167,144 @@ pub async fn clip_put_data(context: &mut dyn WIPICContext, ptr_clip: WIPICWord,
     let mut data = vec![0; buf_size as _];
     context.read_bytes(buf, &mut data)?;
 
-    let handle = context.system().audio().load_smaf(&data);
-    if let Err(x) = handle {
-        tracing::error!("Failed to load audio: {:?}", x);
+    let mut clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    let hint = clip.format_hint.checked_sub(1).and_then(|x| FORMAT_HINTS.get(x as usize)).copied();
+    let handle = context.system().audio().load(&data, hint);
+    let handle = match handle {
+        Ok(x) => x,
+        Err(x) => {
+            tracing::error!("Failed to load audio: {:?}", x);
+            return Ok(0);
+        }
+    };
+
+    set_clip_handle(context, &mut clip, handle);
+    write_generic(context, ptr_clip, clip)?;
+
+    Ok(buf_size)
+}
+
+// replaces audio of the clip, releasing previously loaded one
+fn set_clip_handle(context: &mut dyn WIPICContext, clip: &mut MdaClip, handle: AudioHandle) {
+    if clip.handle != 0 {
+        let _ = context.system().audio().unload(clip.handle);
+    }
+
+    clip.handle = handle;
+}
+
+// MC_MdaToneType is played as midi note number, and values out of midi range as rest. pinned by `test_sdk_values`.
+fn tone(r#type: i32, duration: i32) -> Tone {
+    match r#type {
+        0..=127 => Tone::note(r#type as _, duration.max(0) as _, 100),
+        _ => Tone::silence(duration.max(0) as _),
+    }
+}
+
+pub async fn clip_put_tone_data(
+    context: &mut dyn WIPICContext,
+    ptr_clip: WIPICWord,
+    ptr_tone: WIPICWord,
+    ptr_duration: WIPICWord,
+    count: WIPICWord,
+) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaClipPutToneData({:#x}, {:#x}, {:#x}, {})", ptr_clip, ptr_tone, ptr_duration, count);
+
+    if count > MAX_TONE_COUNT {
+        tracing::error!("Too many tones: {}", count);
         return Ok(0);
     }
 
-    let handle = handle.unwrap();
+    let types = read_words(context, ptr_tone, count)?;
+    let durations = read_words(context, ptr_duration, count)?;
+
+    let tones = types
+        .into_iter()
+        .zip(durations)
+        .map(|(r#type, duration)| tone(r#type, duration))
+        .collect::<Vec<_>>();
+
+    let handle = context.system().audio().load_tones(&tones, Waveform::Square);
+    let handle = match handle {
+        Ok(x) => x,
+        Err(x) => {
+            tracing::error!("Failed to load tones: {:?}", x);
+            return Ok(0);
+        }
+    };
 
     let mut clip: MdaClip = read_generic(context, ptr_clip)?;
-    clip.handle = handle;
+    clip.audio_tone = ptr_tone;
+    clip.audio_tone_duration = ptr_duration;
+    clip.audio_tone_len = count as _;
+    set_clip_handle(context, &mut clip, handle);
     write_generic(context, ptr_clip, clip)?;
 
-    Ok(buf_size)
+    Ok(count)
+}
+
+pub async fn clip_put_freq_tone_data(
+    context: &mut dyn WIPICContext,
+    ptr_clip: WIPICWord,
+    ptr_hi_freq: WIPICWord,
+    ptr_low_freq: WIPICWord,
+    ptr_duration: WIPICWord,
+    count: WIPICWord,
+) -> Result<WIPICWord> {
+    tracing::debug!(
+        "MC_mdaClipPutFreqToneData({:#x}, {:#x}, {:#x}, {:#x}, {})",
+        ptr_clip,
+        ptr_hi_freq,
+        ptr_low_freq,
+        ptr_duration,
+        count
+    );
+
+    if count > MAX_TONE_COUNT {
+        tracing::error!("Too many tones: {}", count);
+        return Ok(0);
+    }
+
+    let high_frequencies = read_words(context, ptr_hi_freq, count)?;
+    let low_frequencies = read_words(context, ptr_low_freq, count)?;
+    let durations = read_words(context, ptr_duration, count)?;
+
+    let tones = (0..count as usize)
+        .map(|i| Tone {
+            high_frequency: high_frequencies[i].max(0) as _,
+            low_frequency: low_frequencies[i].max(0) as _,
+            duration: durations[i].max(0) as _,
+            volume: 100,
+        })
+        .collect::<Vec<_>>();
+
+    let handle = context.system().audio().load_tones(&tones, Waveform::Sine);
+    let handle = match handle {
+        Ok(x) => x,
+        Err(x) => {
+            tracing::error!("Failed to load tones: {:?}", x);
+            return Ok(0);
+        }
+    };
+
+    let mut clip: MdaClip = read_generic(context, ptr_clip)?;
+    clip.audio_hi_freq = ptr_hi_freq;
+    clip.audio_low_freq = ptr_low_freq;
+    clip.audio_freq_duration = ptr_duration;
+    clip.audio_freq_len = count as _;
+    set_clip_handle(context, &mut clip, handle);
+    write_generic(context, ptr_clip, clip)?;
+
+    Ok(count)
+}
+
+// `count` should be bounded by caller, as it comes from application
+fn read_words(context: &mut dyn WIPICContext, address: WIPICWord, count: WIPICWord) -> Result<Vec<i32>> {
+    let mut data = vec![0u8; count as usize * size_of::<i32>()];
+    context.read_bytes(address, &mut data)?;
+
+    Ok(pod_collect_to_vec(&data))
 }
 
 pub async fn clip_get_data(_context: &mut dyn WIPICContext, clip: WIPICWord, buf: WIPICWord, buf_size: WIPICWord) -> Result<WIPICWord> {
@@ -135,8 +313,50 @@ pub async fn clip_get_data(_context: &mut dyn WIPICContext, clip: WIPICWord, buf
     Ok(0)
 }
 
-pub async fn clip_set_position(_context: &mut dyn WIPICContext, clip: WIPICWord, ms: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaClipSetPosition({:#x}, {:#x})", clip, ms);
+pub async fn clip_set_position(context: &mut dyn WIPICContext, ptr_clip: WIPICWord, ms: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaClipSetPosition({:#x}, {})", ptr_clip, ms);
+
+    let mut clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    let position = context.system().audio().seek(clip.handle, ms as _);
+    match position {
+        Ok(x) => {
+            clip.position = x as _;
+            write_generic(context, ptr_clip, clip)?;
+        }
+        Err(x) => tracing::error!("Failed to set position: {:?}", x),
+    }
+
+    Ok(0)
+}
+
+pub async fn clip_get_volume(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaClipGetVolume({:#x})", ptr_clip);
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    let volume = context.system().audio().volume(clip.handle);
+    match volume {
+        Ok(x) => Ok(x as _),
+        Err(x) => {
+            tracing::error!("Failed to get volume: {:?}", x);
+
+            Ok(clip.original_volume as _)
+        }
+    }
+}
+
+pub async fn clip_set_volume(context: &mut dyn WIPICContext, ptr_clip: WIPICWord, level: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaClipSetVolume({:#x}, {})", ptr_clip, level);
+
+    let mut clip: MdaClip = read_generic(context, ptr_clip)?;
+    clip.original_volume = level as _;
+    write_generic(context, ptr_clip, clip)?;
+
+    let result = context.system().audio().set_volume(clip.handle, level.min(100) as _);
+    if let Err(x) = result {
+        tracing::error!("Failed to set volume: {:?}", x);
+    }
 
     Ok(0)
 }
@@ -146,29 +366,82 @@ pub async fn play(context: &mut dyn WIPICContext, ptr_clip: WIPICWord, repeat: W
 
     let clip: MdaClip = read_generic(context, ptr_clip)?;
 
-    let result = context.system().audio().play(clip.handle);
-
+    let loop_count = if repeat != 0 { None } else { Some(1) };
+    let result = context.system().audio().play(clip.handle, loop_count);
     if let Err(x) = result {
-        tracing::error!("Failed to load audio: {:?}", x);
+        tracing::error!("Failed to play audio: {:?}", x);
+        return Ok(());
+    }
+
+    if clip.h_proc != 0 {
+        struct CompletionCallback {
+            ptr_clip: WIPICWord,
+            callback: WIPICWord,
+            handle: u32,
+        }
+
+        #[async_trait::async_trait]
+        impl MethodBody<WieError> for CompletionCallback {
+            #[tracing::instrument(name = "mda", skip_all)]
+            async fn call(&self, context: &mut dyn WIPICContext, _: Box<[WIPICWord]>) -> Result<WIPICWord> {
+                let Ok(completion) = context.system().audio().completion(self.handle) else {
+                    return Ok(0);
+                };
+                if completion.wait(context.system()).await != PlaybackEvent::Finished {
+                    return Ok(0);
+                }
+
+                context.call_function(self.callback, &[self.ptr_clip, MDA_STATUS_END_OF_DATA]).await?;
+
+                Ok(0)
+            }
+        }
+
+        context.spawn(Box::new(CompletionCallback {
+            ptr_clip,
+            callback: clip.h_proc,
+            handle: clip.handle,
+        }))?;
     }
 
     Ok(())
 }
 
-pub async fn pause(_context: &mut dyn WIPICContext, clip: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaPause({:#x})", clip);
+pub async fn pause(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaPause({:#x})", ptr_clip);
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    let result = context.system().audio().pause(clip.handle);
+    if let Err(x) = result {
+        tracing::error!("Failed to pause audio: {:?}", x);
+    }
 
     Ok(0)
 }
 
-pub async fn resume(_context: &mut dyn WIPICContext, clip: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaResume({:#x})", clip);
+pub async fn resume(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaResume({:#x})", ptr_clip);
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    let result = context.system().audio().resume(clip.handle);
+    if let Err(x) = result {
+        tracing::error!("Failed to resume audio: {:?}", x);
+    }
 
     Ok(0)
 }
 
-pub async fn stop(_context: &mut dyn WIPICContext, clip: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaStop({:#x})", clip);
+pub async fn stop(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaStop({:#x})", ptr_clip);
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    let result = context.system().audio().stop(clip.handle);
+    if let Err(x) = result {
+        tracing::error!("Failed to stop audio: {:?}", x);
+    }
 
     Ok(0)
 }
@@ -178,3 +451,97 @@ pub async fn record(_context: &mut dyn WIPICContext, clip: WIPICWord) -> Result<
 
     Ok(0)
 }
+
+#[cfg(test)]
+mod test {
+    use wie_backend::Tone;
+    use wie_util::{read_generic, ByteWrite, Result};
+
+    use crate::{context::test::TestContext, WIPICContext};
+
+    use super::{clip_create, clip_free, clip_put_tone_data, clip_set_position, tone, MdaClip, FORMAT_HINTS, MAX_TONE_COUNT, MDA_STATUS_END_OF_DATA};
+
+    #[test]
+    fn test_sdk_values() {
+        assert_eq!(MDA_STATUS_END_OF_DATA, 1);
+
+        assert_eq!(tone(69, 100), Tone::note(69, 100, 100));
+        assert_eq!(tone(0, 100), Tone::note(0, 100, 100));
+        assert_eq!(tone(128, 100), Tone::silence(100));
+        assert_eq!(tone(-1, -5), Tone::silence(0));
+    }
+
+    #[futures_test::test]
+    async fn test_clip_format_hint() -> Result<()> {
+        let mut context = TestContext::new();
+
+        let ptr_clip = clip_create(&mut context, "wav".into(), 0, 0).await?;
+        let clip = read_generic::<MdaClip, _>(&context, ptr_clip)?;
+        assert_eq!(clip.r#type, 0);
+        assert_eq!(FORMAT_HINTS[clip.format_hint as usize - 1], "wav");
+        assert_eq!(clip_free(&mut context, ptr_clip).await?, 0);
+
+        // allocator may reuse address of freed clip, whose hint shouldn't be kept
+        let ptr_clip = clip_create(&mut context, "unknown".into(), 0, 0).await?;
+        assert_eq!(read_generic::<MdaClip, _>(&context, ptr_clip)?.format_hint, 0);
+
+        Ok(())
+    }
+
+    #[futures_test::test]
+    async fn test_put_tone_data_releases_previous() -> Result<()> {
+        let mut context = TestContext::new();
+        let ptr_clip = clip_create(&mut context, "tone".into(), 0, 0).await?;
+
+        let ptr_tone = context.alloc_raw(8)?;
+        let ptr_duration = context.alloc_raw(8)?;
+        context.write_bytes(ptr_tone, bytemuck::cast_slice(&[60i32, -1]))?;
+        context.write_bytes(ptr_duration, bytemuck::cast_slice(&[100i32, 100]))?;
+
+        assert_eq!(clip_put_tone_data(&mut context, ptr_clip, ptr_tone, ptr_duration, 2).await?, 2);
+        let first = read_generic::<MdaClip, _>(&context, ptr_clip)?.handle;
+
+        assert_eq!(clip_put_tone_data(&mut context, ptr_clip, ptr_tone, ptr_duration, 2).await?, 2);
+        let second = read_generic::<MdaClip, _>(&context, ptr_clip)?.handle;
+
+        assert_ne!(first, second);
+        assert!(context.system().audio().state(first).is_err());
+        assert!(context.system().audio().state(second).is_ok());
+
+        Ok(())
+    }
+
+    #[futures_test::test]
+    async fn test_clip_set_position() -> Result<()> {
+        let mut context = TestContext::new();
+        let ptr_clip = clip_create(&mut context, "tone".into(), 0, 0).await?;
+
+        let ptr_tone = context.alloc_raw(8)?;
+        let ptr_duration = context.alloc_raw(8)?;
+        context.write_bytes(ptr_tone, bytemuck::cast_slice(&[60i32, 62]))?;
+        context.write_bytes(ptr_duration, bytemuck::cast_slice(&[100i32, 100]))?;
+        clip_put_tone_data(&mut context, ptr_clip, ptr_tone, ptr_duration, 2).await?;
+
+        // position is clamped to the length of tones
+        assert_eq!(clip_set_position(&mut context, ptr_clip, 150).await?, 0);
+        let clip = read_generic::<MdaClip, _>(&context, ptr_clip)?;
+        assert_eq!(clip.position, 150);
+        assert_eq!(context.system().audio().position(clip.handle).unwrap(), 150);
+
+        clip_set_position(&mut context, ptr_clip, 1000).await?;
+        assert_eq!(read_generic::<MdaClip, _>(&context, ptr_clip)?.position, 200);
+
+        Ok(())
+    }
+
+    #[futures_test::test]
+    async fn test_put_tone_data_rejects_large_count() -> Result<()> {
+        let mut context = TestContext::new();
+        let ptr_clip = clip_create(&mut context, "tone".into(), 0, 0).await?;
+
+        assert_eq!(clip_put_tone_data(&mut context, ptr_clip, 0, 0, MAX_TONE_COUNT + 1).await?, 0);
+        assert_eq!(read_generic::<MdaClip, _>(&context, ptr_clip)?.handle, 0);
+
+        Ok(())
+    }
+}
@@ -1,15 +1,56 @@
+use wie_backend::BacklightState;
 use wie_util::Result;
 
 use crate::{context::WIPICContext, WIPICWord};
 
-pub async fn back_light(
-    _context: &mut dyn WIPICContext,
-    id: WIPICWord,
-    on_off: WIPICWord,
-    color: WIPICWord,
-    timeout: WIPICWord,
-) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_miscBackLight({}, {}, {}, {})", id, on_off, color, timeout);
+const M_E_SUCCESS: i32 = 0;
+const M_E_INVALID: i32 = -9;
 
-    Ok(0)
+pub async fn back_light(context: &mut dyn WIPICContext, id: WIPICWord, on_off: WIPICWord, color: WIPICWord, timeout: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_miscBackLight({}, {}, {}, {})", id, on_off, color, timeout);
+
+    // only single backlight without color is emulated
+    if id != 0 || color != 0 {
+        tracing::warn!("Unsupported backlight id {} or color {:#x}", id, color);
+
+        return Ok(M_E_INVALID);
+    }
+
+    // values of on_off are not verified against sdk, so we treat it as a flag
+    let state = if on_off != 0 { BacklightState::On } else { BacklightState::Off };
+    let timeout = if timeout != 0 { Some(timeout) } else { None };
+    context.system().platform().set_backlight(state, timeout);
+
+    Ok(M_E_SUCCESS)
+}
+
+#[cfg(test)]
+mod test {
+    use wie_backend::BacklightState;
+    use wie_util::Result;
+
+    use test_utils::TestPlatform;
+
+    use crate::{api::media::vibrator, context::test::TestContext};
+
+    use super::{back_light, M_E_INVALID, M_E_SUCCESS};
+
+    #[futures_test::test]
+    async fn test_back_light_vibrator() -> Result<()> {
+        let platform = TestPlatform::new();
+        let (backlights, vibrations) = (platform.backlights(), platform.vibrations());
+        let mut context = TestContext::with_platform(platform);
+
+        assert_eq!(back_light(&mut context, 0, 1, 0, 3000).await?, M_E_SUCCESS);
+        assert_eq!(back_light(&mut context, 0, 0, 0, 0).await?, M_E_SUCCESS);
+        assert_eq!(back_light(&mut context, 1, 1, 0, 0).await?, M_E_INVALID);
+        assert_eq!(back_light(&mut context, 0, 1, 0xff0000, 0).await?, M_E_INVALID);
+        assert_eq!(*backlights.lock(), [(BacklightState::On, Some(3000)), (BacklightState::Off, None)]);
+
+        vibrator(&mut context, 1, 500).await?;
+        vibrator(&mut context, 0, 500).await?;
+        assert_eq!(*vibrations.lock(), [(500, 100), (0, 100)]);
+
+        Ok(())
+    }
 }
@@ -77,17 +77,25 @@ pub mod test {
 
     use crate::{WIPICContext, WIPICMemoryId, WIPICMethodBody, WIPICWord};
 
+    use test_utils::TestPlatform;
+
     pub struct TestContext {
         memory: [u8; 0x10000],
         last_alloc: usize,
+        system: System,
     }
 
     impl TestContext {
         #[allow(clippy::new_without_default)]
         pub fn new() -> Self {
+            Self::with_platform(TestPlatform::new())
+        }
+
+        pub fn with_platform(platform: TestPlatform) -> Self {
             Self {
                 memory: [0; 0x10000],
                 last_alloc: 0,
+                system: System::new(Box::new(platform), ""),
             }
         }
     }
@@ -126,7 +134,7 @@ pub mod test {
         }
 
         fn system(&mut self) -> &mut System {
-            todo!()
+            &mut self.system
         }
 
         fn spawn(&mut self, _callback: WIPICMethodBody) -> Result<()> {
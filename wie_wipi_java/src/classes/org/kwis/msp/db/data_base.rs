@@ -104,7 +104,7 @@ impl DataBase {
 
         let database = Self::get_database(jvm, context, &this).await?;
 
-        let count = database.get_record_ids().len();
+        let count = database.count();
 
         Ok(count as _)
     }
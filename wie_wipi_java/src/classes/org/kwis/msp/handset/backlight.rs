@@ -4,6 +4,7 @@ use java_class_proto::JavaMethodProto;
 use java_constants::MethodAccessFlags;
 use jvm::{Jvm, Result as JvmResult};
 
+use wie_backend::BacklightState;
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 // class org.kwis.msp.handset.Backlight
@@ -20,9 +21,36 @@ impl BackLight {
         }
     }
 
-    async fn always_on(_: &Jvm, _: &mut WieJvmContext) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.handset.Backlight::alwaysOn");
+    async fn always_on(_: &Jvm, context: &mut WieJvmContext) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.handset.Backlight::alwaysOn");
+
+        context.system().platform().set_backlight(BacklightState::On, None);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::boxed::Box;
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_backend::BacklightState;
+    use wie_util::Result;
+
+    use crate::get_protos;
+
+    #[test]
+    fn test_always_on() -> Result<()> {
+        let platform = TestPlatform::new();
+        let backlights = platform.backlights();
+
+        run_jvm_test(platform, Box::new([get_protos().into()]), move |jvm| async move {
+            let _: () = jvm.invoke_static("org/kwis/msp/handset/BackLight", "alwaysOn", "()V", ()).await?;
+
+            assert_eq!(*backlights.lock(), [(BacklightState::On, None)]);
+
+            Ok(())
+        })
+    }
+}
@@ -1,26 +1,33 @@
 use alloc::{format, vec};
-use core::cmp::min;
-
-use bytemuck::cast_slice;
 
 use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_runtime::classes::java::{io::InputStream, lang::String};
-use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
 
-use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+use wie_jvm_support::{JavaFile, OpenOptions, WieJavaClassProto, WieJvmContext};
 
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
-#[repr(i32)]
-#[derive(Eq, PartialEq)]
-#[allow(dead_code)]
+#[derive(Clone, Copy, Eq, PartialEq)]
 enum Mode {
-    // wipi constant
+    // wipi constant. append mode is left out as its value is unknown, seek to sizeOf() to append
     READ_ONLY = 1,
     WRITE = 2,
     WRITE_TRUNC = 3,
     READ_WRITE = 4,
 }
 
+impl Mode {
+    fn from_raw(raw: i32) -> Option<Self> {
+        Some(match raw {
+            1 => Self::READ_ONLY,
+            2 => Self::WRITE,
+            3 => Self::WRITE_TRUNC,
+            4 => Self::READ_WRITE,
+            _ => return None,
+        })
+    }
+}
+
 // class org.kwis.msp.io.File
 pub struct File;
 
@@ -42,8 +49,11 @@ impl File {
                 JavaMethodProto::new("openInputStream", "()Ljava/io/InputStream;", Self::open_input_stream, Default::default()),
             ],
             fields: vec![
+                JavaFieldProto::new("name", "Ljava/lang/String;", Default::default()),
                 JavaFieldProto::new("data", "[B", Default::default()),
+                JavaFieldProto::new("size", "I", Default::default()),
                 JavaFieldProto::new("pos", "I", Default::default()),
+                JavaFieldProto::new("mode", "I", Default::default()),
             ],
         }
     }
@@ -66,52 +76,60 @@ impl File {
         mode: i32,
         flag: i32,
     ) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.io.File::<init>({:?}, {:?}, {:?}, {:?})", &this, &filename, mode, flag);
-
-        let filename = JavaLangString::to_rust_string(jvm, &filename).await?;
-        tracing::debug!("Opening {}", filename);
-
-        let mode = unsafe { core::mem::transmute::<i32, Mode>(mode) };
-
-        let data = if mode == Mode::WRITE || mode == Mode::WRITE_TRUNC {
-            // TODO: write not implemented
-            Some(vec![])
-        } else {
-            let filesystem = context.system().filesystem();
-            let data = filesystem.read(&filename);
-            data.map(|x| cast_slice(x).to_vec())
+        tracing::debug!("org.kwis.msp.io.File::<init>({:?}, {:?}, {:?}, {:?})", &this, &filename, mode, flag);
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        let options = match Mode::from_raw(mode) {
+            Some(Mode::READ_ONLY | Mode::READ_WRITE) => OpenOptions::default(),
+            Some(Mode::WRITE) => OpenOptions {
+                create: true,
+                ..Default::default()
+            },
+            Some(Mode::WRITE_TRUNC) => OpenOptions {
+                truncate: true,
+                ..Default::default()
+            },
+            None => {
+                return Err(jvm
+                    .exception("java/lang/IllegalArgumentException", &format!("Invalid mode: {}", mode))
+                    .await)
+            }
         };
 
-        if data.is_none() {
-            return Err(jvm.exception("java/io/IOException", &format!("File not found: {}", filename)).await);
-        }
-        let data = data.unwrap();
-
-        let mut data_array = jvm.instantiate_array("B", data.len() as _).await?;
-        jvm.store_byte_array(&mut data_array, 0, data).await?;
-
-        jvm.put_field(&mut this, "data", "[B", data_array).await?;
-        jvm.put_field(&mut this, "pos", "I", 0).await?;
+        JavaFile::open(jvm, context, &mut this, filename, options).await?;
+        jvm.put_field(&mut this, "mode", "I", mode).await?;
 
         Ok(())
     }
 
     async fn write(
-        _jvm: &Jvm,
-        _: &mut WieJvmContext,
-        this: ClassInstanceRef<Self>,
-        buf: ClassInstanceRef<ClassInstanceRef<Array<i8>>>,
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        buf: ClassInstanceRef<Array<i8>>,
         offset: i32,
         len: i32,
     ) -> JvmResult<i32> {
-        tracing::warn!("stub org.kwis.msp.io.File::write({:?}, {:?}, {:?}, {:?})", &this, &buf, offset, len);
+        tracing::debug!("org.kwis.msp.io.File::write({:?}, {:?}, {:?}, {:?})", &this, &buf, offset, len);
 
-        Ok(0)
+        let mode: i32 = jvm.get_field(&this, "mode", "I").await?;
+        if Mode::from_raw(mode) == Some(Mode::READ_ONLY) {
+            return Err(jvm.exception("java/io/IOException", "File is opened read only").await);
+        }
+
+        JavaFile::write(jvm, context, &mut this, &buf, offset, len).await?;
+
+        Ok(len)
     }
 
     async fn seek(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, pos: i32) -> JvmResult<()> {
         tracing::debug!("org.kwis.msp.io.File::seek({:?}, {:?})", &this, pos);
 
+        if pos < 0 {
+            return Err(jvm.exception("java/io/IOException", "Negative seek offset").await);
+        }
+
         jvm.put_field(&mut this, "pos", "I", pos).await?;
 
         Ok(())
@@ -131,25 +149,14 @@ impl File {
         offset: i32,
         length: i32,
     ) -> JvmResult<i32> {
-        tracing::debug!("org.kwis.msp.io.File::read({:?}, {:?})", &this, &buf);
-
-        let data_array = jvm.get_field(&this, "data", "[B").await?;
-        let pos: i32 = jvm.get_field(&this, "pos", "I").await?;
-
-        let data_len = jvm.array_length(&data_array).await? as i32;
+        tracing::debug!("org.kwis.msp.io.File::read({:?}, {:?}, {:?}, {:?})", &this, &buf, offset, length);
 
-        let length_to_read = min(data_len - pos, length);
-
-        let data = jvm.load_byte_array(&data_array, pos as _, length_to_read as _).await?;
-        jvm.store_byte_array(&mut buf, offset as _, data).await?;
-
-        jvm.put_field(&mut this, "pos", "I", pos + length_to_read).await?;
-
-        Ok(length_to_read as _)
+        JavaFile::read(jvm, &mut this, &mut buf, offset, length).await
     }
 
     async fn close(_jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.io.File::close({:?})", &this);
+        // writes are flushed immediately, nothing to do here
+        tracing::debug!("org.kwis.msp.io.File::close({:?})", &this);
 
         Ok(())
     }
@@ -157,19 +164,74 @@ impl File {
     async fn size_of(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
         tracing::debug!("org.kwis.msp.io.File::sizeOf({:?})", &this);
 
-        let data_array = jvm.get_field(&this, "data", "[B").await?;
-        let data_len = jvm.array_length(&data_array).await?;
-
-        Ok(data_len as _)
+        JavaFile::size(jvm, &this).await
     }
 
     async fn open_input_stream(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<InputStream>> {
         tracing::debug!("org.kwis.msp.io.File::openInputStream({:?})", &this);
 
-        let data_array = jvm.get_field(&this, "data", "[B").await?;
+        let data_array = JavaFile::contents(jvm, &this).await?;
 
-        let input_stream = jvm.new_class("java/io/ByteArrayInputStream", "([B)V", [data_array]).await?;
+        let input_stream = jvm.new_class("java/io/ByteArrayInputStream", "([B)V", (data_array,)).await?;
 
         Ok(input_stream.into())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec};
+
+    use jvm::runtime::JavaLangString;
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::get_protos;
+
+    #[test]
+    fn test_file() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let name = JavaLangString::from_rust_string(&jvm, "save.dat").await?;
+
+            // read only open of missing file fails
+            let result = jvm.new_class("org/kwis/msp/io/File", "(Ljava/lang/String;I)V", (name.clone(), 1)).await;
+            assert!(result.is_err());
+
+            let file = jvm.new_class("org/kwis/msp/io/File", "(Ljava/lang/String;I)V", (name.clone(), 2)).await?;
+            let mut data = jvm.instantiate_array("B", 4).await?;
+            jvm.store_byte_array(&mut data, 0, vec![1, 2, 3, 4]).await?;
+            let written: i32 = jvm.invoke_virtual(&file, "write", "([BII)I", (data.clone(), 0, 4)).await?;
+            assert_eq!(written, 4);
+
+            // write past end of file fills gap with zeros
+            let _: () = jvm.invoke_virtual(&file, "seek", "(I)V", (6,)).await?;
+            let written: i32 = jvm.invoke_virtual(&file, "write", "([BII)I", (data.clone(), 1, 2)).await?;
+            assert_eq!(written, 2);
+            let size: i32 = jvm.invoke_virtual(&file, "sizeOf", "()I", ()).await?;
+            assert_eq!(size, 8);
+
+            let result: jvm::Result<i32> = jvm.invoke_virtual(&file, "write", "([BII)I", (data.clone(), 2, i32::MAX)).await;
+            assert!(result.is_err());
+            let _: () = jvm.invoke_virtual(&file, "seek", "(I)V", (i32::MAX,)).await?;
+            let result: jvm::Result<i32> = jvm.invoke_virtual(&file, "write", "([BII)I", (data.clone(), 0, 1)).await;
+            assert!(result.is_err());
+            let _: () = jvm.invoke_virtual(&file, "close", "()V", ()).await?;
+
+            let file = jvm.new_class("org/kwis/msp/io/File", "(Ljava/lang/String;I)V", (name.clone(), 4)).await?;
+            let buf = jvm.instantiate_array("B", 8).await?;
+            let read: i32 = jvm.invoke_virtual(&file, "read", "([B)I", (buf.clone(),)).await?;
+            assert_eq!(read, 8);
+            assert_eq!(jvm.load_byte_array(&buf, 0, 8).await?, [1, 2, 3, 4, 0, 0, 2, 3]);
+            let read: i32 = jvm.invoke_virtual(&file, "read", "([B)I", (buf.clone(),)).await?;
+            assert_eq!(read, 0);
+
+            // truncate
+            let file = jvm.new_class("org/kwis/msp/io/File", "(Ljava/lang/String;I)V", (name.clone(), 3)).await?;
+            let size: i32 = jvm.invoke_virtual(&file, "sizeOf", "()I", ()).await?;
+            assert_eq!(size, 0);
+
+            Ok(())
+        })
+    }
+}
@@ -1,11 +1,12 @@
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 
 use java_class_proto::JavaMethodProto;
 use java_constants::MethodAccessFlags;
 use java_runtime::classes::java::lang::String;
-use jvm::{runtime::JavaLangString, ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{runtime::JavaLangString, Array, ClassInstanceRef, Jvm, Result as JvmResult};
 
-use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+use wie_backend::FilesystemError;
+use wie_jvm_support::{JavaFile, WieJavaClassProto, WieJvmContext};
 
 // class org.kwis.msp.io.FileSystem
 pub struct FileSystem;
@@ -18,29 +19,67 @@ impl FileSystem {
             interfaces: vec![],
             methods: vec![
                 JavaMethodProto::new("isFile", "(Ljava/lang/String;)Z", Self::is_file, MethodAccessFlags::STATIC),
-                JavaMethodProto::new("isDirectory", "(Ljava/lang/String;I)Z", Self::is_directory, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("isFile", "(Ljava/lang/String;I)Z", Self::is_file_with_flag, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("isDirectory", "(Ljava/lang/String;)Z", Self::is_directory, MethodAccessFlags::STATIC),
+                JavaMethodProto::new(
+                    "isDirectory",
+                    "(Ljava/lang/String;I)Z",
+                    Self::is_directory_with_flag,
+                    MethodAccessFlags::STATIC,
+                ),
                 JavaMethodProto::new("exists", "(Ljava/lang/String;)Z", Self::exists, MethodAccessFlags::STATIC),
                 JavaMethodProto::new("exists", "(Ljava/lang/String;I)Z", Self::exists_with_flag, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("list", "(Ljava/lang/String;)[Ljava/lang/String;", Self::list, MethodAccessFlags::STATIC),
+                JavaMethodProto::new(
+                    "list",
+                    "(Ljava/lang/String;I)[Ljava/lang/String;",
+                    Self::list_with_flag,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new("mkdir", "(Ljava/lang/String;)V", Self::mkdir, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("mkdir", "(Ljava/lang/String;I)V", Self::mkdir_with_flag, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("remove", "(Ljava/lang/String;)V", Self::remove, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("remove", "(Ljava/lang/String;I)V", Self::remove_with_flag, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("rmdir", "(Ljava/lang/String;)V", Self::rmdir, MethodAccessFlags::STATIC),
+                JavaMethodProto::new("rmdir", "(Ljava/lang/String;I)V", Self::rmdir_with_flag, MethodAccessFlags::STATIC),
                 JavaMethodProto::new("available", "()I", Self::available, MethodAccessFlags::STATIC),
             ],
             fields: vec![],
         }
     }
 
-    async fn is_file(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<bool> {
-        tracing::warn!("stub org.kwis.msp.io.FileSystem::is_file({:?})", &name);
+    async fn is_file(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<bool> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::isFile({:?})", &name);
+
+        jvm.invoke_static("org/kwis/msp/io/FileSystem", "isFile", "(Ljava/lang/String;I)Z", (name, 0))
+            .await
+    }
+
+    async fn is_file_with_flag(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>, flag: i32) -> JvmResult<bool> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::isFile({:?}, {:?})", &name, flag);
 
         let filename = JavaLangString::to_rust_string(jvm, &name).await?;
 
-        let exists = context.system().filesystem().read(&filename).is_some();
+        let metadata = context.system().filesystem().metadata(&filename);
 
-        Ok(exists)
+        Ok(metadata.is_some_and(|x| !x.is_directory))
+    }
+
+    async fn is_directory(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<bool> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::isDirectory({:?})", &name);
+
+        jvm.invoke_static("org/kwis/msp/io/FileSystem", "isDirectory", "(Ljava/lang/String;I)Z", (name, 0))
+            .await
     }
 
-    async fn is_directory(_jvm: &Jvm, _: &mut WieJvmContext, name: ClassInstanceRef<String>, flag: i32) -> JvmResult<bool> {
-        tracing::warn!("stub org.kwis.msp.io.FileSystem::isDirectory({:?}, {:?})", &name, flag);
+    async fn is_directory_with_flag(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>, flag: i32) -> JvmResult<bool> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::isDirectory({:?}, {:?})", &name, flag);
 
-        Ok(true)
+        let filename = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let metadata = context.system().filesystem().metadata(&filename);
+
+        Ok(metadata.is_some_and(|x| x.is_directory))
     }
 
     async fn exists(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<bool> {
@@ -55,14 +94,206 @@ impl FileSystem {
 
         let filename = JavaLangString::to_rust_string(jvm, &name).await?;
 
-        let exists = context.system().filesystem().read(&filename).is_some();
+        let exists = context.system().filesystem().exists(&filename);
 
         Ok(exists)
     }
 
-    async fn available(_: &Jvm, _: &mut WieJvmContext) -> JvmResult<i32> {
-        tracing::warn!("stub org.kwis.msp.io.FileSystem::available()");
+    async fn list(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<Array<String>>> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::list({:?})", &name);
+
+        jvm.invoke_static(
+            "org/kwis/msp/io/FileSystem",
+            "list",
+            "(Ljava/lang/String;I)[Ljava/lang/String;",
+            (name, 0),
+        )
+        .await
+    }
+
+    async fn list_with_flag(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+        flag: i32,
+    ) -> JvmResult<ClassInstanceRef<Array<String>>> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::list({:?}, {:?})", &name, flag);
+
+        let path = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let entries = context.system().filesystem().list(&path);
+        let entries = match entries {
+            Ok(x) => x,
+            Err(x) => return Err(JavaFile::io_exception(jvm, &path, x).await),
+        };
+
+        let mut names = Vec::with_capacity(entries.len());
+        for entry in entries {
+            names.push(JavaLangString::from_rust_string(jvm, &entry).await?);
+        }
+
+        let mut result = jvm.instantiate_array("Ljava/lang/String;", names.len()).await?;
+        jvm.store_array(&mut result, 0, names).await?;
+
+        Ok(result.into())
+    }
+
+    async fn mkdir(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::mkdir({:?})", &name);
+
+        jvm.invoke_static("org/kwis/msp/io/FileSystem", "mkdir", "(Ljava/lang/String;I)V", (name, 0))
+            .await
+    }
+
+    async fn mkdir_with_flag(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>, flag: i32) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::mkdir({:?}, {:?})", &name, flag);
+
+        let path = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let result = context.system().filesystem().create_dir(&path);
+        if let Err(x) = result {
+            return Err(JavaFile::io_exception(jvm, &path, x).await);
+        }
+
+        Ok(())
+    }
+
+    async fn remove(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::remove({:?})", &name);
+
+        jvm.invoke_static("org/kwis/msp/io/FileSystem", "remove", "(Ljava/lang/String;I)V", (name, 0))
+            .await
+    }
+
+    async fn remove_with_flag(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>, flag: i32) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::remove({:?}, {:?})", &name, flag);
+
+        let path = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let mut filesystem = context.system().filesystem();
+        let result = if filesystem.metadata(&path).is_some_and(|x| x.is_directory) {
+            Err(FilesystemError::IsDirectory)
+        } else {
+            filesystem.remove(&path)
+        };
+        drop(filesystem);
+
+        if let Err(x) = result {
+            return Err(JavaFile::io_exception(jvm, &path, x).await);
+        }
+
+        Ok(())
+    }
+
+    async fn rmdir(jvm: &Jvm, _context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::rmdir({:?})", &name);
+
+        jvm.invoke_static("org/kwis/msp/io/FileSystem", "rmdir", "(Ljava/lang/String;I)V", (name, 0))
+            .await
+    }
+
+    async fn rmdir_with_flag(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>, flag: i32) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::rmdir({:?}, {:?})", &name, flag);
+
+        let path = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        let mut filesystem = context.system().filesystem();
+        let result = match filesystem.metadata(&path) {
+            Some(x) if !x.is_directory => Err(FilesystemError::NotDirectory),
+            _ => filesystem.remove(&path),
+        };
+        drop(filesystem);
+
+        if let Err(x) = result {
+            return Err(JavaFile::io_exception(jvm, &path, x).await);
+        }
+
+        Ok(())
+    }
+
+    async fn available(_: &Jvm, context: &mut WieJvmContext) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.io.FileSystem::available()");
+
+        let available = context.system().filesystem().available_space();
+
+        Ok(available.min(i32::MAX as u64) as i32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec::Vec};
+
+    use java_runtime::classes::java::lang::String;
+    use jvm::{runtime::JavaLangString, Array, ClassInstanceRef};
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::get_protos;
+
+    #[test]
+    fn test_file_system() -> Result<()> {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
+            let dir = JavaLangString::from_rust_string(&jvm, "dir").await?;
+            let name = JavaLangString::from_rust_string(&jvm, "dir/save.dat").await?;
+
+            let _: () = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "mkdir", "(Ljava/lang/String;)V", (dir.clone(),))
+                .await?;
+            let is_directory: bool = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "isDirectory", "(Ljava/lang/String;)Z", (dir.clone(),))
+                .await?;
+            assert!(is_directory);
+
+            let available: i32 = jvm.invoke_static("org/kwis/msp/io/FileSystem", "available", "()I", ()).await?;
+
+            let file = jvm.new_class("org/kwis/msp/io/File", "(Ljava/lang/String;I)V", (name.clone(), 2)).await?;
+            let data = jvm.instantiate_array("B", 4).await?;
+            let _: i32 = jvm.invoke_virtual(&file, "write", "([BII)I", (data, 0, 4)).await?;
+
+            let is_file: bool = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "isFile", "(Ljava/lang/String;)Z", (name.clone(),))
+                .await?;
+            assert!(is_file);
+            let new_available: i32 = jvm.invoke_static("org/kwis/msp/io/FileSystem", "available", "()I", ()).await?;
+            assert_eq!(new_available, available - 4);
+
+            let list: ClassInstanceRef<Array<String>> = jvm
+                .invoke_static(
+                    "org/kwis/msp/io/FileSystem",
+                    "list",
+                    "(Ljava/lang/String;)[Ljava/lang/String;",
+                    (dir.clone(),),
+                )
+                .await?;
+            assert_eq!(jvm.array_length(&list).await?, 1);
+            let list: Vec<ClassInstanceRef<String>> = jvm.load_array(&list, 0, 1).await?;
+            assert_eq!(JavaLangString::to_rust_string(&jvm, &list[0]).await?, "save.dat");
+
+            // non empty directory can't be removed
+            let result: jvm::Result<()> = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "rmdir", "(Ljava/lang/String;)V", (dir.clone(),))
+                .await;
+            assert!(result.is_err());
+
+            let _: () = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "remove", "(Ljava/lang/String;)V", (name.clone(),))
+                .await?;
+            let exists: bool = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "exists", "(Ljava/lang/String;)Z", (name,))
+                .await?;
+            assert!(!exists);
+
+            let _: () = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "rmdir", "(Ljava/lang/String;)V", (dir.clone(),))
+                .await?;
+            let exists: bool = jvm
+                .invoke_static("org/kwis/msp/io/FileSystem", "exists", "(Ljava/lang/String;)Z", (dir,))
+                .await?;
+            assert!(!exists);
 
-        Ok(0x1000000) // TODO temp
+            Ok(())
+        })
     }
 }
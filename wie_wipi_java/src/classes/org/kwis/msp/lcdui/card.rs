@@ -3,6 +3,7 @@ use alloc::{format, vec};
 use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
 
+use wie_backend::Rect;
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 use crate::classes::org::kwis::msp::lcdui::Display;
@@ -34,6 +35,10 @@ impl Card {
                 JavaFieldProto::new("y", "I", Default::default()),
                 JavaFieldProto::new("w", "I", Default::default()),
                 JavaFieldProto::new("h", "I", Default::default()),
+                JavaFieldProto::new("dirtyX", "I", Default::default()),
+                JavaFieldProto::new("dirtyY", "I", Default::default()),
+                JavaFieldProto::new("dirtyW", "I", Default::default()),
+                JavaFieldProto::new("dirtyH", "I", Default::default()),
             ],
         }
     }
@@ -118,15 +123,29 @@ impl Card {
     }
 
     async fn repaint_with_area(
-        _: &Jvm,
+        jvm: &Jvm,
         context: &mut WieJvmContext,
-        this: ClassInstanceRef<Card>,
-        a0: i32,
-        a1: i32,
-        a2: i32,
-        a3: i32,
+        mut this: ClassInstanceRef<Card>,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
     ) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.lcdui.Card::repaint({:?}, {}, {}, {}, {})", &this, a0, a1, a2, a3);
+        tracing::debug!("org.kwis.msp.lcdui.Card::repaint({:?}, {}, {}, {}, {})", &this, x, y, width, height);
+
+        let (screen_width, screen_height) = context.system().display_size();
+
+        let card_x: i32 = jvm.get_field(&this, "x", "I").await?;
+        let card_y: i32 = jvm.get_field(&this, "y", "I").await?;
+
+        if let Some(area) = Rect::clipped(card_x + x, card_y + y, width, height, screen_width, screen_height) {
+            let damage = match Self::damage(jvm, &this).await? {
+                Some(damage) => damage.union(&area),
+                None => area,
+            };
+
+            Self::set_damage(jvm, &mut this, damage).await?;
+        }
 
         let mut platform = context.system().platform();
         let screen = platform.screen();
@@ -150,4 +169,33 @@ impl Card {
 
         Ok(())
     }
+
+    /// Returns screen area requested by repaint since last call, and clears it.
+    pub async fn take_damage(jvm: &Jvm, this: &mut ClassInstanceRef<Card>) -> JvmResult<Option<Rect>> {
+        let damage = Self::damage(jvm, this).await?;
+
+        Self::set_damage(jvm, this, Rect::new(0, 0, 0, 0)).await?;
+
+        Ok(damage)
+    }
+
+    async fn damage(jvm: &Jvm, this: &ClassInstanceRef<Card>) -> JvmResult<Option<Rect>> {
+        let x: i32 = jvm.get_field(this, "dirtyX", "I").await?;
+        let y: i32 = jvm.get_field(this, "dirtyY", "I").await?;
+        let width: i32 = jvm.get_field(this, "dirtyW", "I").await?;
+        let height: i32 = jvm.get_field(this, "dirtyH", "I").await?;
+
+        let damage = Rect::new(x as _, y as _, width as _, height as _);
+
+        Ok(if damage.is_empty() { None } else { Some(damage) })
+    }
+
+    async fn set_damage(jvm: &Jvm, this: &mut ClassInstanceRef<Card>, damage: Rect) -> JvmResult<()> {
+        jvm.put_field(this, "dirtyX", "I", damage.x as i32).await?;
+        jvm.put_field(this, "dirtyY", "I", damage.y as i32).await?;
+        jvm.put_field(this, "dirtyW", "I", damage.width as i32).await?;
+        jvm.put_field(this, "dirtyH", "I", damage.height as i32).await?;
+
+        Ok(())
+    }
 }
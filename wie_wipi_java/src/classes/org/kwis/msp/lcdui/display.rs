@@ -80,11 +80,7 @@ impl Display {
         jvm.put_field(&mut this, "cards", "[Lorg/kwis/msp/lcdui/Card;", cards).await?;
         jvm.put_field(&mut this, "szCard", "I", 0).await?;
 
-        let (width, height) = {
-            let mut platform = context.system().platform();
-            let screen = platform.screen();
-            (screen.width(), screen.height())
-        };
+        let (width, height) = context.system().display_size();
 
         jvm.put_field(&mut this, "m_w", "I", width as i32).await?;
         jvm.put_field(&mut this, "m_h", "I", height as i32).await?;
@@ -207,11 +207,13 @@ impl EventQueue {
             return Ok(());
         }
 
-        let card = Self::get_top_card(jvm, &display).await?;
+        let mut card = Self::get_top_card(jvm, &display).await?;
         if card.is_null() {
             return Ok(());
         }
 
+        let damage = Card::take_damage(jvm, &mut card).await?;
+
         let mut graphics = jvm
             .new_class("org/kwis/msp/lcdui/Graphics", "(Lorg/kwis/msp/lcdui/Display;)V", (display,))
             .await?;
@@ -234,7 +236,10 @@ impl EventQueue {
             let mut platform = context.system().platform();
             let screen = platform.screen();
 
-            screen.paint(&*image);
+            match damage {
+                Some(damage) => screen.paint_region(&*image, damage),
+                None => screen.paint(&*image),
+            }
         }
 
         Ok(())
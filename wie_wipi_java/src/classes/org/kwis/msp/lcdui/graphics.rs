@@ -3,7 +3,7 @@ use alloc::{format, string::String as RustString, vec, vec::Vec};
 use bytemuck::cast_vec;
 use jvm::{runtime::JavaLangString, JavaChar, JavaValue};
 
-use wie_backend::canvas::{PixelType, Rgb8Pixel, TextAlignment, VecImageBuffer};
+use wie_backend::canvas::{CompositeMode, PixelType, Rgb8Pixel, TextAlignment, VecImageBuffer};
 
 use java_class_proto::{JavaFieldProto, JavaMethodProto, TypeConverter};
 use java_runtime::classes::java::lang::String;
@@ -77,6 +77,7 @@ impl Graphics {
                 JavaFieldProto::new("w", "I", Default::default()),
                 JavaFieldProto::new("h", "I", Default::default()),
                 JavaFieldProto::new("rgb", "I", Default::default()),
+                JavaFieldProto::new("alpha", "I", Default::default()),
             ],
         }
     }
@@ -90,6 +91,7 @@ impl Graphics {
 
         jvm.put_field(&mut this, "w", "I", width).await?;
         jvm.put_field(&mut this, "h", "I", height).await?;
+        jvm.put_field(&mut this, "alpha", "I", 0xff).await?;
 
         Ok(())
     }
@@ -118,6 +120,7 @@ impl Graphics {
         jvm.put_field(&mut this, "img", "Lorg/kwis/msp/lcdui/Image;", image).await?;
         jvm.put_field(&mut this, "w", "I", width).await?;
         jvm.put_field(&mut this, "h", "I", height).await?;
+        jvm.put_field(&mut this, "alpha", "I", 0xff).await?;
 
         Ok(())
     }
@@ -154,8 +157,10 @@ impl Graphics {
         Ok(())
     }
 
-    async fn set_alpha(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Graphics>, a1: i32) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.lcdui.Graphics::setAlpha({:?}, {})", &this, a1);
+    async fn set_alpha(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Graphics>, alpha: i32) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.lcdui.Graphics::setAlpha({:?}, {})", &this, alpha);
+
+        jvm.put_field(&mut this, "alpha", "I", alpha.clamp(0, 0xff)).await?;
 
         Ok(())
     }
@@ -204,11 +209,13 @@ impl Graphics {
         let rgb: i32 = jvm.get_field(&this, "rgb", "I").await?;
 
         let image = Self::image(jvm, &mut this).await?;
-        let mut canvas = Image::canvas(jvm, &image).await?;
+        let mut canvas = Image::canvas_rows(jvm, &image, y, height).await?;
+
+        let mode = Self::composite_mode(jvm, &this, CompositeMode::Copy).await?;
 
-        canvas.fill_rect(x as _, y as _, width as _, height as _, Rgb8Pixel::to_color(rgb as _));
+        canvas.fill_rect(x as _, y as _, width as _, height as _, Rgb8Pixel::to_color(rgb as _), mode);
 
-        canvas.flush().await;
+        canvas.flush().await?;
 
         Ok(())
     }
@@ -219,11 +226,11 @@ impl Graphics {
         let rgb: i32 = jvm.get_field(&this, "rgb", "I").await?;
 
         let image = Self::image(jvm, &mut this).await?;
-        let mut canvas = Image::canvas(jvm, &image).await?;
+        let mut canvas = Image::canvas_rows(jvm, &image, y, height).await?;
 
         canvas.draw_rect(x as _, y as _, width as _, height as _, Rgb8Pixel::to_color(rgb as _));
 
-        canvas.flush().await;
+        canvas.flush().await?;
 
         Ok(())
     }
@@ -254,7 +261,7 @@ impl Graphics {
 
         canvas.draw_text(&string, x as _, y as _, alignment);
 
-        canvas.flush().await;
+        canvas.flush().await?;
 
         Ok(())
     }
@@ -292,7 +299,7 @@ impl Graphics {
 
         canvas.draw_text(&rust_string, x as _, y as _, alignment);
 
-        canvas.flush().await;
+        canvas.flush().await?;
 
         Ok(())
     }
@@ -303,11 +310,11 @@ impl Graphics {
         let rgb: i32 = jvm.get_field(&this, "rgb", "I").await?;
 
         let image = Self::image(jvm, &mut this).await?;
-        let mut canvas = Image::canvas(jvm, &image).await?;
+        let mut canvas = Image::canvas_rows(jvm, &image, y1.min(y2), y1.saturating_sub(y2).saturating_abs().saturating_add(1)).await?;
 
         canvas.draw_line(x1 as _, y1 as _, x2 as _, y2 as _, Rgb8Pixel::to_color(rgb as _));
 
-        canvas.flush().await;
+        canvas.flush().await?;
 
         Ok(())
     }
@@ -332,9 +339,6 @@ impl Graphics {
 
         let src_image = Image::image(jvm, &img).await?;
 
-        let image = Self::image(jvm, &mut this).await?;
-        let mut canvas = Image::canvas(jvm, &image).await?;
-
         let x_delta = if anchor.contains(Anchor::HCENTER) {
             -((src_image.width() / 2) as i32)
         } else if anchor.contains(Anchor::RIGHT) {
@@ -354,9 +358,14 @@ impl Graphics {
         let x = (x + x_delta).max(0);
         let y = (y + y_delta).max(0);
 
-        canvas.draw(x as _, y as _, src_image.width(), src_image.height(), &*src_image, 0, 0);
+        let mode = Self::composite_mode(jvm, &this, CompositeMode::SourceOver).await?;
+
+        let image = Self::image(jvm, &mut this).await?;
+        let mut canvas = Image::canvas_rows(jvm, &image, y, src_image.height() as _).await?;
+
+        canvas.draw(x as _, y as _, src_image.width(), src_image.height(), &*src_image, 0, 0, mode, None);
 
-        canvas.flush().await;
+        canvas.flush().await?;
 
         Ok(())
     }
@@ -440,20 +449,27 @@ impl Graphics {
             bpl
         );
 
-        // TODO we need imagebuffer proxy, as it's not optimal to copy entire image from java/c buffer to rust every time
         let pixel_data: Vec<i32> = jvm.load_array(&rgb_pixels, offset as _, (width * height) as _).await?;
         let src_image = VecImageBuffer::<Rgb8Pixel>::from_raw(width as _, height as _, cast_vec(pixel_data));
 
+        let mode = Self::composite_mode(jvm, &this, CompositeMode::SourceOver).await?;
+
         let image = Self::image(jvm, &mut this).await?;
-        let mut canvas = Image::canvas(jvm, &image).await?;
+        let mut canvas = Image::canvas_rows(jvm, &image, y, height).await?;
 
-        canvas.draw(x as _, y as _, width as _, height as _, &src_image, 0, 0);
+        canvas.draw(x as _, y as _, width as _, height as _, &src_image, 0, 0, mode, None);
 
-        canvas.flush().await;
+        canvas.flush().await?;
 
         Ok(())
     }
 
+    async fn composite_mode(jvm: &Jvm, this: &ClassInstanceRef<Graphics>, default: CompositeMode) -> JvmResult<CompositeMode> {
+        let alpha: i32 = jvm.get_field(this, "alpha", "I").await?;
+
+        Ok(if alpha < 0xff { CompositeMode::Alpha(alpha as _) } else { default })
+    }
+
     async fn image(jvm: &Jvm, this: &mut ClassInstanceRef<Graphics>) -> JvmResult<ClassInstanceRef<Image>> {
         let image: ClassInstanceRef<Image> = jvm.get_field(this, "img", "Lorg/kwis/msp/lcdui/Image;").await?;
 
@@ -485,14 +501,14 @@ mod test {
 
     use jvm::ClassInstanceRef;
 
-    use test_utils::run_jvm_test;
+    use test_utils::{run_jvm_test, TestPlatform};
     use wie_util::Result;
 
     use crate::{classes::org::kwis::msp::lcdui::Image, get_protos};
 
     #[test]
     fn test_graphics() -> Result<()> {
-        run_jvm_test(Box::new([get_protos().into()]), |jvm| async move {
+        run_jvm_test(TestPlatform::new(), Box::new([get_protos().into()]), |jvm| async move {
             let image: ClassInstanceRef<Image> = jvm
                 .invoke_static("org/kwis/msp/lcdui/Image", "createImage", "(II)Lorg/kwis/msp/lcdui/Image;", (100, 100))
                 .await?;
@@ -509,14 +525,25 @@ mod test {
 
             let _: () = jvm.invoke_virtual(&graphics, "fillRect", "(IIII)V", (0, 0, 100, 100)).await?;
 
-            let image = Image::image(&jvm, &image).await?;
+            let rendered = Image::image(&jvm, &image).await?;
+
+            assert_eq!(rendered.width(), 100);
+            assert_eq!(rendered.height(), 100);
+
+            assert_eq!(rendered.raw()[0], 0);
+            assert_eq!(rendered.raw()[1], 255);
+            assert_eq!(rendered.raw()[2], 0);
+
+            let _: () = jvm.invoke_virtual(&graphics, "setColor", "(I)V", (0x0000ff,)).await?;
+            let _: () = jvm.invoke_virtual(&graphics, "setAlpha", "(I)V", (0x80,)).await?;
+            let _: () = jvm.invoke_virtual(&graphics, "fillRect", "(IIII)V", (0, 0, 100, 100)).await?;
 
-            assert_eq!(image.width(), 100);
-            assert_eq!(image.height(), 100);
+            let rendered = Image::image(&jvm, &image).await?;
 
-            assert_eq!(image.raw()[0], 0);
-            assert_eq!(image.raw()[1], 255);
-            assert_eq!(image.raw()[2], 0);
+            // half blended blue over green
+            assert!((0x7e..=0x80).contains(&rendered.raw()[0]));
+            assert!((0x7e..=0x80).contains(&rendered.raw()[1]));
+            assert_eq!(rendered.raw()[2], 0);
 
             Ok(())
         })
@@ -1,7 +1,8 @@
-use alloc::{boxed::Box, vec, vec::Vec};
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
 use core::ops::{Deref, DerefMut};
 
 use bytemuck::{cast_vec, pod_collect_to_vec};
+use spin::Mutex;
 
 use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_constants::MethodAccessFlags;
@@ -11,7 +12,10 @@ use jvm::{
     Array, ClassInstanceRef, Jvm, Result as JvmResult,
 };
 
-use wie_backend::canvas::{decode_image, ArgbPixel, Canvas, Image as BackendImage, ImageBufferCanvas, Rgb565Pixel, VecImageBuffer};
+use wie_backend::canvas::{
+    decode_image, ArgbPixel, Canvas, Image as BackendImage, ImageBufferCanvas, ImageMemory, MemoryImageBuffer, Rgb332Pixel, Rgb444Pixel, Rgb565Pixel,
+    VecImageBuffer,
+};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 use crate::classes::org::kwis::msp::lcdui::Graphics;
@@ -54,6 +58,8 @@ impl Image {
                 JavaFieldProto::new("h", "I", Default::default()),
                 JavaFieldProto::new("imgData", "[B", Default::default()),
                 JavaFieldProto::new("bpl", "I", Default::default()),
+                // bits per pixel, as 12-bit and 16-bit images have the same bpl
+                JavaFieldProto::new("bpp", "I", Default::default()),
             ],
         }
     }
@@ -67,16 +73,7 @@ impl Image {
     async fn create_image(jvm: &Jvm, _: &mut WieJvmContext, width: i32, height: i32) -> JvmResult<ClassInstanceRef<Image>> {
         tracing::debug!("org.kwis.msp.lcdui.Image::createImage({}, {})", width, height);
 
-        let bytes_per_pixel = 4;
-
-        Self::create_image_instance(
-            jvm,
-            width as _,
-            height as _,
-            &vec![0; (width * height * bytes_per_pixel) as usize],
-            bytes_per_pixel as _,
-        )
-        .await
+        Self::create_image_instance(jvm, width as _, height as _, &vec![0; (width * height * 4) as usize], 32).await
     }
 
     async fn create_image_from_file(jvm: &Jvm, _: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<Image>> {
@@ -90,7 +87,7 @@ impl Image {
         let image_data = JavaIoInputStream::read_until_end(jvm, &stream).await?;
         let image = decode_image(&image_data).unwrap();
 
-        Self::create_image_instance(jvm, image.width(), image.height(), image.raw(), image.bytes_per_pixel()).await
+        Self::create_image_instance(jvm, image.width(), image.height(), &image.raw(), image.bits_per_pixel()).await
     }
 
     async fn create_image_from_bytes(
@@ -105,7 +102,7 @@ impl Image {
         let image_data = jvm.load_byte_array(&data, offset as _, length as _).await?;
         let image = decode_image(&cast_vec(image_data)).unwrap();
 
-        Self::create_image_instance(jvm, image.width(), image.height(), image.raw(), image.bytes_per_pixel()).await
+        Self::create_image_instance(jvm, image.width(), image.height(), &image.raw(), image.bits_per_pixel()).await
     }
 
     async fn get_graphics(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<Graphics>> {
@@ -151,30 +148,78 @@ impl Image {
 
         let width: i32 = jvm.get_field(this, "w", "I").await?;
         let height: i32 = jvm.get_field(this, "h", "I").await?;
-        let bpl: i32 = jvm.get_field(this, "bpl", "I").await?;
-
-        let bytes_per_pixel = bpl / width;
-
-        Ok(match bytes_per_pixel {
-            2 => Box::new(VecImageBuffer::<Rgb565Pixel>::from_raw(width as _, height as _, pod_collect_to_vec(&buf))) as Box<_>,
-            4 => Box::new(VecImageBuffer::<ArgbPixel>::from_raw(width as _, height as _, pod_collect_to_vec(&buf))) as Box<_>,
-            _ => unimplemented!("Unsupported pixel format: {}", bytes_per_pixel),
+        let bits_per_pixel: i32 = jvm.get_field(this, "bpp", "I").await?;
+
+        Ok(match bits_per_pixel {
+            8 => Box::new(VecImageBuffer::<Rgb332Pixel>::from_raw(width as _, height as _, buf)) as Box<_>,
+            12 => Box::new(VecImageBuffer::<Rgb444Pixel>::from_raw(width as _, height as _, pod_collect_to_vec(&buf))) as Box<_>,
+            16 => Box::new(VecImageBuffer::<Rgb565Pixel>::from_raw(width as _, height as _, pod_collect_to_vec(&buf))) as Box<_>,
+            32 => Box::new(VecImageBuffer::<ArgbPixel>::from_raw(width as _, height as _, pod_collect_to_vec(&buf))) as Box<_>,
+            _ => return Err(jvm.exception("java/lang/IllegalStateException", "Unsupported pixel format").await),
         })
     }
 
     pub async fn canvas<'a>(jvm: &'a Jvm, this: &'a ClassInstanceRef<Self>) -> JvmResult<ImageCanvas<'a>> {
-        let buf = Self::buf(jvm, this).await?;
+        let height: i32 = jvm.get_field(this, "h", "I").await?;
 
+        Self::canvas_rows(jvm, this, 0, height).await
+    }
+
+    /// Canvas over rows `y..y + height` of the image. Drawing outside those rows is discarded.
+    pub async fn canvas_rows<'a>(jvm: &'a Jvm, this: &'a ClassInstanceRef<Self>, y: i32, height: i32) -> JvmResult<ImageCanvas<'a>> {
         let width: i32 = jvm.get_field(this, "w", "I").await?;
-        let height: i32 = jvm.get_field(this, "h", "I").await?;
-        let bpl: i32 = jvm.get_field(this, "bpl", "I").await?;
+        let image_height: i32 = jvm.get_field(this, "h", "I").await?;
+        let bytes_per_line: i32 = jvm.get_field(this, "bpl", "I").await?;
+        let bits_per_pixel: i32 = jvm.get_field(this, "bpp", "I").await?;
 
-        let bytes_per_pixel = bpl / width;
+        let start = y.clamp(0, image_height);
+        let end = y.saturating_add(height.max(0)).clamp(start, image_height);
 
-        Ok(ImageCanvas::new(jvm, this, width as _, height as _, bytes_per_pixel as _, buf))
+        let java_img_data = jvm.get_field(this, "imgData", "[B").await?;
+        let img_data_len = jvm.array_length(&java_img_data).await?;
+        let offset = (start as usize * bytes_per_line as usize).min(img_data_len);
+        let length = (end as usize * bytes_per_line as usize).min(img_data_len) - offset;
+        let data: Vec<i8> = jvm.load_byte_array(&java_img_data, offset, length).await?;
+
+        let memory = ImageDataMemory(Arc::new(Mutex::new(ImageDataRows {
+            offset: offset as _,
+            data: cast_vec(data),
+            dirty: None,
+        })));
+
+        let (width, height, bytes_per_line) = (width as u32, image_height as u32, bytes_per_line as u32);
+        let canvas: Box<dyn Canvas> = match bits_per_pixel {
+            8 => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<Rgb332Pixel, _>::new(
+                width,
+                height,
+                bytes_per_line,
+                memory.clone(),
+            ))),
+            12 => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<Rgb444Pixel, _>::new(
+                width,
+                height,
+                bytes_per_line,
+                memory.clone(),
+            ))),
+            16 => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<Rgb565Pixel, _>::new(
+                width,
+                height,
+                bytes_per_line,
+                memory.clone(),
+            ))),
+            32 => Box::new(ImageBufferCanvas::new(MemoryImageBuffer::<ArgbPixel, _>::new(
+                width,
+                height,
+                bytes_per_line,
+                memory.clone(),
+            ))),
+            _ => return Err(jvm.exception("java/lang/IllegalStateException", "Unsupported pixel format").await),
+        };
+
+        Ok(ImageCanvas::new(jvm, this, canvas, memory))
     }
 
-    async fn create_image_instance(jvm: &Jvm, width: u32, height: u32, data: &[u8], bytes_per_pixel: u32) -> JvmResult<ClassInstanceRef<Image>> {
+    async fn create_image_instance(jvm: &Jvm, width: u32, height: u32, data: &[u8], bits_per_pixel: u32) -> JvmResult<ClassInstanceRef<Image>> {
         let mut instance = jvm.new_class("org/kwis/msp/lcdui/Image", "()V", []).await?;
 
         let mut data_array = jvm.instantiate_array("B", data.len() as _).await?;
@@ -183,60 +228,79 @@ impl Image {
         jvm.put_field(&mut instance, "w", "I", width as i32).await?;
         jvm.put_field(&mut instance, "h", "I", height as i32).await?;
         jvm.put_field(&mut instance, "imgData", "[B", data_array).await?;
-        jvm.put_field(&mut instance, "bpl", "I", (width * bytes_per_pixel) as i32).await?;
+        jvm.put_field(&mut instance, "bpl", "I", (width * bits_per_pixel.div_ceil(8)) as i32)
+            .await?;
+        jvm.put_field(&mut instance, "bpp", "I", bits_per_pixel as i32).await?;
 
         Ok(instance.into())
     }
 }
 
+/// Rows of `imgData` loaded from the java array, with the byte range modified since load.
+struct ImageDataRows {
+    offset: u32,
+    data: Vec<u8>,
+    dirty: Option<(usize, usize)>,
+}
+
+impl ImageDataRows {
+    fn range(&self, offset: u32, length: usize) -> Option<(usize, usize)> {
+        let start = offset.checked_sub(self.offset)? as usize;
+        let end = start.checked_add(length)?;
+
+        (end <= self.data.len()).then_some((start, end))
+    }
+}
+
+#[derive(Clone)]
+struct ImageDataMemory(Arc<Mutex<ImageDataRows>>);
+
+impl ImageMemory for ImageDataMemory {
+    fn read(&self, offset: u32, buf: &mut [u8]) {
+        let rows = self.0.lock();
+        match rows.range(offset, buf.len()) {
+            Some((start, end)) => buf.copy_from_slice(&rows.data[start..end]),
+            None => buf.fill(0),
+        }
+    }
+
+    fn write(&mut self, offset: u32, data: &[u8]) {
+        let mut rows = self.0.lock();
+        if let Some((start, end)) = rows.range(offset, data.len()) {
+            rows.data[start..end].copy_from_slice(data);
+            rows.dirty = Some(match rows.dirty {
+                Some((dirty_start, dirty_end)) => (dirty_start.min(start), dirty_end.max(end)),
+                None => (start, end),
+            });
+        }
+    }
+}
+
 pub struct ImageCanvas<'a> {
     image: &'a ClassInstanceRef<Image>,
     jvm: &'a Jvm,
     canvas: Box<dyn Canvas>,
-    flushed: bool,
+    memory: ImageDataMemory,
 }
 
 impl<'a> ImageCanvas<'a> {
-    pub fn new(jvm: &'a Jvm, image: &'a ClassInstanceRef<Image>, width: u32, height: i32, bytes_per_pixel: u32, buf: Vec<u8>) -> Self {
-        let canvas: Box<dyn Canvas> = match bytes_per_pixel {
-            2 => Box::new(ImageBufferCanvas::new(VecImageBuffer::<Rgb565Pixel>::from_raw(
-                width as _,
-                height as _,
-                pod_collect_to_vec(&buf),
-            ))),
-            4 => Box::new(ImageBufferCanvas::new(VecImageBuffer::<ArgbPixel>::from_raw(
-                width as _,
-                height as _,
-                pod_collect_to_vec(&buf),
-            ))),
-            _ => unimplemented!("Unsupported pixel format: {}", bytes_per_pixel),
-        };
-
-        Self {
-            image,
-            jvm,
-            canvas,
-            flushed: false,
-        }
+    fn new(jvm: &'a Jvm, image: &'a ClassInstanceRef<Image>, canvas: Box<dyn Canvas>, memory: ImageDataMemory) -> Self {
+        Self { image, jvm, canvas, memory }
     }
 
-    // We don't have async drop yet..
-    pub async fn flush(mut self) {
-        let mut data = self.jvm.get_field(self.image, "imgData", "[B").await.unwrap();
+    // We don't have async drop yet.. changes are discarded if not flushed
+    pub async fn flush(self) -> JvmResult<()> {
+        let (offset, data) = {
+            let rows = self.memory.0.lock();
+            let Some((start, end)) = rows.dirty else {
+                return Ok(());
+            };
 
-        self.jvm
-            .store_byte_array(&mut data, 0, cast_vec(self.canvas.image().raw().to_vec()))
-            .await
-            .unwrap();
-        self.flushed = true
-    }
-}
+            (rows.offset as usize + start, rows.data[start..end].to_vec())
+        };
 
-impl Drop for ImageCanvas<'_> {
-    fn drop(&mut self) {
-        if !self.flushed {
-            panic!("ImageCanvas was dropped without flushing")
-        }
+        let mut img_data = self.jvm.get_field(self.image, "imgData", "[B").await?;
+        self.jvm.store_byte_array(&mut img_data, offset, cast_vec(data)).await
     }
 }
 
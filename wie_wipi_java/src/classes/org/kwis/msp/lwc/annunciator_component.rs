@@ -17,20 +17,90 @@ impl AnnunciatorComponent {
             methods: vec![
                 JavaMethodProto::new("<init>", "(Z)V", Self::init, Default::default()),
                 JavaMethodProto::new("show", "()V", Self::show, Default::default()),
+                JavaMethodProto::new("hide", "()V", Self::hide, Default::default()),
+                JavaMethodProto::new("setIcon", "(II)V", Self::set_icon, Default::default()),
+                JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
             ],
             fields: vec![],
         }
     }
 
-    async fn init(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<AnnunciatorComponent>, a0: bool) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.lwc.AnnunciatorComponent::<init>({:?}, {})", &this, a0);
+    // TODO we assume parameter is initial visibility
+    async fn init(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<AnnunciatorComponent>, show: bool) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.lwc.AnnunciatorComponent::<init>({:?}, {})", &this, show);
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        context.system().update_indicators(|x| x.annunciator_visible = show);
+
+        Ok(())
+    }
+
+    async fn show(_: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<AnnunciatorComponent>) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.lwc.AnnunciatorComponent::show({:?})", &this);
+
+        context.system().update_indicators(|x| x.annunciator_visible = true);
 
         Ok(())
     }
 
-    async fn show(_: &Jvm, _: &mut WieJvmContext) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.lwc.AnnunciatorComponent::show");
+    async fn hide(_: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<AnnunciatorComponent>) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.lwc.AnnunciatorComponent::hide({:?})", &this);
+
+        context.system().update_indicators(|x| x.annunciator_visible = false);
 
         Ok(())
     }
+
+    async fn set_icon(_: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<AnnunciatorComponent>, icon: i32, state: i32) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.lwc.AnnunciatorComponent::setIcon({:?}, {}, {})", &this, icon, state);
+
+        context.system().update_indicators(|x| {
+            x.annunciator_icons.insert(icon as _, state as _);
+        });
+
+        Ok(())
+    }
+
+    async fn get_height(_: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<AnnunciatorComponent>) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.lwc.AnnunciatorComponent::getHeight({:?})", &this);
+
+        Ok(context.system().indicators().annunciator_height as _)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::boxed::Box;
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_backend::HandsetProfile;
+    use wie_util::Result;
+
+    use crate::get_protos;
+
+    #[test]
+    fn test_annunciator_component() -> Result<()> {
+        let platform = TestPlatform::with_handset_profile(HandsetProfile {
+            annunciator_height: 16,
+            led_count: 1,
+        });
+        let indicators = platform.indicators();
+
+        run_jvm_test(platform, Box::new([get_protos().into()]), move |jvm| async move {
+            let annunciator = jvm.new_class("org/kwis/msp/lwc/AnnunciatorComponent", "(Z)V", (true,)).await?;
+            assert!(indicators.lock().as_ref().unwrap().annunciator_visible);
+
+            let height: i32 = jvm.invoke_virtual(&annunciator, "getHeight", "()I", ()).await?;
+            assert_eq!(height, 16);
+
+            let _: () = jvm.invoke_virtual(&annunciator, "setIcon", "(II)V", (1, 3)).await?;
+            assert_eq!(indicators.lock().as_ref().unwrap().annunciator_icons.get(&1), Some(&3));
+
+            let _: () = jvm.invoke_virtual(&annunciator, "hide", "()V", ()).await?;
+            assert!(!indicators.lock().as_ref().unwrap().annunciator_visible);
+
+            Ok(())
+        })
+    }
 }
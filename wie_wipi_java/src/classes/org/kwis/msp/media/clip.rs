@@ -4,12 +4,18 @@ use bytemuck::cast_vec;
 
 use java_class_proto::{JavaFieldProto, JavaMethodProto};
 use java_runtime::classes::java::lang::String;
-use jvm::{runtime::JavaIoInputStream, Array, ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{
+    runtime::{JavaIoInputStream, JavaLangString},
+    Array, ClassInstanceRef, Jvm, Result as JvmResult,
+};
 
+use wie_backend::AudioHandle;
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 use crate::classes::org::kwis::msp::media::PlayListener;
 
+const MAX_VOLUME: i32 = 100;
+
 // class org.kwis.msp.media.Clip
 pub struct Clip;
 
@@ -38,15 +44,24 @@ impl Clip {
                 ),
                 JavaMethodProto::new("setBuffer", "([BI)V", Self::set_buffer, Default::default()),
             ],
-            fields: vec![JavaFieldProto::new("data", "[B", Default::default())],
+            fields: vec![
+                JavaFieldProto::new("type", "Ljava/lang/String;", Default::default()),
+                JavaFieldProto::new("data", "[B", Default::default()),
+                JavaFieldProto::new("handle", "I", Default::default()),
+                JavaFieldProto::new("volume", "I", Default::default()),
+                JavaFieldProto::new("listener", "Lorg/kwis/msp/media/PlayListener;", Default::default()),
+            ],
         }
     }
 
-    async fn init(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>, r#type: ClassInstanceRef<String>) -> JvmResult<()> {
+    async fn init(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, r#type: ClassInstanceRef<String>) -> JvmResult<()> {
         tracing::debug!("org.kwis.msp.media.Clip::<init>({:?}, {:?})", &this, r#type);
 
         let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
 
+        jvm.put_field(&mut this, "type", "Ljava/lang/String;", r#type).await?;
+        jvm.put_field(&mut this, "volume", "I", MAX_VOLUME).await?;
+
         Ok(())
     }
 
@@ -93,11 +108,13 @@ impl Clip {
         r#type: ClassInstanceRef<String>,
         data: ClassInstanceRef<Array<i8>>,
     ) -> JvmResult<()> {
-        tracing::debug!("org.kwis.msp.media.Clip::<init>({:?}, {:?}, {:?})", &this, r#type, &data);
+        tracing::debug!("org.kwis.msp.media.Clip::<init>({:?}, {:?}, {:?})", &this, &r#type, &data);
 
         let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
 
+        jvm.put_field(&mut this, "type", "Ljava/lang/String;", r#type).await?;
         jvm.put_field(&mut this, "data", "[B", data).await?;
+        jvm.put_field(&mut this, "volume", "I", MAX_VOLUME).await?;
 
         Ok(())
     }
@@ -109,27 +126,80 @@ impl Clip {
         r#type: ClassInstanceRef<String>,
         size: i32,
     ) -> JvmResult<()> {
-        tracing::debug!("org.kwis.msp.media.Clip::<init>({:?}, {:?}, {})", &this, r#type, size);
+        tracing::debug!("org.kwis.msp.media.Clip::<init>({:?}, {:?}, {})", &this, &r#type, size);
 
         let data = jvm.instantiate_array("B", size as _).await?;
 
+        jvm.put_field(&mut this, "type", "Ljava/lang/String;", r#type).await?;
         jvm.put_field(&mut this, "data", "[B", data).await?;
+        jvm.put_field(&mut this, "volume", "I", MAX_VOLUME).await?;
 
         Ok(())
     }
 
-    async fn set_volume(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Clip>, level: i32) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.media.Clip::setVolume({:?}, {})", &this, level);
+    async fn set_volume(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Clip>, level: i32) -> JvmResult<bool> {
+        tracing::debug!("org.kwis.msp.media.Clip::setVolume({:?}, {})", &this, level);
 
-        Ok(())
+        let level = level.clamp(0, MAX_VOLUME);
+        jvm.put_field(&mut this, "volume", "I", level).await?;
+
+        let handle: i32 = jvm.get_field(&this, "handle", "I").await?;
+        if handle != 0 {
+            let _ = context.system().audio().set_volume(handle as _, level as _);
+        }
+
+        Ok(true)
     }
 
-    async fn set_listener(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>, listener: ClassInstanceRef<PlayListener>) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.media.Clip::setListener({:?}, {:?})", &this, &listener);
+    async fn set_listener(
+        jvm: &Jvm,
+        _: &mut WieJvmContext,
+        mut this: ClassInstanceRef<Self>,
+        listener: ClassInstanceRef<PlayListener>,
+    ) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.media.Clip::setListener({:?}, {:?})", &this, &listener);
+
+        jvm.put_field(&mut this, "listener", "Lorg/kwis/msp/media/PlayListener;", listener)
+            .await?;
 
         Ok(())
     }
 
+    pub async fn listener(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<PlayListener>> {
+        jvm.get_field(this, "listener", "Lorg/kwis/msp/media/PlayListener;").await
+    }
+
+    /// Returns audio handle of this clip, loading data on first call.
+    pub async fn handle(jvm: &Jvm, context: &mut WieJvmContext, this: &mut ClassInstanceRef<Self>) -> JvmResult<Option<AudioHandle>> {
+        let handle: i32 = jvm.get_field(this, "handle", "I").await?;
+        if handle != 0 {
+            return Ok(Some(handle as _));
+        }
+
+        // type is mime type or format name, used when format can't be detected from data
+        let r#type: ClassInstanceRef<String> = jvm.get_field(this, "type", "Ljava/lang/String;").await?;
+        let r#type = if r#type.is_null() {
+            None
+        } else {
+            Some(JavaLangString::to_rust_string(jvm, &r#type).await?)
+        };
+
+        let data = Self::data(jvm, this.clone()).await?;
+        let handle = context.system().audio().load(&data, r#type.as_deref());
+        if let Err(x) = handle {
+            tracing::error!("Failed to load audio: {:?}", x);
+            return Ok(None);
+        }
+        let handle = handle.unwrap();
+
+        let volume: i32 = jvm.get_field(this, "volume", "I").await?;
+        let _ = context.system().audio().set_volume(handle, volume as _);
+
+        jvm.put_field(this, "handle", "I", handle as i32).await?;
+
+        Ok(Some(handle))
+    }
+
     pub async fn data(jvm: &Jvm, this: ClassInstanceRef<Self>) -> JvmResult<Vec<u8>> {
         let data = jvm.get_field(&this, "data", "[B").await?;
 
@@ -146,6 +216,7 @@ impl Clip {
         tracing::debug!("org.kwis.msp.media.Clip::setBuffer({:?}, {:?}, {})", &this, &buffer, size);
 
         jvm.put_field(&mut this, "data", "[B", buffer).await?;
+        jvm.put_field(&mut this, "handle", "I", 0).await?; // reload on next play
 
         Ok(())
     }
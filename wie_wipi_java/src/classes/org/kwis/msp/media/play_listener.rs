@@ -1,5 +1,7 @@
 use alloc::vec;
 
+use java_class_proto::JavaMethodProto;
+
 use wie_jvm_support::WieJavaClassProto;
 
 // interface org.kwis.msp.media.PlayListener
@@ -11,7 +13,11 @@ impl PlayListener {
             name: "org/kwis/msp/media/PlayListener",
             parent_class: None,
             interfaces: vec![],
-            methods: vec![],
+            methods: vec![JavaMethodProto::new_abstract(
+                "playerUpdate",
+                "(ILjava/lang/Object;)V",
+                Default::default(),
+            )],
             fields: vec![],
         }
     }
@@ -1,12 +1,18 @@
-use alloc::vec;
+use alloc::{boxed::Box, vec};
 
-use java_class_proto::JavaMethodProto;
+use java_class_proto::{JavaMethodProto, MethodBody};
 use java_constants::MethodAccessFlags;
-use jvm::{ClassInstanceRef, Jvm, Result as JvmResult};
+use jvm::{ClassInstanceRef, JavaError, JavaValue, Jvm, Result as JvmResult};
 
+use wie_backend::{AudioHandle, PlaybackEvent};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
-use crate::classes::org::kwis::msp::media::Clip;
+use crate::classes::org::kwis::msp::media::{Clip, PlayListener};
+
+// event type passed to PlayListener.playerUpdate when clip reaches the end of media.
+// listener isn't notified on Player.stop, as application already knows it has stopped the clip.
+// TODO value is not verified against wipi sdk
+const EVENT_END_OF_MEDIA: i32 = 1;
 
 // class org.kwis.msp.media.Player
 pub struct Player;
@@ -25,21 +31,69 @@ impl Player {
         }
     }
 
-    async fn play(jvm: &Jvm, context: &mut WieJvmContext, clip: ClassInstanceRef<Clip>, repeat: bool) -> JvmResult<bool> {
+    async fn play(jvm: &Jvm, context: &mut WieJvmContext, mut clip: ClassInstanceRef<Clip>, repeat: bool) -> JvmResult<bool> {
         tracing::debug!("org.kwis.msp.media.Player::play({:?}, {})", &clip, repeat);
 
-        let clip_data = Clip::data(jvm, clip).await?;
+        let handle = Clip::handle(jvm, context, &mut clip).await?;
+        if handle.is_none() {
+            return Ok(false);
+        }
+        let handle = handle.unwrap();
+
+        let loop_count = if repeat { None } else { Some(1) };
+        if let Err(x) = context.system().audio().play(handle, loop_count) {
+            tracing::error!("Failed to play audio: {:?}", x);
+            return Ok(false);
+        }
+
+        let listener = Clip::listener(jvm, &clip).await?;
+        if !listener.is_null() {
+            struct CompletionProxy {
+                clip: ClassInstanceRef<Clip>,
+                listener: ClassInstanceRef<PlayListener>,
+                handle: AudioHandle,
+            }
 
-        let audio_handle = context.system().audio().load_smaf(&clip_data).unwrap();
+            #[async_trait::async_trait]
+            impl MethodBody<JavaError, WieJvmContext> for CompletionProxy {
+                #[tracing::instrument(name = "play_listener", skip_all)]
+                async fn call(&self, jvm: &Jvm, context: &mut WieJvmContext, _: Box<[JavaValue]>) -> Result<JavaValue, JavaError> {
+                    let Ok(completion) = context.system().audio().completion(self.handle) else {
+                        return Ok(JavaValue::Void);
+                    };
+                    if completion.wait(context.system()).await != PlaybackEvent::Finished {
+                        return Ok(JavaValue::Void);
+                    }
 
-        context.system().audio().play(audio_handle).unwrap();
+                    jvm.attach_thread().await?;
+                    let _: () = jvm
+                        .invoke_virtual(
+                            &self.listener,
+                            "playerUpdate",
+                            "(ILjava/lang/Object;)V",
+                            (EVENT_END_OF_MEDIA, self.clip.clone()),
+                        )
+                        .await?;
+                    jvm.detach_thread().await?;
 
-        Ok(false)
+                    Ok(JavaValue::Void)
+                }
+            }
+
+            context.spawn(jvm, Box::new(CompletionProxy { clip, listener, handle }))?;
+        }
+
+        Ok(true)
     }
 
-    async fn stop(_: &Jvm, _: &mut WieJvmContext, clip: ClassInstanceRef<Clip>) -> JvmResult<bool> {
-        tracing::warn!("stub org.kwis.msp.media.Player::stop({:?})", &clip,);
+    async fn stop(jvm: &Jvm, context: &mut WieJvmContext, clip: ClassInstanceRef<Clip>) -> JvmResult<bool> {
+        tracing::debug!("org.kwis.msp.media.Player::stop({:?})", &clip);
+
+        let handle: i32 = jvm.get_field(&clip, "handle", "I").await?;
+        if handle == 0 {
+            return Ok(false);
+        }
 
-        Ok(false)
+        Ok(context.system().audio().stop(handle as _).is_ok())
     }
 }
@@ -25,9 +25,36 @@ impl Vibrator {
         }
     }
 
-    async fn on(_: &Jvm, _: &mut WieJvmContext, level: i32, duration: i32) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msp.media.Vibrator::on({}, {})", level, duration);
+    async fn on(_: &Jvm, context: &mut WieJvmContext, level: i32, duration: i32) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msp.media.Vibrator::on({}, {})", level, duration);
+
+        context.system().platform().vibrate(duration.max(0) as _, level.clamp(0, 100) as _);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::boxed::Box;
+
+    use test_utils::{run_jvm_test, TestPlatform};
+    use wie_util::Result;
+
+    use crate::get_protos;
+
+    #[test]
+    fn test_vibrator() -> Result<()> {
+        let platform = TestPlatform::new();
+        let vibrations = platform.vibrations();
+
+        run_jvm_test(platform, Box::new([get_protos().into()]), move |jvm| async move {
+            let _: () = jvm.invoke_static("org/kwis/msp/media/Vibrator", "on", "(II)V", (50, 300)).await?;
+            let _: () = jvm.invoke_static("org/kwis/msp/media/Vibrator", "on", "(II)V", (200, -1)).await?;
+
+            assert_eq!(*vibrations.lock(), [(300, 50), (0, 100)]);
+
+            Ok(())
+        })
+    }
+}